@@ -0,0 +1,249 @@
+//! File copy orchestration between the host and a running session.
+//!
+//! This module exposes `podbot cp`, which archives a host file or directory
+//! into a container path, or pulls a container path back to the host. Host
+//! paths are opened through capability-scoped `cap_std` directory handles.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use cap_std::fs_utf8::Dir;
+
+use crate::config::AppConfig;
+use crate::engine::{
+    CopyRequest as EngineCopyRequest, EngineConnector, HostCopyPath, SocketResolver,
+};
+use crate::error::{ConfigError, PodbotError, Result as PodbotResult};
+
+use super::CommandOutcome;
+
+/// Largest archive, in bytes, accepted by a copy unless overridden.
+pub const DEFAULT_COPY_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Request to copy a path between the host and a running container.
+///
+/// # Examples
+///
+/// ```rust
+/// use podbot::api::CopyRequest;
+///
+/// let request = CopyRequest::new("sandbox", "/work/specs", "specs")?.with_max_bytes(1024);
+/// assert_eq!(request.container_path(), "/work/specs");
+/// assert_eq!(request.max_bytes(), 1024);
+/// # Ok::<(), podbot::error::PodbotError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyRequest {
+    container: String,
+    container_path: Utf8PathBuf,
+    host_path: Utf8PathBuf,
+    max_bytes: u64,
+}
+
+impl CopyRequest {
+    /// Build a copy request capped at [`DEFAULT_COPY_MAX_BYTES`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` when the container identifier
+    /// is blank, and `ConfigError::InvalidValue` when the container path is
+    /// not absolute or either path does not name a file or directory.
+    pub fn new(
+        container: impl Into<String>,
+        container_path: impl Into<Utf8PathBuf>,
+        host_path: impl Into<Utf8PathBuf>,
+    ) -> PodbotResult<Self> {
+        let request = Self {
+            container: container.into(),
+            container_path: container_path.into(),
+            host_path: host_path.into(),
+            max_bytes: DEFAULT_COPY_MAX_BYTES,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Return a copy of the request with a different size cap.
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Return the target container identifier.
+    #[must_use]
+    pub fn container(&self) -> &str {
+        &self.container
+    }
+
+    /// Return the absolute path inside the container.
+    #[must_use]
+    pub fn container_path(&self) -> &Utf8Path {
+        &self.container_path
+    }
+
+    /// Return the host path.
+    #[must_use]
+    pub fn host_path(&self) -> &Utf8Path {
+        &self.host_path
+    }
+
+    /// Return the largest archive size accepted, in bytes.
+    #[must_use]
+    pub const fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    fn validate(&self) -> PodbotResult<()> {
+        if self.container.trim().is_empty() {
+            return Err(ConfigError::MissingRequired {
+                field: String::from("container"),
+            }
+            .into());
+        }
+        if !self.container_path.is_absolute() || self.container_path.file_name().is_none() {
+            return Err(invalid_copy_value(
+                "cp.container_path",
+                format!(
+                    "'{}' must be an absolute path naming a file or directory",
+                    self.container_path
+                ),
+            ));
+        }
+        if self.host_path.file_name().is_none() {
+            return Err(invalid_copy_value(
+                "cp.host_path",
+                format!("'{}' does not name a file or directory", self.host_path),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Copy a host file or directory into a running container.
+///
+/// The host entry lands at `request.container_path()`; its parent directory
+/// must already exist in the container.
+///
+/// # Errors
+///
+/// Returns runtime creation and engine connection failures,
+/// `FilesystemError` variants when the host path cannot be read or exceeds
+/// the size cap, and `ContainerError::UploadFailed` when the upload fails.
+pub fn copy_to_container(
+    config: &AppConfig,
+    request: &CopyRequest,
+) -> PodbotResult<CommandOutcome> {
+    let runtime = super::create_runtime()?;
+    let connector = connect(config)?;
+    let parent_dir = super::open_host_parent_dir(request.host_path())?;
+    let result = crate::engine::copy_to_container(
+        runtime.handle(),
+        &connector,
+        &engine_request(request, &parent_dir),
+    )?;
+    debug_copy_completed("copy_to_container", request, result.bytes_copied);
+    Ok(CommandOutcome::Success)
+}
+
+/// Copy a file or directory out of a running container to the host.
+///
+/// The container entry is written at `request.host_path()`; its parent
+/// directory must already exist on the host.
+///
+/// # Errors
+///
+/// Returns runtime creation and engine connection failures,
+/// `ContainerError::DownloadFailed` when the download fails or exceeds the
+/// size cap, and `FilesystemError` variants when the host write fails.
+pub fn copy_from_container(
+    config: &AppConfig,
+    request: &CopyRequest,
+) -> PodbotResult<CommandOutcome> {
+    let runtime = super::create_runtime()?;
+    let connector = connect(config)?;
+    let parent_dir = super::open_host_parent_dir(request.host_path())?;
+    let result = crate::engine::copy_from_container(
+        runtime.handle(),
+        &connector,
+        &engine_request(request, &parent_dir),
+    )?;
+    debug_copy_completed("copy_from_container", request, result.bytes_copied);
+    Ok(CommandOutcome::Success)
+}
+
+fn connect(config: &AppConfig) -> PodbotResult<bollard::Docker> {
+    let env = mockable::DefaultEnv::new();
+    let resolver = SocketResolver::new(&env);
+    EngineConnector::connect_with_fallback(config.engine_socket.as_deref(), &resolver)
+}
+
+fn engine_request<'a>(request: &'a CopyRequest, parent_dir: &'a Dir) -> EngineCopyRequest<'a> {
+    EngineCopyRequest {
+        container_id: request.container(),
+        container_path: request.container_path(),
+        host: HostCopyPath {
+            parent_dir,
+            path: request.host_path(),
+        },
+        max_bytes: request.max_bytes(),
+    }
+}
+
+fn debug_copy_completed(operation: &str, request: &CopyRequest, bytes_copied: u64) {
+    tracing::debug!(
+        operation,
+        container = request.container(),
+        container_path = %request.container_path(),
+        host_path = %request.host_path(),
+        bytes_copied,
+        "copy completed"
+    );
+}
+
+fn invalid_copy_value(field: &str, reason: String) -> PodbotError {
+    PodbotError::from(ConfigError::InvalidValue {
+        field: String::from(field),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for copy request validation.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn new_request_uses_default_size_cap() {
+        let request =
+            CopyRequest::new("sandbox", "/work/specs", "specs").expect("request should be valid");
+
+        assert_eq!(request.max_bytes(), DEFAULT_COPY_MAX_BYTES);
+        assert_eq!(request.host_path(), Utf8Path::new("specs"));
+    }
+
+    #[rstest]
+    #[case::blank_container(" ", "/work/specs", "specs", "container")]
+    #[case::relative_container_path("sandbox", "work/specs", "specs", "cp.container_path")]
+    #[case::container_root("sandbox", "/", "specs", "cp.container_path")]
+    #[case::host_parent("sandbox", "/work/specs", "..", "cp.host_path")]
+    fn new_request_rejects_invalid_paths(
+        #[case] container: &str,
+        #[case] container_path: &str,
+        #[case] host_path: &str,
+        #[case] expected_field: &str,
+    ) {
+        let result = CopyRequest::new(container, container_path, host_path);
+
+        assert!(
+            matches!(
+                &result,
+                Err(PodbotError::Config(
+                    ConfigError::MissingRequired { field } | ConfigError::InvalidValue { field, .. }
+                )) if field == expected_field
+            ),
+            "expected {expected_field} rejection, got {result:?}"
+        );
+    }
+}
@@ -5,12 +5,14 @@
 //! trusted value object. For simple callers, [`exec`] resolves a connection on
 //! demand. Embedders that need to reuse a runtime handle and engine
 //! connection can create an [`ExecContext`] and call [`ExecContext::exec`].
+//! Both apply the configured session policy: a protocol-mode request under
+//! `agent.mode = "acp"` enforces the `[agent.acp]` trust boundary.
 
 use bollard::Docker;
 use serde::{Deserialize, Serialize};
 
 use crate::config::AppConfig;
-use crate::engine::{ContainerExecClient, EngineConnector, SocketResolver};
+use crate::engine::{AgentEnv, ContainerExecClient, EngineConnector, SocketResolver};
 use crate::error::{ConfigError, PodbotError, Result as PodbotResult};
 
 use super::CommandOutcome;
@@ -178,6 +180,7 @@ const fn normalized_tty(mode: ExecMode, tty: bool) -> bool {
 pub struct ExecContext {
     connector: Docker,
     runtime_handle: tokio::runtime::Handle,
+    config: AppConfig,
 }
 
 impl ExecContext {
@@ -198,16 +201,19 @@ impl ExecContext {
         Ok(Self {
             connector,
             runtime_handle: runtime_handle.clone(),
+            config: config.clone(),
         })
     }
 
-    /// Execute a validated request using the cached connector and runtime.
+    /// Execute a validated request using the cached connector and runtime,
+    /// applying the session policy of the configuration the context was
+    /// connected with.
     ///
     /// # Errors
     ///
     /// Returns the same engine execution errors as [`exec`].
     pub fn exec(&self, request: &ExecRequest) -> PodbotResult<CommandOutcome> {
-        exec_with_client(&self.connector, &self.runtime_handle, request)
+        exec_with_client_and_config(&self.connector, &self.runtime_handle, &self.config, request)
     }
 }
 
@@ -224,6 +230,8 @@ impl ExecContext {
 /// - [`ExecContext::connect`] may return connection-related failures while
 ///   resolving and opening the engine client for the per-call context
 /// - `ContainerError::ExecFailed` if command execution fails.
+/// - `FilesystemError::IoError` if an ACP session's audit log or served
+///   workspace cannot be opened.
 ///
 /// # Examples
 ///
@@ -252,25 +260,67 @@ pub fn exec(config: &AppConfig, request: &ExecRequest) -> PodbotResult<CommandOu
 /// # Errors
 ///
 /// Returns the same engine execution and request-conversion errors as [`exec`].
+#[cfg(any(feature = "internal", test))]
 pub(crate) fn exec_with_client<C: ContainerExecClient + Sync>(
     connector: &C,
     runtime_handle: &tokio::runtime::Handle,
     request: &ExecRequest,
 ) -> PodbotResult<CommandOutcome> {
-    let engine_request = crate::engine::ExecRequest::new(
+    let exec_result = EngineConnector::exec(runtime_handle, connector, &engine_request(request)?)?;
+    Ok(command_outcome(exec_result.exit_code()))
+}
+
+/// Execute a command using a pre-connected engine client and runtime handle,
+/// applying the session policy `config` selects.
+///
+/// # Errors
+///
+/// Returns the same engine execution and request-conversion errors as [`exec`].
+pub(crate) fn exec_with_client_and_config<C>(
+    connector: &C,
+    runtime_handle: &tokio::runtime::Handle,
+    config: &AppConfig,
+    request: &ExecRequest,
+) -> PodbotResult<CommandOutcome>
+where
+    C: ContainerExecClient + Clone + Send + Sync + 'static,
+{
+    let engine_request = agent_engine_request(&mockable::DefaultEnv::new(), config, request)?;
+    let exec_result =
+        EngineConnector::exec_with_config(runtime_handle, connector, config, &engine_request)?;
+    Ok(command_outcome(exec_result.exit_code()))
+}
+
+/// Convert `request` for the engine, adding the host variables
+/// `config.agent.env_allowlist` names as read from `env`.
+///
+/// # Errors
+///
+/// Returns `ConfigError::MissingRequired` when a required allowlisted
+/// variable is not set, and the same request-conversion errors as [`exec`].
+pub(super) fn agent_engine_request<E: mockable::Env>(
+    env: &E,
+    config: &AppConfig,
+    request: &ExecRequest,
+) -> PodbotResult<crate::engine::ExecRequest> {
+    let agent_env = AgentEnv::from_app_config(env, config)?;
+    Ok(engine_request(request)?.with_agent_env(&agent_env))
+}
+
+fn engine_request(request: &ExecRequest) -> PodbotResult<crate::engine::ExecRequest> {
+    Ok(crate::engine::ExecRequest::new(
         request.container(),
         request.command().to_vec(),
         request.mode().into(),
     )?
-    .with_tty(request.tty());
-    let exec_result = EngineConnector::exec(runtime_handle, connector, &engine_request)?;
+    .with_tty(request.tty()))
+}
 
-    if exec_result.exit_code() == 0 {
-        Ok(CommandOutcome::Success)
+const fn command_outcome(exit_code: i64) -> CommandOutcome {
+    if exit_code == 0 {
+        CommandOutcome::Success
     } else {
-        Ok(CommandOutcome::CommandExit {
-            code: exec_result.exit_code(),
-        })
+        CommandOutcome::CommandExit { code: exit_code }
     }
 }
 
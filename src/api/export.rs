@@ -0,0 +1,290 @@
+//! Workspace export orchestration.
+//!
+//! This module exposes `podbot export`, which copies the agent's work out of
+//! a running sandbox as a patch series, a Git bundle, or a tar archive so it
+//! can be reviewed on the host before anything is pushed to `GitHub`.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AppConfig, WorkspaceSource, default_host_mount_container_path};
+use crate::engine::{
+    ContainerDownloader, ContainerExecClient, EngineConnector, SocketResolver,
+    WorkspaceExportFormat, WorkspaceExportRequest,
+};
+use crate::error::{ConfigError, PodbotError, Result as PodbotResult};
+
+use super::{CommandOutcome, WorkspacePath};
+
+/// Revision used as the export base when none is supplied.
+pub const DEFAULT_EXPORT_BASE: &str = "@{upstream}";
+
+/// Output format for workspace exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ExportFormat {
+    /// Patch series produced by `git format-patch`.
+    Patch,
+    /// Git bundle produced by `git bundle create`.
+    Bundle,
+    /// Tar archive of the whole workspace directory.
+    Tar,
+}
+
+impl From<ExportFormat> for WorkspaceExportFormat {
+    fn from(value: ExportFormat) -> Self {
+        match value {
+            ExportFormat::Patch => Self::Patch,
+            ExportFormat::Bundle => Self::Bundle,
+            ExportFormat::Tar => Self::Tar,
+        }
+    }
+}
+
+/// Request to export a session workspace to a host file.
+///
+/// # Examples
+///
+/// ```rust
+/// use podbot::api::{ExportFormat, ExportRequest};
+///
+/// let request = ExportRequest::new("sandbox", ExportFormat::Patch, "review.patch")?
+///     .with_base("origin/main")?;
+/// assert_eq!(request.base(), "origin/main");
+/// # Ok::<(), podbot::error::PodbotError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportRequest {
+    container: String,
+    format: ExportFormat,
+    output: Utf8PathBuf,
+    base: String,
+}
+
+impl ExportRequest {
+    /// Build an export request using [`DEFAULT_EXPORT_BASE`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` when the container identifier
+    /// is blank and `ConfigError::InvalidValue` when `output` does not name a
+    /// file.
+    pub fn new(
+        container: impl Into<String>,
+        format: ExportFormat,
+        output: impl Into<Utf8PathBuf>,
+    ) -> PodbotResult<Self> {
+        let request = Self {
+            container: container.into(),
+            format,
+            output: output.into(),
+            base: String::from(DEFAULT_EXPORT_BASE),
+        };
+        if request.container.trim().is_empty() {
+            return Err(ConfigError::MissingRequired {
+                field: String::from("container"),
+            }
+            .into());
+        }
+        if request.output.file_name().is_none() {
+            return Err(invalid_export_value(
+                "export.out",
+                format!("'{}' does not name a file", request.output),
+            ));
+        }
+        Ok(request)
+    }
+
+    /// Return a copy of the request exporting commits since `base`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` when `base` is empty, contains
+    /// whitespace, or starts with `-`.
+    pub fn with_base(mut self, base: impl Into<String>) -> PodbotResult<Self> {
+        let value = base.into();
+        if value.is_empty() || value.starts_with('-') || value.chars().any(char::is_whitespace) {
+            return Err(invalid_export_value(
+                "export.base",
+                String::from("must be a revision without whitespace or a leading '-'"),
+            ));
+        }
+        self.base = value;
+        Ok(self)
+    }
+
+    /// Return the target container identifier.
+    #[must_use]
+    pub fn container(&self) -> &str {
+        &self.container
+    }
+
+    /// Return the requested export format.
+    #[must_use]
+    pub const fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    /// Return the host output path.
+    #[must_use]
+    pub fn output(&self) -> &Utf8Path {
+        &self.output
+    }
+
+    /// Return the revision the export starts from.
+    #[must_use]
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+}
+
+/// Export a session workspace to a host file.
+///
+/// The in-container workspace path comes from `config.workspace`: the mount
+/// target for host-mounted sessions and `base_dir` for cloned sessions.
+///
+/// # Errors
+///
+/// Returns runtime creation and engine connection failures,
+/// `FilesystemError` variants when the output directory cannot be opened,
+/// and the export errors documented on the engine export helper.
+pub fn export_workspace(
+    config: &AppConfig,
+    request: &ExportRequest,
+) -> PodbotResult<CommandOutcome> {
+    let runtime = super::create_runtime()?;
+    let env = mockable::DefaultEnv::new();
+    let resolver = SocketResolver::new(&env);
+    let connector =
+        EngineConnector::connect_with_fallback(config.engine_socket.as_deref(), &resolver)?;
+    export_workspace_with_client(&connector, runtime.handle(), config, request)
+}
+
+/// Export a workspace using a pre-connected engine client and runtime handle.
+///
+/// # Errors
+///
+/// Returns the same errors as [`export_workspace`] after connection.
+pub(crate) fn export_workspace_with_client<C>(
+    client: &C,
+    runtime_handle: &tokio::runtime::Handle,
+    config: &AppConfig,
+    request: &ExportRequest,
+) -> PodbotResult<CommandOutcome>
+where
+    C: ContainerExecClient + ContainerDownloader + Sync,
+{
+    let workspace_path = WorkspacePath::parse(workspace_container_path(config).as_str())?;
+    let output_dir = super::open_host_parent_dir(request.output())?;
+
+    let result = crate::engine::export_workspace(
+        runtime_handle,
+        client,
+        &WorkspaceExportRequest {
+            container_id: request.container(),
+            workspace_path: &workspace_path,
+            format: request.format().into(),
+            base_revision: request.base(),
+            output_dir: &output_dir,
+            output_path: request.output(),
+        },
+    )?;
+    tracing::debug!(
+        container = request.container(),
+        output = %request.output(),
+        bytes_written = result.bytes_written,
+        "workspace export completed"
+    );
+    Ok(CommandOutcome::Success)
+}
+
+fn workspace_container_path(config: &AppConfig) -> Utf8PathBuf {
+    match config.workspace.source {
+        WorkspaceSource::HostMount => config
+            .workspace
+            .container_path
+            .clone()
+            .unwrap_or_else(default_host_mount_container_path),
+        WorkspaceSource::GithubClone => config.workspace.base_dir.clone(),
+    }
+}
+
+fn invalid_export_value(field: &str, reason: String) -> PodbotError {
+    PodbotError::from(ConfigError::InvalidValue {
+        field: String::from(field),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for export request validation and workspace resolution.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn new_request_defaults_to_upstream_base() {
+        let request = ExportRequest::new("sandbox", ExportFormat::Bundle, "out/review.bundle")
+            .expect("request should be valid");
+
+        assert_eq!(request.base(), DEFAULT_EXPORT_BASE);
+        assert_eq!(request.output(), Utf8Path::new("out/review.bundle"));
+    }
+
+    #[rstest]
+    #[case::blank_container("  ", "review.patch", "container")]
+    #[case::directory_output("sandbox", "out/..", "export.out")]
+    fn new_request_rejects_invalid_values(
+        #[case] container: &str,
+        #[case] output: &str,
+        #[case] expected_field: &str,
+    ) {
+        let result = ExportRequest::new(container, ExportFormat::Patch, output);
+
+        assert!(
+            matches!(
+                &result,
+                Err(PodbotError::Config(
+                    ConfigError::MissingRequired { field } | ConfigError::InvalidValue { field, .. }
+                )) if field == expected_field
+            ),
+            "expected {expected_field} rejection, got {result:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::option_like("--output=/etc/passwd")]
+    #[case::whitespace("origin/main extra")]
+    fn with_base_rejects_unsafe_revisions(#[case] base: &str) {
+        let result = ExportRequest::new("sandbox", ExportFormat::Patch, "review.patch")
+            .and_then(|request| request.with_base(base));
+
+        assert!(
+            matches!(
+                &result,
+                Err(PodbotError::Config(ConfigError::InvalidValue { field, .. }))
+                    if field == "export.base"
+            ),
+            "expected export.base rejection, got {result:?}"
+        );
+    }
+
+    #[rstest]
+    fn workspace_path_follows_workspace_source() {
+        let mut config = AppConfig::default();
+        assert_eq!(workspace_container_path(&config), Utf8Path::new("/work"));
+
+        config.workspace.source = WorkspaceSource::HostMount;
+        assert_eq!(
+            workspace_container_path(&config),
+            Utf8Path::new("/workspace")
+        );
+
+        config.workspace.container_path = Some(Utf8PathBuf::from("/src"));
+        assert_eq!(workspace_container_path(&config), Utf8Path::new("/src"));
+    }
+}
@@ -2,9 +2,14 @@
 //!
 //! This module provides the stable public orchestration surface: [`exec`],
 //! [`ExecContext`], [`ExecRequest`], [`ExecMode`], [`RunRequest`], and
-//! [`CommandOutcome`]. Under `feature = "experimental"`, `run_agent` performs
-//! `GitHub` configuration and credential validation, while `stop_container`,
-//! `list_containers`, and `run_token_daemon` remain compatibility stubs.
+//! [`CommandOutcome`], plus [`SecretRegistry`] and [`RedactingMakeWriter`]
+//! for scrubbing secrets from diagnostics. Under `feature = "experimental"`,
+//! `run_agent` performs `GitHub` configuration and credential validation,
+//! `export_workspace` copies session work out as a patch, bundle, or tar
+//! archive, `copy_to_container` and `copy_from_container` move files in and
+//! out of a session, and `run_token_daemon` keeps installation tokens fresh
+//! for a session, `stop_container` revokes a session's installation token,
+//! and `list_containers` remains a compatibility stub.
 //!
 //! Internal-feature builds also expose additional compatibility helpers for
 //! Git identity configuration.
@@ -15,23 +20,44 @@
 
 #[cfg(any(feature = "internal", test))]
 mod configure_git_identity;
+#[cfg(feature = "experimental")]
+mod copy;
 mod exec;
+#[cfg(feature = "experimental")]
+mod export;
+#[cfg(feature = "experimental")]
+mod readiness;
+mod redaction;
 mod repository_clone;
 mod run;
+#[cfg(feature = "experimental")]
+mod session_teardown;
+#[cfg(feature = "experimental")]
+mod token_daemon;
 
 #[cfg(any(feature = "internal", test))]
 pub use configure_git_identity::{GitIdentityParams, configure_container_git_identity};
+#[cfg(feature = "experimental")]
+pub use copy::{CopyRequest, DEFAULT_COPY_MAX_BYTES, copy_from_container, copy_to_container};
 #[cfg(feature = "internal")]
 #[doc(hidden)]
 pub use exec::exec_with_client_for_tests;
 pub use exec::{ExecContext, ExecMode, ExecRequest, exec};
+#[cfg(feature = "experimental")]
+pub use export::{DEFAULT_EXPORT_BASE, ExportFormat, ExportRequest, export_workspace};
+pub use redaction::{
+    DEFAULT_KEY_PATTERNS, MIN_SECRET_LEN, REDACTED, RedactingMakeWriter, RedactingWriter,
+    SecretRegistry,
+};
 #[cfg(any(feature = "internal", test))]
 pub use repository_clone::AskpassPath;
 pub use repository_clone::{BranchName, RepositoryRef, WorkspacePath};
 pub use run::RunRequest;
+#[cfg(feature = "experimental")]
+pub use token_daemon::{TokenDaemonRequest, run_token_daemon};
 
 #[cfg(feature = "experimental")]
-use crate::config::AppConfig;
+use crate::config::{AppConfig, GitHubConfig};
 #[cfg(feature = "experimental")]
 use crate::error::ConfigError;
 use crate::error::Result as PodbotResult;
@@ -59,10 +85,12 @@ pub enum CommandOutcome {
 /// Run an AI agent in a sandboxed container.
 ///
 /// This orchestration entry point currently performs `GitHub` configuration
-/// and credential validation before the wider agent lifecycle exists. If any
-/// `GitHub` field is present in the supplied [`AppConfig`], `run_agent` calls
-/// `config.github.validate()` immediately to require a complete credential
-/// set. When both `app_id` and `private_key_path` are present, it then calls
+/// and credential validation before the wider agent lifecycle exists. The
+/// `GitHub` App is chosen by the repository owner with
+/// [`GitHubConfig::for_owner`](crate::config::GitHubConfig::for_owner). If any
+/// credential field of that App is present, `run_agent` calls `validate()`
+/// on it immediately to require a complete credential set. When both `app_id`
+/// and a private key source are present, it then calls
 /// `validate_agent_github_credentials` to confirm the key material can
 /// authenticate successfully.
 ///
@@ -71,18 +99,24 @@ pub enum CommandOutcome {
 /// Returns errors immediately when:
 /// - `request` does not identify a repository in `owner/name` format or uses
 ///   a branch name containing whitespace
-/// - `config.github.validate()` rejects a partial or invalid `GitHub`
-///   configuration in [`AppConfig`]
+/// - more than one `[[github.apps]]` entry matches the repository owner
+/// - `validate()` rejects a partial or invalid `GitHub` configuration for the
+///   selected App
 /// - `validate_agent_github_credentials` rejects the configured `app_id` or
-///   `private_key_path`
+///   private key
 ///
 /// These validation failures are real runtime behaviour, not placeholder
 /// errors deferred until the rest of the orchestration flow is implemented.
 #[cfg(feature = "experimental")]
 pub fn run_agent(config: &AppConfig, request: &RunRequest) -> PodbotResult<CommandOutcome> {
     validate_run_request_for_agent(request)?;
-    validate_github_config_for_run(config, request)?;
-    validate_configured_github_credentials(config, request)?;
+    let owner = request
+        .repository()
+        .split_once('/')
+        .map_or(request.repository(), |(owner, _)| owner);
+    let github = config.github.for_owner(owner)?;
+    validate_github_config_for_run(&github, request)?;
+    validate_configured_github_credentials(&github, request)?;
     Ok(CommandOutcome::Success)
 }
 
@@ -140,10 +174,10 @@ fn is_repository_segment(segment: &str) -> bool {
 }
 
 #[cfg(feature = "experimental")]
-fn validate_github_config_for_run(config: &AppConfig, request: &RunRequest) -> PodbotResult<()> {
-    if config.github.is_partially_configured() {
+fn validate_github_config_for_run(github: &GitHubConfig, request: &RunRequest) -> PodbotResult<()> {
+    if github.is_partially_configured() {
         debug_github_config_validation_performed(request);
-        config.github.validate()?;
+        github.validate()?;
     } else {
         debug_github_config_validation_skipped(request);
     }
@@ -152,15 +186,17 @@ fn validate_github_config_for_run(config: &AppConfig, request: &RunRequest) -> P
 
 #[cfg(feature = "experimental")]
 fn validate_configured_github_credentials(
-    config: &AppConfig,
+    github: &GitHubConfig,
     request: &RunRequest,
 ) -> PodbotResult<()> {
-    if let (Some(app_id), Some(private_key_path)) = (
-        config.github.app_id,
-        config.github.private_key_path.as_ref(),
-    ) {
+    if let (Some(app_id), true) = (github.app_id, github.has_private_key_source()) {
         debug_github_credential_validation_performed(request, app_id);
-        validate_agent_github_credentials(app_id, private_key_path)?;
+        validate_agent_github_credentials(
+            app_id,
+            &github.private_key_source()?,
+            github.private_key_passphrase_source()?.as_ref(),
+            github.api_base_url.as_deref(),
+        )?;
     } else {
         debug_github_credential_validation_skipped(request);
     }
@@ -224,29 +260,26 @@ pub fn list_containers() -> PodbotResult<CommandOutcome> {
 
 /// Stop a running container.
 ///
-/// # Errors
-///
-/// Will return errors when container stop is implemented.
-#[expect(
-    clippy::missing_const_for_fn,
-    reason = "FIXME(https://github.com/leynos/podbot/issues/51): stub is const-eligible but will gain runtime logic"
-)]
-#[cfg(feature = "experimental")]
-pub fn stop_container(_container: &str) -> PodbotResult<CommandOutcome> {
-    Ok(CommandOutcome::Success)
-}
-
-/// Run the token refresh daemon for a container.
+/// Stopping the container itself is not implemented yet. The session's
+/// installation token is torn down: when a token daemon left a runtime
+/// directory for `container`, the last token in it is revoked through the
+/// configured `github.api_base_url` and the directory is removed. The
+/// revocation outcome is logged as a warning, and a failed revocation does
+/// not fail the stop.
 ///
 /// # Errors
 ///
-/// Will return errors when the token daemon is implemented.
-#[expect(
-    clippy::missing_const_for_fn,
-    reason = "FIXME(https://github.com/leynos/podbot/issues/51): stub is const-eligible but will gain runtime logic"
-)]
+/// Returns `ContainerError::RuntimeCreationFailed` when the async runtime
+/// cannot be created, and `FilesystemError` variants when the runtime
+/// directory cannot be opened or removed.
 #[cfg(feature = "experimental")]
-pub fn run_token_daemon(_container_id: &str) -> PodbotResult<CommandOutcome> {
+pub fn stop_container(config: &AppConfig, container: &str) -> PodbotResult<CommandOutcome> {
+    create_runtime()?.block_on(session_teardown::teardown_container_token(
+        &mockable::DefaultEnv::new(),
+        config.github.api_base_url.as_deref(),
+        container,
+        None,
+    ))?;
     Ok(CommandOutcome::Success)
 }
 
@@ -261,9 +294,16 @@ fn create_runtime() -> PodbotResult<tokio::runtime::Runtime> {
 #[cfg(feature = "experimental")]
 fn validate_agent_github_credentials(
     app_id: u64,
-    private_key_path: &camino::Utf8Path,
+    private_key: &crate::config::PrivateKeySource,
+    passphrase: Option<&crate::config::PassphraseSource>,
+    api_base_url: Option<&str>,
 ) -> PodbotResult<()> {
-    validate_agent_github_credentials_on_scoped_thread(app_id, private_key_path)
+    validate_agent_github_credentials_on_scoped_thread(
+        app_id,
+        private_key,
+        passphrase,
+        api_base_url,
+    )
 }
 
 #[cfg(all(feature = "experimental", test))]
@@ -286,11 +326,20 @@ where
 #[cfg(feature = "experimental")]
 fn validate_agent_github_credentials_on_scoped_thread(
     app_id: u64,
-    private_key_path: &camino::Utf8Path,
+    private_key: &crate::config::PrivateKeySource,
+    passphrase: Option<&crate::config::PassphraseSource>,
+    api_base_url: Option<&str>,
 ) -> PodbotResult<()> {
     std::thread::scope(|scope| -> PodbotResult<()> {
         scope
-            .spawn(|| validate_agent_github_credentials_on_local_runtime(app_id, private_key_path))
+            .spawn(|| {
+                validate_agent_github_credentials_on_local_runtime(
+                    app_id,
+                    private_key,
+                    passphrase,
+                    api_base_url,
+                )
+            })
             .join()
             .map_err(|_| credential_validation_thread_panicked())?
     })
@@ -322,13 +371,17 @@ where
 #[cfg(feature = "experimental")]
 fn validate_agent_github_credentials_on_local_runtime(
     app_id: u64,
-    private_key_path: &camino::Utf8Path,
+    private_key: &crate::config::PrivateKeySource,
+    passphrase: Option<&crate::config::PassphraseSource>,
+    api_base_url: Option<&str>,
 ) -> PodbotResult<()> {
     let runtime = create_runtime()?;
     runtime
-        .block_on(crate::github::validate_app_credentials(
+        .block_on(crate::github::validate_app_credentials_from_source(
             app_id,
-            private_key_path,
+            private_key,
+            passphrase,
+            api_base_url,
         ))
         .map_err(crate::error::PodbotError::from)
 }
@@ -351,9 +404,34 @@ where
 #[cfg(feature = "experimental")]
 fn credential_validation_thread_panicked() -> crate::error::PodbotError {
     crate::error::PodbotError::from(crate::error::GitHubError::AuthenticationFailed {
+        kind: crate::error::GitHubFailureKind::Permanent,
         message: String::from("GitHub credential validation thread panicked"),
     })
 }
 
+/// Open the parent directory of a host path as a capability handle.
+///
+/// A bare file name resolves against the current working directory.
+#[cfg(feature = "experimental")]
+fn open_host_parent_dir(host_path: &camino::Utf8Path) -> PodbotResult<cap_std::fs_utf8::Dir> {
+    let parent = host_path
+        .parent()
+        .filter(|candidate| !candidate.as_str().is_empty())
+        .unwrap_or_else(|| camino::Utf8Path::new("."));
+    cap_std::fs_utf8::Dir::open_ambient_dir(parent, cap_std::ambient_authority()).map_err(|error| {
+        let path = parent.as_std_path().to_path_buf();
+        crate::error::PodbotError::from(match error.kind() {
+            std::io::ErrorKind::NotFound => crate::error::FilesystemError::NotFound { path },
+            std::io::ErrorKind::PermissionDenied => {
+                crate::error::FilesystemError::PermissionDenied { path }
+            }
+            _ => crate::error::FilesystemError::IoError {
+                path,
+                message: error.to_string(),
+            },
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests;
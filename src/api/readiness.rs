@@ -0,0 +1,180 @@
+//! Readiness notification for long-running commands under a service manager.
+//!
+//! When `NOTIFY_SOCKET` is set, as systemd does for `Type=notify` units,
+//! readiness is reported by sending `READY=1` as a single datagram to that
+//! socket. A leading `@` names a Linux abstract-namespace socket. Without
+//! `NOTIFY_SOCKET`, the token daemon writes an empty readiness marker into its
+//! runtime directory instead, which service managers and scripts can poll.
+
+use std::io;
+
+use crate::error::{FilesystemError, PodbotError, Result as PodbotResult};
+use crate::github::TokenRuntimeDir;
+
+const NOTIFY_SOCKET_ENV: &str = "NOTIFY_SOCKET";
+const READY_MESSAGE: &[u8] = b"READY=1";
+
+/// How readiness was reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Readiness {
+    /// `READY=1` was sent to the named notification socket.
+    NotifySocket(String),
+    /// The readiness marker was written into the runtime directory.
+    ReadyFile,
+}
+
+/// Report that the token daemon has written its first token.
+///
+/// # Errors
+///
+/// Returns `FilesystemError::IoError` when the notification datagram cannot
+/// be sent, and `FilesystemError` variants when the marker cannot be written.
+pub(super) fn notify_ready<E: mockable::Env>(
+    env: &E,
+    runtime_dir: &TokenRuntimeDir,
+) -> PodbotResult<Readiness> {
+    let notify_socket = env
+        .string(NOTIFY_SOCKET_ENV)
+        .filter(|value| cfg!(unix) && !value.trim().is_empty());
+    let Some(socket) = notify_socket else {
+        runtime_dir.write_ready_marker()?;
+        return Ok(Readiness::ReadyFile);
+    };
+
+    send_ready(&socket).map_err(|error| {
+        PodbotError::from(FilesystemError::IoError {
+            path: socket.clone().into(),
+            message: format!("failed to send readiness notification: {error}"),
+        })
+    })?;
+    Ok(Readiness::NotifySocket(socket))
+}
+
+#[cfg(unix)]
+fn send_ready(socket: &str) -> io::Result<()> {
+    let datagram = std::os::unix::net::UnixDatagram::unbound()?;
+    send_to_socket(&datagram, socket).map(drop)
+}
+
+#[cfg(target_os = "linux")]
+fn send_to_socket(datagram: &std::os::unix::net::UnixDatagram, socket: &str) -> io::Result<usize> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::SocketAddr;
+
+    match socket.strip_prefix('@') {
+        Some(name) => datagram.send_to_addr(
+            READY_MESSAGE,
+            &SocketAddr::from_abstract_name(name.as_bytes())?,
+        ),
+        None => datagram.send_to(READY_MESSAGE, socket),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn send_to_socket(datagram: &std::os::unix::net::UnixDatagram, socket: &str) -> io::Result<usize> {
+    datagram.send_to(READY_MESSAGE, socket)
+}
+
+#[cfg(not(unix))]
+fn send_ready(_socket: &str) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for readiness notification.
+
+    use camino::Utf8PathBuf;
+    use mockable::MockEnv;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    struct Fixture {
+        temp_dir: TempDir,
+        runtime_dir: TokenRuntimeDir,
+    }
+
+    #[fixture]
+    fn fixture() -> Fixture {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+            .expect("tempdir path should be valid UTF-8");
+        let runtime_dir = TokenRuntimeDir::create_in(&root, "sandbox-ready")
+            .expect("runtime directory should be created");
+        Fixture {
+            temp_dir,
+            runtime_dir,
+        }
+    }
+
+    fn env_with_notify_socket(socket: Option<String>) -> MockEnv {
+        let mut env = MockEnv::new();
+        env.expect_string()
+            .returning(move |key| socket.clone().filter(|_| key == NOTIFY_SOCKET_ENV));
+        env
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn notify_ready_sends_ready_datagram_to_notify_socket(fixture: Fixture) {
+        let socket_path = fixture.temp_dir.path().join("notify.sock");
+        let receiver = std::os::unix::net::UnixDatagram::bind(&socket_path)
+            .expect("notification socket should bind");
+        let socket = socket_path
+            .to_str()
+            .expect("socket path should be valid UTF-8")
+            .to_owned();
+        let env = env_with_notify_socket(Some(socket.clone()));
+
+        let readiness =
+            notify_ready(&env, &fixture.runtime_dir).expect("readiness should be reported");
+
+        let mut buffer = [0_u8; 64];
+        let received = receiver
+            .recv(&mut buffer)
+            .expect("notification should be received");
+        assert_eq!(buffer.get(..received), Some(READY_MESSAGE));
+        assert_eq!(readiness, Readiness::NotifySocket(socket));
+        assert!(!fixture.runtime_dir.ready_path().exists());
+    }
+
+    #[rstest]
+    #[case::unset(None)]
+    #[case::blank(Some(String::from(" ")))]
+    fn notify_ready_writes_marker_without_notify_socket(
+        fixture: Fixture,
+        #[case] socket: Option<String>,
+    ) {
+        let env = env_with_notify_socket(socket);
+
+        let readiness =
+            notify_ready(&env, &fixture.runtime_dir).expect("readiness should be reported");
+
+        assert_eq!(readiness, Readiness::ReadyFile);
+        assert!(fixture.runtime_dir.ready_path().is_file());
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn notify_ready_reports_unreachable_socket(fixture: Fixture) {
+        let missing = fixture.temp_dir.path().join("missing.sock");
+        let socket = missing
+            .to_str()
+            .expect("socket path should be valid UTF-8")
+            .to_owned();
+        let env = env_with_notify_socket(Some(socket));
+
+        let result = notify_ready(&env, &fixture.runtime_dir);
+
+        assert!(
+            matches!(
+                &result,
+                Err(PodbotError::Filesystem(FilesystemError::IoError { path, .. }))
+                    if *path == missing
+            ),
+            "expected notification failure, got {result:?}"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Redaction of values logged under sensitive keys.
+
+use std::ops::Range;
+
+use super::REDACTED;
+
+/// A case-insensitive key pattern in which `*` matches any run of characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct KeyPattern(String);
+
+impl KeyPattern {
+    pub(super) fn new(pattern: &str) -> Self {
+        Self(pattern.trim().to_ascii_lowercase())
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        let lowered = key.to_ascii_lowercase();
+        let mut segments = self.0.split('*');
+        let Some(remaining) = segments
+            .next()
+            .and_then(|first| lowered.strip_prefix(first))
+        else {
+            return false;
+        };
+        let rest: Vec<&str> = segments.collect();
+        match rest.split_last() {
+            None => remaining.is_empty(),
+            Some((last, middle)) => matches_segments(remaining, middle, last),
+        }
+    }
+}
+
+/// Match the part of a key after a pattern's first `*`: each of `middle` in
+/// order, then `last` as a suffix.
+fn matches_segments(key: &str, middle: &[&str], last: &str) -> bool {
+    let mut remaining = key;
+    for segment in middle {
+        let Some(rest) = remaining
+            .find(segment)
+            .and_then(|start| remaining.get(start + segment.len()..))
+        else {
+            return false;
+        };
+        remaining = rest;
+    }
+    remaining.ends_with(last)
+}
+
+/// Replace the value of every structured `key=value` field and JSON
+/// `"key": value` member in `text` whose key matches one of `patterns`.
+///
+/// Free text such as `token: rate limit exceeded` is left alone, because a
+/// bare `key:` is as likely to be prose as a field. Quoted values keep their
+/// quotes. Returns `None` when nothing matched.
+pub(super) fn redact_keyed_values(text: &str, patterns: &[KeyPattern]) -> Option<String> {
+    let mut output = String::new();
+    let mut copied = 0;
+    let mut search_from = 0;
+
+    while let Some(offset) = text
+        .get(search_from..)
+        .and_then(|rest| rest.find(['=', ':']))
+    {
+        let separator = search_from + offset;
+        search_from = separator + 1;
+        let Some(value) = keyed_value(text, separator, patterns) else {
+            continue;
+        };
+        output.push_str(text.get(copied..value.start).unwrap_or_default());
+        output.push_str(REDACTED);
+        copied = value.end;
+        search_from = search_from.max(value.end);
+    }
+
+    (copied > 0).then(|| {
+        output.push_str(text.get(copied..).unwrap_or_default());
+        output
+    })
+}
+
+/// Return the span of the value following the separator at `separator`
+/// when the key before it matches one of `patterns`.
+///
+/// An `=` follows a bare key, and a `:` only counts after a quoted JSON key.
+fn keyed_value(text: &str, separator: usize, patterns: &[KeyPattern]) -> Option<Range<usize>> {
+    let before = text.get(..separator)?;
+    let quoted = text.get(separator..=separator)? == ":";
+    let key_source = if quoted {
+        before.strip_suffix('"')?
+    } else {
+        before
+    };
+    let key_len = key_source
+        .chars()
+        .rev()
+        .take_while(|character| is_key_char(*character))
+        .count();
+    let key_start = key_source.len() - key_len;
+    let key = key_source.get(key_start..)?;
+    if key.is_empty()
+        || (quoted && !key_source.get(..key_start)?.ends_with('"'))
+        || !patterns.iter().any(|pattern| pattern.matches(key))
+    {
+        return None;
+    }
+
+    let after = text.get(separator + 1..)?;
+    let padding = after.len() - after.trim_start_matches(' ').len();
+    value_span(text, separator + 1 + padding)
+}
+
+/// Return the span of the value starting at `start`: the contents of a
+/// quoted string, or the run of characters up to the next delimiter.
+fn value_span(text: &str, start: usize) -> Option<Range<usize>> {
+    let rest = text.get(start..)?;
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let length = closing_quote(quoted)?;
+        return Some(start + 1..start + 1 + length);
+    }
+    let length = rest
+        .find(|character: char| {
+            character.is_whitespace()
+                || matches!(character, ',' | ';' | '&' | '"' | ')' | ']' | '}')
+        })
+        .unwrap_or(rest.len());
+    (length > 0).then_some(start..start + length)
+}
+
+/// Return the offset of the first unescaped `"` in `quoted`.
+fn closing_quote(quoted: &str) -> Option<usize> {
+    let mut escaped = false;
+    quoted.char_indices().find_map(|(index, character)| {
+        let closes = character == '"' && !escaped;
+        escaped = character == '\\' && !escaped;
+        closes.then_some(index)
+    })
+}
+
+const fn is_key_char(character: char) -> bool {
+    character.is_ascii_alphanumeric() || matches!(character, '_' | '-' | '.')
+}
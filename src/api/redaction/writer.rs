@@ -0,0 +1,97 @@
+//! `tracing_subscriber` writer that scrubs registered secrets.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use tracing_subscriber::fmt::MakeWriter;
+
+use super::SecretRegistry;
+
+/// A [`MakeWriter`] that redacts registered secrets from everything written
+/// through the writers it makes.
+///
+/// Wrap the writer given to a `tracing_subscriber::fmt` subscriber, for
+/// example `RedactingMakeWriter::new(std::io::stderr)`, to scrub every
+/// formatted event, whatever its format.
+#[derive(Debug)]
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    registry: Arc<SecretRegistry>,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    /// Wrap `inner`, redacting the values in [`SecretRegistry::global`].
+    #[must_use]
+    pub fn new(inner: M) -> Self {
+        Self::with_registry(inner, SecretRegistry::global())
+    }
+
+    /// Wrap `inner`, redacting the values in `registry`.
+    #[must_use]
+    pub const fn with_registry(inner: M, registry: Arc<SecretRegistry>) -> Self {
+        Self { inner, registry }
+    }
+}
+
+impl<'writer, M: MakeWriter<'writer>> MakeWriter<'writer> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'writer self) -> Self::Writer {
+        RedactingWriter::new(self.inner.make_writer(), Arc::clone(&self.registry))
+    }
+
+    fn make_writer_for(&'writer self, meta: &tracing::Metadata<'_>) -> Self::Writer {
+        RedactingWriter::new(self.inner.make_writer_for(meta), Arc::clone(&self.registry))
+    }
+}
+
+/// A writer that buffers output and redacts it on flush or drop.
+///
+/// `tracing_subscriber` makes one writer per event, so buffering until the
+/// writer is dropped redacts each event whole even when it is written in
+/// several pieces.
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    registry: Arc<SecretRegistry>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    /// Wrap `inner`, redacting the values in `registry`.
+    #[must_use]
+    pub const fn new(inner: W, registry: Arc<SecretRegistry>) -> Self {
+        Self {
+            inner,
+            registry,
+            pending: Vec::new(),
+        }
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let text = String::from_utf8_lossy(&pending);
+        self.inner.write_all(self.registry.redact(&text).as_bytes())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        // A failed write has nowhere to be reported, so the event is lost.
+        drop(self.flush());
+    }
+}
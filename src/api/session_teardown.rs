@@ -0,0 +1,96 @@
+//! Installation token teardown when a session ends.
+//!
+//! Installation tokens stay valid for up to an hour after they are issued, so
+//! ending a session revokes the last token written into its runtime directory
+//! through `DELETE /installation/token` and then deletes the directory. The
+//! token daemon tears down its own directory on exit, and `stop_container`
+//! tears down any directory a daemon left behind. Revocation is best effort: a
+//! failure never fails the teardown, because the token still expires on its
+//! own. Every attempt, successful or not, is logged at `warn` so it shows up
+//! under the CLI's default log filter. When the caller supplies a
+//! [`SessionEventSink`], each attempt is also reported as an
+//! [`AcpSessionEvent::TokenRevoked`].
+
+use secrecy::SecretString;
+
+use crate::engine::{AcpSessionEvent, SessionEventSink, TokenRevocation};
+use crate::error::{GitHubError, Result as PodbotResult};
+use crate::github::{TokenRuntimeDir, revoke_installation_token};
+
+/// Tear down the runtime directory a token daemon created for
+/// `container_id`, if one exists.
+///
+/// # Errors
+///
+/// Returns `FilesystemError` variants when the existing directory cannot be
+/// opened or removed.
+pub(super) async fn teardown_container_token<E: mockable::Env>(
+    env: &E,
+    api_base_url: Option<&str>,
+    container_id: &str,
+    events: Option<&SessionEventSink>,
+) -> PodbotResult<()> {
+    let Some(runtime_dir) = TokenRuntimeDir::open_existing(env, container_id)? else {
+        return Ok(());
+    };
+    teardown_session_token(&runtime_dir, api_base_url, container_id, events).await
+}
+
+/// Revoke the last token written to `runtime_dir`, if any, and remove the
+/// directory.
+///
+/// # Errors
+///
+/// Returns `FilesystemError` variants when the directory cannot be removed.
+pub(super) async fn teardown_session_token(
+    runtime_dir: &TokenRuntimeDir,
+    api_base_url: Option<&str>,
+    container_id: &str,
+    events: Option<&SessionEventSink>,
+) -> PodbotResult<()> {
+    if let Some(token) = last_written_token(runtime_dir, container_id) {
+        let result = revoke_installation_token(&token, api_base_url).await;
+        let revocation = TokenRevocation {
+            container_id: String::from(container_id),
+            revoked: result.is_ok(),
+        };
+        report_revocation(container_id, result);
+        if let Some(sink) = events {
+            sink.emit(AcpSessionEvent::TokenRevoked(revocation));
+        }
+    }
+    runtime_dir.remove()
+}
+
+fn last_written_token(runtime_dir: &TokenRuntimeDir, container_id: &str) -> Option<SecretString> {
+    runtime_dir
+        .read_token()
+        .inspect_err(|error| {
+            tracing::warn!(
+                container_id,
+                error = %error,
+                "could not read installation token for revocation"
+            );
+        })
+        .ok()
+        .flatten()
+}
+
+fn report_revocation(container_id: &str, result: Result<(), GitHubError>) {
+    match result {
+        Ok(()) => tracing::warn!(container_id, "revoked GitHub App installation token"),
+        Err(error) => warn_revocation_failed(container_id, &error),
+    }
+}
+
+fn warn_revocation_failed(container_id: &str, error: &GitHubError) {
+    tracing::warn!(
+        container_id,
+        error = %error,
+        "failed to revoke GitHub App installation token; it expires on its own"
+    );
+}
+
+#[cfg(test)]
+#[path = "session_teardown_tests.rs"]
+mod tests;
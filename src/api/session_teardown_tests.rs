@@ -0,0 +1,182 @@
+//! Unit tests for installation token teardown.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use camino::Utf8PathBuf;
+use mockable::MockEnv;
+use rstest::rstest;
+use tokio::sync::mpsc;
+
+use super::*;
+use crate::github::InstallationAccessToken;
+use crate::github::test_support::LocalApiStandIn;
+
+const CONTAINER_ID: &str = "sandbox-teardown";
+
+fn runtime_dir_with_token() -> (tempfile::TempDir, TokenRuntimeDir) {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("tempdir path should be valid UTF-8");
+    let runtime_dir = TokenRuntimeDir::create_in(&root, CONTAINER_ID)
+        .expect("runtime directory should be created");
+    let token = InstallationAccessToken::new(
+        String::from("ghs_fixture"),
+        SystemTime::now(),
+        Duration::from_secs(300),
+    )
+    .expect("token metadata should be representable");
+    runtime_dir
+        .write_token(&token)
+        .expect("token should be written");
+    (temp_dir, runtime_dir)
+}
+
+fn runtime_env(temp_dir: &tempfile::TempDir) -> MockEnv {
+    let root = temp_dir.path().to_string_lossy().into_owned();
+    let mut env = MockEnv::new();
+    env.expect_string()
+        .returning(move |key| (key == "XDG_RUNTIME_DIR").then(|| root.clone()));
+    env
+}
+
+#[rstest]
+#[case::revoked("204 No Content", true)]
+#[case::rejected("401 Unauthorized", false)]
+#[tokio::test]
+async fn teardown_revokes_the_token_reports_it_and_removes_the_directory(
+    #[case] status: &'static str,
+    #[case] revoked: bool,
+) {
+    let (_temp_dir, runtime_dir) = runtime_dir_with_token();
+    let stand_in = LocalApiStandIn::start(status)
+        .await
+        .expect("stand-in should start");
+    let (sender, mut receiver) = mpsc::channel(1);
+    let events = SessionEventSink::new(sender);
+
+    teardown_session_token(
+        &runtime_dir,
+        Some(stand_in.base_url()),
+        CONTAINER_ID,
+        Some(&events),
+    )
+    .await
+    .expect("teardown should succeed even when revocation fails");
+
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the revocation");
+    assert!(head.starts_with("DELETE /installation/token "), "{head}");
+    assert_eq!(
+        receiver.try_recv().ok(),
+        Some(AcpSessionEvent::TokenRevoked(TokenRevocation {
+            container_id: String::from(CONTAINER_ID),
+            revoked,
+        }))
+    );
+    assert!(!runtime_dir.path().exists(), "runtime directory is removed");
+}
+
+#[rstest]
+#[case::revoked("204 No Content", "revoked GitHub App installation token")]
+#[case::rejected("401 Unauthorized", "failed to revoke GitHub App installation token")]
+#[tokio::test]
+async fn revocation_outcome_is_logged_at_warn(
+    #[case] status: &'static str,
+    #[case] expected: &str,
+) {
+    let (_temp_dir, runtime_dir) = runtime_dir_with_token();
+    let stand_in = LocalApiStandIn::start(status)
+        .await
+        .expect("stand-in should start");
+    let logs = WarnLogs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(logs.clone())
+        .finish();
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    teardown_session_token(&runtime_dir, Some(stand_in.base_url()), CONTAINER_ID, None)
+        .await
+        .expect("teardown should succeed even when revocation fails");
+
+    let text = logs.text();
+    assert!(text.contains(expected), "expected {expected:?} in {text:?}");
+    assert!(text.contains(CONTAINER_ID), "{text}");
+}
+
+/// Log lines captured from a test subscriber.
+#[derive(Clone, Default)]
+struct WarnLogs {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl WarnLogs {
+    fn text(&self) -> String {
+        let bytes = self
+            .buffer
+            .lock()
+            .expect("log buffer lock should succeed")
+            .clone();
+        String::from_utf8(bytes).expect("logs should be UTF-8")
+    }
+}
+
+impl<'writer> tracing_subscriber::fmt::MakeWriter<'writer> for WarnLogs {
+    type Writer = Self;
+
+    fn make_writer(&'writer self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl std::io::Write for WarnLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer
+            .lock()
+            .map_err(|error| std::io::Error::other(format!("log buffer poisoned: {error}")))?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn container_teardown_removes_a_directory_left_by_a_daemon() {
+    let (temp_dir, runtime_dir) = runtime_dir_with_token();
+    let stand_in = LocalApiStandIn::start("204 No Content")
+        .await
+        .expect("stand-in should start");
+
+    teardown_container_token(
+        &runtime_env(&temp_dir),
+        Some(stand_in.base_url()),
+        CONTAINER_ID,
+        None,
+    )
+    .await
+    .expect("teardown should succeed");
+
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the revocation");
+    assert!(head.starts_with("DELETE /installation/token "), "{head}");
+    assert!(!runtime_dir.path().exists(), "runtime directory is removed");
+}
+
+#[tokio::test]
+async fn container_teardown_without_a_runtime_directory_does_nothing() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+
+    let result =
+        teardown_container_token(&runtime_env(&temp_dir), None, "sandbox-absent", None).await;
+
+    assert!(result.is_ok(), "expected no-op teardown, got {result:?}");
+}
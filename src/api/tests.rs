@@ -9,9 +9,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use super::{CommandOutcome, RunRequest};
 #[cfg(feature = "experimental")]
-use super::{list_containers, run_agent, run_token_daemon, stop_container};
+use super::{list_containers, run_agent, stop_container};
 #[cfg(feature = "experimental")]
-use crate::config::{AppConfig, GitHubConfig};
+use crate::config::{AppConfig, GitHubAppProfile, GitHubConfig};
 #[cfg(feature = "experimental")]
 use crate::error::{ConfigError, PodbotError};
 #[cfg(feature = "experimental")]
@@ -176,6 +176,7 @@ fn run_agent_requires_complete_github_config() {
             app_id: Some(1),
             installation_id: None,
             private_key_path: Some(Utf8PathBuf::from("/tmp/test-key.pem")),
+            ..Default::default()
         },
         ..AppConfig::default()
     };
@@ -192,6 +193,40 @@ fn run_agent_requires_complete_github_config() {
     ));
 }
 
+#[rstest]
+#[cfg(feature = "experimental")]
+#[case::matching_owner("acme-labs/widget", true)]
+#[case::other_owner("leynos/podbot", false)]
+fn run_agent_validates_the_app_for_the_repository_owner(
+    #[case] repository: &str,
+    #[case] expect_rejected: bool,
+) {
+    let config = AppConfig {
+        github: GitHubConfig {
+            apps: vec![GitHubAppProfile {
+                owner: String::from("acme-*"),
+                app_id: Some(1),
+                ..GitHubAppProfile::default()
+            }],
+            ..Default::default()
+        },
+        ..AppConfig::default()
+    };
+    let request = RunRequest::new(repository, "main").expect("request should be valid");
+
+    let result = run_agent(&config, &request);
+
+    assert_eq!(
+        matches!(
+            result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { ref field }))
+                if field.contains("github.private_key_path")
+        ),
+        expect_rejected,
+        "unexpected result for {repository}: {result:?}"
+    );
+}
+
 #[rstest]
 #[cfg(feature = "experimental")]
 #[case::missing_owner_separator("owner-only", "main", "run.repository")]
@@ -272,7 +307,7 @@ fn credential_validation_thread_panic_maps_to_github_error() {
 
     assert!(matches!(
         error,
-        PodbotError::GitHub(crate::error::GitHubError::AuthenticationFailed { message })
+        PodbotError::GitHub(crate::error::GitHubError::AuthenticationFailed { message, .. })
             if message == "GitHub credential validation thread panicked"
     ));
 }
@@ -342,7 +377,7 @@ fn credential_validation_scoped_thread_panic_maps_to_github_error() {
 
     assert!(matches!(
         error,
-        PodbotError::GitHub(crate::error::GitHubError::AuthenticationFailed { message })
+        PodbotError::GitHub(crate::error::GitHubError::AuthenticationFailed { message, .. })
             if message == "GitHub credential validation thread panicked"
     ));
 }
@@ -351,7 +386,6 @@ fn credential_validation_scoped_thread_panic_maps_to_github_error() {
 #[case::run_agent("run_agent")]
 #[case::list_containers("list_containers")]
 #[case::stop_container("stop_container")]
-#[case::run_token_daemon("run_token_daemon")]
 #[cfg(feature = "experimental")]
 fn stub_returns_success(#[case] stub: &str) {
     let config = AppConfig::default();
@@ -359,8 +393,7 @@ fn stub_returns_success(#[case] stub: &str) {
     let outcome = match stub {
         "run_agent" => run_agent(&config, &request),
         "list_containers" => list_containers(),
-        "stop_container" => stop_container("test-container"),
-        "run_token_daemon" => run_token_daemon("test-container-id"),
+        "stop_container" => stop_container(&config, "test-container"),
         other => panic!("unknown stub: {other}"),
     }
     .expect("stub should return Ok");
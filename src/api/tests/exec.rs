@@ -7,14 +7,16 @@
 
 use bollard::container::LogOutput;
 use futures_util::stream;
+use mockable::MockEnv;
 use mockall::mock;
 use rstest::{fixture, rstest};
 
-use super::super::exec::exec_with_client;
+use super::super::exec::{agent_engine_request, exec_with_client};
 use super::super::{CommandOutcome, ExecMode, ExecRequest};
+use crate::config::AppConfig;
 use crate::engine::{
-    ContainerExecClient, CreateExecFuture, ExecMode as EngineExecMode, InspectExecFuture,
-    ResizeExecFuture, StartExecFuture,
+    ContainerExecClient, CreateExecFuture, EngineConnector, ExecMode as EngineExecMode,
+    InspectExecFuture, ResizeExecFuture, StartExecFuture,
 };
 use crate::error::{ConfigError, PodbotError};
 
@@ -150,6 +152,80 @@ fn exec_with_client_maps_exit_code_to_outcome(
     assert_eq!(outcome, expected);
 }
 
+fn allowlist_config(entries: &[&str]) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.agent.env_allowlist = entries.iter().copied().map(String::from).collect();
+    config
+}
+
+fn host_env(name: &'static str, value: &'static str) -> MockEnv {
+    let mut env = MockEnv::new();
+    env.expect_string()
+        .returning(move |key| (key == name).then(|| String::from(value)));
+    env
+}
+
+#[rstest]
+fn agent_exec_passes_allowlisted_host_variables_to_create_exec() {
+    let base_request =
+        ExecRequest::new("sandbox", vec![String::from("env")]).expect("request should be valid");
+    let request = base_request.with_mode(ExecMode::Detached);
+    let config = allowlist_config(&["API_KEY!", "UNSET"]);
+    let engine_request = agent_engine_request(&host_env("API_KEY", "sk-host"), &config, &request)
+        .expect("required variable is set");
+    let runtime = tokio::runtime::Runtime::new().expect("runtime should be created");
+    let mut client = MockApiExecClient::new();
+    client
+        .expect_create_exec()
+        .withf(|_, options| options.env == Some(vec![String::from("API_KEY=sk-host")]))
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                Ok(bollard::exec::CreateExecResults {
+                    id: String::from("api-exec-id"),
+                })
+            })
+        });
+    client
+        .expect_start_exec()
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(bollard::exec::StartExecResults::Detached) }));
+    client.expect_resize_exec().never();
+    client.expect_inspect_exec().times(1).returning(|_| {
+        let inspect = bollard::models::ExecInspectResponse {
+            running: Some(false),
+            exit_code: Some(0),
+            ..bollard::models::ExecInspectResponse::default()
+        };
+        Box::pin(async move { Ok(inspect) })
+    });
+
+    let result = EngineConnector::exec(runtime.handle(), &client, &engine_request)
+        .expect("exec should succeed");
+
+    assert_eq!(result.exit_code(), 0);
+}
+
+#[rstest]
+fn agent_exec_requires_required_allowlisted_variables() {
+    let request =
+        ExecRequest::new("sandbox", vec![String::from("env")]).expect("request should be valid");
+    let config = allowlist_config(&["API_KEY!"]);
+    let mut env = MockEnv::new();
+    env.expect_string().returning(|_| None);
+
+    let result = agent_engine_request(&env, &config, &request);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field.contains("API_KEY")
+        ),
+        "expected the missing required variable, got: {result:?}"
+    );
+}
+
 #[rstest]
 #[case(r#"{"container":"   ","command":["echo"]}"#, "container")]
 #[case(r#"{"container":"sandbox","command":[]}"#, "command")]
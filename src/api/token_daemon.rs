@@ -0,0 +1,532 @@
+//! Token daemon orchestration for a running session.
+//!
+//! The daemon acquires a GitHub App installation token on the host and writes
+//! it into the runtime directory keyed by the container's name, from which the
+//! sandbox reads it at `/run/secrets/ghapp_token` through a read-only bind
+//! mount. Tokens are limited to the configured `github.token_permissions` and,
+//! when the request names one, to a single repository, whose owner also
+//! selects the App from `[[github.apps]]`. Without a configured installation,
+//! it is looked up from that repository. The daemon then reports readiness
+//! and keeps the token fresh until the container is removed or the daemon
+//! receives `SIGTERM` or `SIGINT`. However the daemon exits, including on an
+//! error after the runtime directory was created, it revokes the last token it
+//! wrote and deletes the runtime directory.
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::time::Duration;
+
+use bollard::Docker;
+use bollard::query_parameters::InspectContainerOptions;
+use mockable::DefaultClock;
+
+use crate::config::{AppConfig, PassphraseSource, PrivateKeySource};
+use crate::engine::{EngineConnector, SocketResolver};
+use crate::error::{ConfigError, ContainerError, GitHubError, PodbotError, Result as PodbotResult};
+use crate::github::{
+    BoxFuture, ContainerLiveness, GitHubInstallationTokenClient, InstallationAccessToken,
+    InstallationResolver, InstallationTokenScope, OctocrabAppClient, RefreshLoopExit,
+    RefreshRetryPolicy, TokenRefreshLoop, TokenRuntimeDir, TokioRefreshSleeper,
+    acquire_scoped_installation_token_with_client, build_app_client_with_base_uri,
+    load_private_key_off_runtime, random_jitter,
+};
+
+use super::readiness::notify_ready;
+use super::session_teardown::teardown_session_token;
+use super::{CommandOutcome, RepositoryRef};
+
+/// Refresh buffer applied before the installation token's expiry.
+const TOKEN_EXPIRY_BUFFER: Duration = Duration::from_secs(5 * 60);
+
+/// Request to run the token daemon for a container.
+///
+/// # Examples
+///
+/// ```rust
+/// use podbot::api::{RepositoryRef, TokenDaemonRequest};
+///
+/// let request = TokenDaemonRequest::new("sandbox")
+///     .with_repository(RepositoryRef::parse("leynos/podbot")?);
+/// assert_eq!(request.container_id(), "sandbox");
+/// assert_eq!(request.repository().map(RepositoryRef::name), Some("podbot"));
+/// # Ok::<(), podbot::error::PodbotError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDaemonRequest {
+    container_id: String,
+    repository: Option<RepositoryRef>,
+}
+
+impl TokenDaemonRequest {
+    /// Build a request whose tokens cover every repository the installation
+    /// can access.
+    #[must_use]
+    pub fn new(container_id: impl Into<String>) -> Self {
+        Self {
+            container_id: container_id.into(),
+            repository: None,
+        }
+    }
+
+    /// Return a copy of the request whose tokens are limited to `repository`.
+    #[must_use]
+    pub fn with_repository(mut self, repository: RepositoryRef) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Return the target container's name, which keys its runtime directory.
+    #[must_use]
+    pub fn container_id(&self) -> &str {
+        &self.container_id
+    }
+
+    /// Return the repository tokens are limited to, if any.
+    #[must_use]
+    pub const fn repository(&self) -> Option<&RepositoryRef> {
+        self.repository.as_ref()
+    }
+
+    /// Return the scope requested for each installation token.
+    fn token_scope(&self, config: &AppConfig) -> InstallationTokenScope {
+        let scope =
+            InstallationTokenScope::default().with_permissions(&config.github.token_permissions);
+        match &self.repository {
+            Some(repository) => scope.with_repository(repository.name()),
+            None => scope,
+        }
+    }
+}
+
+/// Run the token daemon for a container.
+///
+/// Creates `$XDG_RUNTIME_DIR/podbot/<container_name>/`, acquires an installation
+/// token for the configured GitHub App limited to the request's repository and
+/// the configured `github.token_permissions`, and atomically writes it to
+/// `secrets/ghapp_token` in that directory. After the first write it reports
+/// readiness through `NOTIFY_SOCKET` when set, or by writing a `ready` marker
+/// into the runtime directory otherwise. The token is then refreshed before
+/// each expiry until the container no longer exists or `SIGTERM` or `SIGINT`
+/// arrives. On every exit after the runtime directory is created, including
+/// errors, the last written token is revoked and the runtime directory is
+/// removed before returning; a failed revocation is logged as a warning and
+/// does not fail the daemon.
+///
+/// # Errors
+///
+/// Returns `ConfigError::MissingRequired` when `GitHub` credentials or
+/// `XDG_RUNTIME_DIR` are not configured, or when `github.installation_id` is
+/// unset and the request names no repository, `ConfigError::InvalidValue` when the
+/// container name is not a single path segment, engine connection
+/// failures, `GitHubError` variants when a token cannot be acquired or a
+/// refresh fails permanently, `ContainerError::RuntimeCreationFailed` when
+/// signal handlers cannot be installed, and `FilesystemError` variants when
+/// the runtime directory, token file, or readiness notification cannot be
+/// written.
+pub fn run_token_daemon(
+    config: &AppConfig,
+    request: &TokenDaemonRequest,
+) -> PodbotResult<CommandOutcome> {
+    let credentials = GitHubAppCredentials::from_config(config, request)?;
+    let container_id = request.container_id();
+    let runtime = super::create_runtime()?;
+    let runtime_dir = DaemonRuntimeDir {
+        dir: TokenRuntimeDir::create(&mockable::DefaultEnv::new(), container_id)?,
+        api_base_url: config.github.api_base_url.as_deref(),
+        container_id,
+        closed: false,
+    };
+
+    let stop = runtime.block_on(async {
+        let served = serve_tokens(config, request, &credentials, &runtime_dir).await;
+        let closed = runtime_dir.close().await;
+        let stop = served?;
+        closed?;
+        Ok::<_, PodbotError>(stop)
+    })?;
+    tracing::info!(container_id, ?stop, "token daemon stopped");
+    Ok(CommandOutcome::Success)
+}
+
+/// Acquire, publish, and refresh tokens in `guard` until the daemon should
+/// stop.
+///
+/// Every error, including a readiness failure after the first token write, is
+/// returned to [`run_token_daemon`], which then closes `guard`, so no failure
+/// here can leave a token behind.
+async fn serve_tokens(
+    config: &AppConfig,
+    request: &TokenDaemonRequest,
+    credentials: &GitHubAppCredentials,
+    guard: &DaemonRuntimeDir<'_>,
+) -> PodbotResult<DaemonStop> {
+    let container_id = request.container_id();
+    let runtime_dir = &guard.dir;
+    let scope = request.token_scope(config);
+    let env = mockable::DefaultEnv::new();
+    // Register handlers first so a signal during the initial write is held
+    // until the write has finished.
+    let shutdown = shutdown_signal()?;
+    let docker = EngineConnector::connect_with_fallback(
+        config.engine_socket.as_deref(),
+        &SocketResolver::new(&env),
+    )?;
+    let client = credentials
+        .app_client(config.github.api_base_url.as_deref())
+        .await?;
+    let resolver = InstallationResolver::new(&client);
+    let installation_id =
+        installation_id_for(&resolver, credentials.installation_id, request.repository()).await?;
+    let first_write = write_installation_token(&client, runtime_dir, installation_id, &scope);
+    let token = publish_first_token(&env, guard, first_write).await?;
+
+    let liveness = DockerContainerLiveness {
+        docker: &docker,
+        container_id,
+    };
+    let gated = GatedTokenClient::new(&client);
+    let refresh_loop = TokenRefreshLoop {
+        client: &gated,
+        installation_id,
+        scope: &scope,
+        expiry_buffer: TOKEN_EXPIRY_BUFFER,
+        runtime_dir,
+        clock: &DefaultClock,
+        sleeper: &TokioRefreshSleeper,
+        liveness: &liveness,
+        rate_limits: client.rate_limits(),
+        jitter: random_jitter,
+        policy: RefreshRetryPolicy::default(),
+    };
+    until_shutdown(refresh_loop.run(token), shutdown, gated.idle()).await
+}
+
+/// Write the first token into `guard`, then report readiness.
+///
+/// A readiness failure is returned after a valid token has been written, so
+/// the caller must close `guard` on error, as [`run_token_daemon`] does, to
+/// revoke that token and remove the runtime directory.
+async fn publish_first_token<E: mockable::Env>(
+    env: &E,
+    guard: &DaemonRuntimeDir<'_>,
+    first_write: impl Future<Output = PodbotResult<InstallationAccessToken>>,
+) -> PodbotResult<InstallationAccessToken> {
+    let token = first_write.await?;
+    let readiness = notify_ready(env, &guard.dir)?;
+    tracing::info!(
+        container_id = guard.container_id,
+        ?readiness,
+        "token daemon ready"
+    );
+    Ok(token)
+}
+
+/// The daemon's runtime directory, cleaned up on every exit path.
+///
+/// [`Self::close`] revokes the last written token and removes the directory.
+/// A guard dropped without being closed, which only happens while unwinding,
+/// still removes the directory so the token file does not outlive the daemon.
+struct DaemonRuntimeDir<'a> {
+    dir: TokenRuntimeDir,
+    api_base_url: Option<&'a str>,
+    container_id: &'a str,
+    closed: bool,
+}
+
+impl DaemonRuntimeDir<'_> {
+    /// Revoke the last written token, if any, and remove the directory.
+    async fn close(mut self) -> PodbotResult<()> {
+        self.closed = true;
+        teardown_session_token(&self.dir, self.api_base_url, self.container_id, None).await
+    }
+}
+
+impl Drop for DaemonRuntimeDir<'_> {
+    fn drop(&mut self) {
+        if !self.closed {
+            let removed = self.dir.remove();
+            removed.unwrap_or_else(|error| warn_removal_failed(self.container_id, &error));
+        }
+    }
+}
+
+fn warn_removal_failed(container_id: &str, error: &PodbotError) {
+    tracing::warn!(
+        container_id,
+        error = %error,
+        "failed to remove token runtime directory"
+    );
+}
+
+/// Reason the token daemon stopped without an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DaemonStop {
+    /// The target container no longer exists.
+    ContainerGone,
+    /// The named shutdown signal was received.
+    Signal(&'static str),
+}
+
+/// Run the refresh loop until it ends or a shutdown signal arrives.
+///
+/// A signal is honoured only once no token acquisition is in flight:
+/// `acquisitions_idle` resolves when [`GatedTokenClient`] holds no
+/// acquisition, and until then the refresh loop keeps running so a token
+/// GitHub has already minted is written, and later revoked, rather than
+/// dropped with the loop. Token writes are synchronous and follow the
+/// acquisition within the same poll, so a write is never interrupted either.
+#[expect(
+    clippy::integer_division_remainder_used,
+    reason = "false positive triggered inside tokio::select! expansion"
+)]
+async fn until_shutdown(
+    refresh: impl Future<Output = PodbotResult<RefreshLoopExit>>,
+    shutdown: impl Future<Output = &'static str>,
+    acquisitions_idle: impl Future<Output = ()>,
+) -> PodbotResult<DaemonStop> {
+    let mut pinned = std::pin::pin!(refresh);
+    let signal = tokio::select! {
+        biased;
+        exit = &mut pinned => return exit.map(DaemonStop::from),
+        signal = shutdown => signal,
+    };
+    tokio::select! {
+        biased;
+        exit = pinned => exit.map(DaemonStop::from),
+        () = acquisitions_idle => Ok(DaemonStop::Signal(signal)),
+    }
+}
+
+impl From<RefreshLoopExit> for DaemonStop {
+    fn from(exit: RefreshLoopExit) -> Self {
+        match exit {
+            RefreshLoopExit::ContainerGone => Self::ContainerGone,
+        }
+    }
+}
+
+/// Token client that records when an acquisition is in flight.
+///
+/// The daemon's shutdown path waits on [`Self::idle`] so it never drops an
+/// acquisition whose token GitHub may already have minted.
+struct GatedTokenClient<'a> {
+    inner: &'a dyn GitHubInstallationTokenClient,
+    in_flight: tokio::sync::Mutex<()>,
+}
+
+impl<'a> GatedTokenClient<'a> {
+    fn new(inner: &'a dyn GitHubInstallationTokenClient) -> Self {
+        Self {
+            inner,
+            in_flight: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Resolve once no acquisition is in flight.
+    async fn idle(&self) {
+        drop(self.in_flight.lock().await);
+    }
+}
+
+impl GitHubInstallationTokenClient for GatedTokenClient<'_> {
+    fn acquire_installation_token(
+        &self,
+        installation_id: u64,
+        expiry_buffer: Duration,
+    ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>> {
+        Box::pin(async move {
+            let _in_flight = self.in_flight.lock().await;
+            self.inner
+                .acquire_installation_token(installation_id, expiry_buffer)
+                .await
+        })
+    }
+
+    fn acquire_scoped_installation_token(
+        &self,
+        installation_id: u64,
+        scope: InstallationTokenScope,
+        expiry_buffer: Duration,
+    ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>> {
+        Box::pin(async move {
+            let _in_flight = self.in_flight.lock().await;
+            self.inner
+                .acquire_scoped_installation_token(installation_id, scope, expiry_buffer)
+                .await
+        })
+    }
+}
+
+#[cfg(unix)]
+#[expect(
+    clippy::integer_division_remainder_used,
+    reason = "false positive triggered inside tokio::select! expansion"
+)]
+fn shutdown_signal() -> PodbotResult<impl Future<Output = &'static str>> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = signal(SignalKind::terminate()).map_err(|error| signal_error(&error))?;
+    let mut interrupt = signal(SignalKind::interrupt()).map_err(|error| signal_error(&error))?;
+    Ok(async move {
+        tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = interrupt.recv() => "SIGINT",
+        }
+    })
+}
+
+#[cfg(not(unix))]
+#[expect(
+    clippy::unnecessary_wraps,
+    reason = "signature matches the Unix implementation"
+)]
+fn shutdown_signal() -> PodbotResult<impl Future<Output = &'static str>> {
+    Ok(async {
+        drop(tokio::signal::ctrl_c().await);
+        "Ctrl-C"
+    })
+}
+
+#[cfg(unix)]
+fn signal_error(error: &std::io::Error) -> PodbotError {
+    PodbotError::from(ContainerError::RuntimeCreationFailed {
+        message: format!("failed to install shutdown signal handler: {error}"),
+    })
+}
+
+/// Liveness probe that inspects the target container through the engine.
+struct DockerContainerLiveness<'a> {
+    docker: &'a Docker,
+    container_id: &'a str,
+}
+
+impl ContainerLiveness for DockerContainerLiveness<'_> {
+    fn container_exists(&self) -> BoxFuture<'_, bool> {
+        Box::pin(async move {
+            let result = self
+                .docker
+                .inspect_container(self.container_id, None::<InspectContainerOptions>)
+                .await;
+            exists_from_inspect(self.container_id, result.map(drop))
+        })
+    }
+}
+
+/// Map an inspect result to container presence.
+///
+/// Only a 404 response means the container is gone; other engine failures are
+/// logged and treated as present so a transient outage does not stop refresh.
+fn exists_from_inspect(container_id: &str, result: Result<(), bollard::errors::Error>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => false,
+        Err(error) => {
+            tracing::warn!(
+                container_id,
+                error = %error,
+                "container liveness check failed; assuming container is present"
+            );
+            true
+        }
+    }
+}
+
+struct GitHubAppCredentials {
+    app_id: u64,
+    /// Configured installation, or `None` to look it up from the repository.
+    installation_id: Option<u64>,
+    private_key: PrivateKeySource,
+    passphrase: Option<PassphraseSource>,
+}
+
+impl GitHubAppCredentials {
+    /// Read the credentials of the App serving the request's repository
+    /// owner, or the top-level App when the request names no repository.
+    fn from_config(config: &AppConfig, request: &TokenDaemonRequest) -> PodbotResult<Self> {
+        let github = match request.repository() {
+            Some(repository) => config.github.for_owner(repository.owner())?,
+            None => Cow::Borrowed(&config.github),
+        };
+        if github.has_installation_id() || request.repository().is_none() {
+            github.validate()?;
+        } else {
+            github.validate_app()?;
+        }
+        let private_key = github.private_key_source()?;
+        let passphrase = github.private_key_passphrase_source()?;
+        github
+            .app_id
+            .map(|app_id| Self {
+                app_id,
+                installation_id: github.installation_id.filter(|id| *id != 0),
+                private_key,
+                passphrase,
+            })
+            .ok_or_else(|| {
+                ConfigError::MissingRequired {
+                    field: String::from("github.app_id"),
+                }
+                .into()
+            })
+    }
+}
+
+impl GitHubAppCredentials {
+    /// Load the App's private key off the runtime and build its API client.
+    async fn app_client(&self, api_base_url: Option<&str>) -> PodbotResult<OctocrabAppClient> {
+        let private_key =
+            load_private_key_off_runtime(&self.private_key, self.passphrase.as_ref()).await?;
+        Ok(OctocrabAppClient::new(build_app_client_with_base_uri(
+            self.app_id,
+            private_key,
+            api_base_url,
+        )?))
+    }
+}
+
+/// Return the configured installation, or look it up from `repository`
+/// through the daemon's `resolver`.
+async fn installation_id_for(
+    resolver: &InstallationResolver<'_>,
+    configured: Option<u64>,
+    repository: Option<&RepositoryRef>,
+) -> PodbotResult<u64> {
+    match (configured, repository) {
+        (Some(installation_id), _) => Ok(installation_id),
+        (None, Some(target)) => Ok(resolver.resolve(target.owner(), target.name()).await?),
+        (None, None) => Err(ConfigError::MissingRequired {
+            field: String::from("github.installation_id"),
+        }
+        .into()),
+    }
+}
+
+async fn write_installation_token(
+    client: &dyn GitHubInstallationTokenClient,
+    runtime_dir: &TokenRuntimeDir,
+    installation_id: u64,
+    scope: &InstallationTokenScope,
+) -> PodbotResult<InstallationAccessToken> {
+    let token = acquire_scoped_installation_token_with_client(
+        client,
+        installation_id,
+        scope,
+        TOKEN_EXPIRY_BUFFER,
+    )
+    .await?;
+    runtime_dir.write_token(&token)?;
+    tracing::debug!(
+        installation_id,
+        runtime_dir = %runtime_dir.path(),
+        refresh_after = ?token.refresh_after(),
+        granted_scope = ?token.scope(),
+        "installation token written"
+    );
+    Ok(token)
+}
+
+#[cfg(test)]
+#[path = "token_daemon_tests.rs"]
+mod tests;
@@ -0,0 +1,422 @@
+//! Unit tests for token daemon orchestration.
+
+use std::time::SystemTime;
+
+use camino::Utf8PathBuf;
+use rstest::rstest;
+use secrecy::ExposeSecret;
+
+use super::*;
+use crate::config::{GitHubAppProfile, TokenPermissionLevel};
+use crate::error::{GitHubError, GitHubFailureKind};
+use crate::github::test_support::LocalApiStandIn;
+use crate::github::{MockGitHubInstallationLookupClient, MockGitHubInstallationTokenClient};
+
+fn runtime_dir() -> (tempfile::TempDir, TokenRuntimeDir) {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("tempdir path should be valid UTF-8");
+    let runtime_dir = TokenRuntimeDir::create_in(&root, "sandbox-token")
+        .expect("runtime directory should be created");
+    (temp_dir, runtime_dir)
+}
+
+#[rstest]
+fn run_token_daemon_requires_github_configuration() {
+    let result = run_token_daemon(
+        &AppConfig::default(),
+        &TokenDaemonRequest::new("sandbox-token"),
+    );
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field.contains("github.app_id")
+        ),
+        "expected missing GitHub configuration, got {result:?}"
+    );
+}
+
+#[rstest]
+fn github_credentials_need_installation_id_only_without_repository() {
+    let mut config = AppConfig::default();
+    config.github.app_id = Some(1);
+    config.github.private_key_path = Some(Utf8PathBuf::from("/keys/app.pem"));
+    let repository = RepositoryRef::parse("leynos/podbot").expect("repository should parse");
+
+    let unscoped =
+        GitHubAppCredentials::from_config(&config, &TokenDaemonRequest::new("sandbox-token"));
+    let scoped = GitHubAppCredentials::from_config(
+        &config,
+        &TokenDaemonRequest::new("sandbox-token").with_repository(repository),
+    );
+
+    assert!(
+        matches!(
+            &unscoped,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field == "github.installation_id"
+        ),
+        "expected missing installation ID"
+    );
+    assert_eq!(
+        scoped.map(|credentials| credentials.installation_id).ok(),
+        Some(None)
+    );
+}
+
+fn write_fixture_token(runtime_dir: &TokenRuntimeDir) {
+    let token = InstallationAccessToken::new(
+        String::from("ghs_fixture"),
+        SystemTime::now(),
+        TOKEN_EXPIRY_BUFFER,
+    )
+    .expect("token metadata should be representable");
+    runtime_dir
+        .write_token(&token)
+        .expect("token should be written");
+}
+
+#[tokio::test]
+async fn closing_the_runtime_dir_revokes_the_token_and_removes_it() {
+    let (_temp_dir, dir) = runtime_dir();
+    write_fixture_token(&dir);
+    let path = dir.path().to_path_buf();
+    let stand_in = LocalApiStandIn::start("204 No Content")
+        .await
+        .expect("stand-in should start");
+    let guard = DaemonRuntimeDir {
+        dir,
+        api_base_url: Some(stand_in.base_url()),
+        container_id: "sandbox-token",
+        closed: false,
+    };
+
+    guard.close().await.expect("close should succeed");
+
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the revocation");
+    assert!(head.starts_with("DELETE /installation/token "), "{head}");
+    assert!(!path.exists(), "runtime directory should be removed");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn readiness_failure_after_the_first_write_is_cleaned_up_by_the_guard() {
+    let (temp_dir, dir) = runtime_dir();
+    let path = dir.path().to_path_buf();
+    let stand_in = LocalApiStandIn::start("204 No Content")
+        .await
+        .expect("stand-in should start");
+    let guard = DaemonRuntimeDir {
+        dir,
+        api_base_url: Some(stand_in.base_url()),
+        container_id: "sandbox-token",
+        closed: false,
+    };
+    let missing_socket = temp_dir.path().join("missing.sock");
+    let mut env = mockable::MockEnv::new();
+    env.expect_string()
+        .returning(move |_| missing_socket.to_str().map(String::from));
+    let first_write = async {
+        write_fixture_token(&guard.dir);
+        InstallationAccessToken::new(
+            String::from("ghs_fixture"),
+            SystemTime::now(),
+            TOKEN_EXPIRY_BUFFER,
+        )
+        .map_err(PodbotError::from)
+    };
+
+    let published = publish_first_token(&env, &guard, first_write).await;
+    assert!(
+        matches!(
+            &published,
+            Err(PodbotError::Filesystem(
+                crate::error::FilesystemError::IoError { .. }
+            ))
+        ),
+        "expected readiness failure, got {published:?}"
+    );
+    assert!(guard.dir.token_path().exists(), "first token was written");
+    guard.close().await.expect("close should succeed");
+
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the revocation");
+    assert!(head.starts_with("DELETE /installation/token "), "{head}");
+    assert!(!path.exists(), "runtime directory should be removed");
+}
+
+#[rstest]
+fn dropping_an_unclosed_runtime_dir_removes_it() {
+    let (_temp_dir, dir) = runtime_dir();
+    write_fixture_token(&dir);
+    let path = dir.path().to_path_buf();
+
+    drop(DaemonRuntimeDir {
+        dir,
+        api_base_url: None,
+        container_id: "sandbox-token",
+        closed: false,
+    });
+
+    assert!(!path.exists(), "runtime directory should be removed");
+}
+
+#[rstest]
+fn github_credentials_follow_the_repository_owner() {
+    let mut config = AppConfig::default();
+    config.github.app_id = Some(1);
+    config.github.installation_id = Some(10);
+    config.github.private_key_path = Some(Utf8PathBuf::from("/keys/app.pem"));
+    config.github.apps = vec![GitHubAppProfile {
+        owner: String::from("acme-*"),
+        app_id: Some(2),
+        private_key_env: Some(String::from("ACME_APP_KEY")),
+        ..GitHubAppProfile::default()
+    }];
+    let request = |repository: &str| {
+        TokenDaemonRequest::new("sandbox-token")
+            .with_repository(RepositoryRef::parse(repository).expect("repository should parse"))
+    };
+
+    let acme = GitHubAppCredentials::from_config(&config, &request("acme-labs/widget"))
+        .expect("matching profile should be complete");
+    let other = GitHubAppCredentials::from_config(&config, &request("leynos/podbot"))
+        .expect("top-level App should be complete");
+
+    assert_eq!(
+        (acme.app_id, acme.installation_id, acme.private_key),
+        (
+            2,
+            None,
+            PrivateKeySource::EnvVar(String::from("ACME_APP_KEY"))
+        )
+    );
+    assert_eq!((other.app_id, other.installation_id), (1, Some(10)));
+}
+
+#[tokio::test]
+async fn installation_id_for_prefers_configured_installation() {
+    let mut client = MockGitHubInstallationLookupClient::new();
+    client.expect_repository_installation_id().times(0);
+    let repository = RepositoryRef::parse("leynos/podbot").expect("repository should parse");
+
+    let installation_id = installation_id_for(
+        &InstallationResolver::new(&client),
+        Some(42),
+        Some(&repository),
+    )
+    .await;
+
+    assert_eq!(installation_id.ok(), Some(42));
+}
+
+#[tokio::test]
+async fn installation_id_for_looks_up_repository_installation() {
+    let mut client = MockGitHubInstallationLookupClient::new();
+    client
+        .expect_repository_installation_id()
+        .withf(|owner, name| owner == "leynos" && name == "podbot")
+        .times(1)
+        .returning(|_, _| Box::pin(std::future::ready(Ok(7))));
+    let repository = RepositoryRef::parse("leynos/podbot").expect("repository should parse");
+
+    let resolver = InstallationResolver::new(&client);
+
+    let first = installation_id_for(&resolver, None, Some(&repository)).await;
+    let second = installation_id_for(&resolver, None, Some(&repository)).await;
+
+    assert_eq!((first.ok(), second.ok()), (Some(7), Some(7)));
+}
+
+#[rstest]
+#[case::present(Ok(()), true)]
+#[case::removed(
+    Err(bollard::errors::Error::DockerResponseServerError {
+        status_code: 404,
+        message: String::from("No such container"),
+    }),
+    false
+)]
+#[case::engine_unavailable(
+    Err(bollard::errors::Error::DockerResponseServerError {
+        status_code: 500,
+        message: String::from("engine restarting"),
+    }),
+    true
+)]
+fn exists_from_inspect_treats_only_not_found_as_gone(
+    #[case] result: Result<(), bollard::errors::Error>,
+    #[case] expected: bool,
+) {
+    assert_eq!(exists_from_inspect("sandbox-token", result), expected);
+}
+
+#[tokio::test]
+async fn until_shutdown_stops_on_signal() {
+    let refresh = std::future::pending::<PodbotResult<RefreshLoopExit>>();
+
+    let stop = until_shutdown(
+        refresh,
+        std::future::ready("SIGTERM"),
+        std::future::ready(()),
+    )
+    .await;
+
+    assert_eq!(stop.ok(), Some(DaemonStop::Signal("SIGTERM")));
+}
+
+#[tokio::test]
+async fn until_shutdown_stops_when_container_is_gone() {
+    let refresh = std::future::ready(Ok(RefreshLoopExit::ContainerGone));
+
+    let stop = until_shutdown(refresh, std::future::pending(), std::future::ready(())).await;
+
+    assert_eq!(stop.ok(), Some(DaemonStop::ContainerGone));
+}
+
+#[tokio::test]
+async fn until_shutdown_propagates_refresh_failures() {
+    let refresh = std::future::ready(Err(PodbotError::from(GitHubError::TokenRefreshFailed {
+        kind: GitHubFailureKind::Permanent,
+        message: String::from("giving up"),
+    })));
+
+    let stop = until_shutdown(refresh, std::future::pending(), std::future::ready(())).await;
+
+    assert!(
+        matches!(
+            stop,
+            Err(PodbotError::GitHub(GitHubError::TokenRefreshFailed { .. }))
+        ),
+        "expected refresh failure, got {stop:?}"
+    );
+}
+
+#[tokio::test]
+async fn until_shutdown_writes_a_token_acquired_when_the_signal_arrives() {
+    let (_temp_dir, runtime_dir) = runtime_dir();
+    let mut inner = MockGitHubInstallationTokenClient::new();
+    inner
+        .expect_acquire_scoped_installation_token()
+        .times(1)
+        .returning(|_, _, buffer| {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                InstallationAccessToken::new(String::from("ghs_minted"), SystemTime::now(), buffer)
+            })
+        });
+    let client = GatedTokenClient::new(&inner);
+    let scope = InstallationTokenScope::default();
+    let refresh = async {
+        write_installation_token(&client, &runtime_dir, 42, &scope).await?;
+        std::future::pending().await
+    };
+
+    let stop = until_shutdown(refresh, std::future::ready("SIGTERM"), client.idle()).await;
+
+    assert_eq!(stop.ok(), Some(DaemonStop::Signal("SIGTERM")));
+    let written = runtime_dir.read_token().expect("token should be readable");
+    assert_eq!(
+        written.as_ref().map(ExposeSecret::expose_secret),
+        Some("ghs_minted")
+    );
+}
+
+#[tokio::test]
+async fn gated_client_is_idle_between_acquisitions() {
+    let inner = MockGitHubInstallationTokenClient::new();
+    let client = GatedTokenClient::new(&inner);
+
+    let idle = tokio::time::timeout(std::time::Duration::from_secs(1), client.idle()).await;
+
+    assert!(idle.is_ok(), "expected no acquisition in flight");
+}
+
+#[tokio::test]
+async fn write_installation_token_writes_acquired_token() {
+    let (_temp_dir, runtime_dir) = runtime_dir();
+    let mut client = MockGitHubInstallationTokenClient::new();
+    let scope = InstallationTokenScope::default().with_repository("podbot");
+    let expected_scope = scope.clone();
+    client
+        .expect_acquire_scoped_installation_token()
+        .withf(move |installation_id, requested, buffer| {
+            *installation_id == 42 && *requested == expected_scope && *buffer == TOKEN_EXPIRY_BUFFER
+        })
+        .times(1)
+        .returning(|_, requested, buffer| {
+            let token = InstallationAccessToken::new(
+                String::from("ghs_fixture"),
+                SystemTime::now(),
+                buffer,
+            )
+            .map(|token| token.with_scope(requested));
+            Box::pin(std::future::ready(token))
+        });
+
+    let token = write_installation_token(&client, &runtime_dir, 42, &scope)
+        .await
+        .expect("token should be written");
+
+    let contents =
+        std::fs::read_to_string(runtime_dir.token_path()).expect("token should be readable");
+    assert_eq!(contents, "ghs_fixture");
+    assert_eq!(token.scope(), &scope);
+}
+
+#[rstest]
+fn token_scope_combines_configured_permissions_and_repository() {
+    let mut config = AppConfig::default();
+    config
+        .github
+        .token_permissions
+        .insert(String::from("contents"), TokenPermissionLevel::Write);
+    let repository = RepositoryRef::parse("leynos/podbot").expect("repository should parse");
+
+    let unscoped = TokenDaemonRequest::new("sandbox-token").token_scope(&config);
+    let scoped = TokenDaemonRequest::new("sandbox-token")
+        .with_repository(repository)
+        .token_scope(&config);
+
+    let contents_write =
+        InstallationTokenScope::default().with_permission("contents", TokenPermissionLevel::Write);
+    assert_eq!(unscoped, contents_write);
+    assert_eq!(scoped, contents_write.with_repository("podbot"));
+}
+
+#[tokio::test]
+async fn write_installation_token_leaves_no_file_on_acquisition_failure() {
+    let (_temp_dir, runtime_dir) = runtime_dir();
+    let mut client = MockGitHubInstallationTokenClient::new();
+    client
+        .expect_acquire_scoped_installation_token()
+        .times(1)
+        .returning(|_, _, _| {
+            let error = GitHubError::TokenAcquisitionFailed {
+                kind: GitHubFailureKind::Permanent,
+                message: String::from("rejected"),
+            };
+            Box::pin(std::future::ready(Err(error)))
+        });
+
+    let scope = InstallationTokenScope::default();
+    let result = write_installation_token(&client, &runtime_dir, 42, &scope).await;
+
+    assert!(
+        matches!(
+            result,
+            Err(PodbotError::GitHub(
+                GitHubError::TokenAcquisitionFailed { .. }
+            ))
+        ),
+        "expected token acquisition failure, got {result:?}"
+    );
+    assert!(!runtime_dir.token_path().exists());
+}
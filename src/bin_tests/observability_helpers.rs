@@ -74,6 +74,7 @@ pub(super) fn run_observability_config(expect_success: bool) -> AppConfig {
                 app_id: Some(1),
                 installation_id: None,
                 private_key_path: None,
+                ..Default::default()
             },
             ..AppConfig::default()
         }
@@ -7,9 +7,11 @@
 use camino::Utf8PathBuf;
 use clap::{Parser, Subcommand, ValueEnum};
 
+#[cfg(feature = "experimental")]
+use crate::api::ExportFormat;
 use crate::api::RunRequest;
 use crate::config::{AgentKind, AgentMode, CommandIntent, ConfigLoadOptions, ConfigOverrides};
-use crate::error::Result as PodbotResult;
+use crate::error::{ConfigError, Result as PodbotResult};
 
 /// CLI-facing agent kind values.
 ///
@@ -60,6 +62,30 @@ impl From<AgentModeArg> for AgentMode {
     }
 }
 
+/// CLI-facing workspace export formats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum ExportFormatArg {
+    /// Patch series from `git format-patch`.
+    #[default]
+    Patch,
+    /// Git bundle of commits since the base revision.
+    Bundle,
+    /// Tar archive of the whole workspace.
+    Tar,
+}
+
+#[cfg(feature = "experimental")]
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(value: ExportFormatArg) -> Self {
+        match value {
+            ExportFormatArg::Patch => Self::Patch,
+            ExportFormatArg::Bundle => Self::Bundle,
+            ExportFormatArg::Tar => Self::Tar,
+        }
+    }
+}
+
 /// Command-line interface for podbot.
 ///
 /// Configuration is loaded with layered precedence:
@@ -115,9 +141,12 @@ impl Cli {
                 args.agent.map(Into::into),
                 args.mode.map(Into::into),
             ),
-            Commands::TokenDaemon(_) | Commands::Ps | Commands::Stop(_) | Commands::Exec(_) => {
-                (CommandIntent::Any, None, None)
-            }
+            Commands::TokenDaemon(_)
+            | Commands::Ps
+            | Commands::Stop(_)
+            | Commands::Exec(_)
+            | Commands::Export(_)
+            | Commands::Cp(_) => (CommandIntent::Any, None, None),
         };
 
         ConfigLoadOptions {
@@ -154,6 +183,12 @@ pub enum Commands {
 
     /// Execute a command in a running container.
     Exec(ExecArgs),
+
+    /// Export session workspace changes to a host file.
+    Export(ExportArgs),
+
+    /// Copy files between the host and a running container.
+    Cp(CpArgs),
 }
 
 /// Arguments for the `run` subcommand.
@@ -203,9 +238,13 @@ pub struct HostArgs {
 /// Arguments for the `token-daemon` subcommand.
 #[derive(Debug, Parser)]
 pub struct TokenDaemonArgs {
-    /// Container ID to manage tokens for.
+    /// Name of the container to manage tokens for.
     #[arg(required = true)]
     pub container_id: String,
+
+    /// Limit tokens to this repository (owner/name).
+    #[arg(long)]
+    pub repo: Option<String>,
 }
 
 /// Arguments for the `stop` subcommand.
@@ -232,5 +271,109 @@ pub struct ExecArgs {
     pub command: Vec<String>,
 }
 
+/// Arguments for the `export` subcommand.
+#[derive(Debug, Parser)]
+pub struct ExportArgs {
+    /// Session container ID or name.
+    #[arg(required = true)]
+    pub session: String,
+
+    /// Export format.
+    #[arg(long, value_enum, default_value_t = ExportFormatArg::Patch)]
+    pub format: ExportFormatArg,
+
+    /// Host file that receives the export.
+    #[arg(long, required = true)]
+    pub out: Utf8PathBuf,
+
+    /// Revision to export changes since (defaults to the upstream branch).
+    #[arg(long)]
+    pub base: Option<String>,
+}
+
+/// Arguments for the `cp` subcommand.
+///
+/// Exactly one operand must use the `SESSION:PATH` form to name the
+/// container side of the copy.
+#[derive(Debug, Parser)]
+pub struct CpArgs {
+    /// Source path: a host path or `SESSION:PATH`.
+    #[arg(required = true)]
+    pub source: String,
+
+    /// Destination path: a host path or `SESSION:PATH`.
+    #[arg(required = true)]
+    pub destination: String,
+
+    /// Largest archive size, in bytes, accepted for the copy.
+    #[arg(long)]
+    pub max_bytes: Option<u64>,
+}
+
+/// Direction of a `cp` transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyDirection {
+    /// Host to container.
+    ToContainer,
+    /// Container to host.
+    FromContainer,
+}
+
+/// `cp` operands resolved into host and container sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyOperands {
+    /// Which way the copy runs.
+    pub direction: CopyDirection,
+    /// Session container ID or name.
+    pub container: String,
+    /// Path inside the container.
+    pub container_path: String,
+    /// Path on the host.
+    pub host_path: Utf8PathBuf,
+}
+
+impl CpArgs {
+    /// Resolve which operand names the container and which names the host.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` unless exactly one operand uses
+    /// the `SESSION:PATH` form.
+    pub fn operands(&self) -> PodbotResult<CopyOperands> {
+        match (
+            split_container_operand(&self.source),
+            split_container_operand(&self.destination),
+        ) {
+            (Some((container, container_path)), None) => Ok(CopyOperands {
+                direction: CopyDirection::FromContainer,
+                container: String::from(container),
+                container_path: String::from(container_path),
+                host_path: Utf8PathBuf::from(&self.destination),
+            }),
+            (None, Some((container, container_path))) => Ok(CopyOperands {
+                direction: CopyDirection::ToContainer,
+                container: String::from(container),
+                container_path: String::from(container_path),
+                host_path: Utf8PathBuf::from(&self.source),
+            }),
+            _ => Err(ConfigError::InvalidValue {
+                field: String::from("cp"),
+                reason: String::from(
+                    "exactly one of SOURCE and DESTINATION must use the SESSION:PATH form",
+                ),
+            }
+            .into()),
+        }
+    }
+}
+
+/// Split `SESSION:PATH`, treating operands whose prefix contains `/` as host
+/// paths so `./dir:name` is not mistaken for a container reference.
+fn split_container_operand(operand: &str) -> Option<(&str, &str)> {
+    operand
+        .split_once(':')
+        .filter(|(container, _)| !container.is_empty() && !container.contains('/'))
+}
+
 #[cfg(test)]
 mod tests;
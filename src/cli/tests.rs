@@ -4,7 +4,9 @@ use camino::Utf8PathBuf;
 use clap::Parser;
 use rstest::rstest;
 
-use super::{AgentKindArg, AgentModeArg, Cli, Commands, HostArgs};
+use super::{
+    AgentKindArg, AgentModeArg, Cli, Commands, CopyDirection, CpArgs, ExportFormatArg, HostArgs,
+};
 use crate::config::{AgentKind, AgentMode, CommandIntent, ConfigOverrides};
 
 #[rstest]
@@ -158,3 +160,92 @@ fn cli_parses_snake_case_hosted_agent_mode_values() {
         Some(AgentMode::CodexAppServer)
     );
 }
+
+#[rstest]
+fn cli_parses_export_arguments() {
+    let cli = Cli::try_parse_from([
+        "podbot",
+        "export",
+        "sandbox",
+        "--format",
+        "bundle",
+        "--out",
+        "review.bundle",
+        "--base",
+        "origin/main",
+    ])
+    .expect("export arguments should parse");
+
+    let Commands::Export(args) = &cli.command else {
+        panic!("expected export command, got {:?}", cli.command);
+    };
+    assert_eq!(args.session, "sandbox");
+    assert_eq!(args.format, ExportFormatArg::Bundle);
+    assert_eq!(args.out, Utf8PathBuf::from("review.bundle"));
+    assert_eq!(args.base.as_deref(), Some("origin/main"));
+    assert_eq!(cli.config_load_options().command_intent, CommandIntent::Any);
+}
+
+#[rstest]
+fn cli_export_defaults_to_patch_format() {
+    let cli = Cli::try_parse_from(["podbot", "export", "sandbox", "--out", "review.patch"])
+        .expect("export arguments should parse");
+
+    assert!(matches!(
+        &cli.command,
+        Commands::Export(args) if args.format == ExportFormatArg::Patch && args.base.is_none()
+    ));
+}
+
+fn cp_args(source: &str, destination: &str) -> CpArgs {
+    CpArgs {
+        source: String::from(source),
+        destination: String::from(destination),
+        max_bytes: None,
+    }
+}
+
+#[rstest]
+#[case::upload(
+    "./specs",
+    "sandbox:/work/specs",
+    CopyDirection::ToContainer,
+    "./specs"
+)]
+#[case::download(
+    "sandbox:/work/out",
+    "reports",
+    CopyDirection::FromContainer,
+    "reports"
+)]
+fn cp_operands_resolve_direction(
+    #[case] source: &str,
+    #[case] destination: &str,
+    #[case] direction: CopyDirection,
+    #[case] host_path: &str,
+) {
+    let operands = cp_args(source, destination)
+        .operands()
+        .expect("one container operand should resolve");
+
+    assert_eq!(operands.direction, direction);
+    assert_eq!(operands.container, "sandbox");
+    assert_eq!(operands.host_path, Utf8PathBuf::from(host_path));
+}
+
+#[rstest]
+#[case::both_host("./a", "./b:c")]
+#[case::both_container("one:/a", "two:/b")]
+fn cp_operands_require_exactly_one_container_side(#[case] source: &str, #[case] destination: &str) {
+    let result = cp_args(source, destination).operands();
+
+    assert!(
+        matches!(
+            &result,
+            Err(crate::error::PodbotError::Config(
+                crate::error::ConfigError::InvalidValue { field, .. }
+            )) if field == "cp"
+        ),
+        "expected cp operand rejection, got {result:?}"
+    );
+}
@@ -0,0 +1,183 @@
+//! Agentic Control Protocol (ACP) trust-boundary configuration.
+
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// How podbot treats one ACP client-capability family.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AcpFamilyPolicy {
+    /// Mask the capability during initialization and refuse its methods.
+    #[default]
+    Deny,
+    /// Advertise the capability and forward its methods to the host client.
+    Delegate,
+    /// Advertise the capability and answer its methods inside podbot: `fs`
+    /// from the host-mounted workspace and `terminal` by running commands in
+    /// the sandbox container.
+    Serve,
+}
+
+impl AcpFamilyPolicy {
+    /// Returns the `snake_case` token representation used in configuration.
+    #[must_use]
+    pub const fn as_token(&self) -> &'static str {
+        match self {
+            Self::Deny => "deny",
+            Self::Delegate => "delegate",
+            Self::Serve => "serve",
+        }
+    }
+
+    /// Returns whether the family is delegated to the host client.
+    #[must_use]
+    pub const fn is_delegated(&self) -> bool {
+        matches!(self, Self::Delegate)
+    }
+
+    /// Returns whether podbot answers the family's methods itself.
+    #[must_use]
+    pub const fn is_served(&self) -> bool {
+        matches!(self, Self::Serve)
+    }
+}
+
+/// ACP capability policy, configured under `[agent.acp]`.
+///
+/// By default the `fs` and `terminal` families are denied, keeping the
+/// agent's tool calls inside the sandbox. Delegating a family lets the host
+/// client read files or run commands outside the container on the agent's
+/// behalf, so it also requires `acknowledge_host_delegation = true`.
+/// Serving `fs` instead answers file requests inside podbot against the
+/// host-mounted workspace, so it requires `workspace.source = "host_mount"`.
+/// Serving `terminal` runs the agent's commands as execs in the sandbox
+/// container.
+///
+/// Method rules apply to requests and notifications in both directions. Each
+/// rule is an exact method name such as `session/set_mode`, or a family such
+/// as `_zed/*` matching every method under that prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AcpConfig {
+    /// Policy for the `fs/*` family.
+    pub fs: AcpFamilyPolicy,
+
+    /// Policy for the `terminal/*` family.
+    pub terminal: AcpFamilyPolicy,
+
+    /// Confirms that delegated families run on the host, outside the sandbox.
+    pub acknowledge_host_delegation: bool,
+
+    /// Method rules that are always refused.
+    pub deny_methods: Vec<String>,
+
+    /// Refuses every method not matched by `allow_methods`.
+    pub allow_only: bool,
+
+    /// Method rules permitted when `allow_only` is set.
+    pub allow_methods: Vec<String>,
+
+    /// ACP traffic audit log, configured under `[agent.acp.audit]`.
+    pub audit: AcpAuditConfig,
+
+    /// Rules for answering the agent's `session/request_permission`
+    /// requests, configured as `[[agent.acp.permissions]]`. The first
+    /// matching rule applies; unmatched requests go to the host client.
+    pub permissions: Vec<AcpPermissionRule>,
+}
+
+/// How podbot answers a `session/request_permission` request that a rule
+/// matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AcpPermissionAction {
+    /// Select the agent's `allow_once` option.
+    AllowOnce,
+    /// Select the agent's `reject_once` option.
+    Reject,
+    /// Pass the request to the host client unchanged.
+    Forward,
+}
+
+impl AcpPermissionAction {
+    /// Returns the `snake_case` token representation used in configuration.
+    #[must_use]
+    pub const fn as_token(&self) -> &'static str {
+        match self {
+            Self::AllowOnce => "allow_once",
+            Self::Reject => "reject",
+            Self::Forward => "forward",
+        }
+    }
+}
+
+/// One `[[agent.acp.permissions]]` rule.
+///
+/// A rule matches a permission request when its `kind` equals the tool
+/// call's kind and its `title` glob matches the tool call's title; an
+/// omitted field matches anything. In `title`, `*` matches any run of
+/// characters and `?` matches one.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AcpPermissionRule {
+    /// ACP tool kind, such as `read`, `edit` or `execute`.
+    #[serde(default)]
+    pub kind: Option<String>,
+
+    /// Glob over the tool call's title.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// The answer podbot gives when the rule matches.
+    pub action: AcpPermissionAction,
+}
+
+/// The tool kinds an ACP agent may report for a tool call.
+pub(crate) const ACP_TOOL_KINDS: &[&str] = &[
+    "read",
+    "edit",
+    "delete",
+    "move",
+    "search",
+    "execute",
+    "think",
+    "fetch",
+    "switch_mode",
+    "other",
+];
+
+/// Opt-in audit log of ACP frames, configured under `[agent.acp.audit]`.
+///
+/// When `path` is set, each frame the runtime policy sees is appended to that
+/// file as one JSON line recording its direction, JSON-RPC id, method,
+/// policy decision and size. The log never shares a stream with the
+/// protocol's stdout.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AcpAuditConfig {
+    /// File that audit records are appended to; auditing is off when unset.
+    pub path: Option<Utf8PathBuf>,
+
+    /// Records a SHA-256 digest of each frame's `params` instead of the
+    /// params themselves.
+    pub hash_params: bool,
+}
+
+impl AcpConfig {
+    /// Returns the names of the delegated families in a stable order.
+    #[must_use]
+    pub fn delegated_families(&self) -> Vec<&'static str> {
+        [("fs", self.fs), ("terminal", self.terminal)]
+            .into_iter()
+            .filter_map(|(name, policy)| policy.is_delegated().then_some(name))
+            .collect()
+    }
+}
+
+/// Returns whether `rule` is an exact method name or a `prefix/*` family.
+///
+/// Rules must not be blank or contain whitespace, and `*` may only appear as
+/// the final segment of a family.
+pub(crate) fn is_valid_method_rule(rule: &str) -> bool {
+    let name = rule.strip_suffix("/*").unwrap_or(rule);
+    !name.is_empty() && !name.contains('*') && !name.chars().any(char::is_whitespace)
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::acp::AcpConfig;
+
 /// The kind of AI agent to run.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -57,7 +59,14 @@ pub struct AgentConfig {
     pub args: Vec<String>,
 
     /// Environment variable names copied from the host into the agent runtime.
+    ///
+    /// A trailing `!`, as in `ANTHROPIC_API_KEY!`, marks a variable the launch
+    /// cannot proceed without; unmarked variables missing on the host are
+    /// skipped.
     pub env_allowlist: Vec<String>,
+
+    /// ACP capability policy for `agent.mode = "acp"` sessions.
+    pub acp: AcpConfig,
 }
 
 impl Default for AgentConfig {
@@ -68,6 +77,66 @@ impl Default for AgentConfig {
             command: None,
             args: Vec::new(),
             env_allowlist: Vec::new(),
+            acp: AcpConfig::default(),
         }
     }
 }
+
+impl AgentConfig {
+    /// Returns the parsed `agent.env_allowlist` entries in declaration order.
+    #[must_use]
+    pub fn env_allowlist_entries(&self) -> Vec<EnvAllowlistEntry> {
+        self.env_allowlist
+            .iter()
+            .map(|entry| EnvAllowlistEntry::parse(entry))
+            .collect()
+    }
+}
+
+/// One `agent.env_allowlist` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvAllowlistEntry {
+    name: String,
+    required: bool,
+}
+
+impl EnvAllowlistEntry {
+    /// Parses `NAME` or `NAME!`, ignoring surrounding whitespace.
+    #[must_use]
+    pub fn parse(entry: &str) -> Self {
+        let trimmed = entry.trim();
+        trimmed.strip_suffix('!').map_or_else(
+            || Self {
+                name: String::from(trimmed),
+                required: false,
+            },
+            |name| Self {
+                name: String::from(name.trim_end()),
+                required: true,
+            },
+        )
+    }
+
+    /// Returns the host environment variable name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether launch fails when the variable is not set on the host.
+    #[must_use]
+    pub const fn required(&self) -> bool {
+        self.required
+    }
+
+    /// Returns whether the name is a portable environment variable name:
+    /// ASCII letters, digits and underscores, not starting with a digit.
+    #[must_use]
+    pub fn has_valid_name(&self) -> bool {
+        let mut chars = self.name.chars();
+        chars
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && chars.all(|rest| rest.is_ascii_alphanumeric() || rest == '_')
+    }
+}
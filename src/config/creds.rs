@@ -0,0 +1,451 @@
+//! Credential families copied from the host into the sandbox.
+//!
+//! A credential family is one agent's or tool's credential store: the host
+//! directories it reads, the container directory they are copied into, and
+//! the agent it serves, if any. Podbot ships built-in families for Claude,
+//! Codex, Gemini, `OpenCode` and the `gh` CLI. Claude and Codex follow the
+//! `creds.copy_claude` and `creds.copy_codex` toggles; the other built-ins
+//! are copied once named in `[[creds.families]]`. Entries with any other name
+//! declare custom families. A family that names an agent is only copied for
+//! that agent; families without one are copied for every agent. Include and
+//! exclude globs narrow what is copied from each source, and
+//! `creds.max_upload_bytes` caps the total upload. Only the files a family
+//! lists in `sync_files` are ever synced back from the container.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AgentKind, CredsConfig};
+use crate::error::{ConfigError, Result};
+
+/// Container home directory that credential family targets must lie within.
+pub const CREDENTIAL_TARGET_ROOT: &str = "/root";
+
+/// Default cap on the file payload of one credential upload (64 MiB).
+pub const DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// One `[[creds.families]]` entry.
+///
+/// An entry named after a built-in family enables it and replaces whichever
+/// of its fields the entry sets. Any other name declares a custom family,
+/// which must list its `sources`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CredentialFamilyConfig {
+    /// Family name, such as `gemini` or an operator-chosen name.
+    pub name: String,
+
+    /// Whether to copy the family; defaults to `true`, except that Claude and
+    /// Codex default to their `copy_*` toggles.
+    pub enabled: Option<bool>,
+
+    /// Host directories to copy, relative to the home directory or absolute.
+    pub sources: Vec<Utf8PathBuf>,
+
+    /// Container directory the sources are copied into; defaults to `/root`.
+    pub target: Option<Utf8PathBuf>,
+
+    /// Agent the credentials belong to, such as `claude`; unset for tools
+    /// shared by every agent.
+    pub agent: Option<String>,
+
+    /// Globs, relative to each source, selecting what to copy; everything
+    /// when empty.
+    pub include: Vec<String>,
+
+    /// Globs, relative to each source, of entries never to copy.
+    pub exclude: Vec<String>,
+
+    /// Files, relative to each source, that sync-back may update on the
+    /// host; built-in families default to their credential files, and custom
+    /// families sync nothing back unless this is set.
+    pub sync_files: Vec<Utf8PathBuf>,
+}
+
+/// A resolved credential family ready to be copied into a container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialFamily {
+    name: String,
+    sources: Vec<Utf8PathBuf>,
+    target: Utf8PathBuf,
+    agent: Option<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    sync_files: Vec<Utf8PathBuf>,
+}
+
+impl CredentialFamily {
+    /// Create a family copying `sources` into the container directory
+    /// `target`.
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        sources: Vec<Utf8PathBuf>,
+        target: impl Into<Utf8PathBuf>,
+        agent: Option<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            sources,
+            target: target.into(),
+            agent,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            sync_files: Vec::new(),
+        }
+    }
+
+    /// Return this family restricted to entries matching `include` (all
+    /// entries when empty) and not matching `exclude`.
+    ///
+    /// Globs are matched against paths relative to each source and anchored
+    /// at it: `*` and `?` stay within one path segment, and `**` spans any
+    /// number of segments.
+    #[must_use]
+    pub fn with_filters(mut self, include: Vec<String>, exclude: Vec<String>) -> Self {
+        self.include = include;
+        self.exclude = exclude;
+        self
+    }
+
+    /// Return this family with `sync_files`, relative to each source, as the
+    /// only files sync-back may update on the host.
+    #[must_use]
+    pub fn with_sync_files(mut self, sync_files: Vec<Utf8PathBuf>) -> Self {
+        self.sync_files = sync_files;
+        self
+    }
+
+    /// Return the built-in family called `name`, if there is one.
+    #[must_use]
+    pub fn builtin(name: &str) -> Option<Self> {
+        BUILTIN_FAMILIES
+            .iter()
+            .find(|builtin| builtin.name == name)
+            .map(BuiltinFamily::to_family)
+    }
+
+    /// Return the family name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the host source directories, relative to the home directory or
+    /// absolute.
+    #[must_use]
+    pub fn sources(&self) -> &[Utf8PathBuf] {
+        &self.sources
+    }
+
+    /// Return the container directory each source is copied into under its
+    /// own file name.
+    #[must_use]
+    pub fn target(&self) -> &Utf8Path {
+        &self.target
+    }
+
+    /// Return the agent the credentials belong to, if any.
+    #[must_use]
+    pub fn agent(&self) -> Option<&str> {
+        self.agent.as_deref()
+    }
+
+    /// Return `true` when the family should be copied for an agent of
+    /// `kind`.
+    ///
+    /// Families without an agent serve every agent. A custom launcher may
+    /// wrap any agent, so it is served by every family.
+    #[must_use]
+    pub fn serves(&self, kind: AgentKind) -> bool {
+        match (kind, self.agent()) {
+            (_, None) | (AgentKind::Custom, _) => true,
+            (AgentKind::Claude, Some(agent)) => agent == "claude",
+            (AgentKind::Codex, Some(agent)) => agent == "codex",
+        }
+    }
+
+    /// Return the globs selecting entries to copy; empty means everything.
+    #[must_use]
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Return the globs of entries never to copy.
+    #[must_use]
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Return the files, relative to each source, that sync-back may update.
+    #[must_use]
+    pub fn sync_files(&self) -> &[Utf8PathBuf] {
+        &self.sync_files
+    }
+}
+
+struct BuiltinFamily {
+    name: &'static str,
+    sources: &'static [&'static str],
+    target: &'static str,
+    agent: Option<&'static str>,
+    sync_files: &'static [&'static str],
+}
+
+impl BuiltinFamily {
+    fn to_family(&self) -> CredentialFamily {
+        CredentialFamily::new(
+            self.name,
+            self.sources
+                .iter()
+                .copied()
+                .map(Utf8PathBuf::from)
+                .collect(),
+            self.target,
+            self.agent.map(String::from),
+        )
+        .with_sync_files(
+            self.sync_files
+                .iter()
+                .copied()
+                .map(Utf8PathBuf::from)
+                .collect(),
+        )
+    }
+}
+
+/// Built-in families, in the order they are uploaded and reported.
+const BUILTIN_FAMILIES: [BuiltinFamily; 5] = [
+    BuiltinFamily {
+        name: "claude",
+        sources: &[".claude"],
+        target: CREDENTIAL_TARGET_ROOT,
+        agent: Some("claude"),
+        sync_files: &[".credentials.json"],
+    },
+    BuiltinFamily {
+        name: "codex",
+        sources: &[".codex"],
+        target: CREDENTIAL_TARGET_ROOT,
+        agent: Some("codex"),
+        sync_files: &["auth.json"],
+    },
+    BuiltinFamily {
+        name: "gemini",
+        sources: &[".gemini"],
+        target: CREDENTIAL_TARGET_ROOT,
+        agent: Some("gemini"),
+        sync_files: &["oauth_creds.json"],
+    },
+    BuiltinFamily {
+        name: "opencode",
+        sources: &[".local/share/opencode"],
+        target: "/root/.local/share",
+        agent: Some("opencode"),
+        sync_files: &["auth.json"],
+    },
+    BuiltinFamily {
+        name: "gh",
+        sources: &[".config/gh"],
+        target: "/root/.config",
+        agent: None,
+        sync_files: &["hosts.yml"],
+    },
+];
+
+impl CredsConfig {
+    /// Returns the enabled credential families in upload order.
+    ///
+    /// Built-in families come first, in a fixed order (`claude`, `codex`,
+    /// `gemini`, `opencode`, `gh`), followed by custom families in
+    /// declaration order.
+    #[must_use]
+    pub fn enabled_families(&self) -> Vec<CredentialFamily> {
+        let builtins = BUILTIN_FAMILIES.iter().filter_map(|builtin| {
+            let entry = self
+                .families
+                .iter()
+                .find(|entry| entry.name == builtin.name);
+            let enabled_by_default = match builtin.name {
+                "claude" => self.copy_claude,
+                "codex" => self.copy_codex,
+                _ => entry.is_some(),
+            };
+            let enabled = entry
+                .and_then(|family| family.enabled)
+                .unwrap_or(enabled_by_default);
+            enabled.then(|| {
+                entry.map_or_else(
+                    || builtin.to_family(),
+                    |overrides| overrides.apply_to(builtin.to_family()),
+                )
+            })
+        });
+        let custom = self
+            .families
+            .iter()
+            .filter(|entry| !is_builtin(&entry.name) && entry.enabled.unwrap_or(true))
+            .map(|entry| {
+                entry.apply_to(CredentialFamily::new(
+                    entry.name.clone(),
+                    Vec::new(),
+                    CREDENTIAL_TARGET_ROOT,
+                    None,
+                ))
+            });
+        builtins.chain(custom).collect()
+    }
+
+    /// Returns the enabled credential families that serve an agent of
+    /// `kind`, in upload order.
+    ///
+    /// Families whose `agent` names a different agent are left out, so a
+    /// Claude session never receives Codex credentials; see
+    /// [`CredentialFamily::serves`].
+    #[must_use]
+    pub fn families_for_agent(&self, kind: AgentKind) -> Vec<CredentialFamily> {
+        self.enabled_families()
+            .into_iter()
+            .filter(|family| family.serves(kind))
+            .collect()
+    }
+
+    /// Rejects `[[creds.families]]` entries that are unnamed, duplicated,
+    /// missing sources, that copy outside the container home directory, or
+    /// whose globs or sync files are blank, absolute, or climb out of the
+    /// source with `..`, and a zero `max_upload_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the offending entry field.
+    pub fn validate_families(&self) -> Result<()> {
+        for (index, entry) in self.families.iter().enumerate() {
+            let field = |name: &str| format!("creds.families[{index}].{name}");
+            if entry.name.trim().is_empty() {
+                return invalid_family(field("name"), "family name must not be empty");
+            }
+            if self
+                .families
+                .iter()
+                .take(index)
+                .any(|earlier| earlier.name == entry.name)
+            {
+                return invalid_family(
+                    field("name"),
+                    format!("family `{}` is declared more than once", entry.name),
+                );
+            }
+            if entry.sources.is_empty() && !is_builtin(&entry.name) {
+                return invalid_family(
+                    field("sources"),
+                    format!("custom family `{}` must list its sources", entry.name),
+                );
+            }
+            if let Some(source) = entry.sources.iter().find(|source| !is_valid_source(source)) {
+                return invalid_family(
+                    field("sources"),
+                    format!(
+                        "source `{source}` must be an absolute path or a path within the home directory"
+                    ),
+                );
+            }
+            if entry
+                .target
+                .as_deref()
+                .is_some_and(|target| !is_valid_target(target))
+            {
+                return invalid_family(
+                    field("target"),
+                    format!("target must be `{CREDENTIAL_TARGET_ROOT}` or a directory beneath it"),
+                );
+            }
+            validate_globs(&field("include"), &entry.include)?;
+            validate_globs(&field("exclude"), &entry.exclude)?;
+            validate_sync_files(&field("sync_files"), &entry.sync_files)?;
+        }
+        if self.max_upload_bytes == 0 {
+            return invalid_family(
+                String::from("creds.max_upload_bytes"),
+                "creds.max_upload_bytes must be greater than zero",
+            );
+        }
+        Ok(())
+    }
+}
+
+impl CredentialFamilyConfig {
+    /// Returns `family` with the fields this entry sets in place of its own.
+    fn apply_to(&self, family: CredentialFamily) -> CredentialFamily {
+        CredentialFamily {
+            sources: if self.sources.is_empty() {
+                family.sources
+            } else {
+                self.sources.clone()
+            },
+            target: self.target.clone().unwrap_or(family.target),
+            agent: self.agent.clone().or(family.agent),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            sync_files: if self.sync_files.is_empty() {
+                family.sync_files
+            } else {
+                self.sync_files.clone()
+            },
+            ..family
+        }
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    BUILTIN_FAMILIES.iter().any(|builtin| builtin.name == name)
+}
+
+fn is_valid_source(source: &Utf8Path) -> bool {
+    source.file_name().is_some()
+        && source
+            .components()
+            .all(|component| !matches!(component, camino::Utf8Component::ParentDir))
+}
+
+fn is_valid_target(target: &Utf8Path) -> bool {
+    target.starts_with(CREDENTIAL_TARGET_ROOT)
+        && target
+            .components()
+            .all(|component| !matches!(component, camino::Utf8Component::ParentDir))
+}
+
+fn validate_globs(field: &str, globs: &[String]) -> Result<()> {
+    let Some(glob) = globs.iter().find(|glob| {
+        glob.trim().is_empty() || glob.starts_with('/') || glob.split('/').any(|part| part == "..")
+    }) else {
+        return Ok(());
+    };
+    invalid_family(
+        String::from(field),
+        format!("glob `{glob}` must be a non-empty path relative to the source without `..`"),
+    )
+}
+
+fn validate_sync_files(field: &str, files: &[Utf8PathBuf]) -> Result<()> {
+    let Some(file) = files.iter().find(|file| {
+        file.is_absolute()
+            || file.file_name().is_none()
+            || file
+                .components()
+                .any(|component| matches!(component, camino::Utf8Component::ParentDir))
+    }) else {
+        return Ok(());
+    };
+    invalid_family(
+        String::from(field),
+        format!("sync file `{file}` must be a file path relative to the source without `..`"),
+    )
+}
+
+fn invalid_family(field: String, reason: impl Into<String>) -> Result<()> {
+    Err(ConfigError::InvalidValue {
+        field,
+        reason: reason.into(),
+    }
+    .into())
+}
@@ -64,6 +64,46 @@ const ENV_VAR_SPECS: &[EnvVarSpec] = &[
         path: &["github", "private_key_path"],
         var_type: EnvVarType::String,
     },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_ENV",
+        path: &["github", "private_key_env"],
+        var_type: EnvVarType::String,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_FD",
+        path: &["github", "private_key_fd"],
+        var_type: EnvVarType::U64,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_COMMAND",
+        path: &["github", "private_key_command"],
+        var_type: EnvVarType::StringList,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_PASSPHRASE_ENV",
+        path: &["github", "private_key_passphrase_env"],
+        var_type: EnvVarType::String,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_PASSPHRASE_FILE",
+        path: &["github", "private_key_passphrase_file"],
+        var_type: EnvVarType::String,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_PRIVATE_KEY_PASSPHRASE_COMMAND",
+        path: &["github", "private_key_passphrase_command"],
+        var_type: EnvVarType::StringList,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_API_BASE_URL",
+        path: &["github", "api_base_url"],
+        var_type: EnvVarType::String,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_GITHUB_WEB_BASE_URL",
+        path: &["github", "web_base_url"],
+        var_type: EnvVarType::String,
+    },
     // Sandbox fields
     EnvVarSpec {
         env_var: "PODBOT_SANDBOX_PRIVILEGED",
@@ -138,6 +178,16 @@ const ENV_VAR_SPECS: &[EnvVarSpec] = &[
         path: &["creds", "copy_codex"],
         var_type: EnvVarType::Bool,
     },
+    EnvVarSpec {
+        env_var: "PODBOT_CREDS_MAX_UPLOAD_BYTES",
+        path: &["creds", "max_upload_bytes"],
+        var_type: EnvVarType::U64,
+    },
+    EnvVarSpec {
+        env_var: "PODBOT_CREDS_SYNC_BACK",
+        path: &["creds", "sync_back"],
+        var_type: EnvVarType::Bool,
+    },
     // MCP hosting fields
     EnvVarSpec {
         env_var: "PODBOT_MCP_BIND_STRATEGY",
@@ -0,0 +1,199 @@
+//! `GitHub` App profiles selected by repository owner.
+//!
+//! `[[github.apps]]` entries let one podbot configuration hold several
+//! `GitHub` Apps, for example one for internal organizations and one for open
+//! source work. Each entry carries its own App ID, installation, and key
+//! source, and applies to repository owners matching its `owner` glob. Globs
+//! support `*` (any run of characters) and `?` (one character) and match
+//! owners case-insensitively, as `GitHub` does. Shared settings such as the API
+//! base URL and token permissions stay on the top-level `[github]` table, whose
+//! own credentials are used when no entry matches.
+
+use std::borrow::Cow;
+
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GitHubConfig;
+use crate::error::{ConfigError, PodbotError, Result};
+use crate::glob::{wildcard_matches, wildcards_overlap};
+
+/// One `GitHub` App and the repository owners it serves (`[[github.apps]]`).
+///
+/// The credential fields mean the same as their top-level `[github]`
+/// counterparts and replace all of them when the profile is selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GitHubAppProfile {
+    /// Glob matched against the repository owner, such as `acme-*`.
+    pub owner: String,
+
+    /// The `GitHub` App ID.
+    pub app_id: Option<u64>,
+
+    /// The `GitHub` App installation ID; looked up from the repository when
+    /// unset.
+    pub installation_id: Option<u64>,
+
+    /// Path to the `GitHub` App private key file.
+    pub private_key_path: Option<Utf8PathBuf>,
+
+    /// Name of an environment variable holding the private key PEM.
+    pub private_key_env: Option<String>,
+
+    /// File descriptor, inherited from the parent process, to read the
+    /// private key PEM from.
+    pub private_key_fd: Option<i32>,
+
+    /// Command, as argv, whose standard output is the private key PEM.
+    pub private_key_command: Option<Vec<String>>,
+
+    /// Name of an environment variable holding the key passphrase.
+    pub private_key_passphrase_env: Option<String>,
+
+    /// Path to a file holding the key passphrase.
+    pub private_key_passphrase_file: Option<Utf8PathBuf>,
+
+    /// Command, as argv, whose standard output is the key passphrase.
+    pub private_key_passphrase_command: Option<Vec<String>>,
+}
+
+impl GitHubAppProfile {
+    /// Returns whether this profile's `owner` glob matches `owner`.
+    #[must_use]
+    pub fn matches_owner(&self, owner: &str) -> bool {
+        wildcard_matches(
+            &self.owner.to_ascii_lowercase(),
+            &owner.to_ascii_lowercase(),
+        )
+    }
+}
+
+impl GitHubConfig {
+    /// Returns the configuration to use for repositories owned by `owner`.
+    ///
+    /// When a `[[github.apps]]` entry matches, the result is this
+    /// configuration with that entry's App ID, installation, key source, and
+    /// passphrase source in place of the top-level ones. Otherwise the
+    /// top-level configuration is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` for `github.apps` when more than
+    /// one entry matches `owner`.
+    pub fn for_owner(&self, owner: &str) -> Result<Cow<'_, Self>> {
+        let mut matching = self.apps.iter().filter(|app| app.matches_owner(owner));
+        let Some(app) = matching.next() else {
+            return Ok(Cow::Borrowed(self));
+        };
+        if let Some(other) = matching.next() {
+            return Err(ConfigError::InvalidValue {
+                field: String::from("github.apps"),
+                reason: format!(
+                    "owner `{owner}` matches both `{}` and `{}`",
+                    app.owner, other.owner
+                ),
+            }
+            .into());
+        }
+        Ok(Cow::Owned(self.with_app(app)))
+    }
+
+    /// Returns this configuration with `app`'s credentials in place of the
+    /// top-level ones and no `apps` entries.
+    pub(crate) fn with_app(&self, app: &GitHubAppProfile) -> Self {
+        Self {
+            app_id: app.app_id,
+            installation_id: app.installation_id,
+            private_key_path: app.private_key_path.clone(),
+            private_key_env: app.private_key_env.clone(),
+            private_key_fd: app.private_key_fd,
+            private_key_command: app.private_key_command.clone(),
+            private_key_passphrase_env: app.private_key_passphrase_env.clone(),
+            private_key_passphrase_file: app.private_key_passphrase_file.clone(),
+            private_key_passphrase_command: app.private_key_passphrase_command.clone(),
+            apps: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Rejects `[[github.apps]]` owner globs that are malformed or that could
+    /// match the same owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the offending entries.
+    pub fn validate_app_owners(&self) -> Result<()> {
+        for (index, app) in self.apps.iter().enumerate() {
+            if !is_valid_owner_glob(&app.owner) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("github.apps[{index}].owner"),
+                    reason: String::from(
+                        "owner must be a non-empty glob of letters, digits, `-`, `*` and `?`",
+                    ),
+                }
+                .into());
+            }
+        }
+        if let Some((index, other_index)) = self.first_overlapping_apps() {
+            return Err(ConfigError::InvalidValue {
+                field: format!("github.apps[{index}].owner, github.apps[{other_index}].owner"),
+                reason: format!(
+                    "owner globs `{}` and `{}` can match the same owner",
+                    self.apps.get(index).map_or("", |app| app.owner.as_str()),
+                    self.apps
+                        .get(other_index)
+                        .map_or("", |app| app.owner.as_str()),
+                ),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the first pair of `apps` whose owner globs can
+    /// match the same owner.
+    fn first_overlapping_apps(&self) -> Option<(usize, usize)> {
+        let globs: Vec<String> = self
+            .apps
+            .iter()
+            .map(|app| app.owner.to_ascii_lowercase())
+            .collect();
+        globs.iter().enumerate().find_map(|(index, glob)| {
+            globs
+                .iter()
+                .enumerate()
+                .skip(index + 1)
+                .find(|(_, other)| wildcards_overlap(glob, other))
+                .map(|(other_index, _)| (index, other_index))
+        })
+    }
+}
+
+/// Rewrites `github.` field names in a validation error for
+/// `github.apps[index]`.
+pub(crate) fn scope_to_app(error: PodbotError, index: usize) -> PodbotError {
+    let scope = |text: String| text.replace("github.", &format!("github.apps[{index}]."));
+    match error {
+        PodbotError::Config(ConfigError::MissingRequired { field }) => {
+            ConfigError::MissingRequired {
+                field: scope(field),
+            }
+            .into()
+        }
+        PodbotError::Config(ConfigError::InvalidValue { field, reason }) => {
+            ConfigError::InvalidValue {
+                field: scope(field),
+                reason: scope(reason),
+            }
+            .into()
+        }
+        other => other,
+    }
+}
+
+fn is_valid_owner_glob(owner: &str) -> bool {
+    !owner.is_empty()
+        && owner
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '*' | '?'))
+}
@@ -36,8 +36,11 @@
 //! bind_strategy = "host_gateway"
 //! ```
 
+mod acp;
 mod agent;
+mod creds;
 mod env_vars;
+mod github_apps;
 mod hosting;
 mod load_options;
 mod loader;
@@ -48,14 +51,24 @@ mod workspace;
 #[cfg(test)]
 mod tests;
 
-pub use agent::{AgentConfig, AgentKind, AgentMode};
+pub(crate) use acp::{ACP_TOOL_KINDS, is_valid_method_rule};
+pub use acp::{AcpAuditConfig, AcpConfig, AcpFamilyPolicy, AcpPermissionAction, AcpPermissionRule};
+pub use agent::{AgentConfig, AgentKind, AgentMode, EnvAllowlistEntry};
+pub use creds::{
+    CREDENTIAL_TARGET_ROOT, CredentialFamily, CredentialFamilyConfig,
+    DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES,
+};
 pub use env_vars::env_var_names;
+pub use github_apps::GitHubAppProfile;
 pub use hosting::{McpAllowedOriginPolicy, McpAuthTokenPolicy, McpBindStrategy, McpConfig};
 pub use load_options::{ConfigLoadOptions, ConfigOverrides};
 pub use loader::{load_config, load_config_with_env};
 #[cfg(feature = "internal")]
 pub use types::merge_from_layers_for_tests;
-pub use types::{AppConfig, CredsConfig, GitHubConfig, SandboxConfig, SelinuxLabelMode};
+pub use types::{
+    AppConfig, CredsConfig, DEFAULT_GITHUB_API_BASE_URL, DEFAULT_GITHUB_WEB_BASE_URL, GitHubConfig,
+    PassphraseSource, PrivateKeySource, SandboxConfig, SelinuxLabelMode, TokenPermissionLevel,
+};
 pub use validation::CommandIntent;
 pub(crate) use workspace::default_host_mount_container_path;
 pub use workspace::{WorkspaceConfig, WorkspaceSource};
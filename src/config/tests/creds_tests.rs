@@ -0,0 +1,286 @@
+//! Tests for the `[creds]` credential-family registry.
+
+use camino::Utf8PathBuf;
+use rstest::rstest;
+
+use crate::config::{
+    AgentKind, AppConfig, CommandIntent, CredentialFamily, CredentialFamilyConfig, CredsConfig,
+};
+use crate::error::{ConfigError, PodbotError};
+
+fn names(creds: &CredsConfig) -> Vec<String> {
+    creds
+        .enabled_families()
+        .iter()
+        .map(|family| String::from(family.name()))
+        .collect()
+}
+
+fn entry(name: &str) -> CredentialFamilyConfig {
+    CredentialFamilyConfig {
+        name: String::from(name),
+        ..CredentialFamilyConfig::default()
+    }
+}
+
+fn invalid_field(creds: CredsConfig) -> String {
+    let mut config = AppConfig {
+        creds,
+        ..AppConfig::default()
+    };
+    match config.normalize_and_validate(CommandIntent::Any) {
+        Err(PodbotError::Config(ConfigError::InvalidValue { field, .. })) => field,
+        other => panic!("expected InvalidValue, got {other:?}"),
+    }
+}
+
+#[rstest]
+fn defaults_enable_claude_and_codex_only() {
+    assert_eq!(names(&CredsConfig::default()), ["claude", "codex"]);
+}
+
+#[rstest]
+#[case::claude_only(true, false, &["claude"])]
+#[case::codex_only(false, true, &["codex"])]
+#[case::neither(false, false, &[])]
+fn legacy_toggles_select_claude_and_codex(
+    #[case] copy_claude: bool,
+    #[case] copy_codex: bool,
+    #[case] expected: &[&str],
+) {
+    let creds = CredsConfig {
+        copy_claude,
+        copy_codex,
+        ..CredsConfig::default()
+    };
+
+    assert_eq!(names(&creds), expected);
+}
+
+#[rstest]
+fn naming_a_builtin_enables_it_in_registry_order() {
+    let creds = CredsConfig {
+        families: vec![entry("gh"), entry("gemini")],
+        ..CredsConfig::default()
+    };
+
+    assert_eq!(names(&creds), ["claude", "codex", "gemini", "gh"]);
+    assert_eq!(
+        creds.enabled_families().last(),
+        CredentialFamily::builtin("gh").as_ref()
+    );
+}
+
+#[rstest]
+fn entry_overrides_builtin_fields_and_enabled_flag() {
+    let creds = CredsConfig {
+        copy_claude: false,
+        families: vec![
+            CredentialFamilyConfig {
+                enabled: Some(true),
+                sources: vec![Utf8PathBuf::from("/srv/claude")],
+                ..entry("claude")
+            },
+            CredentialFamilyConfig {
+                enabled: Some(false),
+                ..entry("codex")
+            },
+        ],
+        ..CredsConfig::default()
+    };
+
+    let families = creds.enabled_families();
+
+    assert_eq!(
+        families,
+        [CredentialFamily::new(
+            "claude",
+            vec![Utf8PathBuf::from("/srv/claude")],
+            "/root",
+            Some(String::from("claude")),
+        )
+        .with_sync_files(vec![Utf8PathBuf::from(".credentials.json")])]
+    );
+}
+
+#[rstest]
+fn custom_families_follow_builtins_with_default_target() {
+    let creds = CredsConfig {
+        families: vec![CredentialFamilyConfig {
+            sources: vec![Utf8PathBuf::from(".aider")],
+            agent: Some(String::from("aider")),
+            ..entry("aider")
+        }],
+        ..CredsConfig::default()
+    };
+
+    let families = creds.enabled_families();
+
+    assert_eq!(
+        families.last(),
+        Some(&CredentialFamily::new(
+            "aider",
+            vec![Utf8PathBuf::from(".aider")],
+            "/root",
+            Some(String::from("aider")),
+        ))
+    );
+}
+
+#[rstest]
+fn families_parse_from_toml() {
+    let config: AppConfig = toml::from_str(
+        r#"
+        [creds]
+        copy_codex = false
+
+        [[creds.families]]
+        name = "opencode"
+
+        [[creds.families]]
+        name = "aider"
+        sources = [".aider"]
+        target = "/root/.config"
+        "#,
+    )
+    .expect("TOML parsing should succeed");
+
+    assert_eq!(names(&config.creds), ["claude", "opencode", "aider"]);
+    assert!(config.creds.validate_families().is_ok());
+}
+
+#[rstest]
+#[case::empty_name(vec![entry(" ")], "creds.families[0].name")]
+#[case::duplicate(vec![entry("gh"), entry("gh")], "creds.families[1].name")]
+#[case::custom_without_sources(vec![entry("aider")], "creds.families[0].sources")]
+#[case::escaping_source(
+    vec![CredentialFamilyConfig { sources: vec![Utf8PathBuf::from("../etc")], ..entry("aider") }],
+    "creds.families[0].sources"
+)]
+#[case::target_outside_home(
+    vec![CredentialFamilyConfig { target: Some(Utf8PathBuf::from("/etc")), ..entry("gh") }],
+    "creds.families[0].target"
+)]
+#[case::relative_target(
+    vec![CredentialFamilyConfig { target: Some(Utf8PathBuf::from("root")), ..entry("gh") }],
+    "creds.families[0].target"
+)]
+#[case::absolute_include(
+    vec![CredentialFamilyConfig { include: vec![String::from("/etc/*")], ..entry("gh") }],
+    "creds.families[0].include"
+)]
+#[case::escaping_exclude(
+    vec![CredentialFamilyConfig { exclude: vec![String::from("a/../..")], ..entry("gh") }],
+    "creds.families[0].exclude"
+)]
+#[case::blank_exclude(
+    vec![CredentialFamilyConfig { exclude: vec![String::from(" ")], ..entry("gh") }],
+    "creds.families[0].exclude"
+)]
+#[case::escaping_sync_file(
+    vec![CredentialFamilyConfig { sync_files: vec![Utf8PathBuf::from("../auth.json")], ..entry("gh") }],
+    "creds.families[0].sync_files"
+)]
+#[case::absolute_sync_file(
+    vec![CredentialFamilyConfig { sync_files: vec![Utf8PathBuf::from("/etc/passwd")], ..entry("gh") }],
+    "creds.families[0].sync_files"
+)]
+fn malformed_families_are_rejected(
+    #[case] families: Vec<CredentialFamilyConfig>,
+    #[case] expected_field: &str,
+) {
+    let creds = CredsConfig {
+        families,
+        ..CredsConfig::default()
+    };
+
+    assert_eq!(invalid_field(creds), expected_field);
+}
+
+#[rstest]
+fn entry_globs_carry_into_the_family() {
+    let creds = CredsConfig {
+        families: vec![CredentialFamilyConfig {
+            include: vec![String::from("*.json")],
+            exclude: vec![String::from("projects")],
+            ..entry("claude")
+        }],
+        ..CredsConfig::default()
+    };
+
+    let families = creds.enabled_families();
+
+    assert_eq!(
+        families.first().map(CredentialFamily::include),
+        Some([String::from("*.json")].as_slice())
+    );
+    assert_eq!(
+        families.first().map(CredentialFamily::exclude),
+        Some([String::from("projects")].as_slice())
+    );
+}
+
+#[rstest]
+#[case::builtin_default(Vec::new(), &[".credentials.json"])]
+#[case::entry_override(vec![Utf8PathBuf::from("state.json")], &["state.json"])]
+fn sync_files_default_to_the_builtin_credential_files(
+    #[case] sync_files: Vec<Utf8PathBuf>,
+    #[case] expected: &[&str],
+) {
+    let creds = CredsConfig {
+        families: vec![CredentialFamilyConfig {
+            sync_files,
+            ..entry("claude")
+        }],
+        ..CredsConfig::default()
+    };
+
+    let families = creds.enabled_families();
+
+    assert_eq!(
+        families.first().map(|family| {
+            family
+                .sync_files()
+                .iter()
+                .map(|file| file.as_str())
+                .collect::<Vec<_>>()
+        }),
+        Some(expected.to_vec())
+    );
+}
+
+#[rstest]
+fn zero_upload_cap_is_rejected() {
+    let creds = CredsConfig {
+        max_upload_bytes: 0,
+        ..CredsConfig::default()
+    };
+
+    assert_eq!(invalid_field(creds), "creds.max_upload_bytes");
+}
+
+#[rstest]
+#[case::claude(AgentKind::Claude, &["claude", "gh", "tooling"])]
+#[case::codex(AgentKind::Codex, &["codex", "gh", "tooling"])]
+#[case::custom(AgentKind::Custom, &["claude", "codex", "gemini", "gh", "tooling"])]
+fn families_for_agent_keep_shared_families_and_the_agents_own(
+    #[case] kind: AgentKind,
+    #[case] expected: &[&str],
+) {
+    let creds = CredsConfig {
+        families: vec![
+            entry("gemini"),
+            entry("gh"),
+            CredentialFamilyConfig {
+                sources: vec![Utf8PathBuf::from(".tooling")],
+                ..entry("tooling")
+            },
+        ],
+        ..CredsConfig::default()
+    };
+
+    let families = creds.families_for_agent(kind);
+
+    let served: Vec<&str> = families.iter().map(CredentialFamily::name).collect();
+    assert_eq!(served, expected);
+}
@@ -0,0 +1,198 @@
+//! Tests for `[[github.apps]]` profile selection and validation.
+
+use std::borrow::Cow;
+
+use camino::Utf8PathBuf;
+use rstest::rstest;
+
+use crate::config::{AppConfig, CommandIntent, GitHubAppProfile, GitHubConfig, PrivateKeySource};
+use crate::error::{ConfigError, PodbotError};
+
+fn profile(owner: &str, app_id: u64) -> GitHubAppProfile {
+    GitHubAppProfile {
+        owner: String::from(owner),
+        app_id: Some(app_id),
+        private_key_path: Some(Utf8PathBuf::from(format!("/keys/{app_id}.pem"))),
+        ..GitHubAppProfile::default()
+    }
+}
+
+fn config_with_apps(apps: Vec<GitHubAppProfile>) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.github.apps = apps;
+    config
+}
+
+fn invalid_field(result: crate::error::Result<()>) -> String {
+    match result {
+        Err(PodbotError::Config(ConfigError::InvalidValue { field, .. })) => field,
+        other => panic!("expected InvalidValue, got {other:?}"),
+    }
+}
+
+#[rstest]
+#[case::exact("acme", "acme", true)]
+#[case::case_insensitive("Acme", "ACME", true)]
+#[case::prefix_glob("acme-*", "acme-labs", true)]
+#[case::empty_run("acme-*", "acme-", true)]
+#[case::single_char("team?", "team7", true)]
+#[case::single_char_needs_one("team?", "team", false)]
+#[case::other_owner("acme-*", "leynos", false)]
+#[case::everything("*", "leynos", true)]
+fn profile_owner_glob_matching(#[case] glob: &str, #[case] owner: &str, #[case] expected: bool) {
+    assert_eq!(profile(glob, 1).matches_owner(owner), expected);
+}
+
+#[rstest]
+fn for_owner_uses_matching_profile_credentials() {
+    let github = GitHubConfig {
+        app_id: Some(1),
+        installation_id: Some(10),
+        private_key_path: Some(Utf8PathBuf::from("/keys/default.pem")),
+        api_base_url: Some(String::from("https://github.example.com/api/v3")),
+        apps: vec![profile("acme-*", 2), profile("oss-*", 3)],
+        ..GitHubConfig::default()
+    };
+
+    let selected = github.for_owner("oss-tools").expect("one profile matches");
+
+    assert!(matches!(selected, Cow::Owned(_)));
+    assert_eq!(selected.app_id, Some(3));
+    assert_eq!(selected.installation_id, None);
+    assert_eq!(
+        selected.private_key_source().ok(),
+        Some(PrivateKeySource::Path(Utf8PathBuf::from("/keys/3.pem")))
+    );
+    assert_eq!(selected.api_base_url, github.api_base_url);
+    assert!(selected.apps.is_empty());
+}
+
+#[rstest]
+fn for_owner_falls_back_to_top_level_app() {
+    let github = GitHubConfig {
+        app_id: Some(1),
+        apps: vec![profile("acme-*", 2)],
+        ..GitHubConfig::default()
+    };
+
+    let selected = github.for_owner("leynos").expect("fallback should succeed");
+
+    assert!(matches!(selected, Cow::Borrowed(_)));
+    assert_eq!(selected.app_id, Some(1));
+}
+
+#[rstest]
+fn for_owner_rejects_ambiguous_matches() {
+    let github = GitHubConfig {
+        apps: vec![profile("acme-*", 1), profile("*-labs", 2)],
+        ..GitHubConfig::default()
+    };
+
+    let result = github.for_owner("acme-labs").map(drop);
+
+    assert_eq!(invalid_field(result), "github.apps");
+}
+
+#[rstest]
+#[case::duplicate("acme", "ACME")]
+#[case::prefix_and_suffix("acme-*", "*-labs")]
+#[case::wildcard_covers_literal("team?", "team1")]
+#[case::catch_all("*", "oss")]
+fn validation_rejects_overlapping_owner_globs(#[case] first: &str, #[case] second: &str) {
+    let mut config = config_with_apps(vec![profile(first, 1), profile(second, 2)]);
+
+    let field = invalid_field(config.normalize_and_validate(CommandIntent::Any));
+
+    assert_eq!(field, "github.apps[0].owner, github.apps[1].owner");
+}
+
+#[rstest]
+fn validation_accepts_disjoint_owner_globs() {
+    let mut config = config_with_apps(vec![
+        profile("acme-*", 1),
+        profile("oss-*", 2),
+        profile("team?", 3),
+        profile("team", 4),
+    ]);
+
+    config
+        .normalize_and_validate(CommandIntent::Any)
+        .expect("disjoint owner globs should be accepted");
+}
+
+#[rstest]
+#[case::empty("")]
+#[case::whitespace("acme labs")]
+#[case::path("acme/labs")]
+fn validation_rejects_malformed_owner_globs(#[case] owner: &str) {
+    let mut config = config_with_apps(vec![profile(owner, 1)]);
+
+    let field = invalid_field(config.normalize_and_validate(CommandIntent::Any));
+
+    assert_eq!(field, "github.apps[0].owner");
+}
+
+#[rstest]
+fn validation_reports_incomplete_profiles_by_index() {
+    let mut incomplete = profile("oss-*", 2);
+    incomplete.private_key_path = None;
+    let mut config = config_with_apps(vec![profile("acme-*", 1), incomplete]);
+
+    let result = config.normalize_and_validate(CommandIntent::Any);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field == "github.apps[1].private_key_path"
+        ),
+        "expected missing key for the second profile, got {result:?}"
+    );
+}
+
+#[rstest]
+fn validation_reports_conflicting_profile_key_sources_by_index() {
+    let mut conflicting = profile("acme-*", 1);
+    conflicting.private_key_env = Some(String::from("ACME_APP_KEY"));
+    let mut config = config_with_apps(vec![conflicting]);
+
+    let field = invalid_field(config.normalize_and_validate(CommandIntent::Any));
+
+    assert_eq!(
+        field,
+        "github.apps[0].private_key_path, github.apps[0].private_key_env"
+    );
+}
+
+#[rstest]
+fn github_apps_deserialize_from_toml() {
+    let config = toml::from_str::<AppConfig>(
+        r#"
+        [github]
+        app_id = 1
+        private_key_path = "/keys/internal.pem"
+
+        [[github.apps]]
+        owner = "oss-*"
+        app_id = 2
+        installation_id = 20
+        private_key_command = ["pass", "show", "podbot/oss"]
+        "#,
+    )
+    .expect("TOML parsing should succeed");
+
+    assert_eq!(
+        config.github.apps,
+        vec![GitHubAppProfile {
+            owner: String::from("oss-*"),
+            app_id: Some(2),
+            installation_id: Some(20),
+            private_key_command: Some(vec![
+                String::from("pass"),
+                String::from("show"),
+                String::from("podbot/oss"),
+            ]),
+            ..GitHubAppProfile::default()
+        }]
+    );
+}
@@ -50,6 +50,7 @@ pub fn github_config_complete() -> GitHubConfig {
         app_id: Some(12345),
         installation_id: Some(67890),
         private_key_path: Some(Utf8PathBuf::from("/path/to/key.pem")),
+        ..Default::default()
     }
 }
 
@@ -84,6 +85,7 @@ pub fn assert_agent_defaults(config: &AppConfig) {
 pub fn assert_creds_defaults(config: &AppConfig) {
     assert!(config.creds.copy_claude, "creds.copy_claude should be true");
     assert!(config.creds.copy_codex, "creds.copy_codex should be true");
+    assert!(!config.creds.sync_back, "creds.sync_back should be false");
 }
 
 /// Helper: Asserts that a config has all default values.
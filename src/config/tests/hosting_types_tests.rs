@@ -7,8 +7,9 @@ use mockable::MockEnv;
 use rstest::rstest;
 
 use crate::config::{
-    AgentKind, AgentMode, AppConfig, CommandIntent, ConfigLoadOptions, McpAllowedOriginPolicy,
-    McpAuthTokenPolicy, McpBindStrategy, WorkspaceSource, load_config_with_env,
+    AcpConfig, AcpFamilyPolicy, AcpPermissionAction, AcpPermissionRule, AgentKind, AgentMode,
+    AppConfig, CommandIntent, ConfigLoadOptions, McpAllowedOriginPolicy, McpAuthTokenPolicy,
+    McpBindStrategy, WorkspaceSource, load_config_with_env,
 };
 
 #[rstest]
@@ -21,6 +22,9 @@ fn app_config_hosting_defaults_are_explicit() {
     assert!(config.agent.command.is_none());
     assert!(config.agent.args.is_empty());
     assert!(config.agent.env_allowlist.is_empty());
+    assert_eq!(config.agent.acp, AcpConfig::default());
+    assert_eq!(config.agent.acp.fs, AcpFamilyPolicy::Deny);
+    assert_eq!(config.agent.acp.terminal, AcpFamilyPolicy::Deny);
     assert_eq!(config.mcp.bind_strategy, McpBindStrategy::HostGateway);
     assert_eq!(config.mcp.idle_timeout_secs, 900);
     assert_eq!(config.mcp.max_message_size_bytes, 1_048_576);
@@ -122,6 +126,24 @@ fn hosting_toml_deserializes() {
         args = ["acp"]
         env_allowlist = ["OPENAI_API_KEY", "ANTHROPIC_API_KEY"]
 
+        [agent.acp]
+        fs = "delegate"
+        acknowledge_host_delegation = true
+        deny_methods = ["_zed/*"]
+
+        [agent.acp.audit]
+        path = "/var/log/podbot/acp-audit.jsonl"
+        hash_params = true
+
+        [[agent.acp.permissions]]
+        kind = "read"
+        action = "allow_once"
+
+        [[agent.acp.permissions]]
+        kind = "execute"
+        title = "Run `rm *`"
+        action = "reject"
+
         [mcp]
         bind_strategy = "loopback"
         idle_timeout_secs = 30
@@ -148,6 +170,31 @@ fn hosting_toml_deserializes() {
             String::from("ANTHROPIC_API_KEY"),
         ]
     );
+    assert_eq!(config.agent.acp.fs, AcpFamilyPolicy::Delegate);
+    assert_eq!(config.agent.acp.terminal, AcpFamilyPolicy::Deny);
+    assert!(config.agent.acp.acknowledge_host_delegation);
+    assert_eq!(config.agent.acp.deny_methods, vec![String::from("_zed/*")]);
+    assert!(!config.agent.acp.allow_only);
+    assert_eq!(
+        config.agent.acp.audit.path,
+        Some(Utf8PathBuf::from("/var/log/podbot/acp-audit.jsonl"))
+    );
+    assert!(config.agent.acp.audit.hash_params);
+    assert_eq!(
+        config.agent.acp.permissions,
+        vec![
+            AcpPermissionRule {
+                kind: Some(String::from("read")),
+                title: None,
+                action: AcpPermissionAction::AllowOnce,
+            },
+            AcpPermissionRule {
+                kind: Some(String::from("execute")),
+                title: Some(String::from("Run `rm *`")),
+                action: AcpPermissionAction::Reject,
+            },
+        ]
+    );
     assert_eq!(config.mcp.bind_strategy, McpBindStrategy::Loopback);
     assert_eq!(config.mcp.idle_timeout_secs, 30);
     assert_eq!(config.mcp.max_message_size_bytes, 4096);
@@ -4,11 +4,15 @@
 //! - [`helpers`] - Shared fixtures and helper functions
 //! - [`types_tests`] - Basic type and serialization tests
 //! - [`validation`] - `GitHubConfig` validation tests
+//! - [`creds_tests`] - `[creds]` credential-family registry tests
+//! - [`github_apps_tests`] - `[[github.apps]]` selection and validation tests
 //! - [`layer_precedence_tests`] - `MergeComposer` layer precedence tests
 //! - [`hosting_layer_precedence_tests`] - Hosting layer precedence tests
 //! - [`hosting_types_tests`] - Hosting schema defaults and serialization tests
 //! - [`semantic_validation_tests`] - Hosted-era semantic validation tests
 
+mod creds_tests;
+mod github_apps_tests;
 mod helpers;
 mod hosting_layer_precedence_tests;
 mod hosting_types_tests;
@@ -3,7 +3,10 @@
 use camino::Utf8PathBuf;
 use rstest::rstest;
 
-use crate::config::{AgentKind, AgentMode, AppConfig, CommandIntent, WorkspaceSource};
+use crate::config::{
+    AcpAuditConfig, AcpConfig, AcpFamilyPolicy, AcpPermissionAction, AcpPermissionRule, AgentKind,
+    AgentMode, AppConfig, CommandIntent, WorkspaceSource,
+};
 use crate::error::{ConfigError, PodbotError};
 
 struct HostMountCase {
@@ -174,6 +177,224 @@ fn env_allowlist_rejects_empty_or_whitespace_entries() {
     );
 }
 
+#[rstest]
+#[case::dash("OPENAI-API-KEY")]
+#[case::leading_digit("1PASSWORD")]
+#[case::marker_only("!")]
+#[case::assignment("TOKEN=value")]
+fn env_allowlist_rejects_invalid_names(#[case] entry: &str) {
+    let mut config = AppConfig::default();
+    config.agent.env_allowlist = vec![String::from(entry)];
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        "agent.env_allowlist",
+        "must be an environment variable name",
+    );
+}
+
+#[rstest]
+fn env_allowlist_accepts_required_markers() {
+    let mut config = AppConfig::default();
+    config.agent.env_allowlist = vec![String::from("ANTHROPIC_API_KEY!"), String::from("_DEBUG")];
+
+    assert!(config.normalize_and_validate(CommandIntent::Any).is_ok());
+    assert_eq!(
+        config
+            .agent
+            .env_allowlist_entries()
+            .iter()
+            .map(|entry| (entry.name(), entry.required()))
+            .collect::<Vec<_>>(),
+        [("ANTHROPIC_API_KEY", true), ("_DEBUG", false)]
+    );
+}
+
+#[rstest]
+#[case::fs(AcpFamilyPolicy::Delegate, AcpFamilyPolicy::Deny, "`fs`")]
+#[case::terminal(AcpFamilyPolicy::Deny, AcpFamilyPolicy::Delegate, "`terminal`")]
+#[case::both(
+    AcpFamilyPolicy::Delegate,
+    AcpFamilyPolicy::Delegate,
+    "`fs`, `terminal`"
+)]
+fn acp_delegation_requires_acknowledgement(
+    #[case] fs: AcpFamilyPolicy,
+    #[case] terminal: AcpFamilyPolicy,
+    #[case] expected_families: &str,
+) {
+    let mut config = AppConfig::default();
+    config.agent.acp.fs = fs;
+    config.agent.acp.terminal = terminal;
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        "agent.acp.acknowledge_host_delegation",
+        expected_families,
+    );
+}
+
+#[rstest]
+fn acknowledged_acp_delegation_is_accepted() {
+    let mut config = AppConfig::default();
+    config.agent.acp.terminal = AcpFamilyPolicy::Delegate;
+    config.agent.acp.acknowledge_host_delegation = true;
+
+    assert!(config.normalize_and_validate(CommandIntent::Any).is_ok());
+}
+
+fn acp_rules(deny: &[&str], allow: &[&str], allow_only: bool) -> AcpConfig {
+    AcpConfig {
+        deny_methods: deny.iter().copied().map(String::from).collect(),
+        allow_only,
+        allow_methods: allow.iter().copied().map(String::from).collect(),
+        ..AcpConfig::default()
+    }
+}
+
+#[rstest]
+#[case::blank_deny(acp_rules(&[""], &[], false), "agent.acp.deny_methods", "must be an ACP")]
+#[case::bare_wildcard(acp_rules(&["*"], &[], false), "agent.acp.deny_methods", "`*`")]
+#[case::inner_wildcard(
+    acp_rules(&["_zed/*/open"], &[], false),
+    "agent.acp.deny_methods",
+    "`_zed/*/open`"
+)]
+#[case::whitespace(
+    acp_rules(&["session/ new"], &[], false),
+    "agent.acp.deny_methods",
+    "must be an ACP"
+)]
+#[case::bad_allow(acp_rules(&[], &["session*"], true), "agent.acp.allow_methods", "`session*`")]
+#[case::empty_allow_only(acp_rules(&[], &[], true), "agent.acp.allow_methods", "at least one")]
+#[case::allow_without_mode(
+    acp_rules(&[], &["session/*"], false),
+    "agent.acp.allow_methods",
+    "only applies"
+)]
+fn malformed_acp_method_rules_are_rejected(
+    #[case] acp: AcpConfig,
+    #[case] expected_field: &str,
+    #[case] expected_reason: &str,
+) {
+    let mut config = AppConfig::default();
+    config.agent.acp = acp;
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        expected_field,
+        expected_reason,
+    );
+}
+
+#[rstest]
+fn well_formed_acp_method_rules_are_accepted() {
+    let mut config = AppConfig::default();
+    config.agent.acp = acp_rules(
+        &["_zed/*", "session/set_mode"],
+        &["session/*", "initialize"],
+        true,
+    );
+
+    assert!(config.normalize_and_validate(CommandIntent::Any).is_ok());
+}
+
+#[rstest]
+#[case::directory(
+    Some("/var/log/podbot/.."),
+    false,
+    "agent.acp.audit.path",
+    "must name a file"
+)]
+#[case::hash_without_path(None, true, "agent.acp.audit.hash_params", "requires")]
+fn incomplete_acp_audit_is_rejected(
+    #[case] path: Option<&str>,
+    #[case] hash_params: bool,
+    #[case] expected_field: &str,
+    #[case] expected_reason: &str,
+) {
+    let mut config = AppConfig::default();
+    config.agent.acp.audit = AcpAuditConfig {
+        path: path.map(Utf8PathBuf::from),
+        hash_params,
+    };
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        expected_field,
+        expected_reason,
+    );
+}
+
+#[rstest]
+#[case::unknown_kind(
+    Some("shell"),
+    None,
+    "agent.acp.permissions.kind",
+    "not an ACP tool kind"
+)]
+#[case::blank_title(
+    Some("read"),
+    Some("  "),
+    "agent.acp.permissions.title",
+    "must not be empty"
+)]
+fn malformed_acp_permission_rules_are_rejected(
+    #[case] kind: Option<&str>,
+    #[case] title: Option<&str>,
+    #[case] expected_field: &str,
+    #[case] expected_reason: &str,
+) {
+    let mut config = AppConfig::default();
+    config.agent.acp.permissions = vec![AcpPermissionRule {
+        kind: kind.map(String::from),
+        title: title.map(String::from),
+        action: AcpPermissionAction::AllowOnce,
+    }];
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        expected_field,
+        expected_reason,
+    );
+}
+
+#[rstest]
+fn serving_acp_fs_without_a_host_mount_is_rejected() {
+    let mut config = AppConfig::default();
+    config.agent.acp.fs = AcpFamilyPolicy::Serve;
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        "agent.acp.fs",
+        "host_mount",
+    );
+}
+
+#[rstest]
+fn serving_acp_fs_from_a_host_mount_is_accepted() {
+    let mut config = AppConfig::default();
+    config.workspace.source = WorkspaceSource::HostMount;
+    config.workspace.host_path = Some(Utf8PathBuf::from("/tmp/project"));
+    config.agent.acp.fs = AcpFamilyPolicy::Serve;
+
+    config
+        .normalize_and_validate(CommandIntent::Any)
+        .expect("serving fs from a host mount should be valid");
+    assert!(config.agent.acp.delegated_families().is_empty());
+}
+
+#[rstest]
+fn serving_acp_terminal_needs_no_host_mount_or_acknowledgement() {
+    let mut config = AppConfig::default();
+    config.agent.acp.terminal = AcpFamilyPolicy::Serve;
+
+    config
+        .normalize_and_validate(CommandIntent::Any)
+        .expect("serving terminal should be valid");
+    assert!(config.agent.acp.delegated_families().is_empty());
+}
+
 fn assert_invalid_value(
     result: crate::error::Result<()>,
     expected_field: &str,
@@ -192,3 +413,113 @@ fn assert_invalid_value(
         other => panic!("expected ConfigError::InvalidValue, got {other:?}"),
     }
 }
+
+#[rstest]
+#[case::api_http("github.api_base_url", "http://github.example.com/api/v3")]
+#[case::api_relative("github.api_base_url", "/api/v3")]
+#[case::api_query("github.api_base_url", "https://github.example.com/api/v3?x=1")]
+#[case::web_no_scheme("github.web_base_url", "github.example.com")]
+#[case::web_fragment("github.web_base_url", "https://github.example.com/#top")]
+fn github_base_urls_must_be_https(#[case] field: &str, #[case] url: &str) {
+    let mut config = AppConfig::default();
+    if field == "github.api_base_url" {
+        config.github.api_base_url = Some(String::from(url));
+    } else {
+        config.github.web_base_url = Some(String::from(url));
+    }
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        field,
+        "must be an absolute https:// URL",
+    );
+}
+
+#[rstest]
+fn github_enterprise_base_urls_are_accepted() {
+    let mut config = AppConfig::default();
+    config.github.api_base_url = Some(String::from("https://github.example.com/api/v3"));
+    config.github.web_base_url = Some(String::from("https://github.example.com"));
+
+    config
+        .normalize_and_validate(CommandIntent::Any)
+        .expect("HTTPS base URLs should be accepted");
+
+    assert_eq!(
+        config.github.api_base_url_or_default(),
+        "https://github.example.com/api/v3"
+    );
+    assert_eq!(
+        config.github.web_base_url_or_default(),
+        "https://github.example.com"
+    );
+}
+
+#[rstest]
+fn github_private_key_sources_are_exclusive() {
+    let mut config = AppConfig::default();
+    config.github.private_key_path = Some(Utf8PathBuf::from("/keys/app.pem"));
+    config.github.private_key_env = Some(String::from("PODBOT_APP_KEY"));
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        "github.private_key_path, github.private_key_env",
+        "exactly one",
+    );
+}
+
+#[rstest]
+fn github_private_key_passphrase_sources_are_exclusive() {
+    let mut config = AppConfig::default();
+    config.github.private_key_passphrase_env = Some(String::from("PODBOT_KEY_PASSPHRASE"));
+    config.github.private_key_passphrase_file = Some(Utf8PathBuf::from("/keys/passphrase"));
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        "github.private_key_passphrase_env, github.private_key_passphrase_file",
+        "at most one",
+    );
+}
+
+#[rstest]
+#[case::blank_env(
+    |config: &mut AppConfig| config.github.private_key_env = Some(String::from(" ")),
+    "github.private_key_env"
+)]
+#[case::negative_fd(
+    |config: &mut AppConfig| config.github.private_key_fd = Some(-1),
+    "github.private_key_fd"
+)]
+#[case::empty_command(
+    |config: &mut AppConfig| config.github.private_key_command = Some(Vec::new()),
+    "github.private_key_command"
+)]
+#[case::blank_passphrase_env(
+    |config: &mut AppConfig| config.github.private_key_passphrase_env = Some(String::new()),
+    "github.private_key_passphrase_env"
+)]
+#[case::blank_passphrase_file(
+    |config: &mut AppConfig| {
+        config.github.private_key_passphrase_file = Some(Utf8PathBuf::from(" "));
+    },
+    "github.private_key_passphrase_file"
+)]
+#[case::empty_passphrase_command(
+    |config: &mut AppConfig| {
+        config.github.private_key_passphrase_command = Some(vec![String::new()]);
+    },
+    "github.private_key_passphrase_command"
+)]
+fn github_private_key_sources_must_be_usable(
+    #[case] configure: fn(&mut AppConfig),
+    #[case] field: &str,
+) {
+    let mut config = AppConfig::default();
+    configure(&mut config);
+
+    assert_invalid_value(
+        config.normalize_and_validate(CommandIntent::Any),
+        field,
+        field,
+    );
+}
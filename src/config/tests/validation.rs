@@ -9,6 +9,7 @@ fn github_config_validate_succeeds_when_complete() {
         app_id: Some(12345),
         installation_id: Some(67890),
         private_key_path: Some(Utf8PathBuf::from("/path/to/key.pem")),
+        ..Default::default()
     };
     let result = config.validate();
     assert!(
@@ -73,6 +74,7 @@ fn github_config_validate_reports_missing_fields(
         app_id,
         installation_id,
         private_key_path,
+        ..Default::default()
     };
     let result = config.validate();
     let error = result.expect_err("validation should fail with missing fields");
@@ -93,6 +95,7 @@ fn github_config_is_configured_true_when_complete() {
         app_id: Some(12345),
         installation_id: Some(67890),
         private_key_path: Some(Utf8PathBuf::from("/path/to/key.pem")),
+        ..Default::default()
     };
     assert!(config.is_configured());
 }
@@ -109,6 +112,7 @@ fn github_config_is_configured_false_when_partial() {
         app_id: Some(12345),
         installation_id: None,
         private_key_path: Some(Utf8PathBuf::from("/path/to/key.pem")),
+        ..Default::default()
     };
     assert!(!config.is_configured());
 }
@@ -130,6 +134,7 @@ fn github_config_is_partially_configured_matches_any_present_field(
         app_id,
         installation_id,
         private_key_path,
+        ..Default::default()
     };
     assert_eq!(config.is_partially_configured(), expected);
 }
@@ -145,6 +150,136 @@ fn github_config_is_configured_false_when_id_is_zero(
         app_id,
         installation_id,
         private_key_path: Some(Utf8PathBuf::from("/path/to/key.pem")),
+        ..Default::default()
     };
     assert!(!config.is_configured());
 }
+
+#[rstest]
+#[case::without_installation(Some(12345), None, Some("/path/to/key.pem"), None)]
+#[case::zero_installation(Some(12345), Some(0), Some("/path/to/key.pem"), None)]
+#[case::missing_app_id(None, None, Some("/path/to/key.pem"), Some("github.app_id"))]
+#[case::missing_key(Some(12345), Some(67890), None, Some("github.private_key_path"))]
+fn github_config_validate_app_ignores_installation_id(
+    #[case] app_id: Option<u64>,
+    #[case] installation_id: Option<u64>,
+    #[case] private_key_path: Option<&str>,
+    #[case] expected_missing: Option<&str>,
+) {
+    let config = crate::config::GitHubConfig {
+        app_id,
+        installation_id,
+        private_key_path: private_key_path.map(Utf8PathBuf::from),
+        ..Default::default()
+    };
+
+    let missing = match config.validate_app() {
+        Ok(()) => None,
+        Err(crate::error::PodbotError::Config(crate::error::ConfigError::MissingRequired {
+            field,
+        })) => Some(field),
+        Err(other) => panic!("Expected ConfigError::MissingRequired, got: {other:?}"),
+    };
+    assert_eq!(missing.as_deref(), expected_missing);
+}
+
+#[rstest]
+#[case::path(
+    crate::config::GitHubConfig {
+        private_key_path: Some(Utf8PathBuf::from("/keys/app.pem")),
+        ..Default::default()
+    },
+    crate::config::PrivateKeySource::Path(Utf8PathBuf::from("/keys/app.pem"))
+)]
+#[case::env(
+    crate::config::GitHubConfig {
+        private_key_env: Some(String::from("PODBOT_APP_KEY")),
+        ..Default::default()
+    },
+    crate::config::PrivateKeySource::EnvVar(String::from("PODBOT_APP_KEY"))
+)]
+#[case::fd(
+    crate::config::GitHubConfig {
+        private_key_fd: Some(3),
+        ..Default::default()
+    },
+    crate::config::PrivateKeySource::Fd(3)
+)]
+#[case::command(
+    crate::config::GitHubConfig {
+        private_key_command: Some(vec![String::from("vault"), String::from("read")]),
+        ..Default::default()
+    },
+    crate::config::PrivateKeySource::Command(vec![String::from("vault"), String::from("read")])
+)]
+fn github_config_reports_single_private_key_source(
+    #[case] config: crate::config::GitHubConfig,
+    #[case] expected: crate::config::PrivateKeySource,
+) {
+    let source = config
+        .private_key_source()
+        .expect("one private key source should be accepted");
+
+    assert_eq!(source, expected);
+    assert!(config.is_partially_configured());
+}
+
+#[rstest]
+fn github_config_validate_rejects_multiple_private_key_sources() {
+    let config = crate::config::GitHubConfig {
+        app_id: Some(12345),
+        installation_id: Some(67890),
+        private_key_fd: Some(3),
+        private_key_command: Some(vec![String::from("vault")]),
+        ..Default::default()
+    };
+
+    let result = config.validate();
+
+    assert!(
+        matches!(
+            &result,
+            Err(crate::error::PodbotError::Config(
+                crate::error::ConfigError::InvalidValue { field, .. }
+            )) if field == "github.private_key_fd, github.private_key_command"
+        ),
+        "expected conflicting sources to be rejected, got {result:?}"
+    );
+}
+
+#[rstest]
+#[case::none(crate::config::GitHubConfig::default(), None)]
+#[case::env(
+    crate::config::GitHubConfig {
+        private_key_passphrase_env: Some(String::from("PODBOT_KEY_PASSPHRASE")),
+        ..Default::default()
+    },
+    Some(crate::config::PassphraseSource::EnvVar(String::from("PODBOT_KEY_PASSPHRASE")))
+)]
+#[case::file(
+    crate::config::GitHubConfig {
+        private_key_passphrase_file: Some(Utf8PathBuf::from("/keys/passphrase")),
+        ..Default::default()
+    },
+    Some(crate::config::PassphraseSource::File(Utf8PathBuf::from("/keys/passphrase")))
+)]
+#[case::command(
+    crate::config::GitHubConfig {
+        private_key_passphrase_command: Some(vec![String::from("pass"), String::from("podbot")]),
+        ..Default::default()
+    },
+    Some(crate::config::PassphraseSource::Command(vec![
+        String::from("pass"),
+        String::from("podbot"),
+    ]))
+)]
+fn github_config_reports_passphrase_source(
+    #[case] config: crate::config::GitHubConfig,
+    #[case] expected: Option<crate::config::PassphraseSource>,
+) {
+    let source = config
+        .private_key_passphrase_source()
+        .expect("at most one passphrase source should be accepted");
+
+    assert_eq!(source, expected);
+}
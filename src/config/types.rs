@@ -1,5 +1,6 @@
 //! Root configuration types for podbot.
 
+use std::collections::BTreeMap;
 use std::{borrow::Cow, sync::Arc};
 
 use camino::Utf8PathBuf;
@@ -8,7 +9,10 @@ use ortho_config::{MergeLayer, MergeProvenance, OrthoResult, PostMergeContext, P
 use serde::{Deserialize, Serialize};
 use smart_default::SmartDefault;
 
-use crate::config::{AgentConfig, McpConfig, WorkspaceConfig};
+use crate::config::{
+    AgentConfig, CredentialFamilyConfig, DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES, GitHubAppProfile,
+    McpConfig, WorkspaceConfig,
+};
 
 /// How `SELinux` labels should be applied to the container.
 ///
@@ -27,6 +31,59 @@ pub enum SelinuxLabelMode {
     DisableForContainer,
 }
 
+/// Access level for one permission of a scoped installation token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenPermissionLevel {
+    /// Read-only access.
+    Read,
+
+    /// Read and write access.
+    Write,
+
+    /// Administrative access.
+    Admin,
+}
+
+/// REST API base URL used when `github.api_base_url` is unset.
+pub const DEFAULT_GITHUB_API_BASE_URL: &str = "https://api.github.com";
+
+/// Web base URL used for clone remotes when `github.web_base_url` is unset.
+pub const DEFAULT_GITHUB_WEB_BASE_URL: &str = "https://github.com";
+
+/// Where the `GitHub` App private key PEM is read from.
+///
+/// Exactly one source is configured; see [`GitHubConfig::private_key_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivateKeySource {
+    /// A PEM file on the host (`github.private_key_path`).
+    Path(Utf8PathBuf),
+    /// An environment variable holding the PEM (`github.private_key_env`).
+    EnvVar(String),
+    /// An open file descriptor to read the PEM from, such as one passed in by
+    /// a service manager (`github.private_key_fd`).
+    Fd(i32),
+    /// A command, as argv, whose standard output is the PEM
+    /// (`github.private_key_command`).
+    Command(Vec<String>),
+}
+
+/// Where the passphrase for an encrypted PKCS#8 private key is read from.
+///
+/// At most one source is configured; see
+/// [`GitHubConfig::private_key_passphrase_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassphraseSource {
+    /// An environment variable holding the passphrase
+    /// (`github.private_key_passphrase_env`).
+    EnvVar(String),
+    /// A file holding the passphrase (`github.private_key_passphrase_file`).
+    File(Utf8PathBuf),
+    /// A command, as argv, whose standard output is the passphrase
+    /// (`github.private_key_passphrase_command`).
+    Command(Vec<String>),
+}
+
 /// `GitHub` App configuration.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct GitHubConfig {
@@ -38,28 +95,97 @@ pub struct GitHubConfig {
 
     /// Path to the `GitHub` App private key file.
     pub private_key_path: Option<Utf8PathBuf>,
+
+    /// Name of an environment variable holding the private key PEM.
+    pub private_key_env: Option<String>,
+
+    /// File descriptor, inherited from the parent process, to read the
+    /// private key PEM from.
+    pub private_key_fd: Option<i32>,
+
+    /// Command, as argv, whose standard output is the private key PEM, such
+    /// as a secrets-manager CLI.
+    pub private_key_command: Option<Vec<String>>,
+
+    /// Name of an environment variable holding the passphrase for an
+    /// encrypted PKCS#8 private key.
+    pub private_key_passphrase_env: Option<String>,
+
+    /// Path to a file holding the passphrase for an encrypted PKCS#8 private
+    /// key.
+    pub private_key_passphrase_file: Option<Utf8PathBuf>,
+
+    /// Command, as argv, whose standard output is the passphrase for an
+    /// encrypted PKCS#8 private key.
+    pub private_key_passphrase_command: Option<Vec<String>>,
+
+    /// Base URL of the `GitHub` REST API, such as
+    /// `https://github.example.com/api/v3` for `GitHub` Enterprise Server.
+    ///
+    /// Defaults to [`DEFAULT_GITHUB_API_BASE_URL`].
+    pub api_base_url: Option<String>,
+
+    /// Base URL that repositories are cloned from, such as
+    /// `https://github.example.com` for `GitHub` Enterprise Server.
+    ///
+    /// Defaults to [`DEFAULT_GITHUB_WEB_BASE_URL`].
+    pub web_base_url: Option<String>,
+
+    /// Permissions requested for installation tokens, keyed by `GitHub`
+    /// permission name such as `contents` or `pull_requests`.
+    ///
+    /// When empty, tokens carry every permission granted to the installation.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub token_permissions: BTreeMap<String, TokenPermissionLevel>,
+
+    /// Additional `GitHub` Apps selected by repository owner
+    /// (`[[github.apps]]`); see [`Self::for_owner`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<GitHubAppProfile>,
 }
 
 impl GitHubConfig {
     /// Validates that all required `GitHub` fields are present and non-zero.
     ///
-    /// This method checks that `app_id`, `installation_id`, and
-    /// `private_key_path` are all set and that numeric IDs are non-zero. Call
-    /// this before performing `GitHub` operations that require authentication.
+    /// This method checks that `app_id`, `installation_id`, and a private key
+    /// source are all set and that numeric IDs are non-zero. Call this before
+    /// performing `GitHub` operations that require authentication.
     ///
     /// # Errors
     ///
     /// Returns `ConfigError::MissingRequired` when any required field is
-    /// missing or contains the sentinel value `0`.
+    /// missing or contains the sentinel value `0`; a missing private key is
+    /// reported as `github.private_key_path`. Returns
+    /// `ConfigError::InvalidValue` when more than one private key source or
+    /// passphrase source is set.
     pub fn validate(&self) -> crate::error::Result<()> {
+        self.require_fields(true)
+    }
+
+    /// Validates the App credentials needed to look up an installation.
+    ///
+    /// Unlike [`Self::validate`], this accepts an unset `installation_id`,
+    /// for callers that resolve the installation from a repository with the
+    /// App JWT.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` when `app_id` or the private key
+    /// source is missing or `app_id` is `0`, and `ConfigError::InvalidValue`
+    /// when more than one private key source or passphrase source is set.
+    pub fn validate_app(&self) -> crate::error::Result<()> {
+        self.require_fields(false)
+    }
+
+    fn require_fields(&self, require_installation: bool) -> crate::error::Result<()> {
         let mut missing = Vec::new();
         if self.app_id.is_none() || self.app_id == Some(0) {
             missing.push("github.app_id");
         }
-        if self.installation_id.is_none() || self.installation_id == Some(0) {
+        if require_installation && !self.has_installation_id() {
             missing.push("github.installation_id");
         }
-        if self.private_key_path.is_none() {
+        if !self.has_private_key_source() {
             missing.push("github.private_key_path");
         }
         if !missing.is_empty() {
@@ -68,15 +194,145 @@ impl GitHubConfig {
             }
             .into());
         }
-        Ok(())
+        self.validate_private_key_sources()?;
+        self.validate_passphrase_sources()
+    }
+
+    /// Returns the single configured private key source.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` when no source is set and
+    /// `ConfigError::InvalidValue` when more than one is.
+    pub fn private_key_source(&self) -> crate::error::Result<PrivateKeySource> {
+        self.validate_private_key_sources()?;
+        let source = match (
+            &self.private_key_path,
+            &self.private_key_env,
+            self.private_key_fd,
+            &self.private_key_command,
+        ) {
+            (Some(path), _, _, _) => PrivateKeySource::Path(path.clone()),
+            (None, Some(name), _, _) => PrivateKeySource::EnvVar(name.clone()),
+            (None, None, Some(fd), _) => PrivateKeySource::Fd(fd),
+            (None, None, None, Some(argv)) => PrivateKeySource::Command(argv.clone()),
+            (None, None, None, None) => {
+                return Err(crate::error::ConfigError::MissingRequired {
+                    field: String::from("github.private_key_path"),
+                }
+                .into());
+            }
+        };
+        Ok(source)
+    }
+
+    /// Rejects configurations that set more than one private key source.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the conflicting fields.
+    pub fn validate_private_key_sources(&self) -> crate::error::Result<()> {
+        reject_multiple_sources(
+            [
+                ("github.private_key_path", self.private_key_path.is_some()),
+                ("github.private_key_env", self.private_key_env.is_some()),
+                ("github.private_key_fd", self.private_key_fd.is_some()),
+                (
+                    "github.private_key_command",
+                    self.private_key_command.is_some(),
+                ),
+            ],
+            "set exactly one GitHub App private key source",
+        )
+    }
+
+    /// Returns the configured private key passphrase source, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` when more than one passphrase
+    /// source is set.
+    pub fn private_key_passphrase_source(&self) -> crate::error::Result<Option<PassphraseSource>> {
+        self.validate_passphrase_sources()?;
+        let source = self
+            .private_key_passphrase_env
+            .clone()
+            .map(PassphraseSource::EnvVar)
+            .or_else(|| {
+                self.private_key_passphrase_file
+                    .clone()
+                    .map(PassphraseSource::File)
+            })
+            .or_else(|| {
+                self.private_key_passphrase_command
+                    .clone()
+                    .map(PassphraseSource::Command)
+            });
+        Ok(source)
+    }
+
+    /// Rejects configurations that set more than one passphrase source.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the conflicting fields.
+    pub fn validate_passphrase_sources(&self) -> crate::error::Result<()> {
+        reject_multiple_sources(
+            [
+                (
+                    "github.private_key_passphrase_env",
+                    self.private_key_passphrase_env.is_some(),
+                ),
+                (
+                    "github.private_key_passphrase_file",
+                    self.private_key_passphrase_file.is_some(),
+                ),
+                (
+                    "github.private_key_passphrase_command",
+                    self.private_key_passphrase_command.is_some(),
+                ),
+            ],
+            "set at most one GitHub App private key passphrase source",
+        )
+    }
+
+    /// Returns whether any private key source is configured.
+    #[must_use]
+    pub const fn has_private_key_source(&self) -> bool {
+        self.private_key_path.is_some()
+            || self.private_key_env.is_some()
+            || self.private_key_fd.is_some()
+            || self.private_key_command.is_some()
     }
 
     /// Returns whether all `GitHub` credentials are properly configured.
     #[must_use]
     pub fn is_configured(&self) -> bool {
         self.app_id.is_some_and(|v| v != 0)
-            && self.installation_id.is_some_and(|v| v != 0)
-            && self.private_key_path.is_some()
+            && self.has_installation_id()
+            && self.has_private_key_source()
+    }
+
+    /// Returns whether a non-zero `installation_id` is configured.
+    #[must_use]
+    pub fn has_installation_id(&self) -> bool {
+        self.installation_id.is_some_and(|v| v != 0)
+    }
+
+    /// Returns the configured REST API base URL or the public `GitHub` API.
+    #[must_use]
+    pub fn api_base_url_or_default(&self) -> &str {
+        self.api_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_GITHUB_API_BASE_URL)
+    }
+
+    /// Returns the configured web base URL or `https://github.com`.
+    #[must_use]
+    pub fn web_base_url_or_default(&self) -> &str {
+        self.web_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_GITHUB_WEB_BASE_URL)
     }
 
     /// Returns `true` if any `GitHub` credential field has been set.
@@ -86,10 +342,30 @@ impl GitHubConfig {
     /// all required fields are also present.
     #[must_use]
     pub const fn is_partially_configured(&self) -> bool {
-        self.app_id.is_some() || self.installation_id.is_some() || self.private_key_path.is_some()
+        self.app_id.is_some() || self.installation_id.is_some() || self.has_private_key_source()
     }
 }
 
+/// Fails with `ConfigError::InvalidValue` when more than one of `sources` is
+/// set.
+fn reject_multiple_sources<const N: usize>(
+    sources: [(&str, bool); N],
+    reason: &str,
+) -> crate::error::Result<()> {
+    let configured: Vec<&str> = sources
+        .into_iter()
+        .filter_map(|(field, is_set)| is_set.then_some(field))
+        .collect();
+    if configured.len() > 1 {
+        return Err(crate::error::ConfigError::InvalidValue {
+            field: configured.join(", "),
+            reason: String::from(reason),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 /// Sandbox security configuration.
 #[derive(Debug, Clone, SmartDefault, Deserialize, Serialize)]
 #[serde(default)]
@@ -114,6 +390,18 @@ pub struct CredsConfig {
 
     /// Copy `~/.codex` credentials into the container.
     pub copy_codex: bool,
+
+    /// Built-in families to enable or adjust, and custom families to add
+    /// (`[[creds.families]]`).
+    pub families: Vec<CredentialFamilyConfig>,
+
+    /// Most file bytes one credential upload may carry; files that would
+    /// exceed it are skipped and reported.
+    pub max_upload_bytes: u64,
+
+    /// Write credentials the agent refreshed inside the container back to
+    /// the host when the session ends.
+    pub sync_back: bool,
 }
 
 impl Default for CredsConfig {
@@ -121,6 +409,9 @@ impl Default for CredsConfig {
         Self {
             copy_claude: true,
             copy_codex: true,
+            families: Vec::new(),
+            max_upload_bytes: DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES,
+            sync_back: false,
         }
     }
 }
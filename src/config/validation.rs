@@ -1,7 +1,9 @@
 //! Semantic configuration normalization and legality checks.
 
+use crate::config::github_apps::scope_to_app;
 use crate::config::{
-    AgentKind, AgentMode, AppConfig, WorkspaceSource, default_host_mount_container_path,
+    ACP_TOOL_KINDS, AcpConfig, AgentKind, AgentMode, AppConfig, EnvAllowlistEntry, GitHubConfig,
+    WorkspaceSource, default_host_mount_container_path, is_valid_method_rule,
 };
 use crate::error::{ConfigError, Result};
 
@@ -42,12 +44,23 @@ impl AppConfig {
     /// # Errors
     ///
     /// Returns `ConfigError::InvalidValue` when semantic config invariants are
-    /// violated, such as illegal `(command, agent.mode)` combinations or
-    /// missing `host_mount` paths.
+    /// violated, such as illegal `(command, agent.mode)` combinations,
+    /// missing `host_mount` paths, conflicting or malformed `GitHub` private
+    /// key sources, `GitHub` base URLs that are not HTTPS, `[[github.apps]]`
+    /// entries that are incomplete or whose owner globs overlap, malformed
+    /// `[[creds.families]]` entries, `[agent.acp]` host delegation without
+    /// `acknowledge_host_delegation`, an `[agent.acp]` `fs` family served
+    /// without a host-mounted workspace, malformed `[agent.acp]` method rules, an
+    /// `[agent.acp.audit]` table without a log file, or
+    /// `[[agent.acp.permissions]]` rules with an unknown tool kind or a blank
+    /// title. Incomplete `[[github.apps]]` entries are reported as
+    /// `ConfigError::MissingRequired`.
     pub fn normalize_and_validate(&mut self, intent: CommandIntent) -> Result<()> {
         self.apply_dependent_defaults();
+        self.validate_github_config()?;
         self.validate_agent_config()?;
         self.validate_workspace_config()?;
+        self.creds.validate_families()?;
         self.validate_command_intent(intent)
     }
 
@@ -59,8 +72,28 @@ impl AppConfig {
         }
     }
 
+    fn validate_github_config(&self) -> Result<()> {
+        validate_github_key_sources(&self.github)?;
+        validate_https_url("github.api_base_url", self.github.api_base_url.as_deref())?;
+        validate_https_url("github.web_base_url", self.github.web_base_url.as_deref())?;
+        self.github.validate_app_owners()?;
+        for (index, app) in self.github.apps.iter().enumerate() {
+            let github = self.github.with_app(app);
+            github
+                .validate_app()
+                .and_then(|()| validate_github_key_sources(&github))
+                .map_err(|error| scope_to_app(error, index))?;
+        }
+        Ok(())
+    }
+
     fn validate_agent_config(&self) -> Result<()> {
         validate_env_allowlist(&self.agent.env_allowlist)?;
+        validate_acp_delegation(&self.agent.acp)?;
+        validate_acp_served_families(self)?;
+        validate_acp_method_rules(&self.agent.acp)?;
+        validate_acp_audit(&self.agent.acp)?;
+        validate_acp_permissions(&self.agent.acp)?;
 
         match self.agent.kind {
             AgentKind::Custom => validate_custom_agent(self),
@@ -115,6 +148,101 @@ fn is_intent_legal(intent: CommandIntent, mode: AgentMode) -> bool {
     }
 }
 
+fn validate_github_key_sources(github: &GitHubConfig) -> Result<()> {
+    github.validate_private_key_sources()?;
+    validate_private_key_source_values(github)?;
+    github.validate_passphrase_sources()?;
+    validate_passphrase_source_values(github)
+}
+
+fn validate_private_key_source_values(github: &GitHubConfig) -> Result<()> {
+    if github.private_key_env.as_deref().is_some_and(is_blank) {
+        return invalid_value(
+            "github.private_key_env",
+            "github.private_key_env must name an environment variable",
+        );
+    }
+    if github.private_key_fd.is_some_and(|fd| fd < 0) {
+        return invalid_value(
+            "github.private_key_fd",
+            "github.private_key_fd must be a non-negative file descriptor",
+        );
+    }
+    if github
+        .private_key_command
+        .as_deref()
+        .is_some_and(|argv| !names_program(argv))
+    {
+        return invalid_value(
+            "github.private_key_command",
+            "github.private_key_command must start with a program name",
+        );
+    }
+    Ok(())
+}
+
+fn validate_passphrase_source_values(github: &GitHubConfig) -> Result<()> {
+    if github
+        .private_key_passphrase_env
+        .as_deref()
+        .is_some_and(is_blank)
+    {
+        return invalid_value(
+            "github.private_key_passphrase_env",
+            "github.private_key_passphrase_env must name an environment variable",
+        );
+    }
+    if github
+        .private_key_passphrase_file
+        .as_ref()
+        .is_some_and(|path| is_blank(path.as_str()))
+    {
+        return invalid_value(
+            "github.private_key_passphrase_file",
+            "github.private_key_passphrase_file must be a file path",
+        );
+    }
+    if github
+        .private_key_passphrase_command
+        .as_deref()
+        .is_some_and(|argv| !names_program(argv))
+    {
+        return invalid_value(
+            "github.private_key_passphrase_command",
+            "github.private_key_passphrase_command must start with a program name",
+        );
+    }
+    Ok(())
+}
+
+fn is_blank(value: &str) -> bool {
+    value.trim().is_empty()
+}
+
+fn names_program(argv: &[String]) -> bool {
+    argv.first().is_some_and(|program| !is_blank(program))
+}
+
+/// Accept only absolute `https://` URLs with a host and no query or fragment.
+fn validate_https_url(field: &str, value: Option<&str>) -> Result<()> {
+    let Some(url) = value else {
+        return Ok(());
+    };
+    let is_https = url.parse::<http::Uri>().is_ok_and(|uri| {
+        uri.scheme() == Some(&http::uri::Scheme::HTTPS)
+            && uri.host().is_some_and(|host| !host.is_empty())
+            && uri.query().is_none()
+    });
+    if is_https && !url.contains('#') {
+        Ok(())
+    } else {
+        invalid_value(
+            field,
+            format!("{field} must be an absolute https:// URL (got `{url}`)"),
+        )
+    }
+}
+
 fn validate_env_allowlist(values: &[String]) -> Result<()> {
     for value in values {
         if value.trim().is_empty() {
@@ -123,8 +251,109 @@ fn validate_env_allowlist(values: &[String]) -> Result<()> {
                 "agent.env_allowlist entries must not be empty or whitespace only",
             );
         }
+        if !EnvAllowlistEntry::parse(value).has_valid_name() {
+            return invalid_value(
+                "agent.env_allowlist",
+                format!(
+                    "`{value}` must be an environment variable name, optionally followed by `!`"
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Delegating an ACP family moves tool execution out of the sandbox, so it
+/// must be acknowledged explicitly.
+fn validate_acp_delegation(acp: &AcpConfig) -> Result<()> {
+    let delegated = acp.delegated_families();
+    if delegated.is_empty() || acp.acknowledge_host_delegation {
+        return Ok(());
+    }
+    invalid_value(
+        "agent.acp.acknowledge_host_delegation",
+        format!(
+            "delegating ACP `{}` to the host client runs those calls outside the sandbox; set `agent.acp.acknowledge_host_delegation = true` to confirm",
+            delegated.join("`, `")
+        ),
+    )
+}
+
+/// Serving `fs` needs a host-mounted workspace to serve files from.
+fn validate_acp_served_families(config: &AppConfig) -> Result<()> {
+    if config.agent.acp.fs.is_served() && config.workspace.source != WorkspaceSource::HostMount {
+        return invalid_value(
+            "agent.acp.fs",
+            "serving ACP `fs` requires `workspace.source = \"host_mount\"`",
+        );
     }
+    Ok(())
+}
 
+fn validate_acp_method_rules(acp: &AcpConfig) -> Result<()> {
+    for (field, rules) in [
+        ("agent.acp.deny_methods", &acp.deny_methods),
+        ("agent.acp.allow_methods", &acp.allow_methods),
+    ] {
+        if let Some(rule) = rules.iter().find(|rule| !is_valid_method_rule(rule)) {
+            return invalid_value(
+                field,
+                format!("`{rule}` must be an ACP method name or a `prefix/*` method family"),
+            );
+        }
+    }
+    match (acp.allow_only, acp.allow_methods.is_empty()) {
+        (true, true) => invalid_value(
+            "agent.acp.allow_methods",
+            "`agent.acp.allow_only = true` requires at least one `agent.acp.allow_methods` rule",
+        ),
+        (false, false) => invalid_value(
+            "agent.acp.allow_methods",
+            "`agent.acp.allow_methods` only applies when `agent.acp.allow_only = true`",
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn validate_acp_audit(acp: &AcpConfig) -> Result<()> {
+    match acp.audit.path.as_ref() {
+        Some(path) if path.file_name().is_none() => invalid_value(
+            "agent.acp.audit.path",
+            format!("`{path}` must name a file for the ACP audit log"),
+        ),
+        None if acp.audit.hash_params => invalid_value(
+            "agent.acp.audit.hash_params",
+            "`agent.acp.audit.hash_params` requires `agent.acp.audit.path`",
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn validate_acp_permissions(acp: &AcpConfig) -> Result<()> {
+    for rule in &acp.permissions {
+        if let Some(kind) = rule.kind.as_deref()
+            && !ACP_TOOL_KINDS.contains(&kind)
+        {
+            return invalid_value(
+                "agent.acp.permissions.kind",
+                format!(
+                    "`{kind}` is not an ACP tool kind; expected one of `{}`",
+                    ACP_TOOL_KINDS.join("`, `")
+                ),
+            );
+        }
+        if rule
+            .title
+            .as_deref()
+            .is_some_and(|title| title.trim().is_empty())
+        {
+            return invalid_value(
+                "agent.acp.permissions.title",
+                "agent.acp.permissions titles must not be empty or whitespace only",
+            );
+        }
+    }
     Ok(())
 }
 
@@ -0,0 +1,233 @@
+//! Host environment passthrough for agent containers.
+//!
+//! `agent.env_allowlist` names the host variables an agent may see. They are
+//! read through the `mockable::Env` seam and held as secrets, so the resolved
+//! set and the exec and create requests that carry it show variable names but
+//! never values in `Debug` output and tracing. Each value is also registered
+//! with the process-wide [`crate::api::SecretRegistry`] so diagnostics scrub it.
+
+use std::fmt;
+
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::api::{REDACTED, SecretRegistry};
+use crate::config::{AppConfig, EnvAllowlistEntry};
+use crate::error::{ConfigError, PodbotError};
+
+/// Allowlisted host environment variables resolved for one agent launch.
+#[derive(Clone, Default)]
+pub struct AgentEnv {
+    entries: Vec<(String, SecretString)>,
+}
+
+impl AgentEnv {
+    /// Read the allowlisted variables from `env`, in allowlist order.
+    ///
+    /// Variables that are not set are skipped unless their entry is required.
+    /// A name listed more than once is read once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` naming the first required
+    /// variable that is not set on the host.
+    pub fn resolve<E: mockable::Env>(
+        env: &E,
+        allowlist: &[EnvAllowlistEntry],
+    ) -> Result<Self, PodbotError> {
+        let mut entries: Vec<(String, SecretString)> = Vec::new();
+        for entry in allowlist {
+            if entries.iter().any(|(name, _)| name == entry.name()) {
+                continue;
+            }
+            if let Some(value) = read_entry(env, entry)? {
+                entries.push((String::from(entry.name()), value));
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Read the variables named by `config.agent.env_allowlist` from `env`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::resolve`].
+    pub fn from_app_config<E: mockable::Env>(
+        env: &E,
+        config: &AppConfig,
+    ) -> Result<Self, PodbotError> {
+        Self::resolve(env, &config.agent.env_allowlist_entries())
+    }
+
+    /// Return the names of the resolved variables, in allowlist order.
+    #[cfg(any(feature = "internal", test))]
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Return whether no allowlisted variable was set.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append the resolved variables to `env` in `KEY=value` form, leaving
+    /// names `env` already sets untouched.
+    pub(crate) fn merge_into(&self, env: Option<Vec<String>>) -> Option<Vec<String>> {
+        let mut merged = env.unwrap_or_default();
+        for (name, value) in &self.entries {
+            if !merged.iter().any(|existing| env_name(existing) == name) {
+                merged.push(format!("{name}={}", value.expose_secret()));
+            }
+        }
+        (!merged.is_empty()).then_some(merged)
+    }
+}
+
+impl fmt::Debug for AgentEnv {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_map()
+            .entries(self.entries.iter().map(|(name, _)| (name, REDACTED)))
+            .finish()
+    }
+}
+
+/// `Debug` view of a `KEY=value` environment list that hides every value.
+pub(crate) struct RedactedEnv<'a>(pub(crate) Option<&'a [String]>);
+
+impl fmt::Debug for RedactedEnv<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(entries) => formatter
+                .debug_list()
+                .entries(
+                    entries
+                        .iter()
+                        .map(|entry| format!("{}={REDACTED}", env_name(entry))),
+                )
+                .finish(),
+            None => formatter.write_str("None"),
+        }
+    }
+}
+
+fn read_entry<E: mockable::Env>(
+    env: &E,
+    entry: &EnvAllowlistEntry,
+) -> Result<Option<SecretString>, PodbotError> {
+    env.string(entry.name()).map_or_else(
+        || missing_entry(entry),
+        |value| {
+            SecretRegistry::global().register_value(&value);
+            Ok(Some(SecretString::from(value)))
+        },
+    )
+}
+
+fn missing_entry(entry: &EnvAllowlistEntry) -> Result<Option<SecretString>, PodbotError> {
+    if entry.required() {
+        return Err(ConfigError::MissingRequired {
+            field: format!(
+                "host environment variable `{}` (required by agent.env_allowlist)",
+                entry.name()
+            ),
+        }
+        .into());
+    }
+    tracing::debug!(name = entry.name(), "allowlisted variable is not set");
+    Ok(None)
+}
+
+fn env_name(entry: &str) -> &str {
+    entry.split_once('=').map_or(entry, |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for allowlisted environment resolution and redaction.
+
+    use std::collections::HashMap;
+
+    use mockable::MockEnv;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn host_env(values: &[(&'static str, &'static str)]) -> MockEnv {
+        let lookup: HashMap<&str, &str> = values.iter().copied().collect();
+        let mut env = MockEnv::new();
+        env.expect_string()
+            .returning(move |key| lookup.get(key).map(|value| String::from(*value)));
+        env
+    }
+
+    fn allowlist(entries: &[&str]) -> Vec<EnvAllowlistEntry> {
+        entries
+            .iter()
+            .map(|entry| EnvAllowlistEntry::parse(entry))
+            .collect()
+    }
+
+    #[rstest]
+    fn resolve_skips_unset_optional_variables() {
+        let env = host_env(&[("OPENAI_API_KEY", "sk-openai")]);
+
+        let agent_env = AgentEnv::resolve(&env, &allowlist(&["OPENAI_API_KEY", "GH_TOKEN"]))
+            .expect("optional variables may be missing");
+
+        assert_eq!(agent_env.names().collect::<Vec<_>>(), ["OPENAI_API_KEY"]);
+    }
+
+    #[rstest]
+    fn resolve_fails_when_a_required_variable_is_unset() {
+        let env = host_env(&[]);
+
+        let result = AgentEnv::resolve(&env, &allowlist(&["ANTHROPIC_API_KEY!"]));
+
+        assert!(
+            matches!(
+                &result,
+                Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                    if field.contains("ANTHROPIC_API_KEY")
+            ),
+            "expected missing ANTHROPIC_API_KEY, got {result:?}"
+        );
+    }
+
+    #[rstest]
+    fn merge_keeps_explicit_entries_and_appends_the_rest() {
+        let env = host_env(&[("TERM", "xterm"), ("API_KEY", "secret")]);
+        let agent_env =
+            AgentEnv::resolve(&env, &allowlist(&["TERM", "API_KEY"])).expect("variables are set");
+
+        let merged = agent_env.merge_into(Some(vec![String::from("TERM=dumb")]));
+
+        assert_eq!(
+            merged,
+            Some(vec![
+                String::from("TERM=dumb"),
+                String::from("API_KEY=secret")
+            ])
+        );
+    }
+
+    #[rstest]
+    fn debug_output_names_variables_without_values() {
+        let env = host_env(&[("API_KEY", "secret")]);
+        let agent_env =
+            AgentEnv::resolve(&env, &allowlist(&["API_KEY!"])).expect("variable is set");
+        let entries = agent_env.merge_into(None);
+
+        let rendered = format!("{agent_env:?} {:?}", RedactedEnv(entries.as_deref()));
+
+        assert!(
+            rendered.contains("API_KEY"),
+            "names should remain: {rendered}"
+        );
+        assert!(
+            !rendered.contains("secret"),
+            "values must be hidden: {rendered}"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Tar archive extraction into capability-scoped host directories.
+
+use std::io;
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use cap_std::fs_utf8::Dir;
+use tar::{Archive, Entry, EntryType};
+
+use super::ArchiveSource;
+
+/// Extract an archive rooted at `root.archive_name` into `host_dir`.
+///
+/// The archive root is renamed to `root.host_name`, so a container directory
+/// can land under a different host name. Entries outside the root, absolute
+/// paths, and `..` components are rejected. Symbolic links must have relative
+/// targets without `..` components, and no later entry may be written at or
+/// beneath a link created during the extraction, so a link cannot redirect a
+/// write outside the destination. Regular files and directories keep their
+/// archived permission bits, without setuid, setgid, or sticky bits; entry
+/// types other than files, directories, and symbolic links are skipped.
+/// Returns the number of regular-file bytes written.
+pub(crate) fn extract_tar_archive(
+    host_dir: &Dir,
+    archive_bytes: &[u8],
+    root: &ArchiveSource<'_>,
+) -> io::Result<u64> {
+    let mut archive = Archive::new(archive_bytes);
+    let mut extracted = Extraction::default();
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        let host_path = host_entry_path(&entry, root)?;
+        extracted.extract_entry(host_dir, &mut entry, &host_path)?;
+    }
+
+    extracted.apply_directory_modes(host_dir)?;
+    Ok(extracted.bytes_written)
+}
+
+/// Permission bits kept from archived modes; setuid, setgid, and sticky bits
+/// set inside the container are never applied on the host.
+const PERMISSION_BITS: u32 = 0o777;
+
+#[derive(Default)]
+struct Extraction {
+    bytes_written: u64,
+    directory_modes: Vec<(Utf8PathBuf, u32)>,
+    symlinks: Vec<Utf8PathBuf>,
+}
+
+impl Extraction {
+    fn extract_entry(
+        &mut self,
+        host_dir: &Dir,
+        entry: &mut Entry<'_, &[u8]>,
+        host_path: &Utf8Path,
+    ) -> io::Result<()> {
+        let mode = entry.header().mode()? & PERMISSION_BITS;
+        self.reject_symlinked_path(host_path)?;
+        match entry.header().entry_type() {
+            EntryType::Directory => {
+                host_dir.create_dir_all(host_path)?;
+                // Apply directory modes last so a read-only directory does not
+                // block writing its own children.
+                self.directory_modes.push((host_path.to_path_buf(), mode));
+            }
+            EntryType::Regular | EntryType::Continuous => {
+                create_parent_dirs(host_dir, host_path)?;
+                let mut file = host_dir.create(host_path)?;
+                let written = io::copy(entry, &mut file)?;
+                self.bytes_written = self.bytes_written.saturating_add(written);
+                set_mode(host_dir, host_path, mode)?;
+            }
+            EntryType::Symlink => {
+                let target = entry
+                    .link_name()?
+                    .ok_or_else(|| io::Error::other("symbolic link entry has no target"))?;
+                let target_path = confined_link_target(utf8_path(&target)?, host_path)?;
+                create_parent_dirs(host_dir, host_path)?;
+                create_symlink(host_dir, target_path, host_path)?;
+                self.symlinks.push(host_path.to_path_buf());
+            }
+            other => {
+                tracing::debug!(
+                    path = %host_path,
+                    entry_type = ?other,
+                    "skipping unsupported archive entry type"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse to write at or beneath a symbolic link this extraction created,
+    /// since cap-std would follow it.
+    fn reject_symlinked_path(&self, host_path: &Utf8Path) -> io::Result<()> {
+        self.symlinks
+            .iter()
+            .find(|link| host_path.starts_with(link))
+            .map_or(Ok(()), |link| {
+                Err(io::Error::other(format!(
+                    "archive entry '{host_path}' would be written through symbolic link '{link}'"
+                )))
+            })
+    }
+
+    fn apply_directory_modes(&self, host_dir: &Dir) -> io::Result<()> {
+        for (path, mode) in self.directory_modes.iter().rev() {
+            set_mode(host_dir, path, *mode)?;
+        }
+        Ok(())
+    }
+}
+
+/// Map an archive entry rooted at `root.archive_name` to its path beneath
+/// `root.host_name`, rejecting entries outside the root or escaping it.
+pub(crate) fn host_entry_path(
+    entry: &Entry<'_, &[u8]>,
+    root: &ArchiveSource<'_>,
+) -> io::Result<Utf8PathBuf> {
+    let raw_path = entry.path()?;
+    let archive_path = utf8_path(&raw_path)?;
+    let mut components = archive_path.components();
+
+    match components.next() {
+        Some(Utf8Component::Normal(first)) if first == root.archive_name => {}
+        _ => {
+            return Err(io::Error::other(format!(
+                "archive entry '{archive_path}' is outside '{}'",
+                root.archive_name
+            )));
+        }
+    }
+
+    let mut host_path = Utf8PathBuf::from(root.host_name);
+    for component in components {
+        match component {
+            Utf8Component::Normal(segment) => host_path.push(segment),
+            Utf8Component::CurDir => {}
+            Utf8Component::ParentDir | Utf8Component::RootDir | Utf8Component::Prefix(_) => {
+                return Err(io::Error::other(format!(
+                    "archive entry '{archive_path}' escapes the destination"
+                )));
+            }
+        }
+    }
+    Ok(host_path)
+}
+
+fn utf8_path(path: &std::path::Path) -> io::Result<&Utf8Path> {
+    Utf8Path::from_path(path).ok_or_else(|| {
+        io::Error::other(format!(
+            "archive path '{}' is not valid UTF-8",
+            path.display()
+        ))
+    })
+}
+
+/// Accept only relative link targets without `..` components, so a link
+/// cannot point above the directory that contains it.
+fn confined_link_target<'a>(target: &'a Utf8Path, link: &Utf8Path) -> io::Result<&'a Utf8Path> {
+    let escapes = target
+        .components()
+        .any(|component| !matches!(component, Utf8Component::Normal(_) | Utf8Component::CurDir));
+    if escapes {
+        return Err(io::Error::other(format!(
+            "symbolic link '{link}' points outside the destination via '{target}'"
+        )));
+    }
+    Ok(target)
+}
+
+fn create_parent_dirs(host_dir: &Dir, host_path: &Utf8Path) -> io::Result<()> {
+    match host_path.parent() {
+        Some(parent) if !parent.as_str().is_empty() => host_dir.create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(host_dir: &Dir, target: &Utf8Path, link: &Utf8Path) -> io::Result<()> {
+    host_dir.symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_host_dir: &Dir, target: &Utf8Path, link: &Utf8Path) -> io::Result<()> {
+    tracing::debug!(%link, %target, "skipping symbolic link on non-Unix host");
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(host_dir: &Dir, path: &Utf8Path, mode: u32) -> io::Result<()> {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    host_dir.set_permissions(path, Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_host_dir: &Dir, _path: &Utf8Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
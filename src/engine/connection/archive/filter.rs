@@ -0,0 +1,196 @@
+//! Include and exclude globs that select entries beneath an archive source.
+//!
+//! Patterns are matched against `/`-separated paths relative to the source
+//! directory and are anchored at its root. `*` matches any run of characters
+//! within one path segment, `?` matches one character, and a `**` segment
+//! matches any number of segments. A pattern that matches a directory selects
+//! everything beneath it.
+
+use camino::Utf8Path;
+
+use crate::glob::{sequence_matches, wildcard_matches};
+
+/// Entry selection for one archive source.
+///
+/// With no include patterns every entry is included. Exclude patterns win
+/// over include patterns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct EntryFilter {
+    include: Vec<PathGlob>,
+    exclude: Vec<PathGlob>,
+}
+
+impl EntryFilter {
+    /// Build a filter from include and exclude patterns.
+    #[cfg(any(feature = "internal", test))]
+    pub(crate) fn new(include: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: include
+                .iter()
+                .map(|pattern| PathGlob::new(pattern))
+                .collect(),
+            exclude: exclude
+                .iter()
+                .map(|pattern| PathGlob::new(pattern))
+                .collect(),
+        }
+    }
+
+    /// Returns whether the file or symbolic link at `path` is selected.
+    pub(crate) fn accepts_file(&self, path: &Utf8Path) -> bool {
+        let segments = segments(path);
+        !self.is_excluded(&segments)
+            && (self.include.is_empty()
+                || self
+                    .include
+                    .iter()
+                    .any(|glob| glob.matches_path_or_ancestor(&segments)))
+    }
+
+    /// Returns whether the directory at `path` should be archived and
+    /// descended into, because it is not excluded and may hold selected
+    /// entries.
+    pub(crate) fn accepts_directory(&self, path: &Utf8Path) -> bool {
+        let segments = segments(path);
+        !self.is_excluded(&segments)
+            && (self.include.is_empty()
+                || self.include.iter().any(|glob| {
+                    glob.matches_path_or_ancestor(&segments) || glob.may_match_below(&segments)
+                }))
+    }
+
+    fn is_excluded(&self, segments: &[&str]) -> bool {
+        self.exclude
+            .iter()
+            .any(|glob| glob.matches_path_or_ancestor(segments))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PathGlob {
+    segments: Vec<String>,
+}
+
+impl PathGlob {
+    #[cfg(any(feature = "internal", test))]
+    fn new(pattern: &str) -> Self {
+        Self {
+            segments: pattern
+                .split('/')
+                .filter(|segment| !segment.is_empty() && *segment != ".")
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    fn matches_path_or_ancestor(&self, path: &[&str]) -> bool {
+        (1..=path.len()).any(|length| {
+            path.get(..length)
+                .is_some_and(|prefix| matches_segments(&self.segments, prefix))
+        })
+    }
+
+    /// Returns whether some path beneath the directory `path` could match.
+    fn may_match_below(&self, path: &[&str]) -> bool {
+        prefix_may_match(&self.segments, path)
+    }
+}
+
+fn segments(path: &Utf8Path) -> Vec<&str> {
+    path.as_str()
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Match `path` against `pattern`, treating a `**` segment as any run of
+/// segments.
+fn matches_segments(pattern: &[String], path: &[&str]) -> bool {
+    sequence_matches(
+        pattern,
+        path,
+        |glob| glob == "**",
+        |glob, segment| wildcard_matches(glob, segment),
+    )
+}
+
+/// Returns whether `pattern` can match a path that extends `directory`.
+fn prefix_may_match(pattern: &[String], directory: &[&str]) -> bool {
+    match (pattern.split_first(), directory.split_first()) {
+        (Some((glob, _)), _) if glob == "**" => true,
+        (Some(_), None) => true,
+        (Some((glob, rest)), Some((segment, directory_rest))) => {
+            wildcard_matches(glob, segment) && prefix_may_match(rest, directory_rest)
+        }
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for archive include and exclude globs.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> EntryFilter {
+        let owned = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .copied()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        EntryFilter::new(&owned(include), &owned(exclude))
+    }
+
+    #[rstest]
+    #[case::no_patterns(&[], &[], "projects/a.jsonl", true)]
+    #[case::included_file(&["settings.json"], &[], "settings.json", true)]
+    #[case::anchored_include(&["settings.json"], &[], "nested/settings.json", false)]
+    #[case::any_depth(&["**/settings.json"], &[], "nested/settings.json", true)]
+    #[case::star(&["*.json"], &[], ".credentials.json", true)]
+    #[case::star_stays_in_segment(&["*.json"], &[], "projects/a.json", false)]
+    #[case::question_mark(&["auth.???"], &[], "auth.pem", true)]
+    #[case::included_directory(&["statsig"], &[], "statsig/cache", true)]
+    #[case::excluded_directory(&[], &["projects"], "projects/a.jsonl", false)]
+    #[case::exclude_wins(&["*.json"], &["secret.json"], "secret.json", false)]
+    fn files_are_selected_by_globs(
+        #[case] include: &[&str],
+        #[case] exclude: &[&str],
+        #[case] path: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            filter(include, exclude).accepts_file(Utf8Path::new(path)),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn repeated_wildcards_do_not_backtrack_exponentially() {
+        let include = format!("{}b", "**/a*/".repeat(20));
+        let path = vec!["a"; 60].join("/");
+
+        assert!(!filter(&[&include], &[]).accepts_file(Utf8Path::new(&path)));
+    }
+
+    #[rstest]
+    #[case::unrelated(&["settings.json"], &[], "projects", false)]
+    #[case::on_the_way(&["config/*.toml"], &[], "config", true)]
+    #[case::double_star(&["**/auth.json"], &[], "deep/er", true)]
+    #[case::excluded(&[], &["cache"], "cache", false)]
+    #[case::everything(&[], &[], "projects", true)]
+    fn directories_are_descended_when_they_may_hold_selected_entries(
+        #[case] include: &[&str],
+        #[case] exclude: &[&str],
+        #[case] path: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            filter(include, exclude).accepts_directory(Utf8Path::new(path)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! Tar archive helpers shared by credential upload and file copy.
+//!
+//! Archives are built from capability-scoped host [`Dir`] handles and extracted
+//! back into them, so host access never escapes the directory the caller
+//! opened.
+
+use std::collections::BTreeSet;
+use std::io;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use cap_std::fs::Metadata;
+use cap_std::fs_utf8::Dir;
+use tar::{Builder, EntryType, Header};
+
+mod extract;
+mod filter;
+
+pub(crate) use extract::extract_tar_archive;
+#[cfg(any(feature = "internal", test))]
+pub(crate) use extract::host_entry_path;
+pub(crate) use filter::EntryFilter;
+
+const DEFAULT_DIRECTORY_MODE: u32 = 0o755;
+const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Host entry to include in an archive and the name it is stored under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ArchiveSource<'a> {
+    /// Entry name inside the host directory handle.
+    pub(crate) host_name: &'a str,
+    /// Root path recorded for the entry inside the archive.
+    pub(crate) archive_name: &'a str,
+}
+
+#[cfg(test)]
+impl<'a> ArchiveSource<'a> {
+    /// Archive `name` under the same name it has on the host.
+    pub(crate) const fn same_name(name: &'a str) -> Self {
+        Self {
+            host_name: name,
+            archive_name: name,
+        }
+    }
+}
+
+/// Build an in-memory tar archive containing the selected host entries.
+///
+/// Sources may be directories, regular files, or symbolic links. Directory
+/// and file entries preserve source mode bits where available. Construction
+/// fails once the regular-file payload would exceed `max_bytes`.
+pub(crate) fn build_tar_archive(
+    host_dir: &Dir,
+    sources: &[ArchiveSource<'_>],
+    max_bytes: u64,
+) -> io::Result<Vec<u8>> {
+    let mut writer = ArchiveWriter::new(max_bytes, OverflowPolicy::Fail);
+    let accept_all = EntryFilter::default();
+
+    for source in sources {
+        writer.append_source(host_dir, source, &accept_all)?;
+    }
+
+    writer.finish().map(|archive| archive.archive_bytes)
+}
+
+/// Host entry to archive together with the filter selecting what beneath it
+/// is included.
+#[cfg(any(feature = "internal", test))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FilteredArchiveSource<'a> {
+    /// Directory handle the source is read from.
+    pub(crate) host_dir: &'a Dir,
+    /// Source entry and archive name.
+    pub(crate) source: ArchiveSource<'a>,
+    /// Selection applied to paths relative to the source.
+    pub(crate) filter: &'a EntryFilter,
+}
+
+/// Why an entry was left out of a budgeted archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SkipReason {
+    /// The source's filter did not select the entry.
+    Filtered,
+    /// The file did not fit in the remaining byte budget.
+    OverBudget,
+}
+
+/// Entry left out of a budgeted archive, by its would-be archive path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SkippedArchiveEntry {
+    pub(crate) archive_path: String,
+    pub(crate) reason: SkipReason,
+}
+
+/// Archive bytes and the entries left out while building them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BudgetedArchive {
+    pub(crate) archive_bytes: Vec<u8>,
+    pub(crate) skipped_entries: Vec<SkippedArchiveEntry>,
+}
+
+/// Build an in-memory tar archive of filtered sources within a byte budget.
+///
+/// Each source is read from its own directory handle. When an archive name is
+/// nested, its parent directories are recorded first with default permissions
+/// so extraction can create them. Entries a source's filter rejects are
+/// skipped; a rejected directory is reported once and not descended into.
+/// Regular files that would take the payload past `max_bytes` are skipped
+/// rather than failing the build, so smaller files later in the walk can
+/// still fit. Both kinds of skip are reported in walk order.
+#[cfg(any(feature = "internal", test))]
+pub(crate) fn build_budgeted_tar_archive(
+    sources: &[FilteredArchiveSource<'_>],
+    max_bytes: u64,
+) -> io::Result<BudgetedArchive> {
+    let mut writer = ArchiveWriter::new(max_bytes, OverflowPolicy::Skip);
+
+    for filtered in sources {
+        writer.append_source(filtered.host_dir, &filtered.source, filtered.filter)?;
+    }
+
+    writer.finish()
+}
+
+/// What to do with a regular file that does not fit in the byte budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverflowPolicy {
+    Fail,
+    #[cfg_attr(
+        not(any(feature = "internal", test)),
+        expect(
+            dead_code,
+            reason = "budgeted archives are built by internal credential upload"
+        )
+    )]
+    Skip,
+}
+
+struct ArchiveWriter {
+    builder: Builder<Vec<u8>>,
+    remaining_bytes: u64,
+    max_bytes: u64,
+    overflow: OverflowPolicy,
+    /// Parent directories of nested archive names already recorded.
+    parent_dirs: BTreeSet<Utf8PathBuf>,
+    skipped_entries: Vec<SkippedArchiveEntry>,
+}
+
+/// Position of an entry both inside the archive and relative to its source,
+/// together with the source's filter, which matches the relative path.
+struct EntryContext<'a> {
+    archive: &'a Utf8Path,
+    source: &'a Utf8Path,
+    filter: &'a EntryFilter,
+}
+
+impl ArchiveWriter {
+    fn new(max_bytes: u64, overflow: OverflowPolicy) -> Self {
+        Self {
+            builder: Builder::new(vec![]),
+            remaining_bytes: max_bytes,
+            max_bytes,
+            overflow,
+            parent_dirs: BTreeSet::new(),
+            skipped_entries: vec![],
+        }
+    }
+
+    fn finish(mut self) -> io::Result<BudgetedArchive> {
+        self.builder.finish()?;
+        Ok(BudgetedArchive {
+            archive_bytes: self.builder.into_inner()?,
+            skipped_entries: self.skipped_entries,
+        })
+    }
+
+    fn skip(&mut self, archive_path: &Utf8Path, reason: SkipReason) {
+        self.skipped_entries.push(SkippedArchiveEntry {
+            archive_path: normalize_archive_path(archive_path),
+            reason,
+        });
+    }
+
+    fn append_source(
+        &mut self,
+        host_dir: &Dir,
+        source: &ArchiveSource<'_>,
+        filter: &EntryFilter,
+    ) -> io::Result<()> {
+        let archive_path = Utf8PathBuf::from(source.archive_name);
+        self.append_parent_directories(&archive_path)?;
+        // Follow a top-level symlink so the caller receives what the path
+        // points at, matching how the credential directories were resolved.
+        let metadata = host_dir.metadata(source.host_name)?;
+        let file_type = metadata.file_type();
+        let entry = SortedEntry {
+            file_name: String::from(source.host_name),
+            entry_kind: classify_entry_kind(
+                file_type.is_dir(),
+                file_type.is_file(),
+                file_type.is_symlink(),
+            ),
+        };
+
+        match entry.entry_kind {
+            EntryKind::Directory => {
+                append_directory_header(&mut self.builder, &archive_path, &metadata)?;
+                let source_dir = host_dir.open_dir(source.host_name)?;
+                let context = EntryContext {
+                    archive: &archive_path,
+                    source: Utf8Path::new(""),
+                    filter,
+                };
+                self.append_directory_contents(&source_dir, &context)
+            }
+            EntryKind::File | EntryKind::Symlink => {
+                self.append_non_directory_entry(host_dir, &entry, &archive_path)
+            }
+            EntryKind::Other => Err(io::Error::other(format!(
+                "'{}' is not a file, directory, or symbolic link",
+                source.host_name
+            ))),
+        }
+    }
+
+    fn append_parent_directories(&mut self, archive_path: &Utf8Path) -> io::Result<()> {
+        let mut parents: Vec<&Utf8Path> = archive_path
+            .ancestors()
+            .skip(1)
+            .filter(|parent| !parent.as_str().is_empty())
+            .collect();
+        parents.reverse();
+        for parent in parents {
+            if self.parent_dirs.insert(parent.to_path_buf()) {
+                let mut header = new_entry_header(EntryType::Directory, 0, DEFAULT_DIRECTORY_MODE);
+                let path = format!("{}/", normalize_archive_path(parent));
+                self.builder.append_data(&mut header, path, io::empty())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn append_directory_contents(
+        &mut self,
+        current_dir: &Dir,
+        current: &EntryContext<'_>,
+    ) -> io::Result<()> {
+        for entry in sorted_entries(current_dir)? {
+            let child = EntryContext {
+                archive: &current.archive.join(&entry.file_name),
+                source: &current.source.join(&entry.file_name),
+                filter: current.filter,
+            };
+            self.append_directory_entry(current_dir, &entry, &child)?;
+        }
+
+        Ok(())
+    }
+
+    fn append_directory_entry(
+        &mut self,
+        current_dir: &Dir,
+        entry: &SortedEntry,
+        paths: &EntryContext<'_>,
+    ) -> io::Result<()> {
+        match entry.entry_kind {
+            EntryKind::Directory if !paths.filter.accepts_directory(paths.source) => {
+                self.skip(paths.archive, SkipReason::Filtered);
+                Ok(())
+            }
+            EntryKind::Directory => {
+                let metadata = current_dir.metadata(&entry.file_name)?;
+                append_directory_header(&mut self.builder, paths.archive, &metadata)?;
+                let child_dir = current_dir.open_dir(&entry.file_name)?;
+                self.append_directory_contents(&child_dir, paths)
+            }
+            EntryKind::File | EntryKind::Symlink if !paths.filter.accepts_file(paths.source) => {
+                self.skip(paths.archive, SkipReason::Filtered);
+                Ok(())
+            }
+            EntryKind::File | EntryKind::Symlink => {
+                self.append_non_directory_entry(current_dir, entry, paths.archive)
+            }
+            EntryKind::Other => Ok(()),
+        }
+    }
+
+    fn append_non_directory_entry(
+        &mut self,
+        parent_dir: &Dir,
+        entry: &SortedEntry,
+        relative_path: &Utf8Path,
+    ) -> io::Result<()> {
+        let path = normalize_archive_path(relative_path);
+        match entry.entry_kind {
+            EntryKind::File => self.append_file_entry(parent_dir, entry, path),
+            EntryKind::Symlink => append_symlink_entry(&mut self.builder, parent_dir, entry, path),
+            EntryKind::Directory | EntryKind::Other => Err(io::Error::other(
+                "non-directory entry helper received invalid entry kind",
+            )),
+        }
+    }
+
+    fn append_file_entry(
+        &mut self,
+        parent_dir: &Dir,
+        entry: &SortedEntry,
+        path: String,
+    ) -> io::Result<()> {
+        let mut file = parent_dir.open(&entry.file_name)?;
+        // Read the metadata from the open handle so the header size cannot drift
+        // from the bytes actually streamed if the file changes on disk between
+        // the two calls.
+        let metadata = file.metadata()?;
+        let Some(remaining_bytes) = self.remaining_bytes.checked_sub(metadata.len()) else {
+            return match self.overflow {
+                OverflowPolicy::Fail => Err(io::Error::other(format!(
+                    "archive exceeds the {}-byte limit at '{path}'",
+                    self.max_bytes
+                ))),
+                OverflowPolicy::Skip => {
+                    self.skip(Utf8Path::new(&path), SkipReason::OverBudget);
+                    Ok(())
+                }
+            };
+        };
+        self.remaining_bytes = remaining_bytes;
+        let mut header = new_entry_header(
+            EntryType::Regular,
+            metadata.len(),
+            metadata_mode(&metadata, DEFAULT_FILE_MODE),
+        );
+
+        self.builder.append_data(&mut header, path, &mut file)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    Directory,
+    File,
+    Symlink,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SortedEntry {
+    file_name: String,
+    entry_kind: EntryKind,
+}
+
+fn sorted_entries(directory: &Dir) -> io::Result<Vec<SortedEntry>> {
+    let mut entries = vec![];
+
+    for entry_result in directory.entries()? {
+        let entry = entry_result?;
+        let file_name = entry.file_name()?;
+        let file_type = entry.file_type()?;
+        let entry_kind = classify_entry_kind(
+            file_type.is_dir(),
+            file_type.is_file(),
+            file_type.is_symlink(),
+        );
+
+        entries.push(SortedEntry {
+            file_name,
+            entry_kind,
+        });
+    }
+
+    entries.sort_unstable_by(|left, right| left.file_name.cmp(&right.file_name));
+    Ok(entries)
+}
+
+const fn classify_entry_kind(is_directory: bool, is_file: bool, is_symlink: bool) -> EntryKind {
+    if is_directory {
+        return EntryKind::Directory;
+    }
+
+    if is_file {
+        return EntryKind::File;
+    }
+
+    if is_symlink {
+        return EntryKind::Symlink;
+    }
+
+    EntryKind::Other
+}
+
+fn append_directory_header(
+    builder: &mut Builder<Vec<u8>>,
+    relative_path: &Utf8Path,
+    metadata: &Metadata,
+) -> io::Result<()> {
+    let mut header = new_entry_header(
+        EntryType::Directory,
+        0,
+        metadata_mode(metadata, DEFAULT_DIRECTORY_MODE),
+    );
+
+    let path = format!("{}/", normalize_archive_path(relative_path));
+    builder.append_data(&mut header, path, io::empty())
+}
+
+fn append_symlink_entry(
+    builder: &mut Builder<Vec<u8>>,
+    parent_dir: &Dir,
+    entry: &SortedEntry,
+    path: String,
+) -> io::Result<()> {
+    let metadata = parent_dir.symlink_metadata(&entry.file_name)?;
+    let target = parent_dir.read_link_contents(&entry.file_name)?;
+    let mut header = new_entry_header(
+        EntryType::Symlink,
+        0,
+        metadata_mode(&metadata, DEFAULT_FILE_MODE),
+    );
+
+    let normalized_target = normalize_archive_path(target.as_path());
+    builder.append_link(&mut header, path, normalized_target)
+}
+
+fn new_entry_header(entry_type: EntryType, size: u64, mode: u32) -> Header {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(entry_type);
+    header.set_size(size);
+    header.set_mode(mode);
+    header.set_cksum();
+    header
+}
+
+/// Normalize archive entry paths to forward-slash separators.
+///
+/// Tar archives use `/` as the path separator, including on Windows hosts.
+pub(crate) fn normalize_archive_path(path: &Utf8Path) -> String {
+    path.as_str().replace('\\', "/")
+}
+
+#[cfg(unix)]
+fn metadata_mode(metadata: &Metadata, _fallback: u32) -> u32 {
+    use cap_std::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn metadata_mode(_metadata: &Metadata, fallback: u32) -> u32 {
+    fallback
+}
+
+#[cfg(test)]
+mod tests;
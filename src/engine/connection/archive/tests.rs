@@ -0,0 +1,283 @@
+//! Unit tests for shared tar archive construction and extraction.
+
+use std::io;
+
+use camino::Utf8PathBuf;
+use cap_std::ambient_authority;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::*;
+
+struct HostTree {
+    _temp_dir: TempDir,
+    dir: Dir,
+}
+
+#[fixture]
+fn host_tree() -> io::Result<HostTree> {
+    let temp_dir = tempfile::tempdir()?;
+    let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .map_err(|_| io::Error::other("tempdir path should be valid UTF-8"))?;
+    let dir = Dir::open_ambient_dir(&root, ambient_authority())?;
+    Ok(HostTree {
+        _temp_dir: temp_dir,
+        dir,
+    })
+}
+
+fn ensure(condition: bool, failure_message: impl Into<String>) -> io::Result<()> {
+    if condition {
+        return Ok(());
+    }
+
+    Err(io::Error::other(failure_message.into()))
+}
+
+fn archive_paths(archive_bytes: &[u8]) -> io::Result<Vec<String>> {
+    let mut archive = tar::Archive::new(archive_bytes);
+    let mut paths = Vec::new();
+    for entry in archive.entries()? {
+        paths.push(entry?.path()?.to_string_lossy().into_owned());
+    }
+    Ok(paths)
+}
+
+#[rstest]
+fn single_file_source_is_stored_under_archive_name(
+    host_tree: io::Result<HostTree>,
+) -> io::Result<()> {
+    let tree = host_tree?;
+    tree.dir.write("spec.md", "# Spec\n")?;
+
+    let archive_bytes = build_tar_archive(
+        &tree.dir,
+        &[ArchiveSource {
+            host_name: "spec.md",
+            archive_name: "SPEC.md",
+        }],
+        1024,
+    )?;
+
+    ensure(
+        archive_paths(&archive_bytes)? == ["SPEC.md"],
+        "file source should be renamed in the archive",
+    )
+}
+
+#[rstest]
+fn archive_rejects_payloads_over_the_size_cap(host_tree: io::Result<HostTree>) -> io::Result<()> {
+    let tree = host_tree?;
+    tree.dir.create_dir("fixtures")?;
+    tree.dir.write("fixtures/a.bin", [0_u8; 8])?;
+    tree.dir.write("fixtures/b.bin", [0_u8; 8])?;
+
+    let result = build_tar_archive(&tree.dir, &[ArchiveSource::same_name("fixtures")], 12);
+
+    ensure(
+        result
+            .as_ref()
+            .is_err_and(|error| error.to_string().contains("12-byte limit")),
+        format!("expected size-cap error, got {result:?}"),
+    )
+}
+
+#[rstest]
+#[cfg(unix)]
+fn extraction_round_trips_directories_and_modes(host_tree: io::Result<HostTree>) -> io::Result<()> {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    let tree = host_tree?;
+    tree.dir.create_dir_all("reports/nested")?;
+    tree.dir.write("reports/nested/run.sh", "#!/bin/sh\n")?;
+    tree.dir
+        .set_permissions("reports/nested/run.sh", Permissions::from_mode(0o750))?;
+    let archive_bytes = build_tar_archive(&tree.dir, &[ArchiveSource::same_name("reports")], 1024)?;
+
+    let written = extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource {
+            host_name: "copied",
+            archive_name: "reports",
+        },
+    )?;
+
+    ensure(
+        written == 10,
+        format!("expected 10 bytes written, got {written}"),
+    )?;
+    ensure(
+        tree.dir.read_to_string("copied/nested/run.sh")? == "#!/bin/sh\n",
+        "extracted file contents should match",
+    )?;
+    let mode = tree
+        .dir
+        .metadata("copied/nested/run.sh")?
+        .permissions()
+        .mode()
+        & 0o7777;
+    ensure(mode == 0o750, format!("expected mode 0750, got {mode:o}"))
+}
+
+#[rstest]
+fn extraction_rejects_entries_outside_the_root(host_tree: io::Result<HostTree>) -> io::Result<()> {
+    let tree = host_tree?;
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(3);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, "elsewhere/file.txt", &b"bad"[..])?;
+    let archive_bytes = builder.into_inner()?;
+
+    let result = extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource::same_name("reports"),
+    );
+
+    ensure(
+        result
+            .as_ref()
+            .is_err_and(|error| error.to_string().contains("is outside 'reports'")),
+        format!("expected root mismatch error, got {result:?}"),
+    )?;
+    ensure(
+        !tree.dir.exists("elsewhere"),
+        "rejected entries must not be written",
+    )
+}
+
+#[rstest]
+fn extraction_rejects_parent_directory_components(
+    host_tree: io::Result<HostTree>,
+) -> io::Result<()> {
+    let tree = host_tree?;
+    let mut header = tar::Header::new_old();
+    let raw_name = b"reports/../../escape.txt";
+    header
+        .as_old_mut()
+        .name
+        .get_mut(..raw_name.len())
+        .ok_or_else(|| io::Error::other("tar name field should fit the test path"))?
+        .copy_from_slice(raw_name);
+    header.set_size(3);
+    header.set_mode(0o644);
+    header.set_cksum();
+    let mut archive_bytes = Vec::new();
+    archive_bytes.extend_from_slice(header.as_bytes());
+    archive_bytes.extend_from_slice(b"bad");
+    archive_bytes.resize(archive_bytes.len() + 509 + 1024, 0);
+
+    let result = extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource::same_name("reports"),
+    );
+
+    ensure(
+        result
+            .as_ref()
+            .is_err_and(|error| error.to_string().contains("escapes the destination")),
+        format!("expected traversal rejection, got {result:?}"),
+    )
+}
+
+fn symlink_archive(target: &str, then_file: Option<&str>) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut link = tar::Header::new_gnu();
+    link.set_entry_type(tar::EntryType::Symlink);
+    link.set_size(0);
+    link.set_mode(0o777);
+    builder.append_link(&mut link, "reports/link", target)?;
+    if let Some(path) = then_file {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, &b"bad"[..])?;
+    }
+    builder.into_inner()
+}
+
+#[rstest]
+#[case::absolute("/etc")]
+#[case::parent("../victim")]
+#[case::nested_parent("inner/../../victim")]
+fn extraction_rejects_symlinks_leaving_the_destination(
+    host_tree: io::Result<HostTree>,
+    #[case] target: &str,
+) -> io::Result<()> {
+    let tree = host_tree?;
+    let archive_bytes = symlink_archive(target, None)?;
+
+    let result = extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource::same_name("reports"),
+    );
+
+    ensure(
+        result
+            .as_ref()
+            .is_err_and(|error| error.to_string().contains("points outside the destination")),
+        format!("expected symlink target rejection, got {result:?}"),
+    )
+}
+
+#[rstest]
+#[cfg(unix)]
+#[case::at_the_link("reports/link")]
+#[case::beneath_the_link("reports/link/file.txt")]
+fn extraction_never_writes_through_a_created_symlink(
+    host_tree: io::Result<HostTree>,
+    #[case] path: &str,
+) -> io::Result<()> {
+    let tree = host_tree?;
+    tree.dir.create_dir_all("reports/sibling")?;
+    let archive_bytes = symlink_archive("sibling", Some(path))?;
+
+    let result = extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource::same_name("reports"),
+    );
+
+    ensure(
+        result
+            .as_ref()
+            .is_err_and(|error| error.to_string().contains("through symbolic link")),
+        format!("expected write-through rejection, got {result:?}"),
+    )?;
+    ensure(
+        tree.dir.entries()?.count() == 1 && !tree.dir.exists("reports/sibling/file.txt"),
+        "nothing should be written through the link",
+    )
+}
+
+#[rstest]
+#[cfg(unix)]
+fn extraction_drops_setuid_setgid_and_sticky_bits(
+    host_tree: io::Result<HostTree>,
+) -> io::Result<()> {
+    use cap_std::fs::PermissionsExt;
+
+    let tree = host_tree?;
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(3);
+    header.set_mode(0o7755);
+    header.set_cksum();
+    builder.append_data(&mut header, "reports/tool", &b"bin"[..])?;
+    let archive_bytes = builder.into_inner()?;
+
+    extract_tar_archive(
+        &tree.dir,
+        &archive_bytes,
+        &ArchiveSource::same_name("reports"),
+    )?;
+
+    let mode = tree.dir.metadata("reports/tool")?.permissions().mode() & 0o7777;
+    ensure(mode == 0o755, format!("expected mode 0755, got {mode:o}"))
+}
@@ -0,0 +1,160 @@
+//! File and directory copy between the host and a running container.
+//!
+//! Host access goes through capability-scoped [`Dir`] handles opened on the
+//! parent of the host path. Archives are capped at a caller-supplied size in
+//! both directions so a single copy cannot exhaust host memory or disk.
+
+use bollard::query_parameters::UploadToContainerOptionsBuilder;
+use camino::Utf8Path;
+use cap_std::fs_utf8::Dir;
+
+use super::archive::{ArchiveSource, build_tar_archive, extract_tar_archive};
+use super::download::{ArchiveDownload, ContainerDownloader, collect_archive_async};
+use super::upload::ContainerUploader;
+use crate::error::{ConfigError, ContainerError, FilesystemError, PodbotError};
+
+/// Host side of a copy operation.
+#[derive(Clone, Copy)]
+pub struct HostCopyPath<'a> {
+    /// Directory capability for the parent of `path`.
+    pub parent_dir: &'a Dir,
+    /// Full host path, used for its file name and in error messages.
+    pub path: &'a Utf8Path,
+}
+
+/// Request describing a copy between a host path and a container path.
+pub struct CopyRequest<'a> {
+    /// Target container identifier.
+    pub container_id: &'a str,
+    /// Absolute path inside the container.
+    pub container_path: &'a Utf8Path,
+    /// Host path and its parent directory capability.
+    pub host: HostCopyPath<'a>,
+    /// Largest payload, in bytes, accepted for the copy.
+    pub max_bytes: u64,
+}
+
+/// Successful copy result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyResult {
+    /// Number of archive bytes transferred between host and container.
+    pub bytes_copied: u64,
+}
+
+/// Copy a host file or directory to `request.container_path`.
+///
+/// The source is archived under the container path's file name and uploaded
+/// into its parent directory, which must already exist in the container.
+///
+/// # Errors
+///
+/// Returns `ConfigError::InvalidValue` when either path has no file name,
+/// `FilesystemError::IoError` when the host source cannot be read or exceeds
+/// `max_bytes`, and `ContainerError::UploadFailed` when the daemon rejects
+/// the upload.
+pub fn copy_to_container<U: ContainerUploader>(
+    runtime: &tokio::runtime::Handle,
+    uploader: &U,
+    request: &CopyRequest<'_>,
+) -> Result<CopyResult, PodbotError> {
+    let (container_parent, container_name) = split_container_path(request.container_path)?;
+    let host_name = host_file_name(request.host.path)?;
+
+    let archive_bytes = build_tar_archive(
+        request.host.parent_dir,
+        &[ArchiveSource {
+            host_name,
+            archive_name: container_name,
+        }],
+        request.max_bytes,
+    )
+    .map_err(|error| host_io_failed(request.host.path, &error))?;
+    let bytes_copied = u64::try_from(archive_bytes.len()).unwrap_or(u64::MAX);
+
+    let options = UploadToContainerOptionsBuilder::default()
+        .path(container_parent.as_str())
+        .build();
+    runtime
+        .block_on(uploader.upload_to_container(request.container_id, Some(options), archive_bytes))
+        .map_err(|error| {
+            PodbotError::from(ContainerError::UploadFailed {
+                container_id: String::from(request.container_id),
+                message: error.to_string(),
+            })
+        })?;
+
+    Ok(CopyResult { bytes_copied })
+}
+
+/// Copy a container file or directory to the host path.
+///
+/// The container entry is written under the host path's file name inside the
+/// host parent directory, replacing existing files of the same name.
+///
+/// # Errors
+///
+/// Returns `ConfigError::InvalidValue` when either path has no file name,
+/// `ContainerError::DownloadFailed` when the daemon stream fails or exceeds
+/// `max_bytes`, and `FilesystemError::IoError` when the archive cannot be
+/// written to the host.
+pub fn copy_from_container<D: ContainerDownloader>(
+    runtime: &tokio::runtime::Handle,
+    downloader: &D,
+    request: &CopyRequest<'_>,
+) -> Result<CopyResult, PodbotError> {
+    let (_, container_name) = split_container_path(request.container_path)?;
+    let host_name = host_file_name(request.host.path)?;
+
+    let archive_bytes = runtime.block_on(collect_archive_async(
+        downloader,
+        ArchiveDownload {
+            container_id: request.container_id,
+            container_path: request.container_path.as_str(),
+            max_bytes: request.max_bytes,
+        },
+    ))?;
+    extract_tar_archive(
+        request.host.parent_dir,
+        &archive_bytes,
+        &ArchiveSource {
+            host_name,
+            archive_name: container_name,
+        },
+    )
+    .map_err(|error| host_io_failed(request.host.path, &error))?;
+
+    Ok(CopyResult {
+        bytes_copied: u64::try_from(archive_bytes.len()).unwrap_or(u64::MAX),
+    })
+}
+
+fn split_container_path(container_path: &Utf8Path) -> Result<(&Utf8Path, &str), PodbotError> {
+    match (container_path.parent(), container_path.file_name()) {
+        (Some(parent), Some(name)) if container_path.is_absolute() => Ok((parent, name)),
+        _ => Err(PodbotError::from(ConfigError::InvalidValue {
+            field: String::from("cp.container_path"),
+            reason: format!(
+                "'{container_path}' must be an absolute path naming a file or directory"
+            ),
+        })),
+    }
+}
+
+fn host_file_name(host_path: &Utf8Path) -> Result<&str, PodbotError> {
+    host_path.file_name().ok_or_else(|| {
+        PodbotError::from(ConfigError::InvalidValue {
+            field: String::from("cp.host_path"),
+            reason: format!("'{host_path}' does not name a file or directory"),
+        })
+    })
+}
+
+fn host_io_failed(host_path: &Utf8Path, error: &std::io::Error) -> PodbotError {
+    PodbotError::from(FilesystemError::IoError {
+        path: host_path.as_std_path().to_path_buf(),
+        message: error.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
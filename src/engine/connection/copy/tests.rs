@@ -0,0 +1,253 @@
+//! Unit tests for host/container copy orchestration.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use bollard::query_parameters::{DownloadFromContainerOptions, UploadToContainerOptions};
+use camino::Utf8PathBuf;
+use cap_std::ambient_authority;
+use mockall::mock;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::*;
+use crate::engine::connection::download::DownloadFromContainerStream;
+use crate::engine::connection::upload::UploadToContainerFuture;
+
+mock! {
+    Engine {}
+
+    impl ContainerUploader for Engine {
+        fn upload_to_container(
+            &self,
+            container_id: &str,
+            options: Option<UploadToContainerOptions>,
+            archive_bytes: Vec<u8>,
+        ) -> UploadToContainerFuture<'_>;
+    }
+
+    impl ContainerDownloader for Engine {
+        fn download_from_container(
+            &self,
+            container_id: &str,
+            options: Option<DownloadFromContainerOptions>,
+        ) -> DownloadFromContainerStream<'_>;
+    }
+}
+
+struct HostRoot {
+    _temp_dir: TempDir,
+    dir: Dir,
+    path: Utf8PathBuf,
+}
+
+#[fixture]
+fn runtime() -> io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Runtime::new()
+}
+
+#[fixture]
+fn host_root() -> io::Result<HostRoot> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .map_err(|_| io::Error::other("tempdir path should be valid UTF-8"))?;
+    let dir = Dir::open_ambient_dir(&path, ambient_authority())?;
+    Ok(HostRoot {
+        _temp_dir: temp_dir,
+        dir,
+        path,
+    })
+}
+
+fn ensure(condition: bool, failure_message: impl Into<String>) -> io::Result<()> {
+    if condition {
+        return Ok(());
+    }
+
+    Err(io::Error::other(failure_message.into()))
+}
+
+fn copy_request<'a>(
+    root: &'a HostRoot,
+    host_path: &'a Utf8Path,
+    container_path: &'a str,
+    max_bytes: u64,
+) -> CopyRequest<'a> {
+    CopyRequest {
+        container_id: "sandbox-copy",
+        container_path: Utf8Path::new(container_path),
+        host: HostCopyPath {
+            parent_dir: &root.dir,
+            path: host_path,
+        },
+        max_bytes,
+    }
+}
+
+fn archive_paths(archive_bytes: &[u8]) -> io::Result<Vec<String>> {
+    let mut archive = tar::Archive::new(archive_bytes);
+    let mut paths = Vec::new();
+    for entry in archive.entries()? {
+        paths.push(entry?.path()?.to_string_lossy().into_owned());
+    }
+    Ok(paths)
+}
+
+#[rstest]
+fn copy_to_container_uploads_renamed_tree_into_parent(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_root: io::Result<HostRoot>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let root = host_root?;
+    root.dir.create_dir("fixtures")?;
+    root.dir.write("fixtures/input.json", "{}")?;
+    let host_path = root.path.join("fixtures");
+
+    let captured = Arc::new(Mutex::new(None));
+    let captured_for_mock = Arc::clone(&captured);
+    let mut engine = MockEngine::new();
+    engine.expect_upload_to_container().times(1).returning(
+        move |container_id, options, archive_bytes| {
+            if let Ok(mut slot) = captured_for_mock.lock() {
+                *slot = Some((String::from(container_id), options, archive_bytes));
+            }
+            Box::pin(async { Ok(()) })
+        },
+    );
+
+    let result = copy_to_container(
+        rt.handle(),
+        &engine,
+        &copy_request(&root, &host_path, "/work/test-data", 1024),
+    )
+    .map_err(io::Error::other)?;
+
+    let (container_id, options, archive_bytes) = captured
+        .lock()
+        .map_err(|_| io::Error::other("capture lock should succeed"))?
+        .take()
+        .ok_or_else(|| io::Error::other("upload should be captured"))?;
+    ensure(
+        container_id == "sandbox-copy",
+        "upload should target the session",
+    )?;
+    ensure(
+        options.is_some_and(|upload| upload.path == "/work"),
+        "upload should extract into the destination parent",
+    )?;
+    ensure(
+        archive_paths(&archive_bytes)? == ["test-data/", "test-data/input.json"],
+        "archive root should use the destination name",
+    )?;
+    ensure(
+        result.bytes_copied == u64::try_from(archive_bytes.len()).map_err(io::Error::other)?,
+        "result should report the uploaded archive size",
+    )
+}
+
+#[rstest]
+#[case::relative("work/out")]
+#[case::root("/")]
+fn copy_rejects_container_paths_without_a_file_name(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_root: io::Result<HostRoot>,
+    #[case] container_path: &str,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let root = host_root?;
+    let host_path = root.path.join("out");
+    let mut engine = MockEngine::new();
+    engine.expect_upload_to_container().never();
+
+    let result = copy_to_container(
+        rt.handle(),
+        &engine,
+        &copy_request(&root, &host_path, container_path, 1024),
+    );
+
+    ensure(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::InvalidValue { field, .. }))
+                if field == "cp.container_path"
+        ),
+        format!("expected cp.container_path rejection, got {result:?}"),
+    )
+}
+
+#[rstest]
+fn copy_from_container_extracts_under_host_name(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_root: io::Result<HostRoot>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let root = host_root?;
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(6);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, "reports/summary.txt", &b"passed"[..])?;
+    let archive_bytes = builder.into_inner()?;
+
+    let mut engine = MockEngine::new();
+    engine
+        .expect_download_from_container()
+        .withf(|container_id, options| {
+            container_id == "sandbox-copy"
+                && options
+                    .as_ref()
+                    .is_some_and(|requested| requested.path == "/work/reports")
+        })
+        .times(1)
+        .return_once(move |_, _| Box::pin(futures_util::stream::iter(vec![Ok(archive_bytes)])));
+    let host_path = root.path.join("local-reports");
+
+    copy_from_container(
+        rt.handle(),
+        &engine,
+        &copy_request(&root, &host_path, "/work/reports", 1 << 20),
+    )
+    .map_err(io::Error::other)?;
+
+    ensure(
+        root.dir.read_to_string("local-reports/summary.txt")? == "passed",
+        "container tree should land under the host name",
+    )
+}
+
+#[rstest]
+fn copy_from_container_enforces_size_cap(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_root: io::Result<HostRoot>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let root = host_root?;
+    let mut engine = MockEngine::new();
+    engine
+        .expect_download_from_container()
+        .times(1)
+        .return_once(|_, _| Box::pin(futures_util::stream::iter(vec![Ok(vec![0_u8; 64])])));
+    let host_path = root.path.join("big");
+
+    let result = copy_from_container(
+        rt.handle(),
+        &engine,
+        &copy_request(&root, &host_path, "/work/big", 32),
+    );
+
+    ensure(
+        matches!(
+            &result,
+            Err(PodbotError::Container(
+                ContainerError::DownloadFailed { .. }
+            ))
+        ),
+        format!("expected DownloadFailed, got {result:?}"),
+    )?;
+    ensure(
+        !root.dir.exists("big"),
+        "nothing should be written on failure",
+    )
+}
@@ -4,6 +4,7 @@
 //! container-create payloads and provides async/sync helpers for creating
 //! containers.
 
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 
@@ -12,9 +13,11 @@ use bollard::models::{ContainerCreateBody, ContainerCreateResponse, DeviceMappin
 use bollard::query_parameters::{CreateContainerOptions, CreateContainerOptionsBuilder};
 
 use super::EngineConnector;
+use super::agent_env::{AgentEnv, RedactedEnv};
 pub use crate::config::SelinuxLabelMode;
 use crate::config::{AppConfig, SandboxConfig};
 use crate::error::{ConfigError, ContainerError, PodbotError};
+use crate::github::TokenRuntimeDir;
 
 const DEV_FUSE_PATH: &str = "/dev/fuse";
 const FUSE_DEVICE_PERMISSIONS: &str = "rwm";
@@ -85,7 +88,9 @@ impl Default for ContainerSecurityOptions {
 }
 
 /// Container-creation request parameters.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Debug` output shows environment variable names but not their values.
+#[derive(Clone, PartialEq, Eq)]
 pub struct CreateContainerRequest {
     /// The container image to create from.
     image: String,
@@ -99,6 +104,9 @@ pub struct CreateContainerRequest {
     /// Optional environment variables in `KEY=value` form.
     env: Option<Vec<String>>,
 
+    /// Optional bind mounts in `host:container[:options]` form.
+    binds: Option<Vec<String>>,
+
     /// Security profile to apply.
     security: ContainerSecurityOptions,
 }
@@ -122,14 +130,16 @@ impl CreateContainerRequest {
             name: None,
             cmd: None,
             env: None,
+            binds: None,
             security,
         })
     }
 
     /// Create a request from a resolved application configuration.
     ///
-    /// This helper sources the image from `config.image` and security options
-    /// from `config.sandbox`.
+    /// This helper sources the image from `config.image`, security options
+    /// from `config.sandbox`, and the environment from the host variables
+    /// `config.agent.env_allowlist` names.
     ///
     /// # Examples
     ///
@@ -147,10 +157,28 @@ impl CreateContainerRequest {
     /// # Errors
     ///
     /// Returns `ConfigError::MissingRequired` when `config.image` is missing,
-    /// empty, or whitespace-only.
+    /// empty, or whitespace-only, or when a required allowlisted variable is
+    /// not set on the host.
     pub fn from_app_config(config: &AppConfig) -> Result<Self, PodbotError> {
+        Self::from_app_config_with_env(&mockable::DefaultEnv::new(), config)
+    }
+
+    /// Create a request from a resolved application configuration, reading
+    /// allowlisted host variables from `env`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::from_app_config`].
+    pub fn from_app_config_with_env<E: mockable::Env>(
+        env: &E,
+        config: &AppConfig,
+    ) -> Result<Self, PodbotError> {
         let security = ContainerSecurityOptions::from_sandbox_config(&config.sandbox);
-        Self::new(config.image.as_deref().unwrap_or_default(), security)
+        let agent_env = AgentEnv::from_app_config(env, config)?;
+        Ok(
+            Self::new(config.image.as_deref().unwrap_or_default(), security)?
+                .with_agent_env(&agent_env),
+        )
     }
 
     /// Attach an optional container name.
@@ -174,6 +202,36 @@ impl CreateContainerRequest {
         self
     }
 
+    /// Add the allowlisted host variables in `agent_env` to the environment,
+    /// keeping entries already set with [`Self::with_env`].
+    #[must_use]
+    pub fn with_agent_env(mut self, agent_env: &AgentEnv) -> Self {
+        self.env = agent_env.merge_into(self.env.take());
+        self
+    }
+
+    /// Attach optional bind mounts.
+    #[must_use]
+    pub fn with_binds(mut self, binds: Option<Vec<String>>) -> Self {
+        self.binds = binds;
+        self
+    }
+
+    /// Name the container after `runtime_dir` and mount its secrets
+    /// directory read-only at `/run/secrets`, in addition to any binds set
+    /// with [`Self::with_binds`].
+    ///
+    /// The token daemon run for the same container name then keeps
+    /// `/run/secrets/ghapp_token` current inside the container.
+    #[must_use]
+    pub fn with_token_runtime_dir(mut self, runtime_dir: &TokenRuntimeDir) -> Self {
+        self.name = Some(String::from(runtime_dir.container_name()));
+        self.binds
+            .get_or_insert_with(Vec::new)
+            .push(runtime_dir.bind_mount());
+        self
+    }
+
     /// Return the configured image.
     #[must_use]
     pub fn image(&self) -> &str {
@@ -198,6 +256,12 @@ impl CreateContainerRequest {
         self.env.as_deref()
     }
 
+    /// Return the optional configured bind mounts.
+    #[must_use]
+    pub fn binds(&self) -> Option<&[String]> {
+        self.binds.as_deref()
+    }
+
     /// Return the configured security options.
     #[must_use]
     pub const fn security(&self) -> &ContainerSecurityOptions {
@@ -205,6 +269,20 @@ impl CreateContainerRequest {
     }
 }
 
+impl fmt::Debug for CreateContainerRequest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CreateContainerRequest")
+            .field("image", &self.image)
+            .field("name", &self.name)
+            .field("cmd", &self.cmd)
+            .field("env", &RedactedEnv(self.env.as_deref()))
+            .field("binds", &self.binds)
+            .field("security", &self.security)
+            .finish()
+    }
+}
+
 impl EngineConnector {
     /// Create a container using a provided client abstraction (async version).
     ///
@@ -275,7 +353,10 @@ fn build_create_body(request: &CreateContainerRequest) -> ContainerCreateBody {
         image: Some(String::from(request.image())),
         cmd: request.cmd().map(<[String]>::to_vec),
         env: request.env().map(<[String]>::to_vec),
-        host_config: Some(build_host_config(request.security())),
+        host_config: Some(HostConfig {
+            binds: request.binds().map(<[String]>::to_vec),
+            ..build_host_config(request.security())
+        }),
         ..ContainerCreateBody::default()
     }
 }
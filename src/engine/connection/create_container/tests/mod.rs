@@ -397,3 +397,135 @@ fn create_container_sync_uses_provided_runtime(
     )?;
     ensure(call_count(&captured) == 1, "expected one engine call")
 }
+
+#[rstest]
+#[case::minimal(false)]
+#[case::privileged(true)]
+fn create_container_passes_bind_mounts(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+    #[case] privileged: bool,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let (creator, captured) = success_creator("container-id");
+    let binds = vec![String::from(
+        "/run/user/1000/podbot/sandbox:/run/secrets:ro",
+    )];
+    let request = CreateContainerRequest::new(
+        "ghcr.io/example/sandbox:latest",
+        ContainerSecurityOptions {
+            privileged,
+            ..ContainerSecurityOptions::default()
+        },
+    )
+    .map_err(|error| io_error(format!("request construction should succeed: {error}")))?
+    .with_binds(Some(binds.clone()));
+
+    let _ = runtime_handle
+        .block_on(EngineConnector::create_container_async(&creator, &request))
+        .map_err(|error| io_error(format!("container creation should succeed: {error}")))?;
+
+    let body = take_body(&captured).ok_or_else(|| io_error("container body should be captured"))?;
+    let host_config = body
+        .host_config
+        .ok_or_else(|| io_error("host config should be set"))?;
+    ensure(
+        host_config.binds == Some(binds),
+        format!("expected token bind mount, got {:?}", host_config.binds),
+    )?;
+    ensure(
+        host_config.privileged == Some(privileged),
+        "bind mounts should not change the security profile",
+    )
+}
+
+#[rstest]
+fn create_container_mounts_the_token_runtime_dir(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let temp_dir = tempfile::tempdir()?;
+    let root = camino::Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .map_err(|_| io_error("tempdir path should be valid UTF-8"))?;
+    let runtime_dir = TokenRuntimeDir::create_in(&root, "podbot-sandbox")
+        .map_err(|error| io_error(format!("runtime directory should be created: {error}")))?;
+    let (creator, captured) = success_creator("container-id");
+    let request = CreateContainerRequest::new(
+        "ghcr.io/example/sandbox:latest",
+        ContainerSecurityOptions::default(),
+    )
+    .map_err(|error| io_error(format!("request construction should succeed: {error}")))?
+    .with_binds(Some(vec![String::from("/srv/cache:/cache")]))
+    .with_token_runtime_dir(&runtime_dir);
+
+    let _ = runtime_handle
+        .block_on(EngineConnector::create_container_async(&creator, &request))
+        .map_err(|error| io_error(format!("container creation should succeed: {error}")))?;
+
+    let secrets = root.join("podbot").join("podbot-sandbox").join("secrets");
+    let binds = take_body(&captured)
+        .and_then(|body| body.host_config)
+        .and_then(|host_config| host_config.binds);
+    ensure(
+        binds
+            == Some(vec![
+                String::from("/srv/cache:/cache"),
+                format!("{secrets}:/run/secrets:ro"),
+            ]),
+        format!("expected the secrets directory mounted read-only, got {binds:?}"),
+    )?;
+    let name = take_options(&captured).and_then(|options| options.name);
+    ensure(
+        name.as_deref() == Some("podbot-sandbox"),
+        format!("container should be named after the runtime directory, got {name:?}"),
+    )
+}
+
+#[rstest]
+fn create_container_passes_allowlisted_host_variables(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let mut env = mockable::MockEnv::new();
+    env.expect_string()
+        .returning(|name| (name == "API_KEY").then(|| String::from("sk-host")));
+    let mut config = AppConfig {
+        image: Some(String::from("ghcr.io/example/sandbox:latest")),
+        ..AppConfig::default()
+    };
+    config.agent.env_allowlist = vec![String::from("API_KEY!"), String::from("UNSET")];
+    let (creator, captured) = success_creator("container-id");
+    let request = CreateContainerRequest::from_app_config_with_env(&env, &config)
+        .map_err(|error| io_error(format!("request construction should succeed: {error}")))?;
+
+    let _ = runtime_handle
+        .block_on(EngineConnector::create_container_async(&creator, &request))
+        .map_err(|error| io_error(format!("container creation should succeed: {error}")))?;
+
+    let body_env = take_body(&captured).and_then(|body| body.env);
+    ensure(
+        body_env == Some(vec![String::from("API_KEY=sk-host")]),
+        "expected only the set allowlisted variable in the create payload",
+    )
+}
+
+#[rstest]
+fn create_container_request_requires_required_allowlisted_variables() {
+    let mut env = mockable::MockEnv::new();
+    env.expect_string().returning(|_| None);
+    let mut config = AppConfig {
+        image: Some(String::from("ghcr.io/example/sandbox:latest")),
+        ..AppConfig::default()
+    };
+    config.agent.env_allowlist = vec![String::from("API_KEY!")];
+
+    let request = CreateContainerRequest::from_app_config_with_env(&env, &config);
+
+    assert!(
+        matches!(
+            &request,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field.contains("API_KEY")
+        ),
+        "expected the missing required variable, got: {request:?}"
+    );
+}
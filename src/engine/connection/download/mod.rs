@@ -0,0 +1,134 @@
+//! Archive download from container filesystems.
+//!
+//! This module wraps Bollard's `download_from_container` behind a small trait
+//! seam so export and copy flows can be unit-tested without a live daemon.
+
+use std::pin::Pin;
+
+use bollard::Docker;
+use bollard::query_parameters::{
+    DownloadFromContainerOptions, DownloadFromContainerOptionsBuilder,
+};
+use futures_util::{Stream, StreamExt};
+
+use crate::error::{ContainerError, PodbotError};
+
+/// Boxed stream of tar archive chunks returned by [`ContainerDownloader`]
+/// implementors.
+pub type DownloadFromContainerStream<'a> =
+    Pin<Box<dyn Stream<Item = Result<Vec<u8>, bollard::errors::Error>> + Send + 'a>>;
+
+/// Behaviour required to download a tar archive from a container.
+///
+/// This abstraction mirrors `ContainerUploader` for the opposite direction and
+/// keeps download logic testable without a live daemon.
+pub trait ContainerDownloader {
+    /// Stream a tar archive of the requested path from `container_id`.
+    fn download_from_container(
+        &self,
+        container_id: &str,
+        options: Option<DownloadFromContainerOptions>,
+    ) -> DownloadFromContainerStream<'_>;
+}
+
+impl ContainerDownloader for Docker {
+    fn download_from_container(
+        &self,
+        container_id: &str,
+        options: Option<DownloadFromContainerOptions>,
+    ) -> DownloadFromContainerStream<'_> {
+        Box::pin(
+            Self::download_from_container(self, container_id, options)
+                .map(|chunk| chunk.map(|bytes| bytes.to_vec())),
+        )
+    }
+}
+
+/// Archive download parameters shared by streaming and buffered downloads.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ArchiveDownload<'a> {
+    /// Source container identifier.
+    pub(crate) container_id: &'a str,
+    /// Absolute in-container path to archive.
+    pub(crate) container_path: &'a str,
+    /// Largest archive, in bytes, accepted before the download is aborted.
+    pub(crate) max_bytes: u64,
+}
+
+/// Stream the tar archive described by `download` chunk by chunk into `sink`.
+///
+/// Streaming stops with `ContainerError::DownloadFailed` as soon as the
+/// archive exceeds `max_bytes`, so a runaway path cannot fill host disk or
+/// memory. Errors returned by `sink` abort the download unchanged. Returns the
+/// number of archive bytes delivered.
+///
+/// # Errors
+///
+/// Returns `ContainerError::DownloadFailed` when the daemon stream fails or
+/// the size cap is exceeded, and any error produced by `sink`.
+pub(crate) async fn stream_archive_async<D, F>(
+    downloader: &D,
+    download: ArchiveDownload<'_>,
+    mut sink: F,
+) -> Result<u64, PodbotError>
+where
+    D: ContainerDownloader,
+    F: FnMut(&[u8]) -> Result<(), PodbotError>,
+{
+    let ArchiveDownload {
+        container_id,
+        container_path,
+        max_bytes,
+    } = download;
+    let mut stream = downloader
+        .download_from_container(container_id, Some(build_download_options(container_path)));
+    let mut delivered: u64 = 0;
+
+    while let Some(chunk) = stream.next().await {
+        let bytes = chunk.map_err(|error| download_failed(container_id, error.to_string()))?;
+        delivered = delivered.saturating_add(u64::try_from(bytes.len()).unwrap_or(u64::MAX));
+        if delivered > max_bytes {
+            return Err(download_failed(
+                container_id,
+                format!("archive for '{container_path}' exceeds the {max_bytes}-byte limit"),
+            ));
+        }
+        sink(&bytes)?;
+    }
+
+    Ok(delivered)
+}
+
+/// Download the tar archive described by `download` into memory.
+///
+/// # Errors
+///
+/// Returns the same errors as [`stream_archive_async`].
+pub(crate) async fn collect_archive_async<D: ContainerDownloader>(
+    downloader: &D,
+    download: ArchiveDownload<'_>,
+) -> Result<Vec<u8>, PodbotError> {
+    let mut archive_bytes = Vec::new();
+    stream_archive_async(downloader, download, |chunk| {
+        archive_bytes.extend_from_slice(chunk);
+        Ok(())
+    })
+    .await?;
+    Ok(archive_bytes)
+}
+
+fn build_download_options(container_path: &str) -> DownloadFromContainerOptions {
+    DownloadFromContainerOptionsBuilder::default()
+        .path(container_path)
+        .build()
+}
+
+fn download_failed(container_id: &str, message: String) -> PodbotError {
+    PodbotError::from(ContainerError::DownloadFailed {
+        container_id: String::from(container_id),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests;
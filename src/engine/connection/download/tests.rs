@@ -0,0 +1,148 @@
+//! Unit tests for container archive download streaming.
+
+use bollard::query_parameters::DownloadFromContainerOptions;
+use mockall::mock;
+use rstest::{fixture, rstest};
+
+use super::*;
+
+mock! {
+    pub Downloader {}
+
+    impl ContainerDownloader for Downloader {
+        fn download_from_container(
+            &self,
+            container_id: &str,
+            options: Option<DownloadFromContainerOptions>,
+        ) -> DownloadFromContainerStream<'_>;
+    }
+}
+
+fn downloader_yielding(
+    expected_path: &'static str,
+    chunks: Vec<Result<Vec<u8>, bollard::errors::Error>>,
+) -> MockDownloader {
+    let mut downloader = MockDownloader::new();
+    downloader
+        .expect_download_from_container()
+        .withf(move |container_id, options| {
+            container_id == "sandbox"
+                && options
+                    .as_ref()
+                    .is_some_and(|requested| requested.path == expected_path)
+        })
+        .times(1)
+        .return_once(move |_, _| Box::pin(futures_util::stream::iter(chunks)));
+    downloader
+}
+
+const fn archive_download(container_path: &str, max_bytes: u64) -> ArchiveDownload<'_> {
+    ArchiveDownload {
+        container_id: "sandbox",
+        container_path,
+        max_bytes,
+    }
+}
+
+#[fixture]
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Runtime::new().expect("test requires a Tokio runtime")
+}
+
+#[rstest]
+fn collect_archive_concatenates_stream_chunks(runtime: tokio::runtime::Runtime) {
+    let downloader = downloader_yielding(
+        "/tmp/export.patch",
+        vec![Ok(b"first ".to_vec()), Ok(b"second".to_vec())],
+    );
+
+    let archive = runtime
+        .block_on(collect_archive_async(
+            &downloader,
+            archive_download("/tmp/export.patch", 1024),
+        ))
+        .expect("download should succeed");
+
+    assert_eq!(archive, b"first second");
+}
+
+#[rstest]
+fn stream_archive_rejects_archives_over_the_size_cap(runtime: tokio::runtime::Runtime) {
+    let downloader = downloader_yielding(
+        "/workspace",
+        vec![Ok(vec![0; 8]), Ok(vec![0; 8]), Ok(vec![0; 8])],
+    );
+    let mut delivered_chunks = 0_usize;
+
+    let result = runtime.block_on(stream_archive_async(
+        &downloader,
+        archive_download("/workspace", 12),
+        |_| {
+            delivered_chunks += 1;
+            Ok(())
+        },
+    ));
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Container(ContainerError::DownloadFailed { message, .. }))
+                if message.contains("12-byte limit")
+        ),
+        "expected size-cap DownloadFailed, got {result:?}"
+    );
+    assert_eq!(
+        delivered_chunks, 1,
+        "chunks past the cap must not reach the sink"
+    );
+}
+
+#[rstest]
+fn stream_archive_maps_daemon_errors_to_download_failed(runtime: tokio::runtime::Runtime) {
+    let downloader = downloader_yielding(
+        "/workspace",
+        vec![Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404,
+            message: String::from("no such container"),
+        })],
+    );
+
+    let result = runtime.block_on(collect_archive_async(
+        &downloader,
+        archive_download("/workspace", 1024),
+    ));
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Container(ContainerError::DownloadFailed { container_id, message }))
+                if container_id == "sandbox" && message.contains("no such container")
+        ),
+        "expected DownloadFailed, got {result:?}"
+    );
+}
+
+#[rstest]
+fn stream_archive_propagates_sink_errors(runtime: tokio::runtime::Runtime) {
+    let downloader = downloader_yielding("/workspace", vec![Ok(b"bytes".to_vec())]);
+
+    let result = runtime.block_on(stream_archive_async(
+        &downloader,
+        archive_download("/workspace", 1024),
+        |_| {
+            Err(PodbotError::from(crate::error::FilesystemError::IoError {
+                path: std::path::PathBuf::from("/host/out.tar"),
+                message: String::from("disk full"),
+            }))
+        },
+    ));
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Filesystem(crate::error::FilesystemError::IoError { message, .. }))
+                if message == "disk full"
+        ),
+        "expected sink error to propagate, got {result:?}"
+    );
+}
@@ -0,0 +1,263 @@
+//! Opt-in JSONL audit log of Agentic Control Protocol (ACP) traffic.
+//!
+//! When `[agent.acp.audit]` names a file, every frame the runtime policy
+//! classifies is appended to it as one JSON object per line: a UTC
+//! timestamp, the frame's direction, JSON-RPC `id` and `method`, the policy
+//! decision and the frame size in bytes. `params` are recorded with every
+//! value the [`SecretRegistry`] knows redacted or, with `hash_params`, as a
+//! SHA-256 digest. Frames that are not JSON are still recorded, with a null
+//! `id` and `method`.
+//!
+//! The log is written to its own file and never to host stdout, so the
+//! protocol stream stays pure. Records are written by a dedicated thread, so
+//! a slow disk never blocks the proxy's async tasks; the thread drains every
+//! queued record before the last handle is dropped. Write failures never
+//! interrupt a session: the first one is reported on stderr and later records
+//! are attempted as usual.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use camino::Utf8Path;
+use cap_std::ambient_authority;
+use cap_std::fs_utf8::{Dir, File, OpenOptions};
+use chrono::SecondsFormat;
+use mockable::{Clock, DefaultClock};
+use ortho_config::serde_json::{self, Value};
+use sha2::{Digest, Sha256};
+
+use super::acp_helpers::split_frame_line_ending;
+use super::acp_policy::FrameDirection;
+use crate::api::SecretRegistry;
+use crate::config::AcpAuditConfig;
+
+/// The policy outcome recorded for one audited frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditDecision {
+    /// The frame was forwarded unchanged.
+    Forward,
+    /// The frame was refused by the runtime policy.
+    Block,
+    /// Podbot answered the frame itself under a permission rule.
+    Respond,
+}
+
+impl AuditDecision {
+    /// Return the `snake_case` token recorded for this decision.
+    const fn as_token(self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Block => "block",
+            Self::Respond => "respond",
+        }
+    }
+}
+
+/// Shared handle to one session's audit log.
+///
+/// Clones append to the same file, so the host-inbound and agent-outbound
+/// assemblers can record into one ordered trail.
+#[derive(Clone)]
+pub(crate) struct AcpAuditLog {
+    inner: Arc<AuditLogInner>,
+}
+
+struct AuditLogInner {
+    records: Option<Sender<Vec<u8>>>,
+    writer_thread: Option<JoinHandle<()>>,
+    clock: Box<dyn Clock>,
+    hash_params: bool,
+    failure_reported: Arc<AtomicBool>,
+}
+
+impl Drop for AuditLogInner {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer thread drain and exit.
+        drop(self.records.take());
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                report_failure_once(
+                    &self.failure_reported,
+                    &io::Error::other("audit writer thread panicked"),
+                );
+            }
+        }
+    }
+}
+
+impl AcpAuditLog {
+    /// Construct an audit log that appends records to `writer` on a dedicated
+    /// thread, stamping them with `clock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error raised when the writer thread cannot be spawned.
+    pub(crate) fn new(
+        writer: impl Write + Send + 'static,
+        clock: impl Clock + 'static,
+        hash_params: bool,
+    ) -> io::Result<Self> {
+        let (records, queued) = mpsc::channel();
+        let failure_reported = Arc::new(AtomicBool::new(false));
+        let thread_failure_reported = Arc::clone(&failure_reported);
+        let writer_thread = thread::Builder::new()
+            .name(String::from("podbot-acp-audit"))
+            .spawn(move || write_records(writer, &queued, &thread_failure_reported))?;
+        Ok(Self {
+            inner: Arc::new(AuditLogInner {
+                records: Some(records),
+                writer_thread: Some(writer_thread),
+                clock: Box::new(clock),
+                hash_params,
+                failure_reported,
+            }),
+        })
+    }
+
+    /// Open the audit log configured under `[agent.acp.audit]`, creating the
+    /// file if needed and appending to it otherwise.
+    ///
+    /// Returns `Ok(None)` when auditing is not configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error raised while opening the log's directory or file.
+    pub(crate) fn open(config: &AcpAuditConfig) -> io::Result<Option<Self>> {
+        let Some(path) = config.path.as_deref() else {
+            return Ok(None);
+        };
+        let file = open_for_append(path)?;
+        Self::new(file, DefaultClock, config.hash_params).map(Some)
+    }
+
+    /// Queue one record for `frame`, which travelled in `direction` and
+    /// received `decision`, without waiting for it to be written.
+    pub(crate) fn record(&self, direction: FrameDirection, frame: &[u8], decision: AuditDecision) {
+        let entry = self.entry(direction, frame, decision);
+        if let Err(error) = self.enqueue(&entry) {
+            report_failure_once(&self.inner.failure_reported, &error);
+        }
+    }
+
+    fn entry(&self, direction: FrameDirection, frame: &[u8], decision: AuditDecision) -> Value {
+        let (payload, _line_ending) = split_frame_line_ending(frame);
+        let message = serde_json::from_slice::<Value>(payload).ok();
+        let field = |name: &str| message.as_ref().and_then(|value| value.get(name));
+        let mut entry = serde_json::json!({
+            "timestamp": self
+                .inner
+                .clock
+                .utc()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            "direction": direction.as_token(),
+            "id": field("id").cloned().unwrap_or(Value::Null),
+            "method": field("method").and_then(Value::as_str),
+            "decision": decision.as_token(),
+            "size": frame.len(),
+        });
+        if let (Some(params), Some(object)) = (field("params"), entry.as_object_mut()) {
+            if self.inner.hash_params {
+                object.insert(String::from("params_sha256"), Value::from(digest(params)));
+            } else {
+                object.insert(String::from("params"), redacted(params));
+            }
+        }
+        entry
+    }
+
+    fn enqueue(&self, entry: &Value) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        self.inner
+            .records
+            .as_ref()
+            .ok_or_else(|| io::Error::other("audit log is closed"))?
+            .send(line)
+            .map_err(|_| io::Error::other("audit writer thread has stopped"))
+    }
+}
+
+/// Write each queued record line to `writer` until every sender is dropped.
+fn write_records(
+    mut writer: impl Write,
+    queued: &Receiver<Vec<u8>>,
+    failure_reported: &AtomicBool,
+) {
+    for line in queued {
+        if let Err(error) = writer.write_all(&line).and_then(|()| writer.flush()) {
+            report_failure_once(failure_reported, &error);
+        }
+    }
+}
+
+fn report_failure_once(failure_reported: &AtomicBool, error: &io::Error) {
+    if failure_reported.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    tracing::warn!(
+        target = "podbot::acp::audit",
+        %error,
+        "ACP audit record could not be written; the session continues",
+    );
+}
+
+impl fmt::Debug for AcpAuditLog {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AcpAuditLog")
+            .field("hash_params", &self.inner.hash_params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for AcpAuditLog {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for AcpAuditLog {}
+
+/// Return `params` with every secret the process-wide [`SecretRegistry`]
+/// recognises replaced, keeping the JSON structure where it survives.
+fn redacted(params: &Value) -> Value {
+    let text = params.to_string();
+    match SecretRegistry::global().redact(&text) {
+        Cow::Borrowed(_) => params.clone(),
+        Cow::Owned(scrubbed) => serde_json::from_str(&scrubbed).unwrap_or(Value::String(scrubbed)),
+    }
+}
+
+/// Return the lowercase hex SHA-256 digest of `params` as compact JSON.
+fn digest(params: &Value) -> String {
+    let bytes = serde_json::to_vec(params).unwrap_or_default();
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Open `path` for appending through its parent directory, following the
+/// project's capability-oriented filesystem conventions.
+fn open_for_append(path: &Utf8Path) -> io::Result<File> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("audit log path `{path}` does not name a file"),
+        )
+    })?;
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_str().is_empty())
+        .unwrap_or_else(|| Utf8Path::new("."));
+    let dir = Dir::open_ambient_dir(parent, ambient_authority())?;
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    dir.open_with(file_name, &options)
+}
+
+#[cfg(test)]
+#[path = "acp_audit_tests.rs"]
+mod tests;
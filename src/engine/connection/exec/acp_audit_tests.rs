@@ -0,0 +1,209 @@
+//! Unit tests for the ACP traffic audit log.
+
+use std::io;
+
+use camino::Utf8PathBuf;
+use chrono::TimeZone;
+use mockable::MockClock;
+use ortho_config::serde_json::{self, Value};
+use rstest::{fixture, rstest};
+
+use super::{AcpAuditLog, AuditDecision};
+use crate::api::{REDACTED, SecretRegistry};
+use crate::config::AcpAuditConfig;
+use crate::engine::connection::exec::acp_policy::FrameDirection;
+use crate::engine::connection::exec::acp_test_support::{RecordingWriter, json_lines};
+
+/// A writer that always fails, standing in for a full disk.
+struct FailingWriter;
+
+impl io::Write for FailingWriter {
+    fn write(&mut self, _bytes: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[fixture]
+fn clock() -> MockClock {
+    let mut clock = MockClock::new();
+    let at = chrono::Utc
+        .with_ymd_and_hms(2026, 10, 19, 9, 30, 0)
+        .single()
+        .expect("fixture time should be valid");
+    clock.expect_utc().return_const(at);
+    clock
+}
+
+const TOOL_CALL: &[u8] =
+    br#"{"jsonrpc":"2.0","id":4,"method":"terminal/create","params":{"command":"ls"}}
+"#;
+
+#[rstest]
+fn records_carry_frame_metadata_and_params(clock: MockClock) {
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), clock, false).expect("audit writer should start");
+
+    audit.record(
+        FrameDirection::AgentOutbound,
+        TOOL_CALL,
+        AuditDecision::Block,
+    );
+    drop(audit);
+
+    assert_eq!(
+        json_lines(&log.snapshot()),
+        vec![serde_json::json!({
+            "timestamp": "2026-10-19T09:30:00.000Z",
+            "direction": "agent_outbound",
+            "id": 4,
+            "method": "terminal/create",
+            "decision": "block",
+            "size": TOOL_CALL.len(),
+            "params": {"command": "ls"},
+        })]
+    );
+}
+
+#[rstest]
+fn hashed_params_replace_the_params(clock: MockClock) {
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), clock, true).expect("audit writer should start");
+
+    audit.record(
+        FrameDirection::HostInbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+    drop(audit);
+
+    let records = json_lines(&log.snapshot());
+    let record = records.first().expect("one record should be written");
+    assert_eq!(record.get("params"), None);
+    assert_eq!(
+        record.get("params_sha256").and_then(Value::as_str),
+        Some("4cf29611a66934862f29acfcc817e30b905c1ab73d5e65831413eb6b454d49db"),
+    );
+    assert_eq!(record.get("direction"), Some(&Value::from("host_inbound")));
+}
+
+#[rstest]
+fn recorded_params_are_redacted(clock: MockClock) {
+    SecretRegistry::global().register_value("acp-audit-secret-5d1e");
+    let frame = br#"{"jsonrpc":"2.0","id":5,"method":"terminal/create","params":{"command":"curl","args":["-H","acp-audit-secret-5d1e"],"token":"unregistered-token"}}
+"#;
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), clock, false).expect("audit writer should start");
+
+    audit.record(FrameDirection::AgentOutbound, frame, AuditDecision::Forward);
+    drop(audit);
+
+    let records = json_lines(&log.snapshot());
+    assert_eq!(
+        records.first().and_then(|record| record.get("params")),
+        Some(&serde_json::json!({
+            "command": "curl",
+            "args": ["-H", REDACTED],
+            "token": REDACTED,
+        }))
+    );
+}
+
+#[rstest]
+fn records_queued_before_the_last_handle_drops_are_written(clock: MockClock) {
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), clock, true).expect("audit writer should start");
+    let clone = audit.clone();
+
+    audit.record(
+        FrameDirection::HostInbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+    drop(audit);
+    clone.record(
+        FrameDirection::AgentOutbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+    drop(clone);
+
+    assert_eq!(json_lines(&log.snapshot()).len(), 2);
+}
+
+#[rstest]
+fn unparseable_frames_are_recorded_without_id_or_method(clock: MockClock) {
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), clock, false).expect("audit writer should start");
+
+    audit.record(
+        FrameDirection::AgentOutbound,
+        b"not json\n",
+        AuditDecision::Forward,
+    );
+    drop(audit);
+
+    let records = json_lines(&log.snapshot());
+    let record = records.first().expect("one record should be written");
+    assert_eq!(record.get("id"), Some(&Value::Null));
+    assert_eq!(record.get("method"), Some(&Value::Null));
+    assert_eq!(record.get("size"), Some(&Value::from(9)));
+    assert_eq!(record.get("params"), None);
+}
+
+#[rstest]
+fn write_failures_do_not_panic(clock: MockClock) {
+    let audit = AcpAuditLog::new(FailingWriter, clock, false).expect("audit writer should start");
+
+    audit.record(
+        FrameDirection::AgentOutbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+    audit.record(
+        FrameDirection::AgentOutbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+}
+
+#[rstest]
+fn unconfigured_audit_opens_nothing() {
+    let audit = AcpAuditLog::open(&AcpAuditConfig::default()).expect("open should succeed");
+
+    assert!(audit.is_none());
+}
+
+#[rstest]
+fn configured_audit_appends_to_the_log_file() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().join("acp-audit.jsonl"))
+        .expect("tempdir path should be UTF-8");
+    std::fs::write(&path, "{\"earlier\":true}\n").expect("seed record should be written");
+    let config = AcpAuditConfig {
+        path: Some(path.clone()),
+        hash_params: true,
+    };
+
+    let audit = AcpAuditLog::open(&config)
+        .expect("open should succeed")
+        .expect("audit should be configured");
+    audit.record(
+        FrameDirection::AgentOutbound,
+        TOOL_CALL,
+        AuditDecision::Forward,
+    );
+    drop(audit);
+
+    let contents = std::fs::read_to_string(&path).expect("log should be readable");
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 2, "records are appended: {contents}");
+    assert!(
+        lines
+            .last()
+            .is_some_and(|line| line.contains("\"params_sha256\""))
+    );
+}
@@ -13,7 +13,8 @@
 //! - Permitted frames are forwarded **verbatim** (the original byte slice,
 //!   including its line ending). The policy parses to decide; it never
 //!   re-serializes. This preserves any agent-side integrity assumptions
-//!   such as key ordering or embedded hashes.
+//!   such as key ordering or embedded hashes. The one exception is a frame
+//!   carrying a workspace path that the session's [`PathMap`] rewrites.
 //! - The buffer is bounded by [`MAX_RUNTIME_FRAME_BYTES`] (128 KiB). When
 //!   the buffer fills before a newline is observed, the assembler flushes
 //!   the buffered bytes verbatim, sets a one-shot raw-fallback flag, and
@@ -24,15 +25,42 @@
 //!   forwarding unauthorized bytes would re-introduce the leak this step
 //!   is meant to prevent. The adapter logs the dropped byte count once.
 //!
+//! The same assembler polices host stdin when operator method rules are
+//! configured; [`OutboundFrameAssembler::host_inbound`] selects that
+//! direction.
+//!
+//! When podbot serves the `fs` family, agent-outbound `fs/*` frames become
+//! [`FrameOutput::ServeFs`]; see `acp_fs`. When it serves the `terminal`
+//! family, agent-outbound `terminal/*` frames become
+//! [`FrameOutput::ServeTerminal`]; see `acp_terminal`. Agent-outbound
+//! `session/request_permission` requests that a configured
+//! permission rule matches become [`FrameOutput::Permission`]; see
+//! `acp_permission`.
+//!
+//! When the session has a host-mounted workspace, forwarded frames have
+//! their workspace paths rewritten for the receiving side; see `acp_paths`.
+//!
+//! When the session has an ACP audit log, the assembler also records every
+//! completed frame and its decision there; see `acp_audit`.
+//!
 //! ## Concurrency
 //!
 //! Each protocol session owns one assembler on a single Tokio task. The
-//! assembler holds no locks, no channels, and no `tokio` types; it is
-//! purely synchronous data manipulation.
+//! assembler holds no channels and no `tokio` types; it is purely
+//! synchronous data manipulation. The only lock is inside the optional
+//! audit log, which both directions share.
 
 use ortho_config::serde_json;
 
-use super::acp_policy::{FrameDecision, MethodDenylist, evaluate_agent_outbound_frame};
+use super::acp_audit::{AcpAuditLog, AuditDecision};
+use super::acp_fs::FsRequest;
+use super::acp_paths::PathMap;
+use super::acp_permission::{PermissionDecision, PermissionPolicy};
+use super::acp_policy::{
+    FrameDecision, FrameDirection, MethodDenylist, evaluate_agent_outbound_frame,
+    evaluate_host_inbound_frame,
+};
+use super::acp_terminal::TerminalRequest;
 
 /// Maximum bytes buffered while searching for a frame's terminating newline.
 ///
@@ -55,17 +83,28 @@ pub(crate) enum FrameOutput {
     /// Apply the policy decision; the trailing slice is the original line
     /// ending (`b""`, `b"\n"`, or `b"\r\n"`) for synthesized responses.
     Decision(DeniedFrameDecision, Vec<u8>),
+    /// A permission rule matched this `session/request_permission` frame;
+    /// the trailing bytes are the original frame, forwarded when the
+    /// decision does not answer the request itself.
+    Permission(PermissionDecision, Vec<u8>),
+    /// Answer this `fs/*` frame from the workspace; the trailing slice is
+    /// the original line ending for the synthesized response.
+    ServeFs(FsRequest, Vec<u8>),
+    /// Run this `terminal/*` frame in the sandbox container; the trailing
+    /// slice is the original line ending for the synthesized response.
+    ServeTerminal(TerminalRequest, Vec<u8>),
 }
 
 /// Non-forward policy decision emitted for a completed frame.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum DeniedFrameDecision {
     /// Drop a blocked notification without generating a response.
-    BlockNotification { method: String },
+    BlockNotification { method: String, rules: Vec<String> },
     /// Generate a JSON-RPC error response for a blocked request.
     BlockRequest {
         id: serde_json::Value,
         method: String,
+        rules: Vec<String>,
     },
 }
 
@@ -92,7 +131,13 @@ pub(crate) enum FallbackReason {
 pub(crate) struct OutboundFrameAssembler {
     buffer: Vec<u8>,
     denylist: MethodDenylist,
+    direction: FrameDirection,
     raw_fallback: bool,
+    audit: Option<AcpAuditLog>,
+    permissions: PermissionPolicy,
+    serve_fs: bool,
+    serve_terminal: bool,
+    path_map: Option<PathMap>,
 }
 
 /// Produces the output for a chunk received while the assembler is in
@@ -113,7 +158,66 @@ impl OutboundFrameAssembler {
         Self {
             buffer: Vec::with_capacity(8_192),
             denylist,
+            direction: FrameDirection::AgentOutbound,
             raw_fallback: false,
+            audit: None,
+            permissions: PermissionPolicy::new(),
+            serve_fs: false,
+            serve_terminal: false,
+            path_map: None,
+        }
+    }
+
+    /// Construct an assembler that polices host-inbound frames with the
+    /// operator rules in `denylist`.
+    pub(crate) fn host_inbound(denylist: MethodDenylist) -> Self {
+        Self {
+            direction: FrameDirection::HostInbound,
+            ..Self::new(denylist)
+        }
+    }
+
+    /// Record every completed frame and its decision in `audit`, when set.
+    #[must_use]
+    pub(crate) fn with_audit(mut self, audit: Option<AcpAuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Answer agent-outbound permission requests with `permissions`.
+    #[must_use]
+    pub(crate) fn with_permission_policy(mut self, permissions: PermissionPolicy) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Route agent-outbound `fs/*` frames to podbot when `serve` is set.
+    #[must_use]
+    pub(crate) const fn with_served_fs(mut self, serve: bool) -> Self {
+        self.serve_fs = serve;
+        self
+    }
+
+    /// Route agent-outbound `terminal/*` frames to podbot when `serve` is
+    /// set.
+    #[must_use]
+    pub(crate) const fn with_served_terminal(mut self, serve: bool) -> Self {
+        self.serve_terminal = serve;
+        self
+    }
+
+    /// Rewrite workspace paths in forwarded frames with `paths`, when set.
+    #[must_use]
+    pub(crate) fn with_path_map(mut self, paths: Option<PathMap>) -> Self {
+        self.path_map = paths;
+        self
+    }
+
+    /// Record a frame that was forwarded without passing through the
+    /// assembler, such as the masked host `initialize` frame.
+    pub(crate) fn audit_forwarded(&self, frame: &[u8]) {
+        if let Some(audit) = &self.audit {
+            audit.record(self.direction, frame, AuditDecision::Forward);
         }
     }
 
@@ -176,11 +280,75 @@ impl OutboundFrameAssembler {
 
     fn complete_frame(&mut self, fresh_bytes: &[u8]) -> FrameOutput {
         if self.buffer.is_empty() {
-            return classify_frame(fresh_bytes, &self.denylist);
+            return self.classify_and_audit(fresh_bytes);
         }
         self.buffer.extend_from_slice(fresh_bytes);
         let frame = std::mem::take(&mut self.buffer);
-        classify_frame(&frame, &self.denylist)
+        self.classify_and_audit(&frame)
+    }
+
+    fn classify_and_audit(&self, frame: &[u8]) -> FrameOutput {
+        let output = match classify_frame(frame, &self.denylist, self.direction) {
+            FrameOutput::Forward(bytes) => self.intercept_served_frame(bytes),
+            other => other,
+        };
+        if let Some(audit) = &self.audit {
+            let decision = match &output {
+                FrameOutput::Permission(decision, _) if decision.responds() => {
+                    AuditDecision::Respond
+                }
+                FrameOutput::ServeFs(FsRequest { id: Some(_), .. }, _)
+                | FrameOutput::ServeTerminal(TerminalRequest { id: Some(_), .. }, _) => {
+                    AuditDecision::Respond
+                }
+                FrameOutput::Decision(..)
+                | FrameOutput::ServeFs(..)
+                | FrameOutput::ServeTerminal(..) => AuditDecision::Block,
+                FrameOutput::Forward(_) | FrameOutput::Permission(..) => AuditDecision::Forward,
+            };
+            audit.record(self.direction, frame, decision);
+        }
+        self.map_paths(output)
+    }
+
+    /// Rewrite workspace paths in a frame bound for the other side.
+    ///
+    /// Frames podbot answers itself keep the agent's container paths.
+    fn map_paths(&self, output: FrameOutput) -> FrameOutput {
+        let Some(paths) = &self.path_map else {
+            return output;
+        };
+        let rewrite = |frame: Vec<u8>| paths.rewrite(&frame, self.direction).unwrap_or(frame);
+        match output {
+            FrameOutput::Forward(frame) => FrameOutput::Forward(rewrite(frame)),
+            FrameOutput::Permission(decision, frame) => {
+                FrameOutput::Permission(decision, rewrite(frame))
+            }
+            other => other,
+        }
+    }
+
+    /// Divert agent-outbound frames that podbot answers itself: served
+    /// `fs/*` and `terminal/*` calls and permission requests matched by a
+    /// rule.
+    fn intercept_served_frame(&self, frame: Vec<u8>) -> FrameOutput {
+        if self.direction != FrameDirection::AgentOutbound {
+            return FrameOutput::Forward(frame);
+        }
+        if self.serve_fs
+            && let Some(request) = FsRequest::parse(&frame)
+        {
+            return FrameOutput::ServeFs(request, trailing_line_ending(&frame).to_vec());
+        }
+        if self.serve_terminal
+            && let Some(request) = TerminalRequest::parse(&frame)
+        {
+            return FrameOutput::ServeTerminal(request, trailing_line_ending(&frame).to_vec());
+        }
+        match self.permissions.evaluate(&frame) {
+            Some(decision) => FrameOutput::Permission(decision, frame),
+            None => FrameOutput::Forward(frame),
+        }
     }
 
     fn flush_buffer_for_overflow(&mut self, pending: &[u8]) -> FrameOutput {
@@ -212,21 +380,28 @@ impl OutboundFrameAssembler {
     }
 }
 
-fn classify_frame(frame_bytes: &[u8], denylist: &MethodDenylist) -> FrameOutput {
-    let decision = evaluate_agent_outbound_frame(frame_bytes, denylist);
+fn classify_frame(
+    frame_bytes: &[u8],
+    denylist: &MethodDenylist,
+    direction: FrameDirection,
+) -> FrameOutput {
+    let decision = match direction {
+        FrameDirection::AgentOutbound => evaluate_agent_outbound_frame(frame_bytes, denylist),
+        FrameDirection::HostInbound => evaluate_host_inbound_frame(frame_bytes, denylist),
+    };
     match decision {
         FrameDecision::Forward => FrameOutput::Forward(frame_bytes.to_vec()),
-        FrameDecision::BlockNotification { method } => {
+        FrameDecision::BlockNotification { method, rules } => {
             let line_ending = trailing_line_ending(frame_bytes).to_vec();
             FrameOutput::Decision(
-                DeniedFrameDecision::BlockNotification { method },
+                DeniedFrameDecision::BlockNotification { method, rules },
                 line_ending,
             )
         }
-        FrameDecision::BlockRequest { id, method } => {
+        FrameDecision::BlockRequest { id, method, rules } => {
             let line_ending = trailing_line_ending(frame_bytes).to_vec();
             FrameOutput::Decision(
-                DeniedFrameDecision::BlockRequest { id, method },
+                DeniedFrameDecision::BlockRequest { id, method, rules },
                 line_ending,
             )
         }
@@ -12,8 +12,10 @@ use super::{
     DeniedFrameDecision, FallbackReason, FrameOutput, MAX_RUNTIME_FRAME_BYTES,
     OutboundFrameAssembler,
 };
+use crate::config::{AcpPermissionAction, AcpPermissionRule};
+use crate::engine::connection::exec::acp_permission::PermissionPolicy;
 use crate::engine::connection::exec::acp_policy::MethodDenylist;
-use crate::engine::connection::exec::acp_test_support::jsonrpc_frame;
+use crate::engine::connection::exec::acp_test_support::{jsonrpc_frame, permission_request_frame};
 
 fn permitted_frame(method: &str, line_ending: &[u8]) -> Result<Vec<u8>, serde_json::Error> {
     jsonrpc_frame(Some(&serde_json::json!(1)), method, line_ending)
@@ -32,7 +34,10 @@ fn collect_forward_bytes(outputs: &[FrameOutput]) -> Vec<u8> {
         .iter()
         .filter_map(|output| match output {
             FrameOutput::Forward(bytes) => Some(bytes.clone()),
-            FrameOutput::Decision(_, _) => None,
+            FrameOutput::Decision(..)
+            | FrameOutput::Permission(..)
+            | FrameOutput::ServeFs(..)
+            | FrameOutput::ServeTerminal(..) => None,
         })
         .fold(Vec::new(), |mut acc, mut bytes| {
             acc.append(&mut bytes);
@@ -119,11 +124,12 @@ fn blocked_request_emits_decision_with_line_ending() {
     assert_eq!(outputs.len(), 1);
     match outputs.first() {
         Some(FrameOutput::Decision(
-            DeniedFrameDecision::BlockRequest { id, method },
+            DeniedFrameDecision::BlockRequest { id, method, rules },
             line_ending,
         )) => {
             assert_eq!(id, &serde_json::json!(7));
             assert_eq!(method, "terminal/create");
+            assert_eq!(rules, &vec![String::from("terminal/*")]);
             assert_eq!(line_ending, b"\n");
         }
         other => panic!("expected blocked request decision, got {other:?}"),
@@ -144,10 +150,11 @@ fn blocked_notification_emits_decision_without_id() {
 
     match outputs.first() {
         Some(FrameOutput::Decision(
-            DeniedFrameDecision::BlockNotification { method },
+            DeniedFrameDecision::BlockNotification { method, rules },
             line_ending,
         )) => {
             assert_eq!(method, "fs/changed");
+            assert_eq!(rules, &vec![String::from("fs/*")]);
             assert_eq!(line_ending, b"\n");
         }
         other => panic!("expected blocked notification decision, got {other:?}"),
@@ -289,3 +296,61 @@ fn empty_chunk_produces_no_output() {
 
 #[path = "acp_frame_split_tests.rs"]
 mod split_tests;
+
+#[test]
+fn permission_rules_apply_only_to_agent_outbound_frames() {
+    let policy = PermissionPolicy::from_rules(vec![AcpPermissionRule {
+        kind: Some(String::from("read")),
+        title: None,
+        action: AcpPermissionAction::Reject,
+    }]);
+    let frame = permission_request_frame(2, "read", "Read").expect("frame should serialize");
+    let mut outbound = assembler().with_permission_policy(policy.clone());
+    let mut inbound = OutboundFrameAssembler::host_inbound(MethodDenylist::default_families())
+        .with_permission_policy(policy);
+
+    let (outbound_outputs, _) = outbound.ingest_chunk(&frame);
+    let (inbound_outputs, _) = inbound.ingest_chunk(&frame);
+
+    assert!(matches!(
+        outbound_outputs.as_slice(),
+        [FrameOutput::Permission(decision, bytes)]
+            if decision.option_id.as_deref() == Some("deny") && *bytes == frame
+    ));
+    assert_eq!(inbound_outputs, vec![FrameOutput::Forward(frame)]);
+}
+
+#[test]
+fn served_fs_frames_are_routed_to_podbot() {
+    let frame = blocked_request_frame(&Value::from(3), "fs/read_text_file")
+        .expect("frame should serialize");
+    let mut served = OutboundFrameAssembler::new(MethodDenylist::new(&[])).with_served_fs(true);
+
+    let (outputs, _) = served.ingest_chunk(&frame);
+
+    assert!(matches!(
+        outputs.as_slice(),
+        [FrameOutput::ServeFs(request, line_ending)]
+            if request.id == Some(Value::from(3)) && line_ending.as_slice() == b"\n"
+    ));
+}
+
+#[test]
+fn served_terminal_frames_are_routed_to_podbot() {
+    let terminal =
+        blocked_request_frame(&Value::from(4), "terminal/create").expect("frame should serialize");
+    let fs = blocked_request_frame(&Value::from(5), "fs/read_text_file")
+        .expect("frame should serialize");
+    let mut served =
+        OutboundFrameAssembler::new(MethodDenylist::new(&[])).with_served_terminal(true);
+
+    let (terminal_outputs, _) = served.ingest_chunk(&terminal);
+    let (fs_outputs, _) = served.ingest_chunk(&fs);
+
+    assert!(matches!(
+        terminal_outputs.as_slice(),
+        [FrameOutput::ServeTerminal(request, line_ending)]
+            if request.id == Some(Value::from(4)) && line_ending.as_slice() == b"\n"
+    ));
+    assert_eq!(fs_outputs, vec![FrameOutput::Forward(fs)]);
+}
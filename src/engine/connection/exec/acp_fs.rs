@@ -0,0 +1,316 @@
+//! Podbot-served Agentic Control Protocol (ACP) `fs/*` methods.
+//!
+//! With `[agent.acp] fs = "serve"`, podbot advertises the `fs` client
+//! capability itself and answers the agent's `fs/read_text_file` and
+//! `fs/write_text_file` requests instead of denying them or forwarding them
+//! to the host client. Requests arrive with absolute paths as the agent sees
+//! them inside the container; each path must lie under
+//! `workspace.container_path` and is opened relative to a cap-std [`Dir`]
+//! rooted at `workspace.host_path`, so neither `..` components nor symlinks
+//! can reach files outside the workspace.
+//!
+//! Every answer is a synthesized JSON-RPC response queued on container
+//! stdin. Refused and failed requests receive a JSON-RPC error whose
+//! `data.reason` explains the refusal; other `fs/*` requests are answered
+//! with "Method not found", and `fs/*` notifications are dropped.
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use cap_std::ambient_authority;
+use cap_std::fs_utf8::Dir;
+use ortho_config::serde_json::{self, Value};
+
+use super::acp_helpers::parse_client_method_call;
+
+/// ACP method that reads a text file from the client.
+pub(crate) const FS_READ_TEXT_FILE_METHOD: &str = "fs/read_text_file";
+
+/// ACP method that writes a text file through the client.
+pub(crate) const FS_WRITE_TEXT_FILE_METHOD: &str = "fs/write_text_file";
+
+/// Method-name prefix of the `fs` capability family.
+const FS_METHOD_PREFIX: &str = "fs/";
+
+/// Largest file podbot reads on the agent's behalf, in bytes.
+pub(crate) const MAX_SERVED_FILE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// An agent-emitted `fs/*` frame routed to podbot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FsRequest {
+    /// JSON-RPC id, or `None` for a notification.
+    pub(crate) id: Option<Value>,
+    /// The `fs/*` method name.
+    pub(crate) method: String,
+    /// The request's params, or `null` when absent.
+    pub(crate) params: Value,
+}
+
+impl FsRequest {
+    /// Parse `frame` as a JSON-RPC 2.0 `fs/*` request or notification.
+    ///
+    /// Returns `None` for any other frame, including unparseable ones.
+    pub(crate) fn parse(frame: &[u8]) -> Option<Self> {
+        let (id, method, params) = parse_client_method_call(frame, FS_METHOD_PREFIX)?;
+        Some(Self { id, method, params })
+    }
+}
+
+/// Why podbot refused or failed an `fs/*` request.
+#[derive(Debug)]
+pub(crate) enum FsError {
+    /// The method is not one podbot serves.
+    MethodNotFound,
+    /// The params are missing a field or carry one of the wrong type.
+    InvalidParams(&'static str),
+    /// The path does not lie inside the workspace.
+    OutsideWorkspace,
+    /// The file does not exist.
+    NotFound,
+    /// The file exceeds [`MAX_SERVED_FILE_BYTES`].
+    TooLarge,
+    /// No workspace is available to serve the request from.
+    Unavailable,
+    /// Reading or writing the file failed.
+    Io(io::Error),
+}
+
+impl FsError {
+    /// Return the JSON-RPC error code for this failure.
+    const fn code(&self) -> i64 {
+        match self {
+            Self::MethodNotFound => -32_601,
+            Self::InvalidParams(_) | Self::OutsideWorkspace | Self::TooLarge => -32_602,
+            Self::NotFound => -32_002,
+            Self::Unavailable | Self::Io(_) => -32_603,
+        }
+    }
+
+    /// Return the JSON-RPC error message for this failure.
+    const fn message(&self) -> &'static str {
+        match self {
+            Self::MethodNotFound => "Method not found",
+            Self::InvalidParams(_) | Self::OutsideWorkspace | Self::TooLarge => "Invalid params",
+            Self::NotFound => "Resource not found",
+            Self::Unavailable | Self::Io(_) => "Internal error",
+        }
+    }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MethodNotFound => formatter.write_str("podbot does not serve this fs method"),
+            Self::InvalidParams(reason) => formatter.write_str(reason),
+            Self::OutsideWorkspace => formatter.write_str("path is outside the workspace"),
+            Self::NotFound => formatter.write_str("file does not exist"),
+            Self::TooLarge => write!(
+                formatter,
+                "file exceeds the {MAX_SERVED_FILE_BYTES}-byte limit for served reads"
+            ),
+            Self::Unavailable => formatter.write_str("no workspace is available to serve fs"),
+            Self::Io(error) => write!(formatter, "{error}"),
+        }
+    }
+}
+
+impl From<io::Error> for FsError {
+    fn from(error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            Self::NotFound
+        } else {
+            Self::Io(error)
+        }
+    }
+}
+
+/// The host-mounted workspace that served `fs/*` requests are confined to.
+///
+/// Clones share one directory handle.
+#[derive(Clone)]
+pub(crate) struct WorkspaceFs {
+    inner: Arc<WorkspaceFsInner>,
+}
+
+struct WorkspaceFsInner {
+    root: Dir,
+    container_root: Utf8PathBuf,
+}
+
+impl WorkspaceFs {
+    /// Serve files from `root`, which the agent sees at `container_root`.
+    pub(crate) fn new(root: Dir, container_root: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            inner: Arc::new(WorkspaceFsInner {
+                root,
+                container_root: container_root.into(),
+            }),
+        }
+    }
+
+    /// Open the workspace at `host_path`, which the agent sees at
+    /// `container_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error raised while opening `host_path`.
+    pub(crate) fn open(host_path: &Utf8Path, container_path: &Utf8Path) -> io::Result<Self> {
+        let root = Dir::open_ambient_dir(host_path, ambient_authority())?;
+        Ok(Self::new(root, container_path))
+    }
+
+    /// Answer `request`, returning the JSON-RPC `result` on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`FsError`] when the request is refused or the file
+    /// operation fails.
+    pub(crate) fn serve(&self, request: &FsRequest) -> Result<Value, FsError> {
+        match request.method.as_str() {
+            FS_READ_TEXT_FILE_METHOD => self.read_text_file(&request.params),
+            FS_WRITE_TEXT_FILE_METHOD => self.write_text_file(&request.params),
+            _ => Err(FsError::MethodNotFound),
+        }
+    }
+
+    /// Answer `request` on Tokio's blocking pool.
+    pub(crate) async fn serve_blocking(&self, request: FsRequest) -> Result<Value, FsError> {
+        let workspace = self.clone();
+        tokio::task::spawn_blocking(move || workspace.serve(&request))
+            .await
+            .unwrap_or_else(|error| Err(FsError::Io(io::Error::other(error))))
+    }
+
+    fn read_text_file(&self, params: &Value) -> Result<Value, FsError> {
+        let path = self.resolve(params)?;
+        let line = optional_count(params, "line")?;
+        let limit = optional_count(params, "limit")?;
+        if self.inner.root.metadata(&path)?.len() > MAX_SERVED_FILE_BYTES {
+            return Err(FsError::TooLarge);
+        }
+        let content = self.inner.root.read_to_string(&path)?;
+        Ok(serde_json::json!({ "content": select_lines(&content, line, limit) }))
+    }
+
+    fn write_text_file(&self, params: &Value) -> Result<Value, FsError> {
+        let path = self.resolve(params)?;
+        let content = params
+            .get("content")
+            .and_then(Value::as_str)
+            .ok_or(FsError::InvalidParams("`content` must be a string"))?;
+        if let Some(parent) = path.parent()
+            && !parent.as_str().is_empty()
+        {
+            self.inner.root.create_dir_all(parent)?;
+        }
+        self.inner.root.write(&path, content)?;
+        Ok(Value::Null)
+    }
+
+    /// Map the request's absolute container path to a path relative to the
+    /// workspace root.
+    fn resolve(&self, params: &Value) -> Result<Utf8PathBuf, FsError> {
+        let raw = params
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or(FsError::InvalidParams("`path` must be a string"))?;
+        let path = Utf8Path::new(raw);
+        if !path.is_absolute() {
+            return Err(FsError::InvalidParams("`path` must be absolute"));
+        }
+        let relative = path
+            .strip_prefix(&self.inner.container_root)
+            .map_err(|_| FsError::OutsideWorkspace)?;
+        if relative
+            .components()
+            .any(|component| !matches!(component, Utf8Component::Normal(_)))
+        {
+            return Err(FsError::OutsideWorkspace);
+        }
+        Ok(relative.to_path_buf())
+    }
+}
+
+impl fmt::Debug for WorkspaceFs {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("WorkspaceFs")
+            .field("container_root", &self.inner.container_root)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for WorkspaceFs {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for WorkspaceFs {}
+
+/// Build the JSON-RPC response to an `fs/*` request from its `outcome`,
+/// terminated by `line_ending`.
+///
+/// # Errors
+///
+/// Returns the `serde_json` error raised while serializing the response.
+pub(crate) fn build_fs_response(
+    id: &Value,
+    method: &str,
+    outcome: &Result<Value, FsError>,
+    line_ending: &[u8],
+) -> serde_json::Result<Vec<u8>> {
+    let payload = match outcome {
+        Ok(result) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }),
+        Err(error) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": error.code(),
+                "message": error.message(),
+                "data": {
+                    "method": method,
+                    "reason": error.to_string(),
+                },
+            },
+        }),
+    };
+    let mut serialized = serde_json::to_vec(&payload)?;
+    serialized.extend_from_slice(line_ending);
+    Ok(serialized)
+}
+
+/// Read an optional non-negative integer param.
+fn optional_count(params: &Value, name: &'static str) -> Result<Option<u64>, FsError> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or(FsError::InvalidParams(
+            "`line` and `limit` must be non-negative integers",
+        )),
+    }
+}
+
+/// Return `limit` lines of `content` starting at the 1-based `line`.
+fn select_lines(content: &str, line: Option<u64>, limit: Option<u64>) -> String {
+    if line.is_none() && limit.is_none() {
+        return String::from(content);
+    }
+    let to_usize = |count: u64| usize::try_from(count).unwrap_or(usize::MAX);
+    let skip = line.map_or(0, |first| to_usize(first.saturating_sub(1)));
+    let take = limit.map_or(usize::MAX, to_usize);
+    content
+        .split_inclusive('\n')
+        .skip(skip)
+        .take(take)
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "acp_fs_tests.rs"]
+mod tests;
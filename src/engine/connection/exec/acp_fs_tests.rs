@@ -0,0 +1,234 @@
+//! Unit tests for podbot-served ACP `fs/*` methods.
+
+use camino::Utf8PathBuf;
+use cap_std::ambient_authority;
+use cap_std::fs_utf8::Dir;
+use ortho_config::serde_json::{self, Value};
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::{FsError, FsRequest, WorkspaceFs, build_fs_response, select_lines};
+use crate::engine::connection::exec::acp_test_support::jsonrpc_frame;
+
+/// A temporary workspace seeded with `src/lib.rs`, served as `/workspace`.
+struct Workspace {
+    temp_dir: TempDir,
+    fs: WorkspaceFs,
+}
+
+impl Workspace {
+    fn host_path(&self, relative: &str) -> Utf8PathBuf {
+        Utf8PathBuf::from_path_buf(self.temp_dir.path().join(relative))
+            .expect("tempdir path should be UTF-8")
+    }
+}
+
+#[fixture]
+fn workspace() -> Workspace {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root_path = temp_dir.path().join("workspace");
+    std::fs::create_dir_all(root_path.join("src")).expect("workspace should be created");
+    std::fs::write(root_path.join("src/lib.rs"), "one\ntwo\nthree\n")
+        .expect("seed should be written");
+    let root = Utf8PathBuf::from_path_buf(root_path).expect("tempdir path should be UTF-8");
+    let dir = Dir::open_ambient_dir(&root, ambient_authority()).expect("workspace should open");
+    Workspace {
+        temp_dir,
+        fs: WorkspaceFs::new(dir, "/workspace"),
+    }
+}
+
+fn request(method: &str, params: Value) -> FsRequest {
+    FsRequest {
+        id: Some(Value::from(1)),
+        method: String::from(method),
+        params,
+    }
+}
+
+#[rstest]
+fn read_text_file_returns_the_file_content(workspace: Workspace) {
+    let result = workspace.fs.serve(&request(
+        "fs/read_text_file",
+        serde_json::json!({"sessionId": "s", "path": "/workspace/src/lib.rs"}),
+    ));
+
+    assert_eq!(
+        result.ok(),
+        Some(serde_json::json!({"content": "one\ntwo\nthree\n"}))
+    );
+}
+
+#[rstest]
+fn read_text_file_honours_line_and_limit(workspace: Workspace) {
+    let result = workspace.fs.serve(&request(
+        "fs/read_text_file",
+        serde_json::json!({"path": "/workspace/src/lib.rs", "line": 2, "limit": 1}),
+    ));
+
+    assert_eq!(result.ok(), Some(serde_json::json!({"content": "two\n"})));
+}
+
+#[rstest]
+fn write_text_file_creates_the_file_and_its_parents(workspace: Workspace) {
+    let result = workspace.fs.serve(&request(
+        "fs/write_text_file",
+        serde_json::json!({"path": "/workspace/docs/notes.md", "content": "hello"}),
+    ));
+
+    assert_eq!(result.ok(), Some(Value::Null));
+    assert_eq!(
+        std::fs::read_to_string(workspace.host_path("workspace/docs/notes.md"))
+            .expect("written file should be readable"),
+        "hello"
+    );
+}
+
+#[rstest]
+#[case::parent_escape("/workspace/../secret.txt")]
+#[case::outside_container_root("/etc/passwd")]
+#[case::sibling_prefix("/workspace-other/file")]
+fn paths_outside_the_workspace_are_rejected(workspace: Workspace, #[case] path: &str) {
+    std::fs::write(workspace.host_path("secret.txt"), "secret").expect("secret should be written");
+
+    let result = workspace.fs.serve(&request(
+        "fs/read_text_file",
+        serde_json::json!({"path": path}),
+    ));
+
+    assert!(
+        matches!(result, Err(FsError::OutsideWorkspace)),
+        "{result:?}"
+    );
+}
+
+#[cfg(unix)]
+#[rstest]
+fn symlinks_cannot_escape_the_workspace(workspace: Workspace) {
+    std::fs::write(workspace.host_path("secret.txt"), "secret").expect("secret should be written");
+    std::os::unix::fs::symlink(
+        workspace.host_path("secret.txt"),
+        workspace.host_path("workspace/link.txt"),
+    )
+    .expect("symlink should be created");
+
+    let result = workspace.fs.serve(&request(
+        "fs/read_text_file",
+        serde_json::json!({"path": "/workspace/link.txt"}),
+    ));
+
+    assert!(matches!(result, Err(FsError::Io(_))), "{result:?}");
+}
+
+#[rstest]
+#[case::relative_path(
+    "fs/read_text_file",
+    serde_json::json!({"path": "src/lib.rs"}),
+    "must be absolute"
+)]
+#[case::missing_content(
+    "fs/write_text_file",
+    serde_json::json!({"path": "/workspace/a.txt"}),
+    "`content`"
+)]
+#[case::negative_line(
+    "fs/read_text_file",
+    serde_json::json!({"path": "/workspace/src/lib.rs", "line": -1}),
+    "non-negative"
+)]
+fn malformed_params_are_rejected(
+    workspace: Workspace,
+    #[case] method: &str,
+    #[case] params: Value,
+    #[case] reason: &str,
+) {
+    let result = workspace.fs.serve(&request(method, params));
+
+    assert!(
+        matches!(&result, Err(FsError::InvalidParams(message)) if message.contains(reason)),
+        "{result:?}"
+    );
+}
+
+#[rstest]
+fn missing_files_and_unknown_methods_are_reported(workspace: Workspace) {
+    let missing = workspace.fs.serve(&request(
+        "fs/read_text_file",
+        serde_json::json!({"path": "/workspace/absent.txt"}),
+    ));
+    let unknown = workspace
+        .fs
+        .serve(&request("fs/delete", serde_json::json!({})));
+
+    assert!(matches!(missing, Err(FsError::NotFound)), "{missing:?}");
+    assert!(
+        matches!(unknown, Err(FsError::MethodNotFound)),
+        "{unknown:?}"
+    );
+}
+
+#[rstest]
+fn parse_recognizes_fs_requests_and_notifications() {
+    let request_frame = jsonrpc_frame(Some(&Value::from(4)), "fs/read_text_file", b"\n")
+        .expect("frame should serialize");
+    let notification = jsonrpc_frame(None, "fs/changed", b"\n").expect("frame should serialize");
+    let other = jsonrpc_frame(Some(&Value::from(5)), "session/prompt", b"\n")
+        .expect("frame should serialize");
+
+    assert_eq!(
+        FsRequest::parse(&request_frame),
+        Some(FsRequest {
+            id: Some(Value::from(4)),
+            method: String::from("fs/read_text_file"),
+            params: serde_json::json!({}),
+        })
+    );
+    assert_eq!(
+        FsRequest::parse(&notification).map(|parsed| parsed.id),
+        Some(None)
+    );
+    assert_eq!(FsRequest::parse(&other), None);
+    assert_eq!(FsRequest::parse(b"not json\n"), None);
+}
+
+#[rstest]
+fn errors_are_returned_as_json_rpc_errors() {
+    let response = build_fs_response(
+        &Value::from(7),
+        "fs/read_text_file",
+        &Err(FsError::OutsideWorkspace),
+        b"\n",
+    )
+    .expect("response should serialize");
+
+    let parsed: Value = serde_json::from_slice(&response).expect("response should be JSON");
+    assert_eq!(
+        parsed,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "error": {
+                "code": -32_602,
+                "message": "Invalid params",
+                "data": {
+                    "method": "fs/read_text_file",
+                    "reason": "path is outside the workspace",
+                },
+            },
+        })
+    );
+    assert!(response.ends_with(b"\n"));
+}
+
+#[rstest]
+#[case::whole(None, None, "a\nb\nc")]
+#[case::from_line(Some(2), None, "b\nc")]
+#[case::limited(None, Some(2), "a\nb\n")]
+#[case::past_end(Some(9), Some(1), "")]
+fn select_lines_slices_by_line(
+    #[case] line: Option<u64>,
+    #[case] limit: Option<u64>,
+    #[case] expected: &str,
+) {
+    assert_eq!(select_lines("a\nb\nc", line, limit), expected);
+}
@@ -13,9 +13,10 @@
 use std::io;
 use std::pin::Pin;
 
-use ortho_config::serde_json::{self, Value};
+use ortho_config::serde_json::{self, Map, Value};
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt};
 
+use super::acp_policy::AcpDelegation;
 use super::protocol::STDIN_BUFFER_CAPACITY;
 
 /// Upper bound on the number of bytes buffered while searching for the first
@@ -53,31 +54,35 @@ enum ForwardUnchangedReason {
 }
 
 /// Reads the first newline-delimited ACP frame from `buffered_stdin`, rewrites
-/// it by removing masked capabilities, and writes the result to `input`.
+/// it by removing the capabilities not in `delegation`, and writes the result
+/// to `input`.
 ///
 /// If the frame exceeds [`MAX_FIRST_FRAME_BYTES`] before a newline is found,
 /// or if EOF is reached first, the buffered bytes are forwarded unchanged.
 pub(super) async fn forward_initial_acp_frame_async<HostStdin>(
     buffered_stdin: &mut tokio::io::BufReader<HostStdin>,
     input: &mut Pin<Box<dyn AsyncWrite + Send>>,
+    delegation: AcpDelegation,
 ) -> io::Result<()>
 where
     HostStdin: AsyncRead + Unpin,
 {
-    let bytes = read_and_mask_initial_acp_frame(buffered_stdin).await?;
+    let bytes = read_and_mask_initial_acp_frame(buffered_stdin, delegation).await?;
     input.write_all(&bytes).await
 }
 
 /// Reads the first newline-delimited ACP frame from `buffered_stdin` and
 /// returns the bytes that the protocol session should forward to the
 /// container, applying capability masking when the frame is a recognized
-/// `initialize` request.
+/// `initialize` request. Capability families in `delegation` are left
+/// advertised.
 ///
 /// If the frame exceeds [`MAX_FIRST_FRAME_BYTES`] before a newline is found,
 /// or if EOF is reached first, the buffered bytes are returned unchanged so
 /// the caller can forward them verbatim.
 pub(super) async fn read_and_mask_initial_acp_frame<HostStdin>(
     buffered_stdin: &mut tokio::io::BufReader<HostStdin>,
+    delegation: AcpDelegation,
 ) -> io::Result<Vec<u8>>
 where
     HostStdin: AsyncRead + Unpin,
@@ -91,7 +96,7 @@ where
                 return Ok(first_frame);
             }
             InitialFrameAction::ForwardMasked => {
-                let masked = mask_acp_initialize_frame(&first_frame);
+                let masked = mask_acp_initialize_frame(&first_frame, delegation);
                 log_masked_frame_forwarded(&masked, &first_frame);
                 return Ok(masked);
             }
@@ -188,14 +193,15 @@ where
 }
 
 /// Rewrites an ACP `initialize` frame by removing `terminal` and `fs` from
-/// `params.clientCapabilities`, then re-serializes the JSON preserving the
-/// original line ending. Returns the original frame unchanged on any parse or
-/// serialization failure, or if no capabilities were removed.
+/// `params.clientCapabilities`, except for families in `delegation`, then
+/// re-serializes the JSON preserving the original line ending. Returns the
+/// original frame unchanged on any parse or serialization failure, or if no
+/// capabilities were removed.
 #[expect(
     clippy::cognitive_complexity,
     reason = "inline parse and serialize fallback tracing keeps this review-required flow local"
 )]
-pub(super) fn mask_acp_initialize_frame(frame: &[u8]) -> Vec<u8> {
+pub(super) fn mask_acp_initialize_frame(frame: &[u8], delegation: AcpDelegation) -> Vec<u8> {
     let (payload, line_ending) = split_frame_line_ending(frame);
 
     let mut message: Value = match serde_json::from_slice(payload) {
@@ -209,7 +215,7 @@ pub(super) fn mask_acp_initialize_frame(frame: &[u8]) -> Vec<u8> {
         }
     };
 
-    if !remove_masked_acp_capabilities(&mut message) {
+    if !remove_masked_acp_capabilities(&mut message, delegation) {
         tracing::debug!("ACP frame is not a maskable initialize request; forwarding unchanged");
         return frame.to_vec();
     }
@@ -228,6 +234,33 @@ pub(super) fn mask_acp_initialize_frame(frame: &[u8]) -> Vec<u8> {
     serialized
 }
 
+/// Parse `frame` as a JSON-RPC 2.0 request or notification whose method
+/// starts with `prefix`, returning its `id` (absent for a notification),
+/// method name, and params (`null` when absent).
+///
+/// Returns `None` for any other frame, including unparseable ones.
+pub(super) fn parse_client_method_call(
+    frame: &[u8],
+    prefix: &str,
+) -> Option<(Option<Value>, String, Value)> {
+    let (payload, _line_ending) = split_frame_line_ending(frame);
+    let mut message = serde_json::from_slice::<Value>(payload).ok()?;
+    if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return None;
+    }
+    let method = message
+        .get("method")
+        .and_then(Value::as_str)
+        .filter(|name| name.starts_with(prefix))
+        .map(String::from)?;
+    let object = message.as_object_mut()?;
+    Some((
+        object.remove("id"),
+        method,
+        object.remove("params").unwrap_or(Value::Null),
+    ))
+}
+
 /// Splits a newline-delimited frame into its JSON payload and trailing line
 /// ending bytes (`\n` or `\r\n`). Returns the original slice as the payload
 /// with an empty line-ending slice when no recognized line ending is present.
@@ -244,9 +277,9 @@ pub(super) fn split_frame_line_ending(frame: &[u8]) -> (&[u8], &[u8]) {
 }
 
 /// Remove `terminal` and `fs` from `params.clientCapabilities` in an ACP
-/// `initialize` message. Returns `true` when at least one capability was
-/// removed.
-fn remove_masked_acp_capabilities(message: &mut Value) -> bool {
+/// `initialize` message, keeping the families in `delegation`, and advertise
+/// the families podbot serves. Returns `true` when the capabilities changed.
+fn remove_masked_acp_capabilities(message: &mut Value, delegation: AcpDelegation) -> bool {
     if message.get("method").and_then(Value::as_str) != Some(ACP_INITIALIZE_METHOD) {
         return false;
     }
@@ -255,21 +288,35 @@ fn remove_masked_acp_capabilities(message: &mut Value) -> bool {
         return false;
     };
 
+    let advertised_fs = delegation.serves_fs()
+        && advertise_served(
+            params,
+            ACP_FILE_SYSTEM_CAPABILITY,
+            &serde_json::json!({"readTextFile": true, "writeTextFile": true}),
+        );
+    let advertised_terminal = delegation.serves_terminal()
+        && advertise_served(params, ACP_TERMINAL_CAPABILITY, &Value::Bool(true));
+    let advertised = advertised_fs || advertised_terminal;
+
     let Some(client_capabilities) = params
         .get_mut(ACP_CLIENT_CAPABILITIES_FIELD)
         .and_then(Value::as_object_mut)
     else {
-        return false;
+        return advertised;
     };
 
-    let removed_terminal = client_capabilities
-        .remove(ACP_TERMINAL_CAPABILITY)
-        .is_some();
-    let removed_fs = client_capabilities
-        .remove(ACP_FILE_SYSTEM_CAPABILITY)
-        .is_some();
+    let removed_terminal = !delegation.delegates_terminal()
+        && !delegation.serves_terminal()
+        && client_capabilities
+            .remove(ACP_TERMINAL_CAPABILITY)
+            .is_some();
+    let removed_fs = !delegation.delegates_fs()
+        && !delegation.serves_fs()
+        && client_capabilities
+            .remove(ACP_FILE_SYSTEM_CAPABILITY)
+            .is_some();
     if !removed_terminal && !removed_fs {
-        return false;
+        return advertised;
     }
 
     if client_capabilities.is_empty() {
@@ -278,3 +325,18 @@ fn remove_masked_acp_capabilities(message: &mut Value) -> bool {
 
     true
 }
+
+/// Replace the client's `capability` advertisement with the support podbot
+/// provides, `served`. Returns `true` when the advertisement changed.
+fn advertise_served(params: &mut Map<String, Value>, capability: &str, served: &Value) -> bool {
+    let capabilities = params
+        .entry(ACP_CLIENT_CAPABILITIES_FIELD)
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Some(client_capabilities) = capabilities.as_object_mut() else {
+        return false;
+    };
+    client_capabilities
+        .insert(String::from(capability), served.clone())
+        .as_ref()
+        != Some(served)
+}
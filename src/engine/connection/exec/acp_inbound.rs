@@ -0,0 +1,180 @@
+//! Host-inbound Agentic Control Protocol (ACP) rule enforcement.
+//!
+//! When operators configure method rules or an audit log under
+//! `[agent.acp]`, or the workspace is host-mounted, host stdin is
+//! framed by the same [`OutboundFrameAssembler`] that polices the agent's
+//! output, running in the host-inbound direction. Permitted frames are queued
+//! on the container-stdin sink as [`WriteCmd::Forward`]; a blocked request is
+//! answered on the host side by a synthesized JSON-RPC error sent to the
+//! output loop over a reply channel, because host stdout has a single owner.
+//! Blocked notifications are dropped. Each denial emits one stderr
+//! `tracing::warn!`.
+
+use ortho_config::serde_json::{self, Value};
+use tokio::sync::mpsc;
+
+use super::acp_frame::{DeniedFrameDecision, FallbackReason, FrameOutput, OutboundFrameAssembler};
+use super::acp_policy::build_method_blocked_error;
+use super::acp_runtime::WriteCmd;
+
+/// Adapter that turns host-inbound assembler outputs into container-stdin
+/// commands and host-bound error replies.
+pub(super) struct InboundPolicyAdapter {
+    assembler: OutboundFrameAssembler,
+    replies: mpsc::Sender<Vec<u8>>,
+    container_id: String,
+    fallback_logged: bool,
+}
+
+impl InboundPolicyAdapter {
+    /// Construct an adapter over a host-inbound assembler and the channel
+    /// that carries synthesized replies to host stdout.
+    pub(super) fn new(
+        assembler: OutboundFrameAssembler,
+        replies: mpsc::Sender<Vec<u8>>,
+        container_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            assembler,
+            replies,
+            container_id: container_id.into(),
+            fallback_logged: false,
+        }
+    }
+
+    /// Process one chunk of host stdin, queuing permitted frames on `sink`.
+    ///
+    /// Returns `false` once the sink channel has closed and the caller
+    /// should stop reading host stdin.
+    pub(super) async fn handle_chunk(
+        &mut self,
+        chunk: &[u8],
+        sink: &mpsc::Sender<WriteCmd>,
+    ) -> bool {
+        let (outputs, fallback) = self.assembler.ingest_chunk(chunk);
+        if let Some(reason) = fallback {
+            self.log_fallback_once(reason);
+        }
+        for output in outputs {
+            if !self.dispatch_output(output, sink).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Record the masked `initialize` frame, which is forwarded before the
+    /// adapter sees host stdin, in the session's audit log.
+    pub(super) fn audit_initialize(&self, frame: &[u8]) {
+        self.assembler.audit_forwarded(frame);
+    }
+
+    /// Finalize the adapter at end of host stdin, logging any partial-frame
+    /// drop reported by the assembler.
+    pub(super) fn finish(&mut self) {
+        if let Some(reason) = self.assembler.finish() {
+            self.log_fallback_once(reason);
+        }
+    }
+
+    async fn dispatch_output(&self, output: FrameOutput, sink: &mpsc::Sender<WriteCmd>) -> bool {
+        match output {
+            FrameOutput::Forward(bytes) | FrameOutput::Permission(_, bytes) => {
+                sink.send(WriteCmd::Forward(bytes)).await.is_ok()
+            }
+            // Only agent-outbound frames are served by podbot.
+            FrameOutput::ServeFs(..) | FrameOutput::ServeTerminal(..) => true,
+            FrameOutput::Decision(decision, line_ending) => {
+                self.handle_decision(decision, &line_ending).await;
+                true
+            }
+        }
+    }
+
+    async fn handle_decision(&self, decision: DeniedFrameDecision, line_ending: &[u8]) {
+        self.log_denial(&decision);
+        if let DeniedFrameDecision::BlockRequest { id, method, rules } = &decision {
+            match build_method_blocked_error(id, method, rules, line_ending) {
+                Ok(bytes) => self.send_reply_or_log(bytes, method).await,
+                Err(error) => self.log_synthesis_failure(method, &error),
+            }
+        }
+    }
+
+    async fn send_reply_or_log(&self, bytes: Vec<u8>, method: &str) {
+        if self.replies.send(bytes).await.is_err() {
+            self.log_reply_closed(method);
+        }
+    }
+
+    fn log_reply_closed(&self, method: &str) {
+        tracing::warn!(
+            target = "podbot::acp::policy",
+            container_id = %self.container_id,
+            method = %method,
+            "ACP denial response could not be returned; host output already closed",
+        );
+    }
+
+    fn log_synthesis_failure(&self, method: &str, error: &serde_json::Error) {
+        tracing::warn!(
+            target = "podbot::acp::policy",
+            container_id = %self.container_id,
+            method = %method,
+            %error,
+            "ACP denial response failed to serialize; host client will time out",
+        );
+    }
+
+    fn log_denial(&self, decision: &DeniedFrameDecision) {
+        let (method, id, rules, message) = match decision {
+            DeniedFrameDecision::BlockNotification { method, rules } => (
+                method,
+                &Value::Null,
+                rules,
+                "ACP blocked host notification dropped",
+            ),
+            DeniedFrameDecision::BlockRequest { id, method, rules } => {
+                (method, id, rules, "ACP blocked host request denied")
+            }
+        };
+        tracing::warn!(
+            target = "podbot::acp::policy",
+            container_id = %self.container_id,
+            method = %method,
+            id = %id,
+            rules = ?rules,
+            "{message}",
+        );
+    }
+
+    fn log_fallback_once(&mut self, reason: FallbackReason) {
+        if self.fallback_logged {
+            return;
+        }
+        self.fallback_logged = true;
+        match reason {
+            FallbackReason::BufferOverflow => self.warn_buffer_overflow(),
+            FallbackReason::DroppedPartialFrame { byte_count } => {
+                self.warn_partial_frame_drop(byte_count);
+            }
+        }
+    }
+
+    fn warn_buffer_overflow(&self) {
+        tracing::warn!(
+            target = "podbot::acp::policy",
+            container_id = %self.container_id,
+            "ACP host input buffer overflowed; remaining bytes forwarded raw",
+        );
+    }
+
+    fn warn_partial_frame_drop(&self, byte_count: usize) {
+        tracing::warn!(
+            target = "podbot::acp::policy",
+            container_id = %self.container_id,
+            byte_count,
+            "ACP host input dropped unterminated partial frame at end of stream",
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Host-to-container path mapping for Agentic Control Protocol (ACP) frames.
+//!
+//! ACP clients name files by their host paths, starting with the project
+//! directory they send as `session/new.cwd`, but the agent sees a
+//! host-mounted workspace at `workspace.container_path`. When the session
+//! has a [`PathMap`], the frame assembler rewrites host-inbound frames so
+//! that paths under `workspace.host_path` point into the container, and
+//! rewrites agent-outbound frames forwarded to the host the other way.
+//!
+//! Only whole string values inside a frame's `params` or `result` are
+//! rewritten: a string that is the workspace root, an absolute path beneath
+//! it, or a `file://` URI for either. Prose that merely mentions a path is
+//! left alone. Frames with nothing to rewrite, and frames that are not JSON,
+//! pass through byte-for-byte; only rewritten frames are re-serialized.
+
+use camino::Utf8Path;
+use ortho_config::serde_json::{self, Value};
+
+use super::acp_helpers::split_frame_line_ending;
+use super::acp_policy::FrameDirection;
+use crate::config::{WorkspaceConfig, WorkspaceSource, default_host_mount_container_path};
+
+/// URI scheme prefix whose paths are mapped like bare paths.
+const FILE_URI_PREFIX: &str = "file://";
+
+/// Top-level JSON-RPC members whose strings are rewritten.
+const MAPPED_MEMBERS: [&str; 2] = ["params", "result"];
+
+/// The host and container locations of a host-mounted workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PathMap {
+    host_root: String,
+    container_root: String,
+}
+
+impl PathMap {
+    /// Map `host_root` on the host to `container_root` in the container.
+    ///
+    /// Returns `None` when either path is relative or both name the same
+    /// directory, since then there is nothing to rewrite.
+    pub(crate) fn new(host_root: &Utf8Path, container_root: &Utf8Path) -> Option<Self> {
+        if !host_root.is_absolute() || !container_root.is_absolute() {
+            return None;
+        }
+        let host = trim_root(host_root);
+        let container = trim_root(container_root);
+        (host != container).then(|| Self {
+            host_root: String::from(host),
+            container_root: String::from(container),
+        })
+    }
+
+    /// Resolve the mapping for a `host_mount` workspace, or `None` for any
+    /// other workspace source.
+    pub(crate) fn from_config(config: &WorkspaceConfig) -> Option<Self> {
+        if config.source != WorkspaceSource::HostMount {
+            return None;
+        }
+        let host_path = config.host_path.as_deref()?;
+        let container_path = config
+            .container_path
+            .clone()
+            .unwrap_or_else(default_host_mount_container_path);
+        Self::new(host_path, &container_path)
+    }
+
+    /// Rewrite the paths in `frame` for delivery in `direction`.
+    ///
+    /// Host-inbound frames map host paths to container paths, and
+    /// agent-outbound frames map container paths back to host paths. Returns
+    /// `None` when the frame is not a JSON object or has no path to rewrite,
+    /// in which case the caller forwards the original bytes.
+    pub(crate) fn rewrite(&self, frame: &[u8], direction: FrameDirection) -> Option<Vec<u8>> {
+        let (from, to) = match direction {
+            FrameDirection::HostInbound => (&self.host_root, &self.container_root),
+            FrameDirection::AgentOutbound => (&self.container_root, &self.host_root),
+        };
+        let (payload, line_ending) = split_frame_line_ending(frame);
+        let mut message = serde_json::from_slice::<Value>(payload).ok()?;
+        let object = message.as_object_mut()?;
+        let mut rewritten = false;
+        for member in MAPPED_MEMBERS {
+            if let Some(value) = object.get_mut(member) {
+                rewritten |= rewrite_strings(value, from, to);
+            }
+        }
+        if !rewritten {
+            return None;
+        }
+        let mut serialized = serde_json::to_vec(&message).ok()?;
+        serialized.extend_from_slice(line_ending);
+        Some(serialized)
+    }
+}
+
+/// Return `path` without trailing separators; the filesystem root becomes
+/// the empty string so that every absolute path lies beneath it.
+fn trim_root(path: &Utf8Path) -> &str {
+    path.as_str().trim_end_matches('/')
+}
+
+/// Rewrite every mappable string in `value`, returning `true` when any
+/// string changed.
+fn rewrite_strings(value: &mut Value, from: &str, to: &str) -> bool {
+    match value {
+        Value::String(text) => map_string(text, from, to)
+            .map(|mapped| *text = mapped)
+            .is_some(),
+        Value::Array(items) => items.iter_mut().fold(false, |rewritten, item| {
+            rewrite_strings(item, from, to) || rewritten
+        }),
+        Value::Object(fields) => fields.values_mut().fold(false, |rewritten, item| {
+            rewrite_strings(item, from, to) || rewritten
+        }),
+        _ => false,
+    }
+}
+
+/// Map a bare absolute path or a `file://` URI from under `from` to under
+/// `to`.
+fn map_string(text: &str, from: &str, to: &str) -> Option<String> {
+    text.strip_prefix(FILE_URI_PREFIX).map_or_else(
+        || map_path(text, from, to),
+        |path| map_path(path, from, to).map(|mapped| format!("{FILE_URI_PREFIX}{mapped}")),
+    )
+}
+
+/// Map `path` when it is `from` or lies beneath it.
+fn map_path(path: &str, from: &str, to: &str) -> Option<String> {
+    if !path.starts_with('/') {
+        return None;
+    }
+    let rest = path
+        .strip_prefix(from)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))?;
+    let mapped = format!("{to}{rest}");
+    Some(if mapped.is_empty() {
+        String::from("/")
+    } else {
+        mapped
+    })
+}
+
+#[cfg(test)]
+#[path = "acp_paths_tests.rs"]
+mod tests;
@@ -0,0 +1,219 @@
+//! Unit tests for ACP workspace path mapping.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ortho_config::serde_json::{self, Value};
+use rstest::{fixture, rstest};
+
+use super::PathMap;
+use crate::config::{WorkspaceConfig, WorkspaceSource};
+use crate::engine::connection::exec::acp_frame::{FrameOutput, OutboundFrameAssembler};
+use crate::engine::connection::exec::acp_policy::{FrameDirection, MethodDenylist};
+
+#[fixture]
+fn paths() -> PathMap {
+    PathMap::new(
+        Utf8Path::new("/home/me/project"),
+        Utf8Path::new("/workspace"),
+    )
+    .expect("distinct roots should map")
+}
+
+fn frame(message: &Value, line_ending: &[u8]) -> Vec<u8> {
+    let mut bytes = serde_json::to_vec(message).expect("frame should serialize");
+    bytes.extend_from_slice(line_ending);
+    bytes
+}
+
+fn rewritten(paths: &PathMap, message: &Value, direction: FrameDirection) -> Option<Value> {
+    paths
+        .rewrite(&frame(message, b"\n"), direction)
+        .map(|bytes| serde_json::from_slice(&bytes).expect("rewritten frame should be JSON"))
+}
+
+#[rstest]
+#[case::root("/home/me/project", "/workspace")]
+#[case::nested("/home/me/project/src/lib.rs", "/workspace/src/lib.rs")]
+#[case::file_uri("file:///home/me/project/README.md", "file:///workspace/README.md")]
+fn host_inbound_paths_move_into_the_container(
+    paths: PathMap,
+    #[case] host: &str,
+    #[case] container: &str,
+) {
+    let message = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "session/new",
+        "params": {"cwd": host, "mcpServers": []},
+    });
+
+    let mapped = rewritten(&paths, &message, FrameDirection::HostInbound);
+
+    assert_eq!(
+        mapped.and_then(|value| value.pointer("/params/cwd").cloned()),
+        Some(Value::from(container))
+    );
+}
+
+#[rstest]
+fn agent_outbound_paths_move_back_to_the_host(paths: PathMap) {
+    let message = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "result": {"files": ["/workspace/a.rs", "/workspace/b/c.rs"]},
+    });
+
+    let mapped = rewritten(&paths, &message, FrameDirection::AgentOutbound);
+
+    assert_eq!(
+        mapped.and_then(|value| value.pointer("/result/files").cloned()),
+        Some(serde_json::json!([
+            "/home/me/project/a.rs",
+            "/home/me/project/b/c.rs"
+        ]))
+    );
+}
+
+#[rstest]
+#[case::sibling_prefix(serde_json::json!({"cwd": "/home/me/project-old"}))]
+#[case::relative(serde_json::json!({"cwd": "home/me/project"}))]
+#[case::prose(serde_json::json!({"text": "open /home/me/project/src"}))]
+#[case::container_path(serde_json::json!({"cwd": "/workspace"}))]
+#[case::non_string(serde_json::json!({"line": 3, "flag": true}))]
+fn frames_without_host_paths_are_left_alone(paths: PathMap, #[case] params: Value) {
+    let message = serde_json::json!({"jsonrpc": "2.0", "method": "x", "params": params});
+
+    assert_eq!(
+        rewritten(&paths, &message, FrameDirection::HostInbound),
+        None
+    );
+}
+
+#[rstest]
+fn only_params_and_result_are_rewritten(paths: PathMap) {
+    let message = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": "/home/me/project",
+        "error": {"code": 1, "message": "/home/me/project"},
+    });
+
+    assert_eq!(
+        rewritten(&paths, &message, FrameDirection::HostInbound),
+        None
+    );
+}
+
+#[rstest]
+#[case::not_json(b"not json\n".as_slice())]
+#[case::not_an_object(b"[\"/home/me/project\"]\n".as_slice())]
+fn unparseable_frames_pass_through(paths: PathMap, #[case] bytes: &[u8]) {
+    assert_eq!(paths.rewrite(bytes, FrameDirection::HostInbound), None);
+}
+
+#[rstest]
+fn rewritten_frames_keep_their_line_ending(paths: PathMap) {
+    let message =
+        serde_json::json!({"jsonrpc": "2.0", "method": "x", "params": ["/home/me/project"]});
+
+    let bytes = paths
+        .rewrite(&frame(&message, b"\r\n"), FrameDirection::HostInbound)
+        .expect("frame should be rewritten");
+
+    assert!(bytes.ends_with(b"\r\n"));
+}
+
+#[rstest]
+#[case::same_root("/workspace", "/workspace")]
+#[case::trailing_separator("/workspace/", "/workspace")]
+#[case::relative_host("project", "/workspace")]
+fn degenerate_roots_need_no_mapping(#[case] host: &str, #[case] container: &str) {
+    assert_eq!(
+        PathMap::new(Utf8Path::new(host), Utf8Path::new(container)),
+        None
+    );
+}
+
+#[test]
+fn filesystem_root_maps_every_absolute_path() {
+    let paths =
+        PathMap::new(Utf8Path::new("/srv/app"), Utf8Path::new("/")).expect("roots should map");
+    let message = serde_json::json!({"jsonrpc": "2.0", "method": "x", "params": ["/srv/app", "/srv/app/bin"]});
+
+    let mapped = rewritten(&paths, &message, FrameDirection::HostInbound);
+
+    assert_eq!(
+        mapped.and_then(|value| value.get("params").cloned()),
+        Some(serde_json::json!(["/", "/bin"]))
+    );
+}
+
+#[rstest]
+#[case::host_mount(WorkspaceSource::HostMount, Some("/home/me/project"), true)]
+#[case::clone(WorkspaceSource::GithubClone, None, false)]
+fn from_config_maps_host_mounted_workspaces(
+    #[case] source: WorkspaceSource,
+    #[case] host_path: Option<&str>,
+    #[case] maps: bool,
+) {
+    let config = WorkspaceConfig {
+        source,
+        host_path: host_path.map(Utf8PathBuf::from),
+        ..WorkspaceConfig::default()
+    };
+
+    assert_eq!(
+        PathMap::from_config(&config),
+        maps.then(paths),
+        "container_path defaults to /workspace"
+    );
+}
+
+fn request_frame(id: i64, method: &str, params: &Value) -> Vec<u8> {
+    frame(
+        &serde_json::json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}),
+        b"\n",
+    )
+}
+
+#[rstest]
+fn assembler_rewrites_forwarded_frames_but_not_served_ones(paths: PathMap) {
+    let mut inbound = OutboundFrameAssembler::host_inbound(MethodDenylist::new(&[]))
+        .with_path_map(Some(paths.clone()));
+    let mut outbound = OutboundFrameAssembler::new(MethodDenylist::new(&[]))
+        .with_served_fs(true)
+        .with_path_map(Some(paths));
+    let served_params = serde_json::json!({"path": "/workspace/src/lib.rs"});
+
+    let (inbound_outputs, _) = inbound.ingest_chunk(&request_frame(
+        1,
+        "session/new",
+        &serde_json::json!({"cwd": "/home/me/project", "mcpServers": []}),
+    ));
+    let (served_outputs, _) =
+        outbound.ingest_chunk(&request_frame(2, "fs/read_text_file", &served_params));
+    let (update_outputs, _) = outbound.ingest_chunk(&request_frame(
+        3,
+        "session/update",
+        &serde_json::json!({"locations": [{"path": "/workspace/src/lib.rs"}]}),
+    ));
+
+    assert_eq!(
+        inbound_outputs,
+        vec![FrameOutput::Forward(request_frame(
+            1,
+            "session/new",
+            &serde_json::json!({"cwd": "/workspace", "mcpServers": []}),
+        ))]
+    );
+    assert!(matches!(
+        served_outputs.as_slice(),
+        [FrameOutput::ServeFs(request, _)] if request.params == served_params
+    ));
+    assert_eq!(
+        update_outputs,
+        vec![FrameOutput::Forward(request_frame(
+            3,
+            "session/update",
+            &serde_json::json!({"locations": [{"path": "/home/me/project/src/lib.rs"}]}),
+        ))]
+    );
+}
@@ -0,0 +1,266 @@
+//! Policy answers for Agentic Control Protocol (ACP) permission requests.
+//!
+//! Before running a tool, an ACP agent sends the client a
+//! `session/request_permission` request describing the tool call and
+//! offering a list of options. When `[[agent.acp.permissions]]` rules are
+//! configured, the runtime policy matches each such request on the tool
+//! call's `kind` and `title`. The first matching rule decides: `allow_once`
+//! and `reject` select the agent's `allow_once` or `reject_once` option by
+//! synthesizing the JSON-RPC response in the proxy, much as
+//! `build_method_blocked_error` answers a blocked request; `forward` passes
+//! the request to the host client. Requests that no rule matches, or that
+//! do not offer the option a rule asks for, are forwarded unchanged.
+//!
+//! Every decision is logged at `warn` under the `podbot::acp::permission`
+//! target, so it shows up under the CLI's default log filter. A session host
+//! that attaches a [`SessionEventSink`] also receives each decision as an
+//! [`AcpSessionEvent::PermissionDecided`] over a bounded channel; no
+//! production host attaches one yet. Emitting never blocks the proxy: when
+//! the channel is full the event is dropped and a warning is logged instead.
+
+use std::fmt;
+
+use ortho_config::serde_json::{self, Value};
+use tokio::sync::mpsc;
+
+use super::acp_helpers::split_frame_line_ending;
+use crate::config::{AcpConfig, AcpPermissionAction, AcpPermissionRule};
+use crate::glob::wildcard_matches;
+
+/// JSON-RPC method an ACP agent uses to ask the client for permission.
+pub(crate) const REQUEST_PERMISSION_METHOD: &str = "session/request_permission";
+
+/// Ordered `[[agent.acp.permissions]]` rules for one session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PermissionPolicy {
+    rules: Vec<AcpPermissionRule>,
+}
+
+/// How podbot answered one `session/request_permission` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PermissionDecision {
+    /// JSON-RPC id of the permission request.
+    pub(crate) id: Value,
+    /// The tool call's `toolCallId`, when reported.
+    pub(crate) tool_call_id: Option<String>,
+    /// The tool call's `kind`, when reported.
+    pub(crate) kind: Option<String>,
+    /// The tool call's `title`, when reported.
+    pub(crate) title: Option<String>,
+    /// The action applied. A rule whose option the agent did not offer is
+    /// applied as [`AcpPermissionAction::Forward`].
+    pub(crate) action: AcpPermissionAction,
+    /// The option podbot selected, or `None` when the request is forwarded.
+    pub(crate) option_id: Option<String>,
+}
+
+impl PermissionDecision {
+    /// Return `true` when podbot answers the request itself.
+    pub(crate) const fn responds(&self) -> bool {
+        self.option_id.is_some()
+    }
+}
+
+/// Events a protocol session reports to its host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub(crate) enum AcpSessionEvent {
+    /// A permission rule decided how a `session/request_permission` request
+    /// was answered.
+    PermissionDecided(PermissionDecision),
+    /// The session's installation token was revoked, or revocation was
+    /// attempted, while the session was torn down.
+    #[cfg_attr(
+        not(feature = "experimental"),
+        expect(dead_code, reason = "session teardown is an experimental API")
+    )]
+    TokenRevoked(TokenRevocation),
+}
+
+/// Result of revoking a session's installation token at teardown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TokenRevocation {
+    /// Container whose session was torn down.
+    pub(crate) container_id: String,
+    /// Whether GitHub confirmed the revocation. A failure is only a warning,
+    /// because the token still expires on its own.
+    pub(crate) revoked: bool,
+}
+
+/// Non-blocking sender for [`AcpSessionEvent`]s.
+#[derive(Clone)]
+pub(crate) struct SessionEventSink {
+    sender: mpsc::Sender<AcpSessionEvent>,
+}
+
+impl PermissionPolicy {
+    /// Create a policy with no rules, which forwards every request.
+    pub(crate) const fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Build the policy from the `[[agent.acp.permissions]]` rules in
+    /// `config`.
+    pub(crate) fn from_config(config: &AcpConfig) -> Self {
+        Self::from_rules(config.permissions.clone())
+    }
+
+    /// Build the policy from ordered rules.
+    pub(crate) const fn from_rules(rules: Vec<AcpPermissionRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Return `true` when no rules are configured.
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decide how to answer `frame` when it is a permission request that a
+    /// rule matches.
+    ///
+    /// Returns `None` for any other frame, including unparseable ones and
+    /// permission requests that no rule matches.
+    pub(crate) fn evaluate(&self, frame: &[u8]) -> Option<PermissionDecision> {
+        if self.is_empty() {
+            return None;
+        }
+        let (payload, _line_ending) = split_frame_line_ending(frame);
+        let message = serde_json::from_slice::<Value>(payload).ok()?;
+        if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0")
+            || message.get("method").and_then(Value::as_str) != Some(REQUEST_PERMISSION_METHOD)
+        {
+            return None;
+        }
+        let id = message.get("id")?.clone();
+        let params = message.get("params")?;
+        let tool_call = params.get("toolCall");
+        let field = |name: &str| {
+            tool_call
+                .and_then(|call| call.get(name))
+                .and_then(Value::as_str)
+                .map(String::from)
+        };
+        let kind = field("kind");
+        let title = field("title");
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule_matches(rule, kind.as_deref(), title.as_deref()))?;
+        let option_id = selected_option(params, rule.action);
+        let action = if option_id.is_some() {
+            rule.action
+        } else {
+            AcpPermissionAction::Forward
+        };
+        Some(PermissionDecision {
+            id,
+            tool_call_id: field("toolCallId"),
+            kind,
+            title,
+            action,
+            option_id,
+        })
+    }
+}
+
+impl SessionEventSink {
+    /// Wrap the sending half of a session-event channel.
+    #[cfg_attr(
+        not(test),
+        expect(
+            dead_code,
+            reason = "reserved for production ACP session selection when podbot host is enabled"
+        )
+    )]
+    pub(crate) const fn new(sender: mpsc::Sender<AcpSessionEvent>) -> Self {
+        Self { sender }
+    }
+
+    /// Emit `event` without waiting, dropping it with a warning when the
+    /// channel is full. A closed channel means nobody is listening and is
+    /// ignored.
+    pub(crate) fn emit(&self, event: AcpSessionEvent) {
+        if let Err(mpsc::error::TrySendError::Full(dropped)) = self.sender.try_send(event) {
+            tracing::warn!(
+                target = "podbot::acp::events",
+                event = ?dropped,
+                "ACP session event dropped; event channel is full",
+            );
+        }
+    }
+}
+
+impl fmt::Debug for SessionEventSink {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SessionEventSink")
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SessionEventSink {
+    fn eq(&self, other: &Self) -> bool {
+        self.sender.same_channel(&other.sender)
+    }
+}
+
+impl Eq for SessionEventSink {}
+
+/// Build the JSON-RPC response that selects `option_id`, terminated by
+/// `line_ending`.
+///
+/// # Errors
+///
+/// Returns the `serde_json` error raised while serializing the response.
+pub(crate) fn build_permission_response(
+    id: &Value,
+    option_id: &str,
+    line_ending: &[u8],
+) -> serde_json::Result<Vec<u8>> {
+    let payload = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": {
+            "outcome": {
+                "outcome": "selected",
+                "optionId": option_id,
+            },
+        },
+    });
+    let mut serialized = serde_json::to_vec(&payload)?;
+    serialized.extend_from_slice(line_ending);
+    Ok(serialized)
+}
+
+fn rule_matches(rule: &AcpPermissionRule, kind: Option<&str>, title: Option<&str>) -> bool {
+    let kind_matches = rule
+        .kind
+        .as_deref()
+        .is_none_or(|expected| kind == Some(expected));
+    let title_matches = rule
+        .title
+        .as_deref()
+        .is_none_or(|pattern| title.is_some_and(|text| wildcard_matches(pattern, text)));
+    kind_matches && title_matches
+}
+
+/// Return the `optionId` of the option whose `kind` fits `action`.
+fn selected_option(params: &Value, action: AcpPermissionAction) -> Option<String> {
+    let wanted = match action {
+        AcpPermissionAction::AllowOnce => "allow_once",
+        AcpPermissionAction::Reject => "reject_once",
+        AcpPermissionAction::Forward => return None,
+    };
+    params
+        .get("options")?
+        .as_array()?
+        .iter()
+        .find(|option| option.get("kind").and_then(Value::as_str) == Some(wanted))?
+        .get("optionId")?
+        .as_str()
+        .map(String::from)
+}
+
+#[cfg(test)]
+#[path = "acp_permission_tests.rs"]
+mod tests;
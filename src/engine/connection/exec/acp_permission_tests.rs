@@ -0,0 +1,157 @@
+//! Unit tests for ACP permission-request policy answers.
+
+use ortho_config::serde_json::{self, Value};
+use rstest::rstest;
+use tokio::sync::mpsc;
+
+use super::{
+    AcpSessionEvent, PermissionDecision, PermissionPolicy, SessionEventSink,
+    build_permission_response,
+};
+use crate::config::{AcpConfig, AcpPermissionAction, AcpPermissionRule};
+use crate::engine::connection::exec::acp_test_support::{jsonrpc_frame, permission_request_frame};
+
+fn rule(kind: Option<&str>, title: Option<&str>, action: AcpPermissionAction) -> AcpPermissionRule {
+    AcpPermissionRule {
+        kind: kind.map(String::from),
+        title: title.map(String::from),
+        action,
+    }
+}
+
+fn policy() -> PermissionPolicy {
+    PermissionPolicy::from_rules(vec![
+        rule(Some("read"), None, AcpPermissionAction::AllowOnce),
+        rule(
+            Some("execute"),
+            Some("Run `cargo *`"),
+            AcpPermissionAction::AllowOnce,
+        ),
+        rule(
+            Some("execute"),
+            Some("*rm -rf*"),
+            AcpPermissionAction::Reject,
+        ),
+        rule(Some("execute"), None, AcpPermissionAction::Forward),
+    ])
+}
+
+#[rstest]
+#[case::read_allowed(
+    "read",
+    "Read src/lib.rs",
+    AcpPermissionAction::AllowOnce,
+    Some("allow")
+)]
+#[case::cargo_allowed(
+    "execute",
+    "Run `cargo test`",
+    AcpPermissionAction::AllowOnce,
+    Some("allow")
+)]
+#[case::rm_rejected("execute", "Run `rm -rf /`", AcpPermissionAction::Reject, Some("deny"))]
+#[case::other_execute_forwarded("execute", "Run `make`", AcpPermissionAction::Forward, None)]
+fn first_matching_rule_decides(
+    #[case] kind: &str,
+    #[case] title: &str,
+    #[case] action: AcpPermissionAction,
+    #[case] option_id: Option<&str>,
+) {
+    let frame = permission_request_frame(3, kind, title).expect("frame should serialize");
+
+    assert_eq!(
+        policy().evaluate(&frame),
+        Some(PermissionDecision {
+            id: Value::from(3),
+            tool_call_id: Some(String::from("call-3")),
+            kind: Some(String::from(kind)),
+            title: Some(String::from(title)),
+            action,
+            option_id: option_id.map(String::from),
+        })
+    );
+}
+
+#[rstest]
+fn policy_is_built_from_config_rules() {
+    let config = AcpConfig {
+        permissions: vec![rule(Some("read"), None, AcpPermissionAction::AllowOnce)],
+        ..AcpConfig::default()
+    };
+
+    assert!(PermissionPolicy::from_config(&AcpConfig::default()).is_empty());
+    assert_eq!(
+        PermissionPolicy::from_config(&config),
+        PermissionPolicy::from_rules(config.permissions.clone())
+    );
+}
+
+#[rstest]
+fn unmatched_and_unrelated_frames_are_left_alone() {
+    let edit =
+        permission_request_frame(3, "edit", "Edit README.md").expect("frame should serialize");
+    let prompt = jsonrpc_frame(Some(&Value::from(4)), "session/prompt", b"\n")
+        .expect("frame should serialize");
+
+    assert_eq!(policy().evaluate(&edit), None);
+    assert_eq!(policy().evaluate(&prompt), None);
+    assert_eq!(policy().evaluate(b"not json\n"), None);
+    assert_eq!(PermissionPolicy::new().evaluate(&edit), None);
+}
+
+#[rstest]
+fn missing_option_falls_back_to_forwarding() {
+    let frame = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 5,
+        "method": "session/request_permission",
+        "params": {
+            "toolCall": {"toolCallId": "call-5", "kind": "read", "title": "Read a file"},
+            "options": [{"optionId": "always", "name": "Always", "kind": "allow_always"}],
+        },
+    });
+    let bytes = serde_json::to_vec(&frame).expect("frame should serialize");
+
+    let decision = policy()
+        .evaluate(&bytes)
+        .expect("the read rule should match");
+
+    assert_eq!(decision.action, AcpPermissionAction::Forward);
+    assert!(!decision.responds());
+}
+
+#[rstest]
+fn response_selects_the_option() {
+    let response = build_permission_response(&Value::from(3), "allow", b"\r\n")
+        .expect("response should serialize");
+
+    assert!(response.ends_with(b"\r\n"));
+    let parsed: Value = serde_json::from_slice(&response).expect("response should be JSON");
+    assert_eq!(
+        parsed,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "result": {"outcome": {"outcome": "selected", "optionId": "allow"}},
+        })
+    );
+}
+
+#[rstest]
+fn full_event_channel_drops_events_without_blocking() {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let sink = SessionEventSink::new(sender);
+    let frame = permission_request_frame(3, "read", "Read").expect("frame should serialize");
+    let decision = policy()
+        .evaluate(&frame)
+        .expect("the read rule should match");
+
+    sink.emit(AcpSessionEvent::PermissionDecided(decision.clone()));
+    sink.emit(AcpSessionEvent::PermissionDecided(decision.clone()));
+
+    assert_eq!(
+        receiver.try_recv().ok(),
+        Some(AcpSessionEvent::PermissionDecided(decision))
+    );
+    assert!(receiver.try_recv().is_err(), "the second event is dropped");
+}
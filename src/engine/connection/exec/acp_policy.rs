@@ -13,10 +13,17 @@
 //! method passes through unchanged. This mirrors the first-frame masking
 //! philosophy established in `acp_helpers` and protects non-Agentic Control
 //! Protocol traffic.
+//!
+//! Frames are evaluated in both directions. The built-in `fs/*` and
+//! `terminal/*` families name client methods, so they apply only to frames
+//! the agent sends; operator-configured [`MethodRules`] apply to frames in
+//! either direction.
 
 use ortho_config::serde_json::{self, Value};
 
 use super::acp_helpers::split_frame_line_ending;
+use super::acp_rules::MethodRules;
+use crate::config::{AcpConfig, AcpFamilyPolicy};
 
 /// A capability family blocked by Podbot at runtime.
 ///
@@ -40,29 +47,56 @@ impl MethodFamily {
     }
 }
 
+/// The `terminal/*` capability family.
+const TERMINAL_FAMILY: MethodFamily = MethodFamily {
+    prefix: "terminal/",
+};
+
+/// The `fs/*` capability family.
+const FS_FAMILY: MethodFamily = MethodFamily { prefix: "fs/" };
+
 /// The default set of ACP capability families that Podbot blocks at runtime.
 ///
 /// `terminal/` and `fs/` correspond to the same families that the
 /// initialization-time masker strips from `clientCapabilities` advertisements,
 /// so the runtime denylist closes the symmetric door on agent-emitted method
 /// calls in those families.
-pub(crate) const DEFAULT_BLOCKED_FAMILIES: &[MethodFamily] = &[
-    MethodFamily {
-        prefix: "terminal/",
-    },
-    MethodFamily { prefix: "fs/" },
-];
+pub(crate) const DEFAULT_BLOCKED_FAMILIES: &[MethodFamily] = &[TERMINAL_FAMILY, FS_FAMILY];
 
-/// A static set of [`MethodFamily`] values that Podbot refuses to forward.
-#[derive(Debug, Clone, Copy)]
+/// The direction a frame travels through the protocol proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrameDirection {
+    /// Agent stdout bound for the host client.
+    AgentOutbound,
+    /// Host stdin bound for the agent.
+    HostInbound,
+}
+
+impl FrameDirection {
+    /// Return the `snake_case` token used for this direction in audit records.
+    pub(crate) const fn as_token(self) -> &'static str {
+        match self {
+            Self::AgentOutbound => "agent_outbound",
+            Self::HostInbound => "host_inbound",
+        }
+    }
+}
+
+/// The methods Podbot refuses to forward: a static set of blocked
+/// [`MethodFamily`] values plus the operator's [`MethodRules`].
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct MethodDenylist {
     families: &'static [MethodFamily],
+    rules: MethodRules,
 }
 
 impl MethodDenylist {
     /// Construct a denylist over the supplied families.
     pub(crate) const fn new(families: &'static [MethodFamily]) -> Self {
-        Self { families }
+        Self {
+            families,
+            rules: MethodRules::new(),
+        }
     }
 
     /// Construct the default denylist covering `terminal/` and `fs/`.
@@ -70,9 +104,154 @@ impl MethodDenylist {
         Self::new(DEFAULT_BLOCKED_FAMILIES)
     }
 
-    /// Return `true` when `method` belongs to any family in the denylist.
+    /// Add the operator's method rules to this denylist.
+    #[must_use]
+    pub(crate) fn with_rules(mut self, rules: MethodRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Return `true` when operator rules apply to host-inbound frames.
+    pub(crate) const fn has_inbound_rules(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Return `true` when an agent-outbound `method` is refused.
+    #[cfg(test)]
     pub(crate) fn is_blocked(&self, method: &str) -> bool {
-        self.families.iter().any(|family| family.matches(method))
+        self.blocking_rules(method, FrameDirection::AgentOutbound)
+            .is_some()
+    }
+
+    /// Return the rules that refuse `method` travelling in `direction`, or
+    /// `None` when it is permitted. Blocked families are reported as
+    /// `prefix/*`.
+    pub(crate) fn blocking_rules(
+        &self,
+        method: &str,
+        direction: FrameDirection,
+    ) -> Option<Vec<String>> {
+        let family = self
+            .families
+            .iter()
+            .find(|family| family.matches(method))
+            .filter(|_| direction == FrameDirection::AgentOutbound);
+        family.map_or_else(
+            || self.rules.blocking_rules(method),
+            |blocked| Some(vec![format!("{}*", blocked.prefix)]),
+        )
+    }
+}
+
+/// The ACP capability families an operator has delegated to the host client
+/// or asked podbot to serve itself.
+///
+/// Resolved once from `[agent.acp]` and shared by the initialization-time
+/// masker and the runtime denylist, so a family is either advertised and
+/// forwarded or masked and refused, never one without the other. A served
+/// family is advertised and answered by podbot. The default delegates and
+/// serves nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AcpDelegation {
+    fs: AcpFamilyPolicy,
+    terminal: AcpFamilyPolicy,
+}
+
+impl AcpDelegation {
+    /// Construct a delegation for the `fs` and `terminal` families.
+    pub(crate) const fn new(fs: bool, terminal: bool) -> Self {
+        Self {
+            fs: delegate_or_deny(fs),
+            terminal: delegate_or_deny(terminal),
+        }
+    }
+
+    /// Serve the `fs` family from podbot, when `served` is set, instead of
+    /// delegating or denying it.
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) const fn with_served_fs(mut self, served: bool) -> Self {
+        if served {
+            self.fs = AcpFamilyPolicy::Serve;
+        }
+        self
+    }
+
+    /// Serve the `terminal` family from podbot, when `served` is set,
+    /// instead of delegating or denying it.
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) const fn with_served_terminal(mut self, served: bool) -> Self {
+        if served {
+            self.terminal = AcpFamilyPolicy::Serve;
+        }
+        self
+    }
+
+    /// Resolve the delegation configured under `[agent.acp]`.
+    pub(crate) const fn from_config(config: &AcpConfig) -> Self {
+        Self {
+            fs: config.fs,
+            terminal: config.terminal,
+        }
+    }
+
+    /// Return `true` when the `fs` family is delegated.
+    pub(crate) const fn delegates_fs(self) -> bool {
+        self.fs.is_delegated()
+    }
+
+    /// Return `true` when podbot serves the `fs` family itself.
+    pub(crate) const fn serves_fs(self) -> bool {
+        self.fs.is_served()
+    }
+
+    /// Return `true` when the `terminal` family is delegated.
+    pub(crate) const fn delegates_terminal(self) -> bool {
+        self.terminal.is_delegated()
+    }
+
+    /// Return `true` when podbot serves the `terminal` family itself.
+    pub(crate) const fn serves_terminal(self) -> bool {
+        self.terminal.is_served()
+    }
+
+    /// Return `true` when no family is delegated.
+    pub(crate) const fn is_empty(self) -> bool {
+        !self.delegates_fs() && !self.delegates_terminal()
+    }
+
+    /// Return the names of the delegated families, for diagnostics.
+    pub(crate) fn family_names(self) -> Vec<&'static str> {
+        [
+            ("fs", self.delegates_fs()),
+            ("terminal", self.delegates_terminal()),
+        ]
+        .into_iter()
+        .filter_map(|(name, delegated)| delegated.then_some(name))
+        .collect()
+    }
+
+    /// Return the runtime denylist covering every family neither delegated
+    /// nor served.
+    pub(crate) const fn denylist(self) -> MethodDenylist {
+        match (
+            matches!(self.fs, AcpFamilyPolicy::Deny),
+            matches!(self.terminal, AcpFamilyPolicy::Deny),
+        ) {
+            (true, true) => MethodDenylist::default_families(),
+            (false, true) => MethodDenylist::new(&[TERMINAL_FAMILY]),
+            (true, false) => MethodDenylist::new(&[FS_FAMILY]),
+            (false, false) => MethodDenylist::new(&[]),
+        }
+    }
+}
+
+const fn delegate_or_deny(delegated: bool) -> AcpFamilyPolicy {
+    if delegated {
+        AcpFamilyPolicy::Delegate
+    } else {
+        AcpFamilyPolicy::Deny
     }
 }
 
@@ -90,6 +269,8 @@ pub(crate) enum FrameDecision {
     BlockNotification {
         /// The blocked method name from the JSON-RPC frame.
         method: String,
+        /// The rules that refused the method.
+        rules: Vec<String>,
     },
     /// Drop a blocked request and prepare a synthesized error response.
     BlockRequest {
@@ -97,6 +278,8 @@ pub(crate) enum FrameDecision {
         id: Value,
         /// The blocked method name from the JSON-RPC frame.
         method: String,
+        /// The rules that refused the method.
+        rules: Vec<String>,
     },
 }
 
@@ -106,11 +289,32 @@ pub(crate) enum FrameDecision {
 /// On any parse failure, missing JSON-RPC 2.0 marker, missing-method, or
 /// response/batch shape, the function returns [`FrameDecision::Forward`].
 /// Only a JSON-RPC request or notification whose `method` belongs to a
-/// blocked family produces a [`FrameDecision::BlockRequest`] or
-/// [`FrameDecision::BlockNotification`].
+/// blocked family or matches an operator rule produces a
+/// [`FrameDecision::BlockRequest`] or [`FrameDecision::BlockNotification`].
 pub(crate) fn evaluate_agent_outbound_frame(
     frame: &[u8],
     denylist: &MethodDenylist,
+) -> FrameDecision {
+    evaluate_frame(frame, denylist, FrameDirection::AgentOutbound)
+}
+
+/// Decide whether `frame` (a host-inbound JSON-RPC frame) should be
+/// forwarded to the agent or blocked by the operator rules in `denylist`.
+///
+/// Parsing is as tolerant as [`evaluate_agent_outbound_frame`]; only the
+/// blocked families are skipped, because they name client methods.
+pub(crate) fn evaluate_host_inbound_frame(
+    frame: &[u8],
+    denylist: &MethodDenylist,
+) -> FrameDecision {
+    evaluate_frame(frame, denylist, FrameDirection::HostInbound)
+}
+
+/// Decide whether `frame`, travelling in `direction`, should be forwarded.
+fn evaluate_frame(
+    frame: &[u8],
+    denylist: &MethodDenylist,
+    direction: FrameDirection,
 ) -> FrameDecision {
     let (payload, _line_ending) = split_frame_line_ending(frame);
     let Ok(message) = serde_json::from_slice::<Value>(payload) else {
@@ -122,17 +326,19 @@ pub(crate) fn evaluate_agent_outbound_frame(
     let Some(method) = message.get("method").and_then(Value::as_str) else {
         return FrameDecision::Forward;
     };
-    if !denylist.is_blocked(method) {
+    let Some(rules) = denylist.blocking_rules(method, direction) else {
         return FrameDecision::Forward;
-    }
+    };
     let method_owned = String::from(method);
     match message.get("id") {
         Some(id) => FrameDecision::BlockRequest {
             id: id.clone(),
             method: method_owned,
+            rules,
         },
         None => FrameDecision::BlockNotification {
             method: method_owned,
+            rules,
         },
     }
 }
@@ -159,7 +365,8 @@ pub(crate) const METHOD_BLOCKED_ERROR_REASON: &str = "podbot_capability_policy";
 /// Build a JSON-RPC 2.0 error response for a blocked request.
 ///
 /// The original `id` is preserved as a [`Value`] so numeric, string, and null
-/// identifiers retain their JSON type. The supplied `line_ending` bytes are
+/// identifiers retain their JSON type. `rules` lists the rules that refused
+/// the method as `data.rules`. The supplied `line_ending` bytes are
 /// appended verbatim (use `b"\n"` when the original frame carried no
 /// recognized line terminator).
 ///
@@ -173,6 +380,7 @@ pub(crate) const METHOD_BLOCKED_ERROR_REASON: &str = "podbot_capability_policy";
 pub(crate) fn build_method_blocked_error(
     id: &Value,
     method: &str,
+    rules: &[String],
     line_ending: &[u8],
 ) -> serde_json::Result<Vec<u8>> {
     let payload = serde_json::json!({
@@ -184,6 +392,7 @@ pub(crate) fn build_method_blocked_error(
             "data": {
                 "method": method,
                 "reason": METHOD_BLOCKED_ERROR_REASON,
+                "rules": rules,
             },
         },
     });
@@ -8,11 +8,14 @@
 use ortho_config::serde_json::{self, Value};
 use rstest::rstest;
 
+use crate::config::{AcpConfig, AcpFamilyPolicy};
+
 use super::{
-    DEFAULT_BLOCKED_FAMILIES, FrameDecision, METHOD_BLOCKED_ERROR_CODE,
+    AcpDelegation, DEFAULT_BLOCKED_FAMILIES, FrameDecision, METHOD_BLOCKED_ERROR_CODE,
     METHOD_BLOCKED_ERROR_MESSAGE, METHOD_BLOCKED_ERROR_REASON, MethodDenylist, MethodFamily,
-    build_method_blocked_error, evaluate_agent_outbound_frame,
+    build_method_blocked_error, evaluate_agent_outbound_frame, evaluate_host_inbound_frame,
 };
+use crate::engine::connection::exec::acp_rules::MethodRules;
 
 const TERMINAL_FAMILY: MethodFamily = MethodFamily {
     prefix: "terminal/",
@@ -40,6 +43,79 @@ fn default_denylist_blocks_terminal_and_fs_only(#[case] method: &str, #[case] ex
     assert_eq!(denylist.is_blocked(method), expected);
 }
 
+#[rstest]
+#[case::none(AcpDelegation::new(false, false), true, true)]
+#[case::fs(AcpDelegation::new(true, false), false, true)]
+#[case::terminal(AcpDelegation::new(false, true), true, false)]
+#[case::both(AcpDelegation::new(true, true), false, false)]
+#[case::served_fs(AcpDelegation::new(false, false).with_served_fs(true), false, true)]
+#[case::served_terminal(
+    AcpDelegation::new(false, false).with_served_terminal(true),
+    true,
+    false
+)]
+fn delegation_denylist_blocks_only_undelegated_families(
+    #[case] delegation: AcpDelegation,
+    #[case] blocks_fs: bool,
+    #[case] blocks_terminal: bool,
+) {
+    let denylist = delegation.denylist();
+
+    assert_eq!(denylist.is_blocked("fs/read_text_file"), blocks_fs);
+    assert_eq!(denylist.is_blocked("terminal/create"), blocks_terminal);
+    assert!(!denylist.is_blocked("session/prompt"));
+}
+
+#[rstest]
+#[case::deny(AcpFamilyPolicy::Deny, AcpFamilyPolicy::Deny, Vec::new())]
+#[case::fs(AcpFamilyPolicy::Delegate, AcpFamilyPolicy::Deny, vec!["fs"])]
+#[case::both(AcpFamilyPolicy::Delegate, AcpFamilyPolicy::Delegate, vec!["fs", "terminal"])]
+fn delegation_resolves_from_config(
+    #[case] fs: AcpFamilyPolicy,
+    #[case] terminal: AcpFamilyPolicy,
+    #[case] expected: Vec<&str>,
+) {
+    let config = AcpConfig {
+        fs,
+        terminal,
+        acknowledge_host_delegation: true,
+        ..AcpConfig::default()
+    };
+
+    let delegation = AcpDelegation::from_config(&config);
+
+    assert_eq!(delegation.family_names(), expected);
+    assert_eq!(delegation.is_empty(), expected.is_empty());
+}
+
+#[rstest]
+fn served_fs_resolves_from_config_without_delegating() {
+    let config = AcpConfig {
+        fs: AcpFamilyPolicy::Serve,
+        ..AcpConfig::default()
+    };
+
+    let delegation = AcpDelegation::from_config(&config);
+
+    assert!(delegation.serves_fs());
+    assert!(!delegation.delegates_fs());
+    assert!(delegation.is_empty(), "serving is not host delegation");
+}
+
+#[rstest]
+fn served_terminal_resolves_from_config_without_delegating() {
+    let config = AcpConfig {
+        terminal: AcpFamilyPolicy::Serve,
+        ..AcpConfig::default()
+    };
+
+    let delegation = AcpDelegation::from_config(&config);
+
+    assert!(delegation.serves_terminal());
+    assert!(!delegation.serves_fs());
+    assert!(delegation.is_empty(), "serving is not host delegation");
+}
+
 /// Serializes `value` to compact JSON bytes and appends a newline terminator,
 /// producing a well-formed ACP frame suitable for test input.
 fn serialize_frame(value: &serde_json::Value) -> Result<Vec<u8>, serde_json::Error> {
@@ -79,9 +155,11 @@ fn evaluate_returns_block_request_with_preserved_id(#[case] id: Value) {
         FrameDecision::BlockRequest {
             id: actual_id,
             method,
+            rules,
         } => {
             assert_eq!(actual_id, id, "request id should be preserved verbatim");
             assert_eq!(method, "terminal/create");
+            assert_eq!(rules, vec![String::from("terminal/*")]);
         }
         other => panic!("expected BlockRequest, got {other:?}"),
     }
@@ -98,10 +176,47 @@ fn evaluate_returns_block_notification_for_blocked_method_without_id() {
         decision,
         FrameDecision::BlockNotification {
             method: String::from("fs/changed"),
+            rules: vec![String::from("fs/*")],
         }
     );
 }
 
+#[test]
+fn evaluate_blocks_methods_matching_operator_rules() {
+    let frame =
+        jsonrpc_request(&serde_json::json!(3), "_zed/open_buffer").expect("frame should serialize");
+    let denylist = MethodDenylist::default_families().with_rules(MethodRules::denying(&["_zed/*"]));
+
+    assert_eq!(
+        evaluate_agent_outbound_frame(&frame, &denylist),
+        FrameDecision::BlockRequest {
+            id: serde_json::json!(3),
+            method: String::from("_zed/open_buffer"),
+            rules: vec![String::from("_zed/*")],
+        }
+    );
+}
+
+#[rstest]
+#[case::family_not_applied("terminal/output", None)]
+#[case::rule_applied("_zed/ping", Some(vec![String::from("_zed/*")]))]
+#[case::permitted("session/prompt", None)]
+fn host_inbound_frames_apply_only_operator_rules(
+    #[case] method: &str,
+    #[case] expected_rules: Option<Vec<String>>,
+) {
+    let frame = jsonrpc_notification(method).expect("frame should serialize");
+    let denylist = MethodDenylist::default_families().with_rules(MethodRules::denying(&["_zed/*"]));
+
+    let expected = expected_rules.map_or(FrameDecision::Forward, |rules| {
+        FrameDecision::BlockNotification {
+            method: String::from(method),
+            rules,
+        }
+    });
+    assert_eq!(evaluate_host_inbound_frame(&frame, &denylist), expected);
+}
+
 #[test]
 fn evaluate_forwards_permitted_request() {
     let frame =
@@ -197,7 +312,8 @@ fn build_error_payload_round_trips_with_expected_fields(
     #[case] id: Value,
     #[case] line_ending: &[u8],
 ) {
-    let bytes = build_method_blocked_error(&id, "terminal/create", line_ending)
+    let rules = vec![String::from("terminal/*")];
+    let bytes = build_method_blocked_error(&id, "terminal/create", &rules, line_ending)
         .expect("error payload should serialize");
 
     assert!(
@@ -237,6 +353,7 @@ fn build_error_payload_round_trips_with_expected_fields(
         data.get("reason"),
         Some(&Value::from(METHOD_BLOCKED_ERROR_REASON)),
     );
+    assert_eq!(data.get("rules"), Some(&serde_json::json!(["terminal/*"])),);
 }
 
 #[test]
@@ -0,0 +1,135 @@
+//! Operator-configured Agentic Control Protocol (ACP) method rules.
+//!
+//! `[agent.acp]` lets operators refuse methods beyond the built-in `fs/*` and
+//! `terminal/*` families: exact method names, `prefix/*` families such as the
+//! `_zed/*` vendor extensions, and an allow-only mode that refuses everything
+//! not listed. Like `acp_policy`, this module is pure and synchronous.
+
+use crate::config::AcpConfig;
+
+/// The `data.rules` entry reported when allow-only mode refuses a method
+/// that no allow rule matches.
+pub(crate) const ALLOW_ONLY_RULE: &str = "allow_only";
+
+/// One method rule: an exact method name or a `prefix/*` family.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MethodRule {
+    /// The rule as written in configuration, reported in denials.
+    pattern: String,
+}
+
+impl MethodRule {
+    /// Construct a rule from its configured form.
+    pub(crate) fn new(pattern: &str) -> Self {
+        Self {
+            pattern: String::from(pattern.trim()),
+        }
+    }
+
+    /// Return the rule as written in configuration.
+    pub(crate) fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Return `true` when `method` is named by this rule.
+    ///
+    /// A family rule follows the same boundary as the built-in families:
+    /// `_zed/*` matches `_zed/open` but neither `_zed/` nor `_zedx/open`.
+    pub(crate) fn matches(&self, method: &str) -> bool {
+        self.pattern.strip_suffix('*').map_or_else(
+            || method == self.pattern,
+            |prefix| {
+                method
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| !rest.is_empty())
+            },
+        )
+    }
+}
+
+/// The configured method rules for one protocol session.
+///
+/// Deny rules always win. In allow-only mode, a method must also match one
+/// of the allow rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MethodRules {
+    deny: Vec<MethodRule>,
+    allow: Option<Vec<MethodRule>>,
+}
+
+impl MethodRules {
+    /// Construct an empty rule set that permits every method.
+    pub(crate) const fn new() -> Self {
+        Self {
+            deny: Vec::new(),
+            allow: None,
+        }
+    }
+
+    /// Resolve the rules configured under `[agent.acp]`.
+    pub(crate) fn from_config(config: &AcpConfig) -> Self {
+        let deny = parse_rules(&config.deny_methods);
+        let allow = config
+            .allow_only
+            .then(|| parse_rules(&config.allow_methods));
+        Self { deny, allow }
+    }
+
+    /// Construct rules that refuse every method matching `deny`.
+    #[cfg(test)]
+    pub(crate) fn denying(deny: &[&str]) -> Self {
+        Self {
+            deny: deny
+                .iter()
+                .map(|pattern| MethodRule::new(pattern))
+                .collect(),
+            allow: None,
+        }
+    }
+
+    /// Construct allow-only rules that permit only methods matching `allow`.
+    #[cfg(test)]
+    pub(crate) fn allowing_only(allow: &[&str]) -> Self {
+        Self {
+            deny: Vec::new(),
+            allow: Some(
+                allow
+                    .iter()
+                    .map(|pattern| MethodRule::new(pattern))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Return `true` when the rules permit every method.
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.deny.is_empty() && self.allow.is_none()
+    }
+
+    /// Return the rules that refuse `method`, or `None` when it is permitted.
+    pub(crate) fn blocking_rules(&self, method: &str) -> Option<Vec<String>> {
+        let denied: Vec<String> = self
+            .deny
+            .iter()
+            .filter(|rule| rule.matches(method))
+            .map(|rule| String::from(rule.pattern()))
+            .collect();
+        if !denied.is_empty() {
+            return Some(denied);
+        }
+        let allow = self.allow.as_ref()?;
+        (!allow.iter().any(|rule| rule.matches(method)))
+            .then(|| vec![String::from(ALLOW_ONLY_RULE)])
+    }
+}
+
+fn parse_rules(patterns: &[String]) -> Vec<MethodRule> {
+    patterns
+        .iter()
+        .map(|pattern| MethodRule::new(pattern))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "acp_rules_tests.rs"]
+mod tests;
@@ -0,0 +1,81 @@
+//! Unit tests for operator-configured ACP method rules.
+
+use rstest::rstest;
+
+use super::{ALLOW_ONLY_RULE, MethodRule, MethodRules};
+use crate::config::AcpConfig;
+
+#[rstest]
+#[case::exact("session/set_mode", "session/set_mode", true)]
+#[case::exact_other("session/set_mode", "session/set_model", false)]
+#[case::family("_zed/*", "_zed/open_buffer", true)]
+#[case::nested_family("_zed/*", "_zed/buffer/open", true)]
+#[case::bare_family_prefix("_zed/*", "_zed/", false)]
+#[case::similar_prefix("_zed/*", "_zedx/open", false)]
+fn method_rule_matches_exact_names_and_families(
+    #[case] pattern: &str,
+    #[case] method: &str,
+    #[case] expected: bool,
+) {
+    assert_eq!(MethodRule::new(pattern).matches(method), expected);
+}
+
+#[rstest]
+fn empty_rules_permit_everything() {
+    let rules = MethodRules::new();
+
+    assert!(rules.is_empty());
+    assert_eq!(rules.blocking_rules("_zed/open"), None);
+}
+
+#[rstest]
+fn deny_rules_report_every_matching_rule() {
+    let rules = MethodRules::denying(&["_zed/*", "_zed/open", "session/set_mode"]);
+
+    assert_eq!(
+        rules.blocking_rules("_zed/open"),
+        Some(vec![String::from("_zed/*"), String::from("_zed/open")])
+    );
+    assert_eq!(rules.blocking_rules("session/prompt"), None);
+}
+
+#[rstest]
+#[case::allowed("session/prompt", None)]
+#[case::allowed_family("session/update", None)]
+#[case::unlisted("_zed/open", Some(vec![String::from(ALLOW_ONLY_RULE)]))]
+fn allow_only_rules_refuse_unlisted_methods(
+    #[case] method: &str,
+    #[case] expected: Option<Vec<String>>,
+) {
+    let rules = MethodRules::allowing_only(&["session/*", "initialize"]);
+
+    assert_eq!(rules.blocking_rules(method), expected);
+}
+
+#[rstest]
+fn deny_rules_win_over_allow_rules() {
+    let config = AcpConfig {
+        deny_methods: vec![String::from("session/set_mode")],
+        allow_only: true,
+        allow_methods: vec![String::from("session/*")],
+        ..AcpConfig::default()
+    };
+
+    let rules = MethodRules::from_config(&config);
+
+    assert_eq!(
+        rules.blocking_rules("session/set_mode"),
+        Some(vec![String::from("session/set_mode")])
+    );
+    assert_eq!(rules.blocking_rules("session/prompt"), None);
+}
+
+#[rstest]
+fn allow_methods_are_ignored_without_allow_only() {
+    let config = AcpConfig {
+        allow_methods: vec![String::from("session/*")],
+        ..AcpConfig::default()
+    };
+
+    assert!(MethodRules::from_config(&config).is_empty());
+}
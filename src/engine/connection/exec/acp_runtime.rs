@@ -6,7 +6,14 @@
 //! turns those decisions into actual I/O: it writes permitted frames to host
 //! stdout verbatim, synthesizes JSON-RPC error responses for blocked
 //! requests, and emits one stderr `tracing::warn!` per denial or
-//! once-per-session fallback record.
+//! once-per-session fallback record. Permission requests answered by a
+//! configured rule get a synthesized response on container stdin and an
+//! [`AcpSessionEvent`] for the session host. Served `fs/*` requests are
+//! answered from the workspace on Tokio's blocking pool. Served
+//! `terminal/*` requests are answered inline, except
+//! `terminal/wait_for_exit`, which is answered from a task the adapter owns
+//! so a long-running command never stalls agent output; dropping the adapter
+//! aborts waits still pending, releasing their senders.
 //!
 //! ## Sink task model
 //!
@@ -43,9 +50,20 @@ use std::pin::Pin;
 use ortho_config::serde_json::{self, Value};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::mpsc;
+use tokio::task::JoinSet;
 
 use super::acp_frame::{DeniedFrameDecision, FallbackReason, FrameOutput, OutboundFrameAssembler};
+use super::acp_fs::{FsError, FsRequest, WorkspaceFs, build_fs_response};
+use super::acp_helpers::split_frame_line_ending;
+use super::acp_permission::{
+    AcpSessionEvent, PermissionDecision, REQUEST_PERMISSION_METHOD, SessionEventSink,
+    build_permission_response,
+};
 use super::acp_policy::build_method_blocked_error;
+use super::acp_terminal::{
+    TERMINAL_WAIT_FOR_EXIT_METHOD, TerminalError, TerminalHost, TerminalRequest,
+    build_terminal_response,
+};
 
 /// Bounded capacity for the container-stdin command channel.
 ///
@@ -142,6 +160,10 @@ pub(super) struct OutboundPolicyAdapter {
     sender: mpsc::Sender<WriteCmd>,
     container_id: String,
     fallback_logged: bool,
+    events: Option<SessionEventSink>,
+    workspace: Option<WorkspaceFs>,
+    terminals: Option<TerminalHost>,
+    terminal_tasks: JoinSet<()>,
 }
 
 impl OutboundPolicyAdapter {
@@ -156,9 +178,32 @@ impl OutboundPolicyAdapter {
             sender,
             container_id: container_id.into(),
             fallback_logged: false,
+            events: None,
+            workspace: None,
+            terminals: None,
+            terminal_tasks: JoinSet::new(),
         }
     }
 
+    /// Run served `terminal/*` requests through `terminals`, when set.
+    pub(super) fn with_terminal_host(mut self, terminals: Option<TerminalHost>) -> Self {
+        self.terminals = terminals;
+        self
+    }
+
+    /// Answer served `fs/*` requests from `workspace`, when set.
+    pub(super) fn with_workspace_fs(mut self, workspace: Option<WorkspaceFs>) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Report session events such as permission decisions to `events`, when
+    /// set.
+    pub(super) fn with_session_events(mut self, events: Option<SessionEventSink>) -> Self {
+        self.events = events;
+        self
+    }
+
     /// Process one Bollard output chunk, writing permitted bytes to
     /// `host_stdout` and queuing synthesized error responses on the sink
     /// channel.
@@ -205,24 +250,167 @@ impl OutboundPolicyAdapter {
                 self.handle_decision(decision, &line_ending).await;
                 Ok(())
             }
+            FrameOutput::Permission(decision, frame) => {
+                self.handle_permission(decision, frame, host_stdout).await
+            }
+            FrameOutput::ServeFs(request, line_ending) => {
+                self.handle_fs_request(request, &line_ending).await;
+                Ok(())
+            }
+            FrameOutput::ServeTerminal(request, line_ending) => {
+                self.handle_terminal_request(request, line_ending).await;
+                Ok(())
+            }
         }
     }
 
-    async fn handle_decision(&self, decision: DeniedFrameDecision, line_ending: &[u8]) {
-        match decision {
-            DeniedFrameDecision::BlockNotification { method } => {
-                self.log_denial(&method, &Value::Null, "ACP blocked notification dropped");
-            }
-            DeniedFrameDecision::BlockRequest { id, method } => {
-                self.log_denial(&method, &id, "ACP blocked request denied");
-                self.queue_synthesized_error(&id, &method, line_ending)
+    async fn handle_terminal_request(&mut self, request: TerminalRequest, line_ending: Vec<u8>) {
+        let Some(id) = request.id.clone() else {
+            self.log_terminal_notification_dropped(&request.method);
+            return;
+        };
+        let responder = TerminalResponder {
+            terminals: self.terminals.clone(),
+            sender: self.sender.clone(),
+            container_id: self.container_id.clone(),
+        };
+        if request.method != TERMINAL_WAIT_FOR_EXIT_METHOD {
+            responder.answer(request, id, line_ending).await;
+            return;
+        }
+        while self.terminal_tasks.try_join_next().is_some() {}
+        self.terminal_tasks
+            .spawn(responder.answer(request, id, line_ending));
+    }
+
+    async fn handle_fs_request(&self, request: FsRequest, line_ending: &[u8]) {
+        let Some(id) = request.id.clone() else {
+            self.log_fs_notification_dropped(&request.method);
+            return;
+        };
+        let method = request.method.clone();
+        let outcome = match &self.workspace {
+            Some(workspace) => workspace.serve_blocking(request).await,
+            None => Err(FsError::Unavailable),
+        };
+        if let Err(error) = &outcome {
+            self.log_fs_refusal(&method, &id, error);
+        }
+        match build_fs_response(&id, &method, &outcome, line_ending) {
+            Ok(bytes) => self.send_response_or_log(bytes, &method, &id).await,
+            Err(error) => self.warn_response_not_sent(&method, &id, &error.to_string()),
+        }
+    }
+
+    async fn send_response_or_log(&self, bytes: Vec<u8>, method: &str, id: &Value) {
+        if self
+            .sender
+            .send(WriteCmd::Synthesized(bytes))
+            .await
+            .is_err()
+        {
+            self.warn_response_not_sent(method, id, "sink already closed");
+        }
+    }
+
+    fn log_fs_refusal(&self, method: &str, id: &Value, error: &FsError) {
+        tracing::warn!(
+            target = "podbot::acp::fs",
+            container_id = %self.container_id,
+            method = %method,
+            id = %id,
+            %error,
+            "ACP fs request refused",
+        );
+    }
+
+    fn log_fs_notification_dropped(&self, method: &str) {
+        tracing::warn!(
+            target = "podbot::acp::fs",
+            container_id = %self.container_id,
+            method = %method,
+            "ACP fs notification dropped",
+        );
+    }
+
+    fn log_terminal_notification_dropped(&self, method: &str) {
+        tracing::warn!(
+            target = "podbot::acp::terminal",
+            container_id = %self.container_id,
+            method = %method,
+            "ACP terminal notification dropped",
+        );
+    }
+
+    async fn handle_permission<W>(
+        &self,
+        decision: PermissionDecision,
+        frame: Vec<u8>,
+        host_stdout: &mut W,
+    ) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.log_permission(&decision);
+        let responds = decision.responds();
+        if responds {
+            self.queue_permission_response(&decision, &frame).await;
+        }
+        if let Some(events) = &self.events {
+            events.emit(AcpSessionEvent::PermissionDecided(decision));
+        }
+        if responds {
+            return Ok(());
+        }
+        host_stdout.write_all(&frame).await?;
+        host_stdout.flush().await
+    }
+
+    async fn queue_permission_response(&self, decision: &PermissionDecision, frame: &[u8]) {
+        let Some(option_id) = decision.option_id.as_deref() else {
+            return;
+        };
+        let (_payload, line_ending) = split_frame_line_ending(frame);
+        match build_permission_response(&decision.id, option_id, line_ending) {
+            Ok(bytes) => {
+                self.send_response_or_log(bytes, REQUEST_PERMISSION_METHOD, &decision.id)
                     .await;
             }
+            Err(error) => self.warn_response_not_sent(
+                REQUEST_PERMISSION_METHOD,
+                &decision.id,
+                &error.to_string(),
+            ),
+        }
+    }
+
+    fn warn_response_not_sent(&self, method: &str, id: &Value, reason: &str) {
+        warn_response_not_sent(&self.container_id, method, id, reason);
+    }
+
+    fn log_permission(&self, decision: &PermissionDecision) {
+        tracing::warn!(
+            target = "podbot::acp::permission",
+            container_id = %self.container_id,
+            id = %decision.id,
+            kind = ?decision.kind,
+            title = ?decision.title,
+            action = decision.action.as_token(),
+            option_id = ?decision.option_id,
+            "ACP permission request decided by policy",
+        );
+    }
+
+    async fn handle_decision(&self, decision: DeniedFrameDecision, line_ending: &[u8]) {
+        self.log_denial(&decision);
+        if let DeniedFrameDecision::BlockRequest { id, method, rules } = &decision {
+            let response = build_method_blocked_error(id, method, rules, line_ending);
+            self.queue_synthesized_error(response, method).await;
         }
     }
 
-    async fn queue_synthesized_error(&self, id: &Value, method: &str, line_ending: &[u8]) {
-        match build_method_blocked_error(id, method, line_ending) {
+    async fn queue_synthesized_error(&self, response: serde_json::Result<Vec<u8>>, method: &str) {
+        match response {
             Ok(bytes) => self.send_synthesized_or_log(bytes, method).await,
             Err(error) => self.log_synthesis_failure(method, &error),
         }
@@ -255,12 +443,24 @@ impl OutboundPolicyAdapter {
         );
     }
 
-    fn log_denial(&self, method: &str, id: &Value, message: &'static str) {
+    fn log_denial(&self, decision: &DeniedFrameDecision) {
+        let (method, id, rules, message) = match decision {
+            DeniedFrameDecision::BlockNotification { method, rules } => (
+                method,
+                &Value::Null,
+                rules,
+                "ACP blocked notification dropped",
+            ),
+            DeniedFrameDecision::BlockRequest { id, method, rules } => {
+                (method, id, rules, "ACP blocked request denied")
+            }
+        };
         tracing::warn!(
             target = "podbot::acp::policy",
             container_id = %self.container_id,
             method = %method,
             id = %id,
+            rules = ?rules,
             "{message}",
         );
     }
@@ -300,6 +500,59 @@ impl OutboundPolicyAdapter {
     }
 }
 
+/// Answers one served `terminal/*` request, inline or on its own task.
+struct TerminalResponder {
+    terminals: Option<TerminalHost>,
+    sender: mpsc::Sender<WriteCmd>,
+    container_id: String,
+}
+
+impl TerminalResponder {
+    async fn answer(self, request: TerminalRequest, id: Value, line_ending: Vec<u8>) {
+        let outcome = match &self.terminals {
+            Some(terminals) => terminals.serve(&request).await,
+            None => Err(TerminalError::Unavailable),
+        };
+        if let Err(error) = &outcome {
+            self.log_refusal(&request.method, &id, error);
+        }
+        let response = build_terminal_response(&id, &request.method, &outcome, &line_ending);
+        if let Err(reason) = self.queue(response).await {
+            warn_response_not_sent(&self.container_id, &request.method, &id, &reason);
+        }
+    }
+
+    async fn queue(&self, response: serde_json::Result<Vec<u8>>) -> Result<(), String> {
+        let bytes = response.map_err(|error| error.to_string())?;
+        self.sender
+            .send(WriteCmd::Synthesized(bytes))
+            .await
+            .map_err(|_| String::from("sink already closed"))
+    }
+
+    fn log_refusal(&self, method: &str, id: &Value, error: &TerminalError) {
+        tracing::warn!(
+            target = "podbot::acp::terminal",
+            container_id = %self.container_id,
+            method = %method,
+            id = %id,
+            %error,
+            "ACP terminal request refused",
+        );
+    }
+}
+
+fn warn_response_not_sent(container_id: &str, method: &str, id: &Value, reason: &str) {
+    tracing::warn!(
+        target = "podbot::acp::policy",
+        container_id,
+        method = %method,
+        id = %id,
+        reason,
+        "ACP response could not be sent; agent will time out",
+    );
+}
+
 #[cfg(test)]
 #[path = "acp_runtime_tests.rs"]
 mod tests;
@@ -0,0 +1,106 @@
+//! ACP session options resolved from configuration.
+//!
+//! When `agent.mode = "acp"`, a protocol-mode exec hosts an ACP agent and the
+//! `[agent.acp]` trust boundary applies to its traffic: `fs` and `terminal`
+//! capabilities are masked and their methods refused unless delegated or
+//! served, method and permission rules apply, frames are audited, and served
+//! requests are answered inside podbot. Every other exec keeps the
+//! byte-transparent default.
+
+use std::io;
+use std::path::PathBuf;
+
+use camino::Utf8Path;
+
+use super::acp_audit::AcpAuditLog;
+use super::acp_fs::WorkspaceFs;
+use super::acp_paths::PathMap;
+use super::acp_permission::PermissionPolicy;
+use super::acp_policy::AcpDelegation;
+use super::acp_rules::MethodRules;
+use super::acp_terminal::TerminalHost;
+use super::session::{CapabilityPolicy, ExecSessionOptions};
+use super::{ContainerExecClient, ExecMode, ExecRequest};
+use crate::config::{AgentMode, AppConfig, default_host_mount_container_path};
+use crate::error::{FilesystemError, PodbotError};
+
+impl ExecSessionOptions {
+    /// Resolve the ACP session options `config` selects for `request`.
+    ///
+    /// Protocol-mode requests under `agent.mode = "acp"` enforce the
+    /// `[agent.acp]` policy, running served `terminal/*` commands in the
+    /// request's container through `client`. Returns `None` for any other
+    /// request, which keeps the default options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesystemError::IoError` when the audit log or the served
+    /// workspace cannot be opened.
+    pub(crate) fn from_config<C>(
+        config: &AppConfig,
+        client: &C,
+        request: &ExecRequest,
+    ) -> Result<Option<Self>, PodbotError>
+    where
+        C: ContainerExecClient + Clone + Send + Sync + 'static,
+    {
+        if request.mode() != ExecMode::Protocol || config.agent.mode != AgentMode::Acp {
+            return Ok(None);
+        }
+        let acp = &config.agent.acp;
+        let terminals = acp
+            .terminal
+            .is_served()
+            .then(|| TerminalHost::new(client.clone(), request.container_id()));
+        Ok(Some(
+            Self::new()
+                .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+                .with_acp_delegation(AcpDelegation::from_config(acp))
+                .with_method_rules(MethodRules::from_config(acp))
+                .with_acp_audit(open_audit_log(config)?)
+                .with_permission_policy(PermissionPolicy::from_config(acp))
+                .with_workspace_fs(open_served_workspace(config)?)
+                .with_terminal_host(terminals)
+                .with_path_map(PathMap::from_config(&config.workspace)),
+        ))
+    }
+}
+
+fn open_audit_log(config: &AppConfig) -> Result<Option<AcpAuditLog>, PodbotError> {
+    let audit = &config.agent.acp.audit;
+    AcpAuditLog::open(audit).map_err(|error| {
+        let path = audit.path.as_deref().unwrap_or_else(|| Utf8Path::new(""));
+        io_error(path, &error)
+    })
+}
+
+/// Open the host-mounted workspace when `[agent.acp] fs = "serve"`.
+///
+/// Validation guarantees a `host_mount` workspace whenever `fs` is served.
+fn open_served_workspace(config: &AppConfig) -> Result<Option<WorkspaceFs>, PodbotError> {
+    if !config.agent.acp.fs.is_served() {
+        return Ok(None);
+    }
+    let Some(host_path) = config.workspace.host_path.as_deref() else {
+        return Ok(None);
+    };
+    let container_path = config
+        .workspace
+        .container_path
+        .clone()
+        .unwrap_or_else(default_host_mount_container_path);
+    WorkspaceFs::open(host_path, &container_path)
+        .map(Some)
+        .map_err(|error| io_error(host_path, &error))
+}
+
+fn io_error(path: &Utf8Path, error: &io::Error) -> PodbotError {
+    PodbotError::from(FilesystemError::IoError {
+        path: PathBuf::from(path.as_std_path()),
+        message: error.to_string(),
+    })
+}
+
+#[cfg(test)]
+#[path = "acp_session_tests.rs"]
+mod tests;
@@ -0,0 +1,99 @@
+//! Unit tests for resolving ACP session options from configuration.
+
+use camino::Utf8PathBuf;
+use rstest::rstest;
+
+use super::*;
+use crate::config::{AcpAuditConfig, AcpConfig, AcpFamilyPolicy, AgentConfig};
+use crate::engine::connection::exec::acp_test_support::FakeExecClient;
+
+fn protocol_request(mode: ExecMode) -> ExecRequest {
+    ExecRequest::new("sandbox", vec![String::from("agent")], mode).expect("request should be valid")
+}
+
+fn acp_config(acp: AcpConfig) -> AppConfig {
+    AppConfig {
+        agent: AgentConfig {
+            mode: AgentMode::Acp,
+            acp,
+            ..AgentConfig::default()
+        },
+        ..AppConfig::default()
+    }
+}
+
+fn resolve(config: &AppConfig, mode: ExecMode) -> Result<Option<ExecSessionOptions>, PodbotError> {
+    ExecSessionOptions::from_config(config, &FakeExecClient::new(), &protocol_request(mode))
+}
+
+#[rstest]
+#[case::podbot_mode(AgentMode::Podbot, ExecMode::Protocol)]
+#[case::codex_app_server(AgentMode::CodexAppServer, ExecMode::Protocol)]
+#[case::attached_acp(AgentMode::Acp, ExecMode::Attached)]
+fn other_sessions_keep_the_default_options(#[case] agent_mode: AgentMode, #[case] mode: ExecMode) {
+    let mut config = acp_config(AcpConfig::default());
+    config.agent.mode = agent_mode;
+
+    let options = resolve(&config, mode).expect("options should resolve");
+
+    assert!(options.is_none());
+}
+
+#[rstest]
+fn acp_protocol_sessions_mask_and_deny_by_default() {
+    let options = resolve(&acp_config(AcpConfig::default()), ExecMode::Protocol)
+        .expect("options should resolve");
+
+    assert_eq!(
+        options,
+        Some(ExecSessionOptions::new().with_capability_policy(CapabilityPolicy::MaskAndDeny))
+    );
+}
+
+#[rstest]
+fn acp_protocol_sessions_apply_the_configured_rules() {
+    let acp = AcpConfig {
+        fs: AcpFamilyPolicy::Delegate,
+        acknowledge_host_delegation: true,
+        deny_methods: vec![String::from("session/set_mode")],
+        ..AcpConfig::default()
+    };
+
+    let options =
+        resolve(&acp_config(acp.clone()), ExecMode::Protocol).expect("options should resolve");
+
+    assert_eq!(
+        options,
+        Some(
+            ExecSessionOptions::new()
+                .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+                .with_acp_delegation(AcpDelegation::new(true, false))
+                .with_method_rules(MethodRules::from_config(&acp))
+        )
+    );
+}
+
+#[rstest]
+fn unopenable_audit_logs_are_reported() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().join("missing").join("audit.jsonl"))
+        .expect("tempdir path should be valid UTF-8");
+    let acp = AcpConfig {
+        audit: AcpAuditConfig {
+            path: Some(path.clone()),
+            hash_params: false,
+        },
+        ..AcpConfig::default()
+    };
+
+    let result = resolve(&acp_config(acp), ExecMode::Protocol);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Filesystem(FilesystemError::IoError { path: reported, .. }))
+                if reported.as_path() == path.as_std_path()
+        ),
+        "expected audit log open failure, got {result:?}"
+    );
+}
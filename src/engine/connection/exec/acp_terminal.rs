@@ -0,0 +1,744 @@
+//! Podbot-served Agentic Control Protocol (ACP) `terminal/*` methods.
+//!
+//! With `[agent.acp] terminal = "serve"`, podbot advertises the `terminal`
+//! client capability itself and runs each `terminal/create` command as a
+//! [`ExecMode::Capture`] exec in the session's sandbox container, so the
+//! agent's commands never run on the client host. Each terminal gets a
+//! podbot-assigned id and an [`OutputCapture`] that retains the most recent
+//! `outputByteLimit` bytes, capped at [`MAX_OUTPUT_BYTE_LIMIT`];
+//! `terminal/output`, `terminal/wait_for_exit`, `terminal/kill`, and
+//! `terminal/release` act on that id. At most [`MAX_TERMINALS`] terminals
+//! are open at once.
+//!
+//! The exec API cannot signal a running process, so every command starts
+//! through a `sh` wrapper that records its PID in a file under `/tmp`;
+//! `terminal/kill` and `terminal/release` run a second exec that sends
+//! `SIGKILL` to that PID. A terminal reports `SIGKILL` only when that exec
+//! actually signalled its command. Served terminals therefore need a POSIX
+//! `sh` in the container. Commands still running when the session's
+//! terminal host is dropped are killed the same way, on a best-effort basis.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions};
+use ortho_config::serde_json::{self, Value};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use super::acp_helpers::parse_client_method_call;
+use super::capture::OutputCapture;
+use super::session::ExecSessionOptions;
+use super::{
+    ContainerExecClient, CreateExecFuture, EngineConnector, ExecMode, ExecRequest,
+    InspectExecFuture, ResizeExecFuture, StartExecFuture,
+};
+use crate::error::PodbotError;
+
+/// ACP method that starts a command in a new terminal.
+pub(crate) const TERMINAL_CREATE_METHOD: &str = "terminal/create";
+
+/// ACP method that returns a terminal's output so far.
+pub(crate) const TERMINAL_OUTPUT_METHOD: &str = "terminal/output";
+
+/// ACP method that waits for a terminal's command to exit.
+pub(crate) const TERMINAL_WAIT_FOR_EXIT_METHOD: &str = "terminal/wait_for_exit";
+
+/// ACP method that kills a terminal's command without releasing it.
+pub(crate) const TERMINAL_KILL_METHOD: &str = "terminal/kill";
+
+/// ACP method that kills a terminal's command, if running, and forgets it.
+pub(crate) const TERMINAL_RELEASE_METHOD: &str = "terminal/release";
+
+/// Method-name prefix of the `terminal` capability family.
+const TERMINAL_METHOD_PREFIX: &str = "terminal/";
+
+/// Output retained per terminal when `terminal/create` sets no
+/// `outputByteLimit`, in bytes.
+pub(crate) const DEFAULT_OUTPUT_BYTE_LIMIT: usize = 1024 * 1024;
+
+/// Largest `outputByteLimit` podbot honours, in bytes.
+pub(crate) const MAX_OUTPUT_BYTE_LIMIT: usize = 8 * 1024 * 1024;
+
+/// Most terminals one session may have open at once.
+pub(crate) const MAX_TERMINALS: usize = 16;
+
+/// Records the shell's PID in `$1`, then replaces the shell with the
+/// command so the recorded PID is the command's own.
+const LAUNCH_SCRIPT: &str = r#"echo $$ > "$1" && shift && exec "$@""#;
+
+/// Kills the process whose PID is recorded in `$1`, exiting with status 0
+/// only when a process was signalled.
+const KILL_SCRIPT: &str = r#"kill -KILL "$(cat "$1" 2>/dev/null)" 2>/dev/null"#;
+
+/// Kills the process whose PID is recorded in `$1` and removes the file,
+/// exiting with status 0 only when a process was signalled.
+const KILL_AND_REMOVE_SCRIPT: &str = r#"kill -KILL "$(cat "$1" 2>/dev/null)" 2>/dev/null; signalled=$?; rm -f "$1"; exit $signalled"#;
+
+/// Removes the PID file `$1`.
+const REMOVE_SCRIPT: &str = r#"rm -f "$1""#;
+
+/// `$0` for the wrapper scripts, shown in the container's process list.
+const WRAPPER_NAME: &str = "podbot-terminal";
+
+/// Signal reported for commands stopped by `terminal/kill` or
+/// `terminal/release`.
+const KILL_SIGNAL: &str = "SIGKILL";
+
+/// Source of terminal ids, unique within this podbot process.
+static NEXT_TERMINAL: AtomicU64 = AtomicU64::new(1);
+
+/// An agent-emitted `terminal/*` frame routed to podbot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TerminalRequest {
+    /// JSON-RPC id, or `None` for a notification.
+    pub(crate) id: Option<Value>,
+    /// The `terminal/*` method name.
+    pub(crate) method: String,
+    /// The request's params, or `null` when absent.
+    pub(crate) params: Value,
+}
+
+impl TerminalRequest {
+    /// Parse `frame` as a JSON-RPC 2.0 `terminal/*` request or notification.
+    ///
+    /// Returns `None` for any other frame, including unparseable ones.
+    pub(crate) fn parse(frame: &[u8]) -> Option<Self> {
+        let (id, method, params) = parse_client_method_call(frame, TERMINAL_METHOD_PREFIX)?;
+        Some(Self { id, method, params })
+    }
+}
+
+/// Why podbot refused or failed a `terminal/*` request.
+#[derive(Debug)]
+pub(crate) enum TerminalError {
+    /// The method is not one podbot serves.
+    MethodNotFound,
+    /// The params are missing a field or carry one of the wrong type.
+    InvalidParams(&'static str),
+    /// No open terminal has the requested id.
+    UnknownTerminal,
+    /// [`MAX_TERMINALS`] terminals are already open.
+    TooManyTerminals,
+    /// No sandbox container is available to run commands in.
+    Unavailable,
+    /// Running the command or a helper exec failed.
+    Exec(String),
+}
+
+impl TerminalError {
+    /// Return the JSON-RPC error code for this failure.
+    const fn code(&self) -> i64 {
+        match self {
+            Self::MethodNotFound => -32_601,
+            Self::InvalidParams(_) | Self::UnknownTerminal => -32_602,
+            Self::TooManyTerminals | Self::Unavailable | Self::Exec(_) => -32_603,
+        }
+    }
+
+    /// Return the JSON-RPC error message for this failure.
+    const fn message(&self) -> &'static str {
+        match self {
+            Self::MethodNotFound => "Method not found",
+            Self::InvalidParams(_) | Self::UnknownTerminal => "Invalid params",
+            Self::TooManyTerminals | Self::Unavailable | Self::Exec(_) => "Internal error",
+        }
+    }
+}
+
+impl fmt::Display for TerminalError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MethodNotFound => {
+                formatter.write_str("podbot does not serve this terminal method")
+            }
+            Self::InvalidParams(reason) => formatter.write_str(reason),
+            Self::UnknownTerminal => formatter.write_str("no open terminal has this id"),
+            Self::TooManyTerminals => write!(
+                formatter,
+                "{MAX_TERMINALS} terminals are already open; release one first"
+            ),
+            Self::Unavailable => {
+                formatter.write_str("no sandbox container is available to run terminals")
+            }
+            Self::Exec(reason) => formatter.write_str(reason),
+        }
+    }
+}
+
+impl From<PodbotError> for TerminalError {
+    fn from(error: PodbotError) -> Self {
+        Self::Exec(error.to_string())
+    }
+}
+
+/// How a terminal's command ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TerminalExit {
+    /// The command's exit code, or `None` when podbot killed it.
+    pub(crate) exit_code: Option<i64>,
+    /// The signal podbot stopped the command with, if any.
+    pub(crate) signal: Option<&'static str>,
+}
+
+impl TerminalExit {
+    const fn new(exit_code: i64, killed: bool) -> Self {
+        if killed {
+            Self {
+                exit_code: None,
+                signal: Some(KILL_SIGNAL),
+            }
+        } else {
+            Self {
+                exit_code: Some(exit_code),
+                signal: None,
+            }
+        }
+    }
+
+    fn to_json(self) -> Value {
+        serde_json::json!({ "exitCode": self.exit_code, "signal": self.signal })
+    }
+}
+
+/// Future returned by [`run_wrapper`], resolving to the helper's exit code.
+type HelperFuture<'a> = Pin<Box<dyn Future<Output = Result<i64, TerminalError>> + Send + 'a>>;
+
+/// Progress of a `terminal/kill` or `terminal/release` against a running
+/// command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KillState {
+    /// No kill is in flight, or the last one found no process to signal.
+    NotRequested,
+    /// A kill helper exec is running.
+    Pending,
+    /// The kill helper signalled the command.
+    Signalled,
+}
+
+/// The exit outcome a terminal's exec task publishes: `None` while the
+/// command runs, then the exit or the exec failure.
+type ExitSlot = Option<Result<TerminalExit, String>>;
+
+/// One open terminal.
+struct Terminal {
+    output: OutputCapture,
+    exit: watch::Receiver<ExitSlot>,
+    kill: Arc<watch::Sender<KillState>>,
+    pid_file: String,
+    task: JoinHandle<()>,
+}
+
+impl Terminal {
+    fn is_running(&self) -> bool {
+        self.exit.borrow().is_none()
+    }
+
+    /// Claim the right to kill a running command, returning the kill state
+    /// to resolve once the helper exits, or `None` when the command has
+    /// exited or another kill is already in flight.
+    fn begin_kill(&self) -> Option<Arc<watch::Sender<KillState>>> {
+        let claimed = self.is_running()
+            && self.kill.send_if_modified(|state| {
+                let idle = *state == KillState::NotRequested;
+                if idle {
+                    *state = KillState::Pending;
+                }
+                idle
+            });
+        claimed.then(|| Arc::clone(&self.kill))
+    }
+}
+
+/// The sandbox container that served `terminal/*` commands run in, and the
+/// terminals open in it.
+///
+/// Clones share one set of terminals.
+#[derive(Clone)]
+pub(crate) struct TerminalHost {
+    inner: Arc<TerminalHostInner>,
+}
+
+struct TerminalHostInner {
+    client: SharedExecClient,
+    container_id: String,
+    terminals: Mutex<HashMap<String, Terminal>>,
+}
+
+impl TerminalHost {
+    /// Run terminal commands in `container_id` through `client`.
+    pub(crate) fn new<C>(client: C, container_id: impl Into<String>) -> Self
+    where
+        C: ContainerExecClient + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(TerminalHostInner {
+                client: SharedExecClient(Arc::new(client)),
+                container_id: container_id.into(),
+                terminals: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Answer `request`, returning the JSON-RPC `result` on success.
+    ///
+    /// `terminal/wait_for_exit` resolves only once the command exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TerminalError`] when the request is refused or an exec
+    /// fails.
+    pub(crate) async fn serve(&self, request: &TerminalRequest) -> Result<Value, TerminalError> {
+        let params = &request.params;
+        match request.method.as_str() {
+            TERMINAL_CREATE_METHOD => self.create(params),
+            TERMINAL_OUTPUT_METHOD => self.output(params),
+            TERMINAL_WAIT_FOR_EXIT_METHOD => self.wait_for_exit(params).await,
+            TERMINAL_KILL_METHOD => self.kill(params).await,
+            TERMINAL_RELEASE_METHOD => self.release(params).await,
+            _ => Err(TerminalError::MethodNotFound),
+        }
+    }
+
+    fn create(&self, params: &Value) -> Result<Value, TerminalError> {
+        let command = CreateParams::parse(params)?;
+        let mut terminals = self.lock();
+        if terminals.len() >= MAX_TERMINALS {
+            return Err(TerminalError::TooManyTerminals);
+        }
+        let serial = NEXT_TERMINAL.fetch_add(1, Ordering::Relaxed);
+        let terminal_id = format!("term-{serial}");
+        let pid_file = format!("/tmp/{WRAPPER_NAME}-{}-{serial}.pid", std::process::id());
+        let mut argv = wrapper_argv(LAUNCH_SCRIPT, &pid_file);
+        argv.push(command.command);
+        argv.extend(command.args);
+        let request = ExecRequest::new(self.inner.container_id.as_str(), argv, ExecMode::Capture)?
+            .with_env(Some(command.env))
+            .with_working_dir(command.cwd);
+        let output = OutputCapture::new(command.output_byte_limit);
+        let (exit_sender, exit) = watch::channel(None);
+        let (kill, kill_state) = watch::channel(KillState::NotRequested);
+        let task = tokio::spawn(run_terminal(
+            self.inner.client.clone(),
+            request,
+            TerminalSignals {
+                output: output.clone(),
+                kill: kill_state,
+                exit: exit_sender,
+            },
+        ));
+        terminals.insert(
+            terminal_id.clone(),
+            Terminal {
+                output,
+                exit,
+                kill: Arc::new(kill),
+                pid_file,
+                task,
+            },
+        );
+        Ok(serde_json::json!({ "terminalId": terminal_id }))
+    }
+
+    fn output(&self, params: &Value) -> Result<Value, TerminalError> {
+        let terminals = self.lock();
+        let terminal = terminals
+            .get(terminal_id(params)?)
+            .ok_or(TerminalError::UnknownTerminal)?;
+        let captured = terminal.output.snapshot();
+        let exit_status = terminal
+            .exit
+            .borrow()
+            .as_ref()
+            .and_then(|outcome| outcome.as_ref().ok())
+            .map(|exit| exit.to_json());
+        Ok(serde_json::json!({
+            "output": captured.text,
+            "truncated": captured.truncated,
+            "exitStatus": exit_status,
+        }))
+    }
+
+    async fn wait_for_exit(&self, params: &Value) -> Result<Value, TerminalError> {
+        let mut exit = self.with_terminal(params, |terminal| terminal.exit.clone())?;
+        let outcome = exit
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| TerminalError::Exec(String::from("terminal exec task stopped")))?
+            .clone();
+        outcome
+            .unwrap_or_else(|| Err(String::from("terminal exec task stopped")))
+            .map(TerminalExit::to_json)
+            .map_err(TerminalError::Exec)
+    }
+
+    async fn kill(&self, params: &Value) -> Result<Value, TerminalError> {
+        let claimed = self.with_terminal(params, |terminal| {
+            terminal
+                .begin_kill()
+                .map(|kill| (kill, terminal.pid_file.clone()))
+        })?;
+        if let Some((kill, pid_file)) = claimed {
+            self.signal(&kill, KILL_SCRIPT, &pid_file).await?;
+        }
+        Ok(serde_json::json!({}))
+    }
+
+    async fn release(&self, params: &Value) -> Result<Value, TerminalError> {
+        let terminal_id = terminal_id(params)?;
+        let terminal = self
+            .lock()
+            .remove(terminal_id)
+            .ok_or(TerminalError::UnknownTerminal)?;
+        if let Some(kill) = terminal.begin_kill() {
+            self.signal(&kill, KILL_AND_REMOVE_SCRIPT, &terminal.pid_file)
+                .await?;
+        } else {
+            self.run_helper(REMOVE_SCRIPT, &terminal.pid_file).await?;
+        }
+        Ok(serde_json::json!({}))
+    }
+
+    /// Run the kill `script` against `pid_file` and resolve `kill` by
+    /// whether it signalled a process.
+    async fn signal(
+        &self,
+        kill: &watch::Sender<KillState>,
+        script: &str,
+        pid_file: &str,
+    ) -> Result<(), TerminalError> {
+        let outcome = self.run_helper(script, pid_file).await;
+        kill.send_replace(if matches!(outcome, Ok(0)) {
+            KillState::Signalled
+        } else {
+            KillState::NotRequested
+        });
+        outcome.map(drop)
+    }
+
+    /// Run a wrapper `script` against `pid_file` and wait for it to exit.
+    fn run_helper(&self, script: &str, pid_file: &str) -> HelperFuture<'_> {
+        run_wrapper(
+            &self.inner.client,
+            &self.inner.container_id,
+            script,
+            pid_file,
+        )
+    }
+
+    fn with_terminal<T>(
+        &self,
+        params: &Value,
+        read: impl FnOnce(&Terminal) -> T,
+    ) -> Result<T, TerminalError> {
+        let terminal_id = terminal_id(params)?;
+        self.lock()
+            .get(terminal_id)
+            .map(read)
+            .ok_or(TerminalError::UnknownTerminal)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Terminal>> {
+        self.inner
+            .terminals
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for TerminalHostInner {
+    /// Kill every command still running and stop the local exec tasks.
+    ///
+    /// The kills run as detached tasks on the current Tokio runtime; without
+    /// one, the commands are left to container teardown.
+    fn drop(&mut self) {
+        let terminals = self
+            .terminals
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        for terminal in terminals.values() {
+            terminal.task.abort();
+            if !terminal.is_running() {
+                continue;
+            }
+            let Some(handle) = runtime.as_ref() else {
+                log_kill_skipped(&terminal.pid_file);
+                continue;
+            };
+            handle.spawn(kill_abandoned(
+                self.client.clone(),
+                self.container_id.clone(),
+                terminal.pid_file.clone(),
+            ));
+        }
+    }
+}
+
+/// Kill the command recorded in `pid_file` after its host has gone.
+async fn kill_abandoned(client: SharedExecClient, container_id: String, pid_file: String) {
+    let killed = run_wrapper(&client, &container_id, KILL_AND_REMOVE_SCRIPT, &pid_file).await;
+    if let Err(error) = killed {
+        log_kill_failed(&pid_file, &error);
+    }
+}
+
+fn log_kill_skipped(pid_file: &str) {
+    tracing::debug!(
+        pid_file,
+        "no runtime to kill a served terminal; leaving it to teardown"
+    );
+}
+
+fn log_kill_failed(pid_file: &str, error: &TerminalError) {
+    tracing::debug!(pid_file, %error, "failed to kill a served terminal");
+}
+
+impl fmt::Debug for TerminalHost {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TerminalHost")
+            .field("container_id", &self.inner.container_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for TerminalHost {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for TerminalHost {}
+
+/// The state a terminal's exec task reports through.
+struct TerminalSignals {
+    output: OutputCapture,
+    kill: watch::Receiver<KillState>,
+    exit: watch::Sender<ExitSlot>,
+}
+
+/// Run one terminal's command to completion and publish how it ended.
+///
+/// A command that exits while a kill is in flight is reported once the kill
+/// helper says whether it was the one that stopped it.
+async fn run_terminal(client: SharedExecClient, request: ExecRequest, signals: TerminalSignals) {
+    let options = ExecSessionOptions::new().with_output_capture(Some(signals.output));
+    let result = EngineConnector::exec_async_with_options(&client, &request, options).await;
+    let killed = kill_signalled(signals.kill).await;
+    let outcome = result
+        .map(|exec| TerminalExit::new(exec.exit_code(), killed))
+        .map_err(|error| error.to_string());
+    signals.exit.send_replace(Some(outcome));
+}
+
+/// Wait for any in-flight kill to resolve, returning whether it signalled
+/// the command.
+async fn kill_signalled(mut kill: watch::Receiver<KillState>) -> bool {
+    kill.wait_for(|state| *state != KillState::Pending)
+        .await
+        .is_ok_and(|state| *state == KillState::Signalled)
+}
+
+/// Run a wrapper `script` with `pid_file` in `container_id` and wait for it
+/// to exit, returning its exit code.
+///
+/// The future is boxed because the exec path it drives includes protocol
+/// sessions, which serve terminals in turn; naming the type breaks the
+/// cycle the compiler would otherwise need to prove the future `Send`.
+fn run_wrapper<'a>(
+    client: &'a SharedExecClient,
+    container_id: &str,
+    script: &str,
+    pid_file: &str,
+) -> HelperFuture<'a> {
+    let request = ExecRequest::new(
+        container_id,
+        wrapper_argv(script, pid_file),
+        ExecMode::Detached,
+    );
+    Box::pin(async move {
+        let result = EngineConnector::exec_async(client, &request?).await?;
+        Ok(result.exit_code())
+    })
+}
+
+/// Return the `sh -c` argv that runs `script` with `pid_file` as `$1`.
+fn wrapper_argv(script: &str, pid_file: &str) -> Vec<String> {
+    ["sh", "-c", script, WRAPPER_NAME, pid_file]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Validated `terminal/create` params.
+struct CreateParams {
+    command: String,
+    args: Vec<String>,
+    env: Vec<String>,
+    cwd: Option<String>,
+    output_byte_limit: usize,
+}
+
+impl CreateParams {
+    fn parse(params: &Value) -> Result<Self, TerminalError> {
+        let command = params
+            .get("command")
+            .and_then(Value::as_str)
+            .filter(|command| !command.trim().is_empty())
+            .map(String::from)
+            .ok_or(TerminalError::InvalidParams(
+                "`command` must be a non-empty string",
+            ))?;
+        Ok(Self {
+            command,
+            args: parse_args(params.get("args"))?,
+            env: parse_env(params.get("env"))?,
+            cwd: parse_cwd(params.get("cwd"))?,
+            output_byte_limit: parse_output_byte_limit(params.get("outputByteLimit"))?,
+        })
+    }
+}
+
+fn parse_args(value: Option<&Value>) -> Result<Vec<String>, TerminalError> {
+    const REASON: &str = "`args` must be an array of strings";
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(String::from)
+                    .ok_or(TerminalError::InvalidParams(REASON))
+            })
+            .collect(),
+        Some(_) => Err(TerminalError::InvalidParams(REASON)),
+    }
+}
+
+/// Convert ACP `{name, value}` entries into `KEY=value` form.
+fn parse_env(value: Option<&Value>) -> Result<Vec<String>, TerminalError> {
+    const REASON: &str = "`env` must be an array of `{name, value}` string pairs";
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                let name = item
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .filter(|name| !name.is_empty() && !name.contains('='));
+                let entry_value = item.get("value").and_then(Value::as_str);
+                name.zip(entry_value)
+                    .map(|(key, text)| format!("{key}={text}"))
+                    .ok_or(TerminalError::InvalidParams(REASON))
+            })
+            .collect(),
+        Some(_) => Err(TerminalError::InvalidParams(REASON)),
+    }
+}
+
+fn parse_cwd(value: Option<&Value>) -> Result<Option<String>, TerminalError> {
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(cwd)) if cwd.starts_with('/') => Ok(Some(cwd.clone())),
+        Some(_) => Err(TerminalError::InvalidParams(
+            "`cwd` must be an absolute path",
+        )),
+    }
+}
+
+fn parse_output_byte_limit(value: Option<&Value>) -> Result<usize, TerminalError> {
+    match value {
+        None | Some(Value::Null) => Ok(DEFAULT_OUTPUT_BYTE_LIMIT),
+        Some(limit) => limit
+            .as_u64()
+            .map(|bytes| {
+                usize::try_from(bytes).map_or(MAX_OUTPUT_BYTE_LIMIT, |fits| {
+                    fits.min(MAX_OUTPUT_BYTE_LIMIT)
+                })
+            })
+            .ok_or(TerminalError::InvalidParams(
+                "`outputByteLimit` must be a non-negative integer",
+            )),
+    }
+}
+
+fn terminal_id(params: &Value) -> Result<&str, TerminalError> {
+    params
+        .get("terminalId")
+        .and_then(Value::as_str)
+        .ok_or(TerminalError::InvalidParams(
+            "`terminalId` must be a string",
+        ))
+}
+
+/// Build the JSON-RPC response to a `terminal/*` request from its
+/// `outcome`, terminated by `line_ending`.
+///
+/// # Errors
+///
+/// Returns the `serde_json` error raised while serializing the response.
+pub(crate) fn build_terminal_response(
+    id: &Value,
+    method: &str,
+    outcome: &Result<Value, TerminalError>,
+    line_ending: &[u8],
+) -> serde_json::Result<Vec<u8>> {
+    let payload = match outcome {
+        Ok(result) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }),
+        Err(error) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": error.code(),
+                "message": error.message(),
+                "data": {
+                    "method": method,
+                    "reason": error.to_string(),
+                },
+            },
+        }),
+    };
+    let mut serialized = serde_json::to_vec(&payload)?;
+    serialized.extend_from_slice(line_ending);
+    Ok(serialized)
+}
+
+/// A type-erased exec client shared by a host's terminals and their tasks.
+#[derive(Clone)]
+struct SharedExecClient(Arc<dyn ContainerExecClient + Send + Sync>);
+
+impl ContainerExecClient for SharedExecClient {
+    fn create_exec(
+        &self,
+        container_id: &str,
+        options: CreateExecOptions<String>,
+    ) -> CreateExecFuture<'_> {
+        self.0.create_exec(container_id, options)
+    }
+
+    fn start_exec(&self, exec_id: &str, options: Option<StartExecOptions>) -> StartExecFuture<'_> {
+        self.0.start_exec(exec_id, options)
+    }
+
+    fn inspect_exec(&self, exec_id: &str) -> InspectExecFuture<'_> {
+        self.0.inspect_exec(exec_id)
+    }
+
+    fn resize_exec(&self, exec_id: &str, options: ResizeExecOptions) -> ResizeExecFuture<'_> {
+        self.0.resize_exec(exec_id, options)
+    }
+}
+
+#[cfg(test)]
+#[path = "acp_terminal_tests.rs"]
+mod tests;
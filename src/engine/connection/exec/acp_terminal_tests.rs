@@ -0,0 +1,421 @@
+//! Unit tests for podbot-served ACP `terminal/*` methods.
+
+use ortho_config::serde_json::{self, Value};
+use rstest::{fixture, rstest};
+
+use super::{
+    KILL_AND_REMOVE_SCRIPT, KILL_SCRIPT, LAUNCH_SCRIPT, MAX_TERMINALS, REMOVE_SCRIPT,
+    TerminalError, TerminalHost, TerminalRequest, WRAPPER_NAME, build_terminal_response,
+};
+use crate::engine::connection::exec::acp_test_support::{FakeExecClient, jsonrpc_frame};
+
+/// A terminal host backed by a scripted exec client.
+struct Sandbox {
+    client: FakeExecClient,
+    host: TerminalHost,
+}
+
+impl Sandbox {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, TerminalError> {
+        self.host
+            .serve(&TerminalRequest {
+                id: Some(Value::from(1)),
+                method: String::from(method),
+                params,
+            })
+            .await
+    }
+
+    /// Create a terminal and wait until its exec reaches the client.
+    async fn create(&self, params: Value) -> String {
+        let before = self.client.commands().len();
+        let created = self
+            .call("terminal/create", params)
+            .await
+            .expect("terminal should be created");
+        while self.client.commands().len() == before {
+            tokio::task::yield_now().await;
+        }
+        created
+            .get("terminalId")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .expect("terminal id should be a string")
+    }
+
+    /// Return the PID file passed to the exec at `index`.
+    fn pid_file(&self, index: usize) -> String {
+        self.client
+            .commands()
+            .get(index)
+            .and_then(|argv| argv.get(4))
+            .cloned()
+            .expect("exec should carry a PID file")
+    }
+}
+
+#[fixture]
+fn sandbox() -> Sandbox {
+    let client = FakeExecClient::new();
+    let host = TerminalHost::new(client.clone(), "sandbox");
+    Sandbox { client, host }
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_runs_the_command_in_the_sandbox(sandbox: Sandbox) {
+    sandbox
+        .create(serde_json::json!({
+            "sessionId": "s1",
+            "command": "cargo",
+            "args": ["test", "--quiet"],
+            "env": [{"name": "K", "value": "V"}],
+            "cwd": "/workspace",
+        }))
+        .await;
+
+    let options = sandbox
+        .client
+        .created_options()
+        .into_iter()
+        .next()
+        .expect("an exec should be created");
+    let pid_file = sandbox.pid_file(0);
+    assert!(
+        pid_file.starts_with("/tmp/podbot-terminal-"),
+        "unexpected PID file {pid_file}"
+    );
+    assert_eq!(
+        options.cmd,
+        Some(
+            [
+                "sh",
+                "-c",
+                LAUNCH_SCRIPT,
+                WRAPPER_NAME,
+                &pid_file,
+                "cargo",
+                "test",
+                "--quiet"
+            ]
+            .map(String::from)
+            .to_vec()
+        )
+    );
+    assert_eq!(options.env, Some(vec![String::from("K=V")]));
+    assert_eq!(options.working_dir.as_deref(), Some("/workspace"));
+    assert_eq!(options.attach_stdin, Some(false));
+    assert_eq!(options.tty, Some(false));
+}
+
+#[rstest]
+#[tokio::test]
+async fn wait_for_exit_and_output_report_the_finished_command(sandbox: Sandbox) {
+    let terminal_id = sandbox
+        .create(serde_json::json!({"sessionId": "s1", "command": "make"}))
+        .await;
+    let params = serde_json::json!({"sessionId": "s1", "terminalId": terminal_id});
+
+    let exit = sandbox
+        .call("terminal/wait_for_exit", params.clone())
+        .await
+        .expect("wait should succeed");
+    let output = sandbox
+        .call("terminal/output", params)
+        .await
+        .expect("output should succeed");
+
+    assert_eq!(exit, serde_json::json!({"exitCode": 3, "signal": null}));
+    assert_eq!(
+        output,
+        serde_json::json!({
+            "output": "hello\nwarn\n",
+            "truncated": false,
+            "exitStatus": {"exitCode": 3, "signal": null},
+        })
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn output_keeps_the_tail_within_the_byte_limit(sandbox: Sandbox) {
+    let terminal_id = sandbox
+        .create(serde_json::json!({"command": "make", "outputByteLimit": 4}))
+        .await;
+    let params = serde_json::json!({"terminalId": terminal_id});
+
+    sandbox
+        .call("terminal/wait_for_exit", params.clone())
+        .await
+        .expect("wait should succeed");
+    let output = sandbox
+        .call("terminal/output", params)
+        .await
+        .expect("output should succeed");
+
+    assert_eq!(output.get("output"), Some(&Value::from("arn\n")));
+    assert_eq!(output.get("truncated"), Some(&Value::Bool(true)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn kill_stops_the_command_and_reports_the_signal(sandbox: Sandbox) {
+    let terminal_id = sandbox
+        .create(serde_json::json!({"command": "sleep", "args": ["600"]}))
+        .await;
+    let params = serde_json::json!({"terminalId": terminal_id});
+
+    let killed = sandbox
+        .call("terminal/kill", params.clone())
+        .await
+        .expect("kill should succeed");
+    let exit = sandbox
+        .call("terminal/wait_for_exit", params.clone())
+        .await
+        .expect("wait should succeed");
+    let output = sandbox
+        .call("terminal/output", params)
+        .await
+        .expect("a killed terminal should stay readable");
+
+    assert_eq!(killed, serde_json::json!({}));
+    assert_eq!(
+        exit,
+        serde_json::json!({"exitCode": null, "signal": "SIGKILL"})
+    );
+    assert_eq!(output.get("output"), Some(&Value::from("started\n")));
+    let helper = sandbox
+        .client
+        .commands()
+        .get(1)
+        .cloned()
+        .expect("kill should run a helper exec");
+    assert_eq!(helper.get(2).map(String::as_str), Some(KILL_SCRIPT));
+    assert_eq!(helper.get(4), Some(&sandbox.pid_file(0)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn kill_that_signals_nothing_is_not_reported(sandbox: Sandbox) {
+    let terminal_id = sandbox
+        .create(serde_json::json!({"command": "sleep", "args": ["600"]}))
+        .await;
+    let params = serde_json::json!({"terminalId": terminal_id});
+    sandbox.client.set_pid_files_missing(true);
+
+    sandbox
+        .call("terminal/kill", params.clone())
+        .await
+        .expect("a no-op kill should still succeed");
+    let output = sandbox
+        .call("terminal/output", params.clone())
+        .await
+        .expect("output should succeed");
+    sandbox.client.set_pid_files_missing(false);
+    sandbox
+        .call("terminal/kill", params.clone())
+        .await
+        .expect("kill should succeed");
+    let exit = sandbox
+        .call("terminal/wait_for_exit", params)
+        .await
+        .expect("wait should succeed");
+
+    assert_eq!(output.get("exitStatus"), Some(&Value::Null));
+    assert_eq!(
+        exit,
+        serde_json::json!({"exitCode": null, "signal": "SIGKILL"})
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn dropping_the_host_kills_running_terminals(sandbox: Sandbox) {
+    let finished = sandbox.create(serde_json::json!({"command": "make"})).await;
+    sandbox
+        .call(
+            "terminal/wait_for_exit",
+            serde_json::json!({"terminalId": finished}),
+        )
+        .await
+        .expect("wait should succeed");
+    sandbox
+        .create(serde_json::json!({"command": "sleep", "args": ["600"]}))
+        .await;
+    let pid_file = sandbox.pid_file(1);
+    let Sandbox { client, host } = sandbox;
+
+    drop(host);
+    while client.commands().len() < 3 {
+        tokio::task::yield_now().await;
+    }
+
+    let commands = client.commands();
+    let helper = commands.get(2).expect("drop should run a kill helper");
+    assert_eq!(
+        helper.get(2).map(String::as_str),
+        Some(KILL_AND_REMOVE_SCRIPT)
+    );
+    assert_eq!(helper.get(4), Some(&pid_file));
+    tokio::task::yield_now().await;
+    assert_eq!(
+        client.commands().len(),
+        3,
+        "finished terminals are not killed"
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn release_forgets_the_terminal(sandbox: Sandbox) {
+    let terminal_id = sandbox.create(serde_json::json!({"command": "make"})).await;
+    let params = serde_json::json!({"terminalId": terminal_id});
+    sandbox
+        .call("terminal/wait_for_exit", params.clone())
+        .await
+        .expect("wait should succeed");
+
+    let released = sandbox
+        .call("terminal/release", params.clone())
+        .await
+        .expect("release should succeed");
+    let after = sandbox.call("terminal/output", params).await;
+
+    assert_eq!(released, serde_json::json!({}));
+    assert!(
+        matches!(after, Err(TerminalError::UnknownTerminal)),
+        "{after:?}"
+    );
+    let helper = sandbox
+        .client
+        .commands()
+        .get(1)
+        .cloned()
+        .expect("release should run a helper exec");
+    assert_eq!(helper.get(2).map(String::as_str), Some(REMOVE_SCRIPT));
+}
+
+#[rstest]
+#[case::missing_command(serde_json::json!({"args": []}), "`command`")]
+#[case::blank_command(serde_json::json!({"command": "  "}), "`command`")]
+#[case::non_string_arg(serde_json::json!({"command": "ls", "args": [1]}), "`args`")]
+#[case::env_name_with_equals(
+    serde_json::json!({"command": "ls", "env": [{"name": "A=B", "value": "c"}]}),
+    "`env`"
+)]
+#[case::relative_cwd(serde_json::json!({"command": "ls", "cwd": "src"}), "`cwd`")]
+#[case::negative_limit(
+    serde_json::json!({"command": "ls", "outputByteLimit": -1}),
+    "`outputByteLimit`"
+)]
+#[tokio::test]
+async fn malformed_create_params_are_rejected(
+    sandbox: Sandbox,
+    #[case] params: Value,
+    #[case] reason: &str,
+) {
+    let result = sandbox.call("terminal/create", params).await;
+
+    assert!(
+        matches!(&result, Err(TerminalError::InvalidParams(message)) if message.contains(reason)),
+        "{result:?}"
+    );
+    assert!(sandbox.client.commands().is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn open_terminals_are_capped(sandbox: Sandbox) {
+    for _ in 0..MAX_TERMINALS {
+        sandbox.create(serde_json::json!({"command": "make"})).await;
+    }
+
+    let result = sandbox
+        .call("terminal/create", serde_json::json!({"command": "make"}))
+        .await;
+
+    assert!(
+        matches!(result, Err(TerminalError::TooManyTerminals)),
+        "{result:?}"
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn unknown_methods_and_terminals_are_reported(sandbox: Sandbox) {
+    let unknown_method = sandbox.call("terminal/resize", serde_json::json!({})).await;
+    let unknown_terminal = sandbox
+        .call(
+            "terminal/wait_for_exit",
+            serde_json::json!({"terminalId": "term-0"}),
+        )
+        .await;
+    let missing_id = sandbox.call("terminal/kill", serde_json::json!({})).await;
+
+    assert!(
+        matches!(unknown_method, Err(TerminalError::MethodNotFound)),
+        "{unknown_method:?}"
+    );
+    assert!(
+        matches!(unknown_terminal, Err(TerminalError::UnknownTerminal)),
+        "{unknown_terminal:?}"
+    );
+    assert!(
+        matches!(missing_id, Err(TerminalError::InvalidParams(_))),
+        "{missing_id:?}"
+    );
+}
+
+#[rstest]
+fn parse_recognizes_terminal_requests_and_notifications() {
+    let request_frame = jsonrpc_frame(Some(&Value::from(4)), "terminal/create", b"\n")
+        .expect("frame should serialize");
+    let notification =
+        jsonrpc_frame(None, "terminal/closed", b"\n").expect("frame should serialize");
+    let other = jsonrpc_frame(Some(&Value::from(5)), "fs/read_text_file", b"\n")
+        .expect("frame should serialize");
+
+    assert_eq!(
+        TerminalRequest::parse(&request_frame),
+        Some(TerminalRequest {
+            id: Some(Value::from(4)),
+            method: String::from("terminal/create"),
+            params: serde_json::json!({}),
+        })
+    );
+    assert_eq!(
+        TerminalRequest::parse(&notification).map(|parsed| parsed.id),
+        Some(None)
+    );
+    assert_eq!(TerminalRequest::parse(&other), None);
+    assert_eq!(TerminalRequest::parse(b"not json\n"), None);
+}
+
+#[rstest]
+fn errors_are_returned_as_json_rpc_errors() {
+    let response = build_terminal_response(
+        &Value::from(7),
+        "terminal/output",
+        &Err(TerminalError::UnknownTerminal),
+        b"\r\n",
+    )
+    .expect("response should serialize");
+
+    let parsed: Value = serde_json::from_slice(&response).expect("response should be JSON");
+    assert_eq!(
+        parsed,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "error": {
+                "code": -32_602,
+                "message": "Invalid params",
+                "data": {
+                    "method": "terminal/output",
+                    "reason": "no open terminal has this id",
+                },
+            },
+        })
+    );
+    assert!(response.ends_with(b"\r\n"));
+}
@@ -1,16 +1,26 @@
 //! Shared test doubles and frame builders for the ACP test modules.
 //!
 //! Consolidates the recording writer used to capture host or container
-//! output in tests, together with the newline-terminated JSON-RPC frame
-//! builder, so the individual ACP test modules do not duplicate them.
+//! output in tests, the scripted exec client behind served terminals, and
+//! the newline-terminated JSON-RPC frame builder, so the individual ACP test
+//! modules do not duplicate them.
 
 use std::io;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, PoisonError};
 use std::task::{Context, Poll};
 
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, CreateExecResults, ResizeExecOptions, StartExecOptions};
+use futures_util::{StreamExt, stream};
 use serde_json::Value;
 use tokio::io::AsyncWrite;
+use tokio::sync::Notify;
+
+use super::{
+    ContainerExecClient, CreateExecFuture, InspectExecFuture, ResizeExecFuture, StartExecFuture,
+};
 
 /// Recording writer that captures every byte written to it and tracks
 /// whether `poll_shutdown` was observed.
@@ -73,6 +83,30 @@ impl AsyncWrite for RecordingWriter {
     }
 }
 
+/// Synchronous writes, used when the recorder stands in for an audit log.
+impl io::Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parse every newline-terminated JSON record in `bytes`, skipping lines
+/// that are not JSON.
+pub(super) fn json_lines(bytes: &[u8]) -> Vec<Value> {
+    bytes
+        .split(|byte| *byte == b'\n')
+        .filter_map(|line| serde_json::from_slice(line).ok())
+        .collect()
+}
+
 /// Build a serialized JSON-RPC 2.0 frame terminated by `line_ending`.
 ///
 /// Pass `id = Some(…)` for requests and `id = None` for notifications.
@@ -102,3 +136,168 @@ pub(super) fn jsonrpc_frame(
     bytes.extend_from_slice(line_ending);
     Ok(bytes)
 }
+
+/// Build a newline-terminated `session/request_permission` request for a
+/// tool call of `kind` titled `title`, offering the usual allow and reject
+/// options.
+pub(super) fn permission_request_frame(
+    id: i64,
+    kind: &str,
+    title: &str,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let payload = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "session/request_permission",
+        "params": {
+            "sessionId": "sess-1",
+            "toolCall": {"toolCallId": format!("call-{id}"), "kind": kind, "title": title},
+            "options": [
+                {"optionId": "allow", "name": "Allow once", "kind": "allow_once"},
+                {"optionId": "always", "name": "Always allow", "kind": "allow_always"},
+                {"optionId": "deny", "name": "Reject", "kind": "reject_once"},
+            ],
+        },
+    });
+    let mut bytes = serde_json::to_vec(&payload)?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+/// Scripted exec client standing in for the container engine.
+///
+/// Every created exec is recorded. Attached commands whose argv contains
+/// `sleep` print `started` and run until a detached exec whose script kills
+/// a process arrives, then exit with status 137; other attached commands
+/// print `hello` on stdout and `warn` on stderr and exit with status 3.
+/// Detached helper execs exit with status 0, except that kill helpers exit
+/// with status 1 and signal nothing while PID files are marked missing.
+#[derive(Clone, Default)]
+pub(super) struct FakeExecClient {
+    created: Arc<Mutex<Vec<CreateExecOptions<String>>>>,
+    kill: Arc<Notify>,
+    pid_files_missing: Arc<AtomicBool>,
+}
+
+impl FakeExecClient {
+    /// Create a client with no recorded execs.
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the argv of every exec created so far, in order.
+    pub(super) fn commands(&self) -> Vec<Vec<String>> {
+        self.created_options()
+            .into_iter()
+            .map(|options| options.cmd.unwrap_or_default())
+            .collect()
+    }
+
+    /// Return the options of every exec created so far, in order.
+    pub(super) fn created_options(&self) -> Vec<CreateExecOptions<String>> {
+        self.created
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Make kill helpers find no PID file, as when they race a command's
+    /// launch, until reset.
+    pub(super) fn set_pid_files_missing(&self, missing: bool) {
+        self.pid_files_missing.store(missing, Ordering::SeqCst);
+    }
+
+    fn kill_finds_no_process(&self, command: &[String]) -> bool {
+        runs_kill(command) && self.pid_files_missing.load(Ordering::SeqCst)
+    }
+
+    fn command(&self, exec_id: &str) -> Vec<String> {
+        exec_id
+            .strip_prefix("exec-")
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| self.commands().into_iter().nth(index))
+            .unwrap_or_default()
+    }
+}
+
+fn runs_sleep(command: &[String]) -> bool {
+    command.iter().any(|arg| arg == "sleep")
+}
+
+fn runs_kill(command: &[String]) -> bool {
+    command.iter().any(|arg| arg.contains("kill"))
+}
+
+fn stdout(text: &str) -> LogOutput {
+    LogOutput::StdOut {
+        message: Vec::from(text).into(),
+    }
+}
+
+impl ContainerExecClient for FakeExecClient {
+    fn create_exec(
+        &self,
+        _container_id: &str,
+        options: CreateExecOptions<String>,
+    ) -> CreateExecFuture<'_> {
+        let mut created = self.created.lock().unwrap_or_else(PoisonError::into_inner);
+        let id = format!("exec-{}", created.len());
+        created.push(options);
+        Box::pin(async move { Ok(CreateExecResults { id }) })
+    }
+
+    fn start_exec(&self, exec_id: &str, options: Option<StartExecOptions>) -> StartExecFuture<'_> {
+        let command = self.command(exec_id);
+        if options.is_some_and(|start| start.detach) {
+            if runs_kill(&command) && !self.kill_finds_no_process(&command) {
+                self.kill.notify_one();
+            }
+            return Box::pin(async { Ok(bollard::exec::StartExecResults::Detached) });
+        }
+        let output = if runs_sleep(&command) {
+            let kill = Arc::clone(&self.kill);
+            stream::iter([Ok(stdout("started\n"))])
+                .chain(
+                    stream::once(async move { kill.notified().await })
+                        .filter_map(|()| async { None }),
+                )
+                .boxed()
+        } else {
+            stream::iter([
+                Ok(stdout("hello\n")),
+                Ok(LogOutput::StdErr {
+                    message: Vec::from("warn\n").into(),
+                }),
+            ])
+            .boxed()
+        };
+        Box::pin(async move {
+            Ok(bollard::exec::StartExecResults::Attached {
+                output,
+                input: Box::pin(tokio::io::sink()),
+            })
+        })
+    }
+
+    fn inspect_exec(&self, exec_id: &str) -> InspectExecFuture<'_> {
+        let command = self.command(exec_id);
+        let exit_code = if runs_sleep(&command) {
+            137
+        } else if command.iter().any(|arg| arg.contains("exec \"$@\"")) {
+            3
+        } else {
+            i64::from(self.kill_finds_no_process(&command))
+        };
+        Box::pin(async move {
+            Ok(bollard::models::ExecInspectResponse {
+                running: Some(false),
+                exit_code: Some(exit_code),
+                ..bollard::models::ExecInspectResponse::default()
+            })
+        })
+    }
+
+    fn resize_exec(&self, _exec_id: &str, _options: ResizeExecOptions) -> ResizeExecFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -0,0 +1,128 @@
+//! Capture-mode exec output buffering.
+//!
+//! [`super::ExecMode::Capture`] attaches the exec's stdout and stderr without
+//! stdin and without a pseudo-terminal, and collects both streams into an
+//! [`OutputCapture`] instead of writing them to host stdio. The buffer keeps
+//! only the most recent `limit` bytes so a chatty command cannot exhaust
+//! memory; once earlier output is discarded the capture reports itself as
+//! truncated.
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bollard::container::LogOutput;
+use bollard::errors::Error as BollardError;
+use futures_util::{Stream, StreamExt};
+
+use super::exec_failed;
+use crate::error::PodbotError;
+
+/// Shared, bounded buffer of a capture-mode exec's combined output.
+///
+/// Clones share one buffer, so the caller can read the output while the exec
+/// is still running.
+#[derive(Debug, Clone)]
+pub(crate) struct OutputCapture {
+    inner: Arc<Mutex<CaptureBuffer>>,
+}
+
+#[derive(Debug)]
+struct CaptureBuffer {
+    bytes: Vec<u8>,
+    limit: usize,
+    truncated: bool,
+}
+
+/// A point-in-time copy of an [`OutputCapture`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CapturedOutput {
+    /// The retained output, decoded as UTF-8 with invalid sequences replaced.
+    pub(crate) text: String,
+    /// `true` when earlier output was discarded to stay within the limit.
+    pub(crate) truncated: bool,
+}
+
+impl OutputCapture {
+    /// Create an empty capture that retains at most `limit` bytes.
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(CaptureBuffer {
+                bytes: Vec::new(),
+                limit,
+                truncated: false,
+            })),
+        }
+    }
+
+    /// Append `chunk`, discarding the oldest bytes beyond the limit.
+    ///
+    /// Truncation never leaves a partial UTF-8 character at the start of the
+    /// retained output.
+    pub(crate) fn append(&self, chunk: &[u8]) {
+        let mut buffer = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        buffer.bytes.extend_from_slice(chunk);
+        let Some(excess) = buffer
+            .bytes
+            .len()
+            .checked_sub(buffer.limit)
+            .filter(|excess| *excess > 0)
+        else {
+            return;
+        };
+        let continuation = buffer
+            .bytes
+            .get(excess..)
+            .unwrap_or_default()
+            .iter()
+            .take_while(|byte| (**byte & 0b1100_0000) == 0b1000_0000)
+            .count();
+        buffer.bytes.drain(..excess + continuation);
+        buffer.truncated = true;
+    }
+
+    /// Return a copy of the output retained so far.
+    pub(crate) fn snapshot(&self) -> CapturedOutput {
+        let buffer = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        CapturedOutput {
+            text: String::from_utf8_lossy(&buffer.bytes).into_owned(),
+            truncated: buffer.truncated,
+        }
+    }
+}
+
+impl PartialEq for OutputCapture {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for OutputCapture {}
+
+/// Drain a capture-mode exec's output stream into `capture`.
+///
+/// Output is discarded when no capture is supplied. Returns once the stream
+/// ends, or the mapped error when the daemon stream fails.
+pub(super) async fn run_capture_session_async(
+    container_id: &str,
+    mut output: Pin<Box<dyn Stream<Item = Result<LogOutput, BollardError>> + Send>>,
+    capture: Option<&OutputCapture>,
+) -> Result<(), PodbotError> {
+    while let Some(chunk) = output.next().await {
+        let log_output = chunk
+            .map_err(|error| exec_failed(container_id, format!("exec stream failed: {error}")))?;
+        if let (
+            LogOutput::StdOut { message }
+            | LogOutput::StdErr { message }
+            | LogOutput::Console { message },
+            Some(buffer),
+        ) = (log_output, capture)
+        {
+            buffer.append(&message);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "capture_tests.rs"]
+mod tests;
@@ -0,0 +1,92 @@
+//! Unit tests for capture-mode exec output buffering.
+
+use bollard::container::LogOutput;
+use bollard::errors::Error as BollardError;
+use futures_util::{StreamExt, stream};
+use rstest::rstest;
+
+use super::{CapturedOutput, OutputCapture, run_capture_session_async};
+use crate::error::{ContainerError, PodbotError};
+
+fn captured(text: &str, truncated: bool) -> CapturedOutput {
+    CapturedOutput {
+        text: String::from(text),
+        truncated,
+    }
+}
+
+#[rstest]
+#[case::within_limit(&["ab", "cd"], 8, captured("abcd", false))]
+#[case::exactly_at_limit(&["abcd"], 4, captured("abcd", false))]
+#[case::keeps_the_tail(&["abc", "def"], 4, captured("cdef", true))]
+#[case::zero_limit(&["abc"], 0, captured("", true))]
+#[case::drops_partial_character(&["é✓"], 4, captured("✓", true))]
+fn output_capture_retains_the_most_recent_bytes(
+    #[case] chunks: &[&str],
+    #[case] limit: usize,
+    #[case] expected: CapturedOutput,
+) {
+    let capture = OutputCapture::new(limit);
+    for chunk in chunks {
+        capture.append(chunk.as_bytes());
+    }
+
+    assert_eq!(capture.snapshot(), expected);
+}
+
+#[test]
+fn output_capture_clones_share_one_buffer() {
+    let capture = OutputCapture::new(16);
+    let reader = capture.clone();
+
+    capture.append(b"shared");
+
+    assert_eq!(reader.snapshot(), captured("shared", false));
+    assert_eq!(reader, capture);
+    assert_ne!(reader, OutputCapture::new(16));
+}
+
+#[tokio::test]
+async fn capture_session_collects_stdout_and_stderr() {
+    let capture = OutputCapture::new(64);
+    let output = stream::iter([
+        Ok(LogOutput::StdOut {
+            message: Vec::from("out\n").into(),
+        }),
+        Ok(LogOutput::StdErr {
+            message: Vec::from("err\n").into(),
+        }),
+        Ok(LogOutput::StdIn {
+            message: Vec::from("ignored\n").into(),
+        }),
+    ])
+    .boxed();
+
+    run_capture_session_async("sandbox", output, Some(&capture))
+        .await
+        .expect("capture session should succeed");
+
+    assert_eq!(capture.snapshot(), captured("out\nerr\n", false));
+}
+
+#[tokio::test]
+async fn capture_session_reports_stream_failures() {
+    let output = stream::iter([Err(BollardError::DockerResponseServerError {
+        status_code: 500,
+        message: String::from("boom"),
+    })])
+    .boxed();
+
+    let error = run_capture_session_async("sandbox", output, None)
+        .await
+        .expect_err("stream failure should be reported");
+
+    assert!(
+        matches!(
+            &error,
+            PodbotError::Container(ContainerError::ExecFailed { container_id, message })
+                if container_id == "sandbox" && message.contains("boom")
+        ),
+        "unexpected error: {error:?}",
+    );
+}
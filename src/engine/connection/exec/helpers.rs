@@ -14,11 +14,12 @@ use crate::error::{ConfigError, PodbotError};
 pub(super) fn build_create_exec_options(request: &ExecRequest) -> CreateExecOptions<String> {
     let attached = request.mode().is_attached();
     CreateExecOptions::<String> {
-        attach_stdin: Some(attached),
+        attach_stdin: Some(request.mode().attaches_stdin()),
         attach_stdout: Some(attached),
         attach_stderr: Some(attached),
         tty: Some(attached && request.tty()),
         env: request.env().map(<[String]>::to_vec),
+        working_dir: request.working_dir().map(String::from),
         cmd: Some(request.command().to_vec()),
         ..CreateExecOptions::default()
     }
@@ -27,7 +28,7 @@ pub(super) fn build_create_exec_options(request: &ExecRequest) -> CreateExecOpti
 pub(super) const fn build_start_exec_options(request: &ExecRequest) -> StartExecOptions {
     let output_capacity = match request.mode() {
         super::ExecMode::Protocol => Some(super::PROTOCOL_OUTPUT_CAPACITY),
-        super::ExecMode::Attached | super::ExecMode::Detached => None,
+        super::ExecMode::Attached | super::ExecMode::Detached | super::ExecMode::Capture => None,
     };
 
     StartExecOptions {
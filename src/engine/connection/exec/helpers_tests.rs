@@ -68,6 +68,14 @@ struct CreateExecOptionsCase {
     expected_stderr: Some(true),
     expected_tty: Some(false),
 })]
+#[case(CreateExecOptionsCase {
+    mode: ExecMode::Capture,
+    tty: true,
+    expected_stdin: Some(false),
+    expected_stdout: Some(true),
+    expected_stderr: Some(true),
+    expected_tty: Some(false),
+})]
 fn build_create_exec_options_maps_mode_tty_env_and_command(#[case] case: CreateExecOptionsCase) {
     let request = ExecRequest::new(
         "sandbox",
@@ -86,6 +94,18 @@ fn build_create_exec_options_maps_mode_tty_env_and_command(#[case] case: CreateE
     assert_eq!(options.tty, case.expected_tty);
     assert_eq!(options.env, request.env().map(<[String]>::to_vec));
     assert_eq!(options.cmd, Some(request.command().to_vec()));
+    assert_eq!(options.working_dir, None);
+}
+
+#[test]
+fn build_create_exec_options_maps_working_dir() {
+    let request = ExecRequest::new("sandbox", vec![String::from("pwd")], ExecMode::Capture)
+        .expect("request should be valid")
+        .with_working_dir(Some(String::from("/workspace/src")));
+
+    let options: CreateExecOptions<String> = build_create_exec_options(&request);
+
+    assert_eq!(options.working_dir.as_deref(), Some("/workspace/src"));
 }
 
 struct StartExecOptionsCase {
@@ -139,6 +159,13 @@ struct StartExecOptionsCase {
     expected_tty: false,
     expected_output_capacity: Some(crate::engine::connection::exec::PROTOCOL_OUTPUT_CAPACITY),
 })]
+#[case(StartExecOptionsCase {
+    mode: ExecMode::Capture,
+    tty: true,
+    expected_detach: false,
+    expected_tty: false,
+    expected_output_capacity: None,
+})]
 fn build_start_exec_options_maps_mode_tty_and_output_capacity(#[case] case: StartExecOptionsCase) {
     let request = ExecRequest::new("sandbox", vec![String::from("echo")], case.mode)
         .expect("request should be valid")
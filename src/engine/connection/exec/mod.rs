@@ -3,13 +3,22 @@
 //! This module wraps Bollard exec APIs behind a small trait seam so command
 //! execution behaviour can be unit-tested without a live daemon.
 
+mod acp_audit;
 mod acp_frame;
+mod acp_fs;
 mod acp_helpers;
+mod acp_inbound;
+mod acp_paths;
+mod acp_permission;
 mod acp_policy;
+mod acp_rules;
 mod acp_runtime;
+mod acp_session;
+mod acp_terminal;
 #[cfg(test)]
 mod acp_test_support;
 mod attached;
+mod capture;
 mod helpers;
 mod host_io;
 mod protocol;
@@ -17,6 +26,7 @@ mod runtime_helpers;
 mod session;
 mod terminal;
 
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 
@@ -24,6 +34,7 @@ use bollard::exec::{CreateExecOptions, CreateExecResults, ResizeExecOptions, Sta
 use bollard::{Docker, errors::Error as BollardError};
 
 use self::attached::{run_attached_session_async, wait_for_exit_code_async};
+use self::capture::run_capture_session_async;
 use self::helpers::{
     build_create_exec_options, build_start_exec_options, map_create_exec_error,
     map_start_exec_error, validate_command, validate_required_field,
@@ -33,8 +44,13 @@ use self::runtime_helpers::{block_on_runtime, exec_failed};
 use self::session::{ExecSessionOptions, protocol_session_options};
 use self::terminal::{SystemTerminalSizeProvider, TerminalSizeProvider};
 use super::EngineConnector;
+use super::agent_env::{AgentEnv, RedactedEnv};
+use crate::config::AppConfig;
 use crate::error::PodbotError;
 
+#[cfg(feature = "experimental")]
+pub(crate) use self::acp_permission::{AcpSessionEvent, SessionEventSink, TokenRevocation};
+
 pub(super) const EXEC_INSPECT_POLL_INTERVAL_MS: u64 = 100;
 
 /// Maximum bytes per `LogOutput` chunk from the daemon for protocol-mode exec
@@ -120,11 +136,18 @@ pub enum ExecMode {
     Detached,
     /// Attach streams for protocol proxying with tty permanently disabled.
     Protocol,
+    /// Attach output streams without stdin and collect them in memory.
+    Capture,
 }
 
 impl ExecMode {
     #[must_use]
     const fn is_attached(self) -> bool {
+        matches!(self, Self::Attached | Self::Protocol | Self::Capture)
+    }
+
+    #[must_use]
+    const fn attaches_stdin(self) -> bool {
         matches!(self, Self::Attached | Self::Protocol)
     }
 
@@ -138,11 +161,14 @@ impl ExecMode {
 }
 
 /// Parameters required to run a command in a running container.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Debug` output shows environment variable names but not their values.
+#[derive(Clone, PartialEq, Eq)]
 pub struct ExecRequest {
     container_id: String,
     command: Vec<String>,
     env: Option<Vec<String>>,
+    working_dir: Option<String>,
     mode: ExecMode,
     tty: bool,
 }
@@ -167,22 +193,37 @@ impl ExecRequest {
             container_id: id,
             command: validated_command,
             env: None,
+            working_dir: None,
             mode,
             tty: mode == ExecMode::Attached,
         })
     }
 
     /// Set environment variables in `KEY=value` form.
-    #[cfg(any(feature = "internal", test))]
     #[must_use]
     pub fn with_env(mut self, env: Option<Vec<String>>) -> Self {
         self.env = env.filter(|entries| !entries.is_empty());
         self
     }
 
+    /// Add the allowlisted host variables in `agent_env` to the environment,
+    /// keeping entries already set with [`Self::with_env`].
+    #[must_use]
+    pub fn with_agent_env(mut self, agent_env: &AgentEnv) -> Self {
+        self.env = agent_env.merge_into(self.env.take());
+        self
+    }
+
+    /// Run the command in `working_dir` instead of the image's default.
+    #[must_use]
+    pub fn with_working_dir(mut self, working_dir: Option<String>) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
     /// Control pseudo-terminal allocation for attached mode.
     ///
-    /// Detached and protocol modes always force `tty = false`.
+    /// Detached, protocol, and capture modes always force `tty = false`.
     #[must_use]
     pub const fn with_tty(mut self, tty: bool) -> Self {
         self.tty = matches!(self.mode, ExecMode::Attached) && tty;
@@ -207,6 +248,12 @@ impl ExecRequest {
         self.env.as_deref()
     }
 
+    /// Return the configured working directory.
+    #[must_use]
+    pub fn working_dir(&self) -> Option<&str> {
+        self.working_dir.as_deref()
+    }
+
     /// Return execution mode.
     #[must_use]
     pub const fn mode(&self) -> ExecMode {
@@ -220,6 +267,20 @@ impl ExecRequest {
     }
 }
 
+impl fmt::Debug for ExecRequest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ExecRequest")
+            .field("container_id", &self.container_id)
+            .field("command", &self.command)
+            .field("env", &RedactedEnv(self.env.as_deref()))
+            .field("working_dir", &self.working_dir)
+            .field("mode", &self.mode)
+            .field("tty", &self.tty)
+            .finish()
+    }
+}
+
 /// Outcome of a container command execution.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExecResult {
@@ -302,6 +363,35 @@ impl EngineConnector {
         block_on_runtime(runtime, async { Self::exec_async(client, request).await })
     }
 
+    /// Execute a command in a running container, applying the session policy
+    /// `config` selects, using a caller runtime handle.
+    ///
+    /// A protocol-mode request under `agent.mode = "acp"` enforces the
+    /// `[agent.acp]` trust boundary on its traffic; any other request behaves
+    /// exactly like [`Self::exec`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::exec_async`], and
+    /// `FilesystemError::IoError` when the ACP audit log or served workspace
+    /// cannot be opened.
+    pub(crate) fn exec_with_config<C>(
+        runtime: &tokio::runtime::Handle,
+        client: &C,
+        config: &AppConfig,
+        request: &ExecRequest,
+    ) -> Result<ExecResult, PodbotError>
+    where
+        C: ContainerExecClient + Clone + Send + Sync + 'static,
+    {
+        let Some(options) = ExecSessionOptions::from_config(config, client, request)? else {
+            return Self::exec(runtime, client, request);
+        };
+        block_on_runtime(runtime, async {
+            Self::exec_async_with_options(client, request, options).await
+        })
+    }
+
     #[cfg(test)]
     async fn exec_async_with_terminal_size_provider<
         C: ContainerExecClient,
@@ -354,8 +444,12 @@ impl EngineConnector {
                 )
                 .await?;
             }
+            (ExecMode::Capture, bollard::exec::StartExecResults::Attached { output, .. }) => {
+                run_capture_session_async(request.container_id(), output, options.output_capture())
+                    .await?;
+            }
             (
-                ExecMode::Attached | ExecMode::Protocol,
+                ExecMode::Attached | ExecMode::Protocol | ExecMode::Capture,
                 bollard::exec::StartExecResults::Detached,
             ) => {
                 return Err(exec_failed(
@@ -67,11 +67,18 @@ use futures_util::Stream;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use super::ExecRequest;
+use super::acp_audit::AcpAuditLog;
 use super::acp_frame::OutboundFrameAssembler;
-use super::acp_policy::MethodDenylist;
+use super::acp_fs::WorkspaceFs;
+use super::acp_inbound::InboundPolicyAdapter;
+use super::acp_paths::PathMap;
+use super::acp_permission::{PermissionPolicy, SessionEventSink};
+use super::acp_policy::{AcpDelegation, MethodDenylist};
+use super::acp_rules::MethodRules;
 use super::acp_runtime::{
     OutboundPolicyAdapter, SINK_CHANNEL_CAPACITY, WriteCmd, run_container_stdin_sink,
 };
+use super::acp_terminal::TerminalHost;
 use super::helpers::spawn_stdin_forwarding_task;
 use super::host_io::stdin_forwarding_disabled_for_tests;
 use super::runtime_helpers::exec_failed;
@@ -107,7 +114,7 @@ pub(super) struct ProtocolProxyIo<HostStdin, HostStdout, HostStderr> {
 pub(super) const STDIN_BUFFER_CAPACITY: usize = 65_536;
 
 /// Per-session configuration knobs for protocol-mode exec proxy loops.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub(super) struct ProtocolSessionOptions {
     /// When `true`, host stdin is replaced by a held-open no-op reader so that
     /// the process's inherited stdin is never forwarded to the container.
@@ -115,6 +122,23 @@ pub(super) struct ProtocolSessionOptions {
     /// When `true`, the first ACP `initialize` frame is rewritten to remove
     /// `terminal` and `fs` capabilities before being forwarded to the container.
     capability_policy: CapabilityPolicy,
+    /// ACP capability families delegated to the host client, which are
+    /// neither masked nor denied.
+    acp_delegation: AcpDelegation,
+    /// Operator-configured method rules enforced in both directions.
+    method_rules: MethodRules,
+    /// Audit log that records every frame the runtime policy classifies.
+    acp_audit: Option<AcpAuditLog>,
+    /// Rules that answer the agent's permission requests.
+    permission_policy: PermissionPolicy,
+    /// Channel that receives this session's ACP events.
+    session_events: Option<SessionEventSink>,
+    /// Workspace that served `fs/*` requests are answered from.
+    workspace_fs: Option<WorkspaceFs>,
+    /// Container that served `terminal/*` commands run in.
+    terminal_host: Option<TerminalHost>,
+    /// Host-mounted workspace locations rewritten in forwarded frames.
+    path_map: Option<PathMap>,
 }
 
 impl ProtocolSessionOptions {
@@ -123,6 +147,14 @@ impl ProtocolSessionOptions {
         Self {
             disable_stdin_forwarding: false,
             capability_policy: CapabilityPolicy::Disabled,
+            acp_delegation: AcpDelegation::new(false, false),
+            method_rules: MethodRules::new(),
+            acp_audit: None,
+            permission_policy: PermissionPolicy::new(),
+            session_events: None,
+            workspace_fs: None,
+            terminal_host: None,
+            path_map: None,
         }
     }
 
@@ -139,6 +171,65 @@ impl ProtocolSessionOptions {
         self.capability_policy = policy;
         self
     }
+
+    /// Select the ACP capability families delegated to the host client.
+    pub(super) const fn with_acp_delegation(mut self, delegation: AcpDelegation) -> Self {
+        self.acp_delegation = delegation;
+        self
+    }
+
+    /// Select the operator-configured ACP method rules for this session.
+    pub(super) fn with_method_rules(mut self, rules: MethodRules) -> Self {
+        self.method_rules = rules;
+        self
+    }
+
+    /// Record this session's ACP frames in `audit`, when set.
+    pub(super) fn with_acp_audit(mut self, audit: Option<AcpAuditLog>) -> Self {
+        self.acp_audit = audit;
+        self
+    }
+
+    /// Select the rules that answer the agent's permission requests.
+    pub(super) fn with_permission_policy(mut self, policy: PermissionPolicy) -> Self {
+        self.permission_policy = policy;
+        self
+    }
+
+    /// Report this session's ACP events to `events`, when set.
+    pub(super) fn with_session_events(mut self, events: Option<SessionEventSink>) -> Self {
+        self.session_events = events;
+        self
+    }
+
+    /// Answer served `fs/*` requests from `workspace`, when set.
+    pub(super) fn with_workspace_fs(mut self, workspace: Option<WorkspaceFs>) -> Self {
+        self.workspace_fs = workspace;
+        self
+    }
+
+    /// Run served `terminal/*` commands through `terminals`, when set.
+    pub(super) fn with_terminal_host(mut self, terminals: Option<TerminalHost>) -> Self {
+        self.terminal_host = terminals;
+        self
+    }
+
+    /// Rewrite workspace paths between host and container with `paths`,
+    /// when set.
+    pub(super) fn with_path_map(mut self, paths: Option<PathMap>) -> Self {
+        self.path_map = paths;
+        self
+    }
+
+    /// Return the delegation to apply when masking the first ACP
+    /// `initialize` frame, or `None` when the policy leaves it unchanged.
+    const fn initialize_mask(&self) -> Option<AcpDelegation> {
+        if self.capability_policy.rewrites_initialize() {
+            Some(self.acp_delegation)
+        } else {
+            None
+        }
+    }
 }
 
 /// An `AsyncRead` adapter that stays open indefinitely without producing
@@ -178,7 +269,7 @@ impl<HostStdin, HostStdout, HostStderr> ProtocolProxyIo<HostStdin, HostStdout, H
     }
 
     /// Attach session options to this host-IO bundle.
-    pub(super) const fn with_options(mut self, options: ProtocolSessionOptions) -> Self {
+    pub(super) fn with_options(mut self, options: ProtocolSessionOptions) -> Self {
         self.options = options;
         self
     }
@@ -193,7 +284,7 @@ pub(super) async fn run_protocol_session_async_with_options(
     options: ProtocolSessionOptions,
 ) -> Result<(), PodbotError> {
     let stdio = ProtocolProxyIo::new(
-        protocol_host_stdin(options),
+        protocol_host_stdin(&options),
         tokio::io::stdout(),
         tokio::io::stderr(),
     )
@@ -203,7 +294,7 @@ pub(super) async fn run_protocol_session_async_with_options(
 
 /// Return the appropriate stdin reader for a protocol session. Yields a
 /// held-open no-op reader when stdin forwarding is disabled.
-fn protocol_host_stdin(options: ProtocolSessionOptions) -> Pin<Box<dyn AsyncRead + Send>> {
+fn protocol_host_stdin(options: &ProtocolSessionOptions) -> Pin<Box<dyn AsyncRead + Send>> {
     if options.disable_stdin_forwarding || stdin_forwarding_disabled_for_tests() {
         let (writer_guard, reader) = tokio::io::duplex(1);
         Box::pin(HeldOpenStdin {
@@ -227,6 +318,7 @@ where
     HostStdout: AsyncWrite + Send + Unpin,
     HostStderr: AsyncWrite + Send + Unpin,
 {
+    warn_if_acp_delegated(request.container_id(), &stdio.options);
     if stdio.options.capability_policy.allows_runtime_enforcement() {
         run_session_with_runtime_enforcement(request, output, input, stdio).await
     } else {
@@ -234,6 +326,21 @@ where
     }
 }
 
+/// Warn on stderr when an ACP session delegates capability families to the
+/// host client, because calls in those families run outside the sandbox.
+fn warn_if_acp_delegated(container_id: &str, options: &ProtocolSessionOptions) {
+    if options.initialize_mask().is_none() || options.acp_delegation.is_empty() {
+        return;
+    }
+    let families = options.acp_delegation.family_names().join(", ");
+    tracing::warn!(
+        target = "podbot::acp::policy",
+        container_id,
+        families,
+        "ACP host delegation enabled: the host client will run these calls outside the sandbox",
+    );
+}
+
 async fn run_session_without_runtime_enforcement<HostStdin, HostStdout, HostStderr>(
     request: &ExecRequest,
     mut output: Pin<Box<dyn Stream<Item = Result<LogOutput, BollardError>> + Send>>,
@@ -251,10 +358,10 @@ where
         stderr: mut host_stderr,
         options,
     } = stdio;
-    let rewrite_acp_initialize = options.capability_policy.rewrites_initialize();
+    let initialize_mask = options.initialize_mask();
     let stdin_task =
         spawn_stdin_forwarding_task(host_stdin, input, move |stdin_reader, exec_input| {
-            forward_host_stdin_to_exec_async(stdin_reader, exec_input, rewrite_acp_initialize)
+            forward_host_stdin_to_exec_async(stdin_reader, exec_input, initialize_mask)
         });
     let output_result = run_output_loop_async(
         request.container_id(),
@@ -264,7 +371,7 @@ where
     )
     .await;
     let stdin_result =
-        settle_stdin_forwarding_task(request.container_id(), stdin_task, options).await;
+        settle_stdin_forwarding_task(request.container_id(), stdin_task, &options).await;
     output_result?;
     stdin_result
 }
@@ -286,23 +393,43 @@ where
         stderr: mut host_stderr,
         options,
     } = stdio;
-    let rewrite_acp_initialize = options.capability_policy.rewrites_initialize();
+    let initialize_mask = options.initialize_mask();
     let container_id_owned = String::from(request.container_id());
     let (sink_tx, sink_rx) = tokio::sync::mpsc::channel::<WriteCmd>(SINK_CHANNEL_CAPACITY);
     let sink_task = tokio::spawn(run_container_stdin_sink(input, sink_rx));
 
+    let denylist = session_denylist(&options);
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(SINK_CHANNEL_CAPACITY);
+    let frames_host_input =
+        denylist.has_inbound_rules() || options.acp_audit.is_some() || options.path_map.is_some();
+    let inbound = frames_host_input.then(|| {
+        let assembler = OutboundFrameAssembler::host_inbound(denylist.clone())
+            .with_audit(options.acp_audit.clone())
+            .with_path_map(options.path_map.clone());
+        InboundPolicyAdapter::new(assembler, reply_tx, container_id_owned.clone())
+    });
+
     let stdin_sender = sink_tx.clone();
     let stdin_task = tokio::spawn(async move {
-        forward_host_stdin_to_channel(host_stdin, stdin_sender, rewrite_acp_initialize).await
+        forward_host_stdin_to_channel(host_stdin, stdin_sender, initialize_mask, inbound).await
     });
 
-    let assembler = OutboundFrameAssembler::new(MethodDenylist::default_families());
-    let mut adapter = OutboundPolicyAdapter::new(assembler, sink_tx, container_id_owned);
+    let assembler = OutboundFrameAssembler::new(denylist)
+        .with_audit(options.acp_audit.clone())
+        .with_permission_policy(options.permission_policy.clone())
+        .with_served_fs(options.acp_delegation.serves_fs())
+        .with_served_terminal(options.acp_delegation.serves_terminal())
+        .with_path_map(options.path_map.clone());
+    let mut adapter = OutboundPolicyAdapter::new(assembler, sink_tx, container_id_owned)
+        .with_session_events(options.session_events.clone())
+        .with_workspace_fs(options.workspace_fs.clone())
+        .with_terminal_host(options.terminal_host.clone());
 
     let mut adapter_io = AdapterOutputIo {
         adapter: &mut adapter,
         host_stdout: &mut host_stdout,
         host_stderr: &mut host_stderr,
+        host_replies: &mut reply_rx,
     };
     let output_result =
         run_output_loop_with_adapter(request.container_id(), &mut output, &mut adapter_io).await;
@@ -310,7 +437,7 @@ where
     drop(adapter);
 
     let stdin_result =
-        settle_stdin_forwarding_task(request.container_id(), stdin_task, options).await;
+        settle_stdin_forwarding_task(request.container_id(), stdin_task, &options).await;
 
     if let Err(error) = output_result {
         sink_task.abort();
@@ -337,6 +464,14 @@ where
         })
 }
 
+/// Combine the families left undelegated with the operator's method rules.
+fn session_denylist(options: &ProtocolSessionOptions) -> MethodDenylist {
+    options
+        .acp_delegation
+        .denylist()
+        .with_rules(options.method_rules.clone())
+}
+
 #[cfg(test)]
 #[path = "protocol_acp_tests.rs"]
 mod acp_tests;
@@ -6,8 +6,8 @@ use super::*;
 fn forwarding_leaves_initialize_unchanged_when_acp_rewrite_is_disabled() {
     let host_stdin_bytes = initialize_frame("\n").expect("initialize frame should serialize");
 
-    let (forwarded, shutdown_called) = run_forwarding_with_rewrite(&host_stdin_bytes, false)
-        .expect("stdin forwarding should succeed");
+    let (forwarded, shutdown_called) =
+        run_forwarding_with_mask(&host_stdin_bytes, None).expect("stdin forwarding should succeed");
 
     assert_eq!(
         forwarded, host_stdin_bytes,
@@ -69,7 +69,11 @@ fn forwarding_does_not_wait_indefinitely_for_oversized_initial_frame() {
         .block_on(async {
             tokio::time::timeout(
                 test_timeout,
-                forward_initial_acp_frame_async(&mut buffered_stdin, &mut container_input),
+                forward_initial_acp_frame_async(
+                    &mut buffered_stdin,
+                    &mut container_input,
+                    AcpDelegation::default(),
+                ),
             )
             .await
         })
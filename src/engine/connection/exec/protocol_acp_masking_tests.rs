@@ -4,6 +4,8 @@
 
 use rstest::rstest;
 
+use crate::engine::connection::exec::acp_policy::AcpDelegation;
+
 use super::{
     check_masked_client_capabilities, client_capabilities, initialize_frame,
     initialize_frame_with_capabilities, initialize_with_only_blocked_capabilities,
@@ -16,7 +18,7 @@ use super::{
 #[case("\r\n")]
 fn mask_acp_initialize_frame_removes_blocked_capabilities(#[case] line_ending: &str) {
     let frame = initialize_frame(line_ending).expect("initialize frame should serialize");
-    let masked = mask_acp_initialize_frame(&frame);
+    let masked = mask_acp_initialize_frame(&frame, AcpDelegation::default());
     let payload = parse_frame_payload(&masked).expect("frame should contain JSON payload");
 
     assert_eq!(
@@ -32,7 +34,7 @@ fn mask_acp_initialize_frame_removes_blocked_capabilities(#[case] line_ending: &
 fn mask_acp_initialize_frame_removes_empty_client_capabilities() {
     let frame =
         initialize_with_only_blocked_capabilities("\n").expect("initialize frame should serialize");
-    let masked = mask_acp_initialize_frame(&frame);
+    let masked = mask_acp_initialize_frame(&frame, AcpDelegation::default());
     let payload = parse_frame_payload(&masked).expect("frame should contain JSON payload");
     let masked_params = params(&payload).expect("initialize params should remain present");
 
@@ -89,7 +91,7 @@ fn mask_acp_initialize_frame_preserves_unrelated_capabilities(
 ) {
     let frame = initialize_frame_with_capabilities(&capabilities, "\n")
         .expect("initialize frame should serialize");
-    let masked = mask_acp_initialize_frame(&frame);
+    let masked = mask_acp_initialize_frame(&frame, AcpDelegation::default());
     let result = parse_frame_payload(&masked).expect("frame should contain JSON payload");
     let caps = client_capabilities(&result).expect("clientCapabilities should remain");
 
@@ -107,11 +109,73 @@ fn mask_acp_initialize_frame_preserves_unrelated_capabilities(
     }
 }
 
+#[rstest]
+#[case::fs(AcpDelegation::new(true, false), "fs", "terminal")]
+#[case::terminal(AcpDelegation::new(false, true), "terminal", "fs")]
+fn mask_acp_initialize_frame_keeps_delegated_capabilities(
+    #[case] delegation: AcpDelegation,
+    #[case] kept: &str,
+    #[case] removed: &str,
+) {
+    let frame = initialize_frame("\n").expect("initialize frame should serialize");
+    let masked = mask_acp_initialize_frame(&frame, delegation);
+    let result = parse_frame_payload(&masked).expect("frame should contain JSON payload");
+    let caps = client_capabilities(&result).expect("clientCapabilities should remain");
+
+    assert!(caps.contains_key(kept), "{kept} is delegated");
+    assert!(!caps.contains_key(removed), "{removed} should be removed");
+}
+
+#[rstest]
+#[case::client_fs(serde_json::json!({"fs": {"readTextFile": false}, "terminal": true}))]
+#[case::no_client_fs(serde_json::json!({"terminal": true}))]
+fn mask_acp_initialize_frame_advertises_served_fs(#[case] capabilities: serde_json::Value) {
+    let frame = initialize_frame_with_capabilities(&capabilities, "\n")
+        .expect("initialize frame should serialize");
+    let delegation = AcpDelegation::default().with_served_fs(true);
+
+    let masked = mask_acp_initialize_frame(&frame, delegation);
+    let result = parse_frame_payload(&masked).expect("frame should contain JSON payload");
+    let caps = client_capabilities(&result).expect("clientCapabilities should remain");
+
+    assert_eq!(
+        caps.get("fs"),
+        Some(&serde_json::json!({"readTextFile": true, "writeTextFile": true})),
+    );
+    assert!(!caps.contains_key("terminal"), "terminal should be removed");
+}
+
+#[rstest]
+#[case::client_terminal(serde_json::json!({"fs": {"readTextFile": true}, "terminal": false}))]
+#[case::no_client_terminal(serde_json::json!({}))]
+fn mask_acp_initialize_frame_advertises_served_terminal(#[case] capabilities: serde_json::Value) {
+    let frame = initialize_frame_with_capabilities(&capabilities, "\n")
+        .expect("initialize frame should serialize");
+    let delegation = AcpDelegation::default().with_served_terminal(true);
+
+    let masked = mask_acp_initialize_frame(&frame, delegation);
+    let result = parse_frame_payload(&masked).expect("frame should contain JSON payload");
+    let caps = client_capabilities(&result).expect("clientCapabilities should remain");
+
+    assert_eq!(caps.get("terminal"), Some(&serde_json::json!(true)));
+    assert!(!caps.contains_key("fs"), "fs should be removed");
+}
+
+#[test]
+fn mask_acp_initialize_frame_leaves_fully_delegated_frame_unchanged() {
+    let frame = initialize_frame("\n").expect("initialize frame should serialize");
+
+    assert_eq!(
+        mask_acp_initialize_frame(&frame, AcpDelegation::new(true, true)),
+        frame
+    );
+}
+
 #[test]
 fn mask_acp_initialize_frame_passes_through_frame_without_line_ending() {
     let frame =
         initialize_with_only_blocked_capabilities("").expect("initialize frame should serialize");
-    let masked = mask_acp_initialize_frame(&frame);
+    let masked = mask_acp_initialize_frame(&frame, AcpDelegation::default());
 
     assert!(
         !masked.ends_with(b"\n"),
@@ -131,12 +195,18 @@ fn mask_acp_initialize_frame_leaves_non_initialize_messages_unchanged() {
     let mut frame = session_new_bytes();
     frame.push(b'\n');
 
-    assert_eq!(mask_acp_initialize_frame(&frame), frame);
+    assert_eq!(
+        mask_acp_initialize_frame(&frame, AcpDelegation::default()),
+        frame
+    );
 }
 
 #[test]
 fn mask_acp_initialize_frame_leaves_malformed_input_unchanged() {
     let frame = malformed_initialize_bytes();
 
-    assert_eq!(mask_acp_initialize_frame(&frame), frame);
+    assert_eq!(
+        mask_acp_initialize_frame(&frame, AcpDelegation::default()),
+        frame
+    );
 }
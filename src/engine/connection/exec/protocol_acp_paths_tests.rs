@@ -0,0 +1,131 @@
+//! End-to-end tests for ACP workspace path mapping in the protocol proxy.
+
+use std::io;
+use std::time::Duration;
+
+use bollard::container::LogOutput;
+use futures_util::{StreamExt, future, stream};
+
+use super::super::{ProtocolProxyIo, ProtocolSessionOptions, run_protocol_session_with_io_async};
+use crate::engine::connection::exec::acp_paths::PathMap;
+use crate::engine::connection::exec::acp_test_support::{RecordingWriter, json_lines};
+use crate::engine::connection::exec::session::CapabilityPolicy;
+use crate::engine::connection::exec::{ExecMode, ExecRequest};
+
+fn request_frame(id: i64, method: &str, params: &serde_json::Value) -> Vec<u8> {
+    let mut frame = serde_json::to_vec(&serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": params,
+    }))
+    .expect("frame should serialize");
+    frame.push(b'\n');
+    frame
+}
+
+/// Keeps the container output open until the agent has received a
+/// `session/new` frame, so host stdin is fully policed first.
+async fn wait_for_session_new(recorder: RecordingWriter) {
+    while !json_lines(&recorder.snapshot())
+        .iter()
+        .any(|message| message.get("method") == Some(&serde_json::json!("session/new")))
+    {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+/// Drives a session in which the host opens a session at its project
+/// directory and the agent reports an edit inside the workspace.
+fn drive_mapped_session(paths: Option<PathMap>) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut stdin_bytes = super::initialize_frame("\n").map_err(io::Error::other)?;
+    stdin_bytes.extend(request_frame(
+        1,
+        "session/new",
+        &serde_json::json!({"cwd": "/home/me/project", "mcpServers": []}),
+    ));
+    let host_stdin = runtime.block_on(super::build_host_stdin(&stdin_bytes))?;
+    let host_stdout = RecordingWriter::default();
+    let host_stdout_handle = host_stdout.clone();
+    let container_input = RecordingWriter::new();
+    let container_recorder = container_input.clone();
+    let agent_update = request_frame(
+        2,
+        "session/update",
+        &serde_json::json!({"locations": [{"path": "/workspace/src/lib.rs"}]}),
+    );
+    let output = stream::iter([Ok(LogOutput::StdOut {
+        message: agent_update.into(),
+    })])
+    .chain(
+        stream::once(wait_for_session_new(container_input.clone()))
+            .filter_map(|()| future::ready(None)),
+    );
+    let request = ExecRequest::new(
+        "path-mapping-sandbox",
+        vec![String::from("agent"), String::from("acp")],
+        ExecMode::Protocol,
+    )
+    .map_err(io::Error::other)?;
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_path_map(paths);
+    let stdio = ProtocolProxyIo::new(host_stdin, host_stdout, RecordingWriter::default())
+        .with_options(options);
+
+    runtime
+        .block_on(run_protocol_session_with_io_async(
+            &request,
+            Box::pin(output),
+            Box::pin(container_input),
+            stdio,
+        ))
+        .map_err(io::Error::other)?;
+
+    Ok((container_recorder.snapshot(), host_stdout_handle.snapshot()))
+}
+
+fn path_at(bytes: &[u8], id: i64, pointer: &str) -> Option<serde_json::Value> {
+    json_lines(bytes)
+        .into_iter()
+        .find(|message| message.get("id") == Some(&serde_json::json!(id)))
+        .and_then(|message| message.pointer(pointer).cloned())
+}
+
+#[test]
+fn workspace_paths_are_rewritten_in_both_directions() {
+    let paths = PathMap::new(
+        camino::Utf8Path::new("/home/me/project"),
+        camino::Utf8Path::new("/workspace"),
+    );
+
+    let (container_stdin, host_stdout) =
+        drive_mapped_session(paths).expect("mapped session should run");
+
+    assert_eq!(
+        path_at(&container_stdin, 1, "/params/cwd"),
+        Some(serde_json::json!("/workspace")),
+        "the agent should see the container workspace",
+    );
+    assert_eq!(
+        path_at(&host_stdout, 2, "/params/locations/0/path"),
+        Some(serde_json::json!("/home/me/project/src/lib.rs")),
+        "the host client should see its own paths",
+    );
+}
+
+#[test]
+fn sessions_without_a_path_map_forward_paths_unchanged() {
+    let (container_stdin, host_stdout) =
+        drive_mapped_session(None).expect("unmapped session should run");
+
+    assert_eq!(
+        path_at(&container_stdin, 1, "/params/cwd"),
+        Some(serde_json::json!("/home/me/project")),
+    );
+    assert_eq!(
+        path_at(&host_stdout, 2, "/params/locations/0/path"),
+        Some(serde_json::json!("/workspace/src/lib.rs")),
+    );
+}
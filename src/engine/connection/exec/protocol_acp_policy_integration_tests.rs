@@ -1,13 +1,25 @@
 //! Integration-style ACP policy-selection tests for protocol sessions.
 
 use std::io;
+use std::time::Duration;
 
 use bollard::container::LogOutput;
-use futures_util::stream;
+use futures_util::{StreamExt, future, stream};
 use rstest::rstest;
 
 use super::super::{ProtocolProxyIo, ProtocolSessionOptions, run_protocol_session_with_io_async};
-use crate::engine::connection::exec::acp_test_support::{RecordingWriter, jsonrpc_frame};
+use crate::config::{AcpPermissionAction, AcpPermissionRule};
+use crate::engine::connection::exec::acp_audit::AcpAuditLog;
+use crate::engine::connection::exec::acp_fs::WorkspaceFs;
+use crate::engine::connection::exec::acp_permission::{
+    AcpSessionEvent, PermissionPolicy, SessionEventSink,
+};
+use crate::engine::connection::exec::acp_policy::AcpDelegation;
+use crate::engine::connection::exec::acp_rules::MethodRules;
+use crate::engine::connection::exec::acp_terminal::TerminalHost;
+use crate::engine::connection::exec::acp_test_support::{
+    FakeExecClient, RecordingWriter, json_lines, jsonrpc_frame, permission_request_frame,
+};
 use crate::engine::connection::exec::session::CapabilityPolicy;
 use crate::engine::connection::exec::{ExecMode, ExecRequest};
 use crate::error::PodbotError;
@@ -25,6 +37,10 @@ fn blocked_request_frame(id: i64) -> Result<Vec<u8>, serde_json::Error> {
 }
 
 fn drive_policy_session(policy: CapabilityPolicy) -> io::Result<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    drive_session(ProtocolSessionOptions::new().with_capability_policy(policy))
+}
+
+fn drive_session(options: ProtocolSessionOptions) -> io::Result<(Vec<u8>, Vec<u8>, Vec<u8>)> {
     let runtime = tokio::runtime::Runtime::new()?;
     let initialize = super::initialize_frame("\n").map_err(io::Error::other)?;
     let host_stdin = runtime.block_on(super::build_host_stdin(&initialize))?;
@@ -39,8 +55,7 @@ fn drive_policy_session(policy: CapabilityPolicy) -> io::Result<(Vec<u8>, Vec<u8
         message: blocked.into(),
     })]);
     let request = protocol_request().map_err(io::Error::other)?;
-    let stdio = ProtocolProxyIo::new(host_stdin, host_stdout, host_stderr)
-        .with_options(ProtocolSessionOptions::new().with_capability_policy(policy));
+    let stdio = ProtocolProxyIo::new(host_stdin, host_stdout, host_stderr).with_options(options);
 
     runtime
         .block_on(run_protocol_session_with_io_async(
@@ -93,7 +108,8 @@ fn mask_and_deny_masks_initialize_and_synthesizes_blocked_response() {
         drive_policy_session(CapabilityPolicy::MaskAndDeny).expect("policy session should run");
     let lines = split_lines(&container_stdin);
     let initialize = super::initialize_frame("\n").expect("initialize frame should serialize");
-    let expected_initialize = super::mask_acp_initialize_frame(&initialize);
+    let expected_initialize =
+        super::mask_acp_initialize_frame(&initialize, AcpDelegation::default());
 
     assert!(
         host_stdout.is_empty(),
@@ -125,7 +141,7 @@ fn non_enforcing_policies_stream_blocked_frames_to_host_stdout(
     let initialize = super::initialize_frame("\n").expect("initialize frame should serialize");
     let blocked = blocked_request_frame(7).expect("blocked request should serialize");
     let expected_stdin = if masks_initialize {
-        super::mask_acp_initialize_frame(&initialize)
+        super::mask_acp_initialize_frame(&initialize, AcpDelegation::default())
     } else {
         initialize
     };
@@ -139,3 +155,411 @@ fn non_enforcing_policies_stream_blocked_frames_to_host_stdout(
         "{policy:?} should not enforce the outbound denylist",
     );
 }
+
+#[test]
+fn delegated_family_is_advertised_and_forwarded_under_mask_and_deny() {
+    let delegation = AcpDelegation::new(false, true);
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_acp_delegation(delegation);
+    let (container_stdin, host_stdout, _host_stderr) =
+        drive_session(options).expect("policy session should run");
+    let initialize = super::initialize_frame("\n").expect("initialize frame should serialize");
+    let blocked = blocked_request_frame(7).expect("request should serialize");
+    let masked_initialize =
+        super::parse_frame_payload(&container_stdin).expect("forwarded initialize should parse");
+    let capabilities =
+        super::client_capabilities(&masked_initialize).expect("clientCapabilities should remain");
+
+    assert_eq!(
+        container_stdin,
+        super::mask_acp_initialize_frame(&initialize, delegation),
+        "only the initialize frame should reach container stdin",
+    );
+    assert!(
+        capabilities.contains_key("terminal"),
+        "terminal is delegated"
+    );
+    assert!(!capabilities.contains_key("fs"), "fs remains masked");
+    assert_eq!(
+        host_stdout, blocked,
+        "delegated terminal requests should reach the host client",
+    );
+}
+
+/// Waits until `recorder` has received `frame`, so the container output
+/// stream stays open while host stdin is still being policed.
+async fn wait_for_container_frame(recorder: RecordingWriter, frame: Vec<u8>) {
+    while !recorder
+        .snapshot()
+        .windows(frame.len())
+        .any(|window| window == frame.as_slice())
+    {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+fn drive_rules_session(rules: MethodRules) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    drive_enforced_session(
+        ProtocolSessionOptions::new()
+            .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+            .with_method_rules(rules),
+    )
+}
+
+/// Drives a session in which the agent requests `_zed/open_buffer` while the
+/// host sends `initialize`, `_zed/ping` and `session/prompt`.
+fn drive_enforced_session(options: ProtocolSessionOptions) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut stdin_bytes = super::initialize_frame("\n").map_err(io::Error::other)?;
+    stdin_bytes.extend(
+        jsonrpc_frame(Some(&serde_json::json!(9)), "_zed/ping", b"\n").map_err(io::Error::other)?,
+    );
+    let prompt = jsonrpc_frame(Some(&serde_json::json!(10)), "session/prompt", b"\n")
+        .map_err(io::Error::other)?;
+    stdin_bytes.extend_from_slice(&prompt);
+    let host_stdin = runtime.block_on(super::build_host_stdin(&stdin_bytes))?;
+    let host_stdout = RecordingWriter::default();
+    let host_stdout_handle = host_stdout.clone();
+    let container_input = RecordingWriter::new();
+    let container_recorder = container_input.clone();
+    let agent_request = jsonrpc_frame(Some(&serde_json::json!(7)), "_zed/open_buffer", b"\n")
+        .map_err(io::Error::other)?;
+    let output = stream::iter([Ok(LogOutput::StdOut {
+        message: agent_request.into(),
+    })])
+    .chain(
+        stream::once(wait_for_container_frame(container_input.clone(), prompt))
+            .filter_map(|()| future::ready(None)),
+    );
+    let request = protocol_request().map_err(io::Error::other)?;
+    let stdio = ProtocolProxyIo::new(host_stdin, host_stdout, RecordingWriter::default())
+        .with_options(options);
+
+    runtime
+        .block_on(run_protocol_session_with_io_async(
+            &request,
+            Box::pin(output),
+            Box::pin(container_input),
+            stdio,
+        ))
+        .map_err(io::Error::other)?;
+
+    Ok((container_recorder.snapshot(), host_stdout_handle.snapshot()))
+}
+
+fn denial_rules(bytes: &[u8], id: i64) -> Option<serde_json::Value> {
+    split_lines(bytes)
+        .into_iter()
+        .filter_map(|line| parse_json_line(line).ok())
+        .find(|response| response.get("id") == Some(&serde_json::json!(id)))
+        .and_then(|response| response.pointer("/error/data/rules").cloned())
+}
+
+#[test]
+fn method_rules_block_frames_in_both_directions() {
+    let (container_stdin, host_stdout) =
+        drive_rules_session(MethodRules::denying(&["_zed/*"])).expect("rules session should run");
+    let prompt = jsonrpc_frame(Some(&serde_json::json!(10)), "session/prompt", b"\n")
+        .expect("prompt should serialize");
+
+    assert_eq!(
+        denial_rules(&container_stdin, 7),
+        Some(serde_json::json!(["_zed/*"])),
+        "the agent's vendor request should be refused",
+    );
+    assert_eq!(
+        denial_rules(&host_stdout, 9),
+        Some(serde_json::json!(["_zed/*"])),
+        "the host's vendor request should be answered on host stdout",
+    );
+    assert_eq!(
+        split_lines(&host_stdout).len(),
+        1,
+        "no agent frame should reach host stdout",
+    );
+    assert!(
+        split_lines(&container_stdin).contains(&prompt.as_slice()),
+        "permitted host requests should reach the agent",
+    );
+    assert!(
+        !split_lines(&container_stdin)
+            .iter()
+            .any(|line| line.windows(9).any(|window| window == b"_zed/ping")),
+        "refused host requests should not reach the agent",
+    );
+}
+
+#[test]
+fn audit_log_records_every_frame_in_both_directions() {
+    let log = RecordingWriter::new();
+    let audit = AcpAuditLog::new(log.clone(), mockable::DefaultClock, false)
+        .expect("audit writer should start");
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_method_rules(MethodRules::denying(&["_zed/*"]))
+        .with_acp_audit(Some(audit));
+
+    drive_enforced_session(options).expect("audited session should run");
+
+    let records: Vec<(String, String, String)> = json_lines(&log.snapshot())
+        .iter()
+        .map(|record| {
+            let field = |name: &str| {
+                String::from(
+                    record
+                        .get(name)
+                        .and_then(|value| value.as_str())
+                        .unwrap_or_default(),
+                )
+            };
+            (field("direction"), field("method"), field("decision"))
+        })
+        .collect();
+    let expected = [
+        ("host_inbound", "initialize", "forward"),
+        ("host_inbound", "_zed/ping", "block"),
+        ("host_inbound", "session/prompt", "forward"),
+        ("agent_outbound", "_zed/open_buffer", "block"),
+    ];
+    for (direction, method, decision) in expected {
+        assert!(
+            records.contains(&(
+                String::from(direction),
+                String::from(method),
+                String::from(decision)
+            )),
+            "missing {direction} {method} {decision} in {records:?}",
+        );
+    }
+    assert_eq!(records.len(), expected.len(), "one record per frame");
+}
+
+fn drive_agent_frames(
+    options: ProtocolSessionOptions,
+    agent_frames: Vec<u8>,
+) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let initialize = super::initialize_frame("\n").map_err(io::Error::other)?;
+    let host_stdin = runtime.block_on(super::build_host_stdin(&initialize))?;
+    let host_stdout = RecordingWriter::default();
+    let host_stdout_handle = host_stdout.clone();
+    let container_input = RecordingWriter::new();
+    let container_recorder = container_input.clone();
+    let output = stream::iter([Ok(LogOutput::StdOut {
+        message: agent_frames.into(),
+    })]);
+    let request = protocol_request().map_err(io::Error::other)?;
+    let stdio = ProtocolProxyIo::new(host_stdin, host_stdout, RecordingWriter::default())
+        .with_options(options);
+
+    runtime
+        .block_on(run_protocol_session_with_io_async(
+            &request,
+            Box::pin(output),
+            Box::pin(container_input),
+            stdio,
+        ))
+        .map_err(io::Error::other)?;
+
+    Ok((container_recorder.snapshot(), host_stdout_handle.snapshot()))
+}
+
+#[test]
+fn permission_rules_answer_matching_requests_and_report_events() {
+    let policy = PermissionPolicy::from_rules(vec![AcpPermissionRule {
+        kind: Some(String::from("read")),
+        title: None,
+        action: AcpPermissionAction::AllowOnce,
+    }]);
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(8);
+    let log = RecordingWriter::new();
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_permission_policy(policy)
+        .with_session_events(Some(SessionEventSink::new(sender)))
+        .with_acp_audit(Some(
+            AcpAuditLog::new(log.clone(), mockable::DefaultClock, false)
+                .expect("audit writer should start"),
+        ));
+
+    let mut agent_frames =
+        permission_request_frame(1, "read", "Read src/lib.rs").expect("frame should serialize");
+    agent_frames.extend(
+        permission_request_frame(2, "execute", "Run `make`").expect("frame should serialize"),
+    );
+
+    let (container_stdin, host_stdout) =
+        drive_agent_frames(options, agent_frames).expect("permission session should run");
+
+    let answer = json_lines(&container_stdin)
+        .into_iter()
+        .find(|message| message.get("id") == Some(&serde_json::json!(1)));
+    assert_eq!(
+        answer.and_then(|message| message.pointer("/result/outcome/optionId").cloned()),
+        Some(serde_json::json!("allow")),
+        "the read request should be answered in the proxy",
+    );
+    let forwarded =
+        permission_request_frame(2, "execute", "Run `make`").expect("frame should serialize");
+    assert_eq!(
+        host_stdout, forwarded,
+        "only the unmatched request reaches the host"
+    );
+
+    let event = receiver
+        .try_recv()
+        .expect("the decision should be reported");
+    let AcpSessionEvent::PermissionDecided(decision) = event else {
+        panic!("expected a permission decision, got {event:?}");
+    };
+    assert_eq!(decision.option_id.as_deref(), Some("allow"));
+    assert!(
+        receiver.try_recv().is_err(),
+        "unmatched requests emit no event"
+    );
+
+    let decisions: Vec<Option<String>> = json_lines(&log.snapshot())
+        .iter()
+        .filter(|record| record.get("direction") == Some(&serde_json::json!("agent_outbound")))
+        .map(|record| {
+            record
+                .get("decision")
+                .and_then(|value| value.as_str())
+                .map(String::from)
+        })
+        .collect();
+    assert_eq!(
+        decisions,
+        vec![Some(String::from("respond")), Some(String::from("forward"))],
+    );
+}
+
+fn agent_request(id: i64, method: &str, params: &serde_json::Value) -> Vec<u8> {
+    let mut frame = serde_json::to_vec(&serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": params,
+    }))
+    .expect("frame should serialize");
+    frame.push(b'\n');
+    frame
+}
+
+#[test]
+fn served_fs_requests_are_answered_from_the_workspace() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root_path = temp_dir.path().join("workspace");
+    std::fs::create_dir(&root_path).expect("workspace should be created");
+    std::fs::write(root_path.join("notes.txt"), "from the host\n").expect("seed should be written");
+    let root = cap_std::fs_utf8::Dir::open_ambient_dir(
+        camino::Utf8Path::from_path(&root_path).expect("tempdir path should be UTF-8"),
+        cap_std::ambient_authority(),
+    )
+    .expect("workspace should open");
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_acp_delegation(AcpDelegation::default().with_served_fs(true))
+        .with_workspace_fs(Some(WorkspaceFs::new(root, "/workspace")));
+    let mut agent_frames = agent_request(
+        1,
+        "fs/read_text_file",
+        &serde_json::json!({"path": "/workspace/notes.txt"}),
+    );
+    agent_frames.extend(agent_request(
+        2,
+        "fs/write_text_file",
+        &serde_json::json!({"path": "/workspace/../escape.txt", "content": "x"}),
+    ));
+
+    let (container_stdin, host_stdout) =
+        drive_agent_frames(options, agent_frames).expect("served session should run");
+
+    let responses = json_lines(&container_stdin);
+    let response = |id: i64| {
+        responses
+            .iter()
+            .find(|message| message.get("id") == Some(&serde_json::json!(id)))
+            .cloned()
+    };
+    assert_eq!(
+        response(1).and_then(|message| message.pointer("/result/content").cloned()),
+        Some(serde_json::json!("from the host\n")),
+    );
+    assert_eq!(
+        response(2).and_then(|message| message.pointer("/error/data/reason").cloned()),
+        Some(serde_json::json!("path is outside the workspace")),
+    );
+    assert!(!temp_dir.path().join("escape.txt").exists());
+    assert!(
+        host_stdout.is_empty(),
+        "served requests never reach the host"
+    );
+}
+
+fn response_by_id(bytes: &[u8], id: i64) -> Option<serde_json::Value> {
+    json_lines(bytes)
+        .into_iter()
+        .find(|message| message.get("id") == Some(&serde_json::json!(id)))
+}
+
+#[test]
+fn served_terminal_requests_run_in_the_sandbox() {
+    let client = FakeExecClient::new();
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_acp_delegation(AcpDelegation::default().with_served_terminal(true))
+        .with_terminal_host(Some(TerminalHost::new(client.clone(), "sandbox")));
+    let mut agent_frames = agent_request(
+        1,
+        "terminal/create",
+        &serde_json::json!({"sessionId": "s1", "command": "make"}),
+    );
+    agent_frames.extend(agent_request(
+        2,
+        "terminal/output",
+        &serde_json::json!({"sessionId": "s1", "terminalId": "term-0"}),
+    ));
+
+    let (container_stdin, host_stdout) =
+        drive_agent_frames(options, agent_frames).expect("served session should run");
+
+    assert!(
+        response_by_id(&container_stdin, 1)
+            .and_then(|message| message.pointer("/result/terminalId").cloned())
+            .is_some_and(|terminal_id| terminal_id.is_string()),
+        "terminal/create should return a terminal id",
+    );
+    assert_eq!(
+        response_by_id(&container_stdin, 2)
+            .and_then(|message| message.pointer("/error/data/reason").cloned()),
+        Some(serde_json::json!("no open terminal has this id")),
+    );
+    assert!(
+        host_stdout.is_empty(),
+        "served requests never reach the host"
+    );
+}
+
+#[test]
+fn served_terminal_requests_without_a_sandbox_are_refused() {
+    let options = ProtocolSessionOptions::new()
+        .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+        .with_acp_delegation(AcpDelegation::default().with_served_terminal(true));
+    let agent_frames = agent_request(
+        1,
+        "terminal/create",
+        &serde_json::json!({"command": "make"}),
+    );
+
+    let (container_stdin, host_stdout) =
+        drive_agent_frames(options, agent_frames).expect("served session should run");
+
+    assert_eq!(
+        response_by_id(&container_stdin, 1)
+            .and_then(|message| message.pointer("/error/message").cloned()),
+        Some(serde_json::json!("Internal error")),
+    );
+    assert!(host_stdout.is_empty());
+}
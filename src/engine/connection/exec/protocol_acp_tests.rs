@@ -13,6 +13,7 @@ use crate::engine::connection::exec::acp_helpers::{
     ACP_FILE_SYSTEM_CAPABILITY, ACP_TERMINAL_CAPABILITY, MAX_FIRST_FRAME_BYTES,
     forward_initial_acp_frame_async, mask_acp_initialize_frame, split_frame_line_ending,
 };
+use crate::engine::connection::exec::acp_policy::AcpDelegation;
 use crate::engine::connection::exec::acp_test_support::RecordingWriter as RecordingInputWriter;
 
 fn initialize_frame_with_capabilities(
@@ -138,16 +139,16 @@ async fn build_host_stdin(bytes: &[u8]) -> io::Result<DuplexStream> {
     Ok(reader)
 }
 
-/// Runs ACP stdin forwarding synchronously with `rewrite_acp_initialize =
-/// true`, returning the bytes written to the container input and whether
+/// Runs ACP stdin forwarding synchronously with the default `initialize`
+/// mask, returning the bytes written to the container input and whether
 /// `poll_shutdown` was called.
 pub(super) fn run_forwarding(host_stdin_bytes: &[u8]) -> io::Result<(Vec<u8>, bool)> {
-    run_forwarding_with_rewrite(host_stdin_bytes, true)
+    run_forwarding_with_mask(host_stdin_bytes, Some(AcpDelegation::default()))
 }
 
-fn run_forwarding_with_rewrite(
+fn run_forwarding_with_mask(
     host_stdin_bytes: &[u8],
-    rewrite_acp_initialize: bool,
+    initialize_mask: Option<AcpDelegation>,
 ) -> io::Result<(Vec<u8>, bool)> {
     let runtime = tokio::runtime::Runtime::new()?;
     let host_stdin = runtime.block_on(build_host_stdin(host_stdin_bytes))?;
@@ -157,7 +158,7 @@ fn run_forwarding_with_rewrite(
     runtime.block_on(forward_host_stdin_to_exec_async(
         host_stdin,
         Box::pin(container_input),
-        rewrite_acp_initialize,
+        initialize_mask,
     ))?;
 
     Ok((recorder.snapshot(), recorder.shutdown_observed()))
@@ -208,5 +209,8 @@ mod forwarding_tests;
 #[path = "protocol_acp_policy_integration_tests.rs"]
 mod policy_integration_tests;
 
+#[path = "protocol_acp_paths_tests.rs"]
+mod paths_tests;
+
 #[path = "protocol_acp_bdd_tests.rs"]
 mod bdd_tests;
@@ -4,7 +4,9 @@
 //! host stdout or stderr, either directly (plain byte proxy) or through the
 //! outbound ACP policy adapter when runtime enforcement is active. The stdout
 //! purity contract documented in the parent module is upheld here: only
-//! container stdout and console bytes ever reach host stdout.
+//! container stdout and console bytes ever reach host stdout, plus the
+//! JSON-RPC error responses that answer host requests refused by the
+//! operator's inbound method rules.
 
 use std::pin::Pin;
 
@@ -12,6 +14,7 @@ use bollard::container::LogOutput;
 use bollard::errors::Error as BollardError;
 use futures_util::{Stream, StreamExt};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
 
 use super::super::acp_runtime::OutboundPolicyAdapter;
 use super::super::runtime_helpers::exec_failed;
@@ -25,6 +28,32 @@ pub(super) struct AdapterOutputIo<'a, HostStdout, HostStderr> {
     pub(super) host_stdout: &'a mut HostStdout,
     /// Host stderr writer used for container stderr output.
     pub(super) host_stderr: &'a mut HostStderr,
+    /// Synthesized error responses for host requests refused inbound.
+    pub(super) host_replies: &'a mut mpsc::Receiver<Vec<u8>>,
+}
+
+/// The next thing the adapter-driven output loop must handle.
+enum OutputEvent {
+    /// A synthesized reply bound for host stdout.
+    Reply(Vec<u8>),
+    /// A chunk from the container output stream.
+    Chunk(Result<LogOutput, BollardError>),
+    /// The container output stream has ended.
+    End,
+}
+
+#[expect(
+    clippy::integer_division_remainder_used,
+    reason = "false positive triggered inside tokio::select! expansion"
+)]
+async fn next_output_event(
+    output: &mut Pin<Box<dyn Stream<Item = Result<LogOutput, BollardError>> + Send>>,
+    host_replies: &mut mpsc::Receiver<Vec<u8>>,
+) -> OutputEvent {
+    tokio::select! {
+        Some(reply) = host_replies.recv() => OutputEvent::Reply(reply),
+        maybe_chunk = output.next() => maybe_chunk.map_or(OutputEvent::End, OutputEvent::Chunk),
+    }
 }
 
 /// Drain the container output stream through the outbound policy adapter.
@@ -37,7 +66,15 @@ where
     HostStdout: AsyncWrite + Unpin,
     HostStderr: AsyncWrite + Unpin,
 {
-    while let Some(chunk_result) = output.next().await {
+    loop {
+        let chunk_result = match next_output_event(output, io.host_replies).await {
+            OutputEvent::Reply(bytes) => {
+                write_output_chunk(container_id, io.host_stdout, &bytes, "stdout").await?;
+                continue;
+            }
+            OutputEvent::Chunk(chunk_result) => chunk_result,
+            OutputEvent::End => return write_queued_replies(container_id, io).await,
+        };
         let chunk = chunk_result
             .map_err(|error| exec_failed(container_id, format!("exec stream failed: {error}")))?;
         match chunk {
@@ -59,6 +96,20 @@ where
             LogOutput::StdIn { .. } => {}
         }
     }
+}
+
+/// Write replies already queued when the container output stream ends, so a
+/// refused host request is not left unanswered.
+async fn write_queued_replies<HostStdout, HostStderr>(
+    container_id: &str,
+    io: &mut AdapterOutputIo<'_, HostStdout, HostStderr>,
+) -> Result<(), PodbotError>
+where
+    HostStdout: AsyncWrite + Unpin,
+{
+    while let Ok(reply) = io.host_replies.try_recv() {
+        write_output_chunk(container_id, io.host_stdout, &reply, "stdout").await?;
+    }
     Ok(())
 }
 
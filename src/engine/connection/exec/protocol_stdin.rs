@@ -15,6 +15,8 @@ use tokio::task::JoinHandle;
 use tokio::time::timeout;
 
 use super::super::acp_helpers;
+use super::super::acp_inbound::InboundPolicyAdapter;
+use super::super::acp_policy::AcpDelegation;
 use super::super::acp_runtime::WriteCmd;
 use super::super::host_io::stdin_forwarding_disabled_for_tests;
 use super::super::runtime_helpers::exec_failed;
@@ -30,8 +32,9 @@ use crate::error::PodbotError;
 const STDIN_SETTLE_TIMEOUT: Duration = Duration::from_millis(50);
 
 /// Reads the first newline-delimited ACP frame from `buffered_stdin`,
-/// applies capability masking, and forwards the resulting bytes to
-/// `sender` as a [`WriteCmd::Forward`].
+/// masks the capabilities not in `delegation`, and forwards the resulting bytes to
+/// `sender` as a [`WriteCmd::Forward`], recording them in the audit log of
+/// `inbound` when present.
 ///
 /// Returns `Ok(true)` when the caller should continue pumping host
 /// stdin (either because the masker produced no bytes to forward, or
@@ -40,14 +43,19 @@ const STDIN_SETTLE_TIMEOUT: Duration = Duration::from_millis(50);
 async fn send_masked_initialize_frame<R>(
     buffered_stdin: &mut tokio::io::BufReader<R>,
     sender: &tokio::sync::mpsc::Sender<WriteCmd>,
+    delegation: AcpDelegation,
+    inbound: Option<&InboundPolicyAdapter>,
 ) -> io::Result<bool>
 where
     R: AsyncRead + Unpin,
 {
-    let bytes = acp_helpers::read_and_mask_initial_acp_frame(buffered_stdin).await?;
+    let bytes = acp_helpers::read_and_mask_initial_acp_frame(buffered_stdin, delegation).await?;
     if bytes.is_empty() {
         return Ok(true);
     }
+    if let Some(adapter) = inbound {
+        adapter.audit_initialize(&bytes);
+    }
     Ok(sender.send(WriteCmd::Forward(bytes)).await.is_ok())
 }
 
@@ -81,24 +89,61 @@ where
     Ok(())
 }
 
-/// Copy host stdin into the container-stdin sink channel, optionally masking
-/// the first ACP `initialize` frame before the raw copy begins.
+/// Pumps the remainder of host stdin through the inbound rule adapter,
+/// which queues permitted frames on the sink and answers blocked requests
+/// on the host side. Stops on EOF or when the sink channel closes.
+async fn pump_policed_frames<R>(
+    buffered_stdin: &mut tokio::io::BufReader<R>,
+    sender: &tokio::sync::mpsc::Sender<WriteCmd>,
+    mut adapter: InboundPolicyAdapter,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut buf = vec![0u8; STDIN_BUFFER_CAPACITY];
+    loop {
+        let bytes_read = buffered_stdin.read(&mut buf).await?;
+        if bytes_read == 0 {
+            break;
+        }
+        let chunk = buf.get(..bytes_read).unwrap_or_default();
+        if !adapter.handle_chunk(chunk, sender).await {
+            return Ok(());
+        }
+    }
+    adapter.finish();
+    Ok(())
+}
+
+/// Copy host stdin into the container-stdin sink channel. When
+/// `initialize_mask` is set, the first ACP `initialize` frame is masked,
+/// keeping the delegated families, before the copy begins. When `inbound`
+/// is set, the remaining frames are checked against the operator's method
+/// rules, and audited, rather than copied raw.
 pub(super) async fn forward_host_stdin_to_channel<HostStdin>(
     host_stdin: HostStdin,
     sender: tokio::sync::mpsc::Sender<WriteCmd>,
-    rewrite_acp_initialize: bool,
+    initialize_mask: Option<AcpDelegation>,
+    inbound: Option<InboundPolicyAdapter>,
 ) -> io::Result<()>
 where
     HostStdin: AsyncRead + Unpin,
 {
     let mut buffered_stdin = tokio::io::BufReader::with_capacity(STDIN_BUFFER_CAPACITY, host_stdin);
 
-    if rewrite_acp_initialize && !send_masked_initialize_frame(&mut buffered_stdin, &sender).await?
+    if let Some(delegation) = initialize_mask
+        && !send_masked_initialize_frame(&mut buffered_stdin, &sender, delegation, inbound.as_ref())
+            .await?
     {
         return Ok(());
     }
 
-    pump_raw_frames(&mut buffered_stdin, &sender).await
+    match inbound {
+        Some(adapter) => pump_policed_frames(&mut buffered_stdin, &sender, adapter).await,
+        None => pump_raw_frames(&mut buffered_stdin, &sender).await,
+    }
 }
 
 /// Wait for the stdin forwarding task to complete within a short grace
@@ -106,7 +151,7 @@ where
 pub(super) async fn settle_stdin_forwarding_task(
     container_id: &str,
     mut stdin_task: JoinHandle<io::Result<()>>,
-    options: ProtocolSessionOptions,
+    options: &ProtocolSessionOptions,
 ) -> Result<(), PodbotError> {
     let Ok(join_result) = timeout(STDIN_SETTLE_TIMEOUT, &mut stdin_task).await else {
         // The container output path has already completed, so stdin can be
@@ -157,20 +202,22 @@ fn abort_stdin_forwarding_task(stdin_task: JoinHandle<io::Result<()>>) {
     }
 }
 
-/// Copy host stdin to the container exec input, optionally rewriting the
-/// first ACP `initialize` frame before the raw copy begins.
+/// Copy host stdin to the container exec input. When `initialize_mask` is
+/// set, the first ACP `initialize` frame is masked, keeping the delegated
+/// families, before the raw copy begins.
 pub(super) async fn forward_host_stdin_to_exec_async<HostStdin>(
     host_stdin: HostStdin,
     mut input: Pin<Box<dyn AsyncWrite + Send>>,
-    rewrite_acp_initialize: bool,
+    initialize_mask: Option<AcpDelegation>,
 ) -> io::Result<()>
 where
     HostStdin: AsyncRead + Unpin,
 {
     let mut buffered_stdin = tokio::io::BufReader::with_capacity(STDIN_BUFFER_CAPACITY, host_stdin);
 
-    if rewrite_acp_initialize {
-        acp_helpers::forward_initial_acp_frame_async(&mut buffered_stdin, &mut input).await?;
+    if let Some(delegation) = initialize_mask {
+        acp_helpers::forward_initial_acp_frame_async(&mut buffered_stdin, &mut input, delegation)
+            .await?;
     }
     tokio::io::copy(&mut buffered_stdin, &mut input).await?;
 
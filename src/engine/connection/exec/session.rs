@@ -1,5 +1,13 @@
 //! Internal exec-session options for protocol and test seams.
 
+use super::acp_audit::AcpAuditLog;
+use super::acp_fs::WorkspaceFs;
+use super::acp_paths::PathMap;
+use super::acp_permission::{PermissionPolicy, SessionEventSink};
+use super::acp_policy::AcpDelegation;
+use super::acp_rules::MethodRules;
+use super::acp_terminal::TerminalHost;
+use super::capture::OutputCapture;
 use super::protocol::ProtocolSessionOptions;
 
 /// Capability-enforcement policy for Agentic Control Protocol (ACP) hosting.
@@ -12,7 +20,7 @@ use super::protocol::ProtocolSessionOptions;
 /// runtime denylist on agent-emitted JSON-RPC frames, refusing
 /// `terminal/*` and `fs/*` requests with a synthesized JSON-RPC error
 /// response and recording each denial on stderr.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub(crate) struct ExecSessionOptions {
     /// When `true`, protocol-mode sessions replace host stdin with a held-open
     /// no-op reader so that the process's inherited stdin is not forwarded.
@@ -22,6 +30,31 @@ pub(crate) struct ExecSessionOptions {
     /// `fs` capabilities before forwarding it, and `MaskAndDeny` also denies
     /// later requests that try to use those capabilities.
     capability_policy: CapabilityPolicy,
+    /// ACP capability families the operator delegated to the host client
+    /// through `[agent.acp]`; both masking and denial skip them.
+    acp_delegation: AcpDelegation,
+    /// Operator-configured ACP method rules from `[agent.acp]`, enforced in
+    /// both directions alongside the denied families.
+    method_rules: MethodRules,
+    /// Audit log from `[agent.acp.audit]` that records each ACP frame.
+    acp_audit: Option<AcpAuditLog>,
+    /// `[[agent.acp.permissions]]` rules that answer the agent's
+    /// `session/request_permission` requests.
+    permission_policy: PermissionPolicy,
+    /// Channel that receives ACP session events, such as permission
+    /// decisions.
+    session_events: Option<SessionEventSink>,
+    /// Host-mounted workspace that serves `fs/*` requests when
+    /// `[agent.acp] fs = "serve"`.
+    workspace_fs: Option<WorkspaceFs>,
+    /// Sandbox container that runs `terminal/*` commands when
+    /// `[agent.acp] terminal = "serve"`.
+    terminal_host: Option<TerminalHost>,
+    /// Buffer that collects a capture-mode exec's output.
+    output_capture: Option<OutputCapture>,
+    /// Host-mounted workspace locations used to rewrite paths between the
+    /// host client and the agent.
+    path_map: Option<PathMap>,
 }
 
 impl ExecSessionOptions {
@@ -31,6 +64,15 @@ impl ExecSessionOptions {
         Self {
             disable_protocol_stdin_forwarding: false,
             capability_policy: CapabilityPolicy::Disabled,
+            acp_delegation: AcpDelegation::new(false, false),
+            method_rules: MethodRules::new(),
+            acp_audit: None,
+            permission_policy: PermissionPolicy::new(),
+            session_events: None,
+            workspace_fs: None,
+            terminal_host: None,
+            output_capture: None,
+            path_map: None,
         }
     }
 
@@ -44,6 +86,41 @@ impl ExecSessionOptions {
     }
 
     /// Select the [`CapabilityPolicy`] for this protocol-mode session.
+    #[must_use]
+    pub const fn with_capability_policy(mut self, policy: CapabilityPolicy) -> Self {
+        self.capability_policy = policy;
+        self
+    }
+
+    /// Select the ACP capability families delegated to the host client.
+    #[must_use]
+    pub const fn with_acp_delegation(mut self, delegation: AcpDelegation) -> Self {
+        self.acp_delegation = delegation;
+        self
+    }
+
+    /// Select the operator-configured ACP method rules.
+    #[must_use]
+    pub fn with_method_rules(mut self, rules: MethodRules) -> Self {
+        self.method_rules = rules;
+        self
+    }
+
+    /// Select the ACP audit log for this session.
+    #[must_use]
+    pub fn with_acp_audit(mut self, audit: Option<AcpAuditLog>) -> Self {
+        self.acp_audit = audit;
+        self
+    }
+
+    /// Select the rules that answer the agent's permission requests.
+    #[must_use]
+    pub fn with_permission_policy(mut self, policy: PermissionPolicy) -> Self {
+        self.permission_policy = policy;
+        self
+    }
+
+    /// Select the channel that receives ACP session events.
     #[cfg_attr(
         not(test),
         expect(
@@ -52,20 +129,59 @@ impl ExecSessionOptions {
         )
     )]
     #[must_use]
-    pub const fn with_capability_policy(mut self, policy: CapabilityPolicy) -> Self {
-        self.capability_policy = policy;
+    pub fn with_session_events(mut self, events: Option<SessionEventSink>) -> Self {
+        self.session_events = events;
+        self
+    }
+
+    /// Select the workspace that serves `fs/*` requests.
+    #[must_use]
+    pub fn with_workspace_fs(mut self, workspace: Option<WorkspaceFs>) -> Self {
+        self.workspace_fs = workspace;
+        self
+    }
+
+    /// Select the container that runs `terminal/*` commands.
+    #[must_use]
+    pub fn with_terminal_host(mut self, terminals: Option<TerminalHost>) -> Self {
+        self.terminal_host = terminals;
         self
     }
+
+    /// Select the workspace locations used to rewrite ACP paths.
+    #[must_use]
+    pub fn with_path_map(mut self, paths: Option<PathMap>) -> Self {
+        self.path_map = paths;
+        self
+    }
+
+    /// Collect a capture-mode exec's output in `capture`.
+    #[must_use]
+    pub fn with_output_capture(mut self, capture: Option<OutputCapture>) -> Self {
+        self.output_capture = capture;
+        self
+    }
+
+    /// Return the buffer that collects a capture-mode exec's output.
+    pub(super) const fn output_capture(&self) -> Option<&OutputCapture> {
+        self.output_capture.as_ref()
+    }
 }
 
 /// Convert exec-session options into the lower-level [`ProtocolSessionOptions`]
 /// consumed by the protocol proxy loop.
-pub(super) const fn protocol_session_options(
-    options: ExecSessionOptions,
-) -> ProtocolSessionOptions {
+pub(super) fn protocol_session_options(options: ExecSessionOptions) -> ProtocolSessionOptions {
     ProtocolSessionOptions::new()
         .with_stdin_forwarding_disabled(options.disable_protocol_stdin_forwarding)
         .with_capability_policy(options.capability_policy)
+        .with_acp_delegation(options.acp_delegation)
+        .with_method_rules(options.method_rules)
+        .with_acp_audit(options.acp_audit)
+        .with_permission_policy(options.permission_policy)
+        .with_session_events(options.session_events)
+        .with_workspace_fs(options.workspace_fs)
+        .with_terminal_host(options.terminal_host)
+        .with_path_map(options.path_map)
 }
 
 #[cfg(test)]
@@ -73,6 +189,7 @@ mod tests {
     //! Unit tests for exec session capability policies.
 
     use super::*;
+    use crate::config::{AcpPermissionAction, AcpPermissionRule};
 
     #[test]
     fn exec_session_options_default_enables_stdin_forwarding() {
@@ -96,7 +213,9 @@ mod tests {
     #[test]
     fn exec_session_options_builder_is_non_mutating() {
         let original = ExecSessionOptions::new();
-        let updated = original.with_protocol_stdin_forwarding_disabled(true);
+        let updated = original
+            .clone()
+            .with_protocol_stdin_forwarding_disabled(true);
         assert!(
             !original.disable_protocol_stdin_forwarding,
             "original should be unchanged",
@@ -145,6 +264,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn protocol_session_options_reflects_acp_delegation() {
+        let delegation = AcpDelegation::new(true, false);
+        let opts = ExecSessionOptions::new()
+            .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+            .with_acp_delegation(delegation);
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new()
+                .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+                .with_acp_delegation(delegation),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_method_rules() {
+        let rules = MethodRules::denying(&["_zed/*"]);
+        let opts = ExecSessionOptions::new()
+            .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+            .with_method_rules(rules.clone());
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new()
+                .with_capability_policy(CapabilityPolicy::MaskAndDeny)
+                .with_method_rules(rules),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_acp_audit() {
+        let audit = AcpAuditLog::new(std::io::sink(), mockable::DefaultClock, false)
+            .expect("audit writer should start");
+        let opts = ExecSessionOptions::new().with_acp_audit(Some(audit.clone()));
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new().with_acp_audit(Some(audit)),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_permission_policy_and_events() {
+        let policy = PermissionPolicy::from_rules(vec![AcpPermissionRule {
+            kind: Some(String::from("read")),
+            title: None,
+            action: AcpPermissionAction::AllowOnce,
+        }]);
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let events = SessionEventSink::new(sender);
+        let opts = ExecSessionOptions::new()
+            .with_permission_policy(policy.clone())
+            .with_session_events(Some(events.clone()));
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new()
+                .with_permission_policy(policy)
+                .with_session_events(Some(events)),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_workspace_fs() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = camino::Utf8Path::from_path(temp_dir.path()).expect("tempdir should be UTF-8");
+        let workspace = WorkspaceFs::open(root, camino::Utf8Path::new("/workspace"))
+            .expect("workspace should open");
+        let opts = ExecSessionOptions::new().with_workspace_fs(Some(workspace.clone()));
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new().with_workspace_fs(Some(workspace)),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_terminal_host() {
+        let terminals = TerminalHost::new(
+            super::super::acp_test_support::FakeExecClient::new(),
+            "sandbox",
+        );
+        let opts = ExecSessionOptions::new().with_terminal_host(Some(terminals.clone()));
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new().with_terminal_host(Some(terminals)),
+        );
+    }
+
+    #[test]
+    fn protocol_session_options_reflects_path_map() {
+        let paths = PathMap::new(
+            camino::Utf8Path::new("/home/me/project"),
+            camino::Utf8Path::new("/workspace"),
+        );
+        let opts = ExecSessionOptions::new().with_path_map(paths.clone());
+
+        assert_eq!(
+            protocol_session_options(opts),
+            ProtocolSessionOptions::new().with_path_map(paths),
+        );
+    }
+
     #[test]
     fn capability_policy_rewrites_initialize_for_masked_modes() {
         assert!(!CapabilityPolicy::Disabled.rewrites_initialize());
@@ -164,7 +388,7 @@ mod tests {
     not(test),
     expect(
         dead_code,
-        reason = "MaskOnly and MaskAndDeny remain unused until podbot host wires the opt-in"
+        reason = "MaskOnly remains unused outside tests; ACP sessions use MaskAndDeny"
     )
 )]
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -2,12 +2,20 @@
 //!
 //! This module provides the engine-facing connection and exec surface used by
 //! Podbot's stable API. Normal builds expose the exec path plus socket
-//! resolution; the git identity, health-check, create-container, and
-//! credential-upload utilities are available only when the `internal` feature
-//! or test builds are enabled.
-
+//! resolution; the git identity, health-check, create-container,
+//! credential-upload and credential sync-back utilities are available only when the `internal` feature
+//! or test builds are enabled. The archive transfer seams behind copy and
+//! export also compile under the `experimental` feature.
+
+mod agent_env;
+#[cfg(any(feature = "internal", feature = "experimental", test))]
+mod archive;
+#[cfg(any(feature = "internal", feature = "experimental", test))]
+mod copy;
 #[cfg(any(feature = "internal", test))]
 mod create_container;
+#[cfg(any(feature = "internal", feature = "experimental", test))]
+mod download;
 mod error_classification;
 mod exec;
 #[cfg(any(feature = "internal", test))]
@@ -17,7 +25,13 @@ mod health_check;
 #[cfg(any(feature = "internal", test))]
 mod repository_clone;
 #[cfg(any(feature = "internal", test))]
+mod sync_credentials;
+#[cfg(any(feature = "internal", feature = "experimental", test))]
+mod upload;
+#[cfg(any(feature = "internal", test))]
 mod upload_credentials;
+#[cfg(any(feature = "internal", feature = "experimental", test))]
+mod workspace_export;
 
 use std::fmt;
 
@@ -26,11 +40,25 @@ use error_classification::classify_connection_error;
 
 use crate::error::PodbotError;
 
+#[cfg(any(feature = "internal", test))]
+pub use agent_env::AgentEnv;
+#[cfg(not(any(feature = "internal", test)))]
+pub(crate) use agent_env::AgentEnv;
+#[cfg(any(feature = "internal", test))]
+pub use copy::{CopyRequest, CopyResult, HostCopyPath, copy_from_container, copy_to_container};
+#[cfg(all(feature = "experimental", not(any(feature = "internal", test))))]
+pub(crate) use copy::{CopyRequest, HostCopyPath, copy_from_container, copy_to_container};
 #[cfg(any(feature = "internal", test))]
 pub use create_container::{
     ContainerCreator, ContainerSecurityOptions, CreateContainerFuture, CreateContainerRequest,
     SelinuxLabelMode,
 };
+#[cfg(all(feature = "experimental", not(any(feature = "internal", test))))]
+pub(crate) use download::ContainerDownloader;
+#[cfg(any(feature = "internal", test))]
+pub use download::{ContainerDownloader, DownloadFromContainerStream};
+#[cfg(feature = "experimental")]
+pub(crate) use exec::{AcpSessionEvent, SessionEventSink, TokenRevocation};
 #[cfg(any(feature = "internal", test))]
 pub use exec::{
     ContainerExecClient, CreateExecFuture, ExecMode, ExecRequest, ExecResult, InspectExecFuture,
@@ -48,8 +76,23 @@ pub use repository_clone::{
     RepositoryCloneRequest, RepositoryCloneResult, clone_repository_into_workspace,
 };
 #[cfg(any(feature = "internal", test))]
+pub use sync_credentials::{
+    CredentialSyncAction, CredentialSyncRequest, CredentialSyncResult, SyncedCredentialFile,
+};
+#[cfg(any(feature = "internal", test))]
+pub use upload::{ContainerUploader, UploadToContainerFuture};
+#[cfg(any(feature = "internal", test))]
 pub use upload_credentials::{
-    ContainerUploader, CredentialUploadRequest, CredentialUploadResult, UploadToContainerFuture,
+    CredentialSkipReason, CredentialUploadRequest, CredentialUploadResult, SkippedCredentialEntry,
+};
+#[cfg(any(feature = "internal", test))]
+pub use workspace_export::{
+    MAX_EXPORT_BYTES, WorkspaceExportFormat, WorkspaceExportRequest, WorkspaceExportResult,
+    export_workspace,
+};
+#[cfg(all(feature = "experimental", not(any(feature = "internal", test))))]
+pub(crate) use workspace_export::{
+    WorkspaceExportFormat, WorkspaceExportRequest, export_workspace,
 };
 
 // =============================================================================
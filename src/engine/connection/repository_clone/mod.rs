@@ -19,6 +19,12 @@ pub struct RepositoryCloneRequest<'a> {
     pub workspace_base_dir: &'a WorkspacePath,
     /// Validated in-container path to the `GIT_ASKPASS` helper.
     pub askpass_path: &'a AskpassPath,
+    /// Web base URL to clone from, such as a `GitHub` Enterprise Server host.
+    ///
+    /// Callers building the request from configuration pass
+    /// [`GitHubConfig::web_base_url_or_default`](crate::config::GitHubConfig::web_base_url_or_default),
+    /// so a configured `github.web_base_url` is always honoured.
+    pub web_base_url: &'a str,
 }
 
 /// Successful repository clone result.
@@ -135,8 +141,9 @@ impl<'a, C: ContainerExecClient + Sync> GitCommandRunner<'a, C> {
 }
 
 fn github_remote(request: &RepositoryCloneRequest<'_>) -> String {
+    let base_url = request.web_base_url.trim_end_matches('/');
     format!(
-        "https://github.com/{}/{}.git",
+        "{base_url}/{}/{}.git",
         request.repository.owner(),
         request.repository.name()
     )
@@ -149,6 +156,7 @@ mod tests {
     use std::io;
 
     use super::*;
+    use crate::config::DEFAULT_GITHUB_WEB_BASE_URL;
     use crate::engine::{CreateExecFuture, InspectExecFuture, ResizeExecFuture, StartExecFuture};
     use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions};
     use mockall::{mock, predicate::eq};
@@ -202,6 +210,7 @@ mod tests {
             branch: branch_name,
             workspace_base_dir: workspace,
             askpass_path: askpass,
+            web_base_url: DEFAULT_GITHUB_WEB_BASE_URL,
         }
     }
 
@@ -270,6 +279,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn github_remote_uses_configured_web_base_url() {
+        let (repository, branch, workspace) =
+            typed_request_values("main").expect("test request values should parse");
+        let askpass = typed_askpass().expect("test askpass should parse");
+        let public = request(&repository, &branch, &workspace, &askpass);
+        let enterprise = RepositoryCloneRequest {
+            web_base_url: "https://github.example.com/",
+            ..request(&repository, &branch, &workspace, &askpass)
+        };
+
+        assert_eq!(
+            github_remote(&public),
+            "https://github.com/leynos/podbot.git"
+        );
+        assert_eq!(
+            github_remote(&enterprise),
+            "https://github.example.com/leynos/podbot.git"
+        );
+    }
+
     #[test]
     fn clones_repository_and_verifies_branch() {
         let (_rt, handle) = runtime().expect("test requires a Tokio runtime");
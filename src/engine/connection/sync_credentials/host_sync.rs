@@ -0,0 +1,223 @@
+//! Host-side comparison and atomic write-back of synced credential files.
+
+use std::io::{self, Read, Write};
+use std::time::UNIX_EPOCH;
+
+use camino::Utf8Path;
+use cap_std::fs::{Metadata, OpenOptions};
+use cap_std::fs_utf8::{Dir, File};
+use sha2::{Digest, Sha256};
+use tar::{Archive, Entry, EntryType};
+
+use super::{CredentialSyncAction, SyncedCredentialFile};
+use crate::engine::connection::archive::{ArchiveSource, host_entry_path};
+use crate::engine::connection::upload_credentials::SelectedSource;
+
+/// Suffix of the temporary file a write-back goes through before it is
+/// renamed over its target.
+const TEMP_FILE_SUFFIX: &str = ".podbot-sync";
+
+/// Mode given to a write-back's temporary file before it takes the host
+/// file's mode, so the credential is never readable by other users.
+const TEMP_FILE_MODE: u32 = 0o600;
+
+/// A regular file as archived from the container.
+///
+/// The container's file mode is deliberately not kept: write-backs always
+/// take the mode of the host file they replace.
+struct ContainerCopy {
+    contents: Vec<u8>,
+    modified_secs: u64,
+}
+
+impl ContainerCopy {
+    fn read(entry: &mut Entry<'_, &[u8]>) -> io::Result<Self> {
+        let modified_secs = entry.header().mtime()?;
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        Ok(Self {
+            contents,
+            modified_secs,
+        })
+    }
+}
+
+/// Compare each file `source` lists in its sync files with its host copy in
+/// `archive_bytes`, writing back container copies that are newer.
+///
+/// Only regular files that already exist on the host are synced: unlisted
+/// files, directories and symbolic links in the archive are ignored, and
+/// files absent from the host are never created.
+pub(super) fn sync_archive(
+    host_home_dir: &Dir,
+    source: &SelectedSource,
+    archive_bytes: &[u8],
+) -> io::Result<Vec<SyncedCredentialFile>> {
+    let host_dir = source.host_dir.resolve(host_home_dir);
+    let archive_name = Utf8Path::new(&source.container_path)
+        .file_name()
+        .ok_or_else(|| {
+            io::Error::other(format!(
+                "credential source '{}' does not name a directory",
+                source.source
+            ))
+        })?;
+    let root = ArchiveSource {
+        host_name: &source.host_name,
+        archive_name,
+    };
+    let mut archive = Archive::new(archive_bytes);
+    let mut files = Vec::new();
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        if !matches!(
+            entry.header().entry_type(),
+            EntryType::Regular | EntryType::Continuous
+        ) {
+            continue;
+        }
+        let host_path = host_entry_path(&entry, &root)?;
+        let relative_path = host_path.strip_prefix(&source.host_name).map_err(|_| {
+            io::Error::other(format!(
+                "synced entry '{host_path}' is outside credential source '{}'",
+                source.source
+            ))
+        })?;
+        if !source.filter.accepts_file(relative_path)
+            || !source.sync_files.iter().any(|file| file == relative_path)
+        {
+            continue;
+        }
+        let container_copy = ContainerCopy::read(&mut entry)?;
+        if let Some(action) = sync_file(host_dir, &host_path, &container_copy)? {
+            files.push(SyncedCredentialFile {
+                host_path: source.source.join(relative_path),
+                action,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+fn sync_file(
+    host_dir: &Dir,
+    path: &Utf8Path,
+    container_copy: &ContainerCopy,
+) -> io::Result<Option<CredentialSyncAction>> {
+    let Some(metadata) = host_file_metadata(host_dir, path)? else {
+        return Ok(None);
+    };
+    if Sha256::digest(host_dir.read(path)?) == Sha256::digest(&container_copy.contents) {
+        return Ok(Some(CredentialSyncAction::Unchanged));
+    }
+    if container_copy.modified_secs <= modified_secs(&metadata)? {
+        return Ok(Some(CredentialSyncAction::HostNewer));
+    }
+
+    write_atomically(
+        host_dir,
+        path,
+        &container_copy.contents,
+        host_mode(&metadata),
+    )?;
+    Ok(Some(CredentialSyncAction::WrittenBack))
+}
+
+/// Return the metadata of the regular host file at `path`, or `None` when it
+/// is absent or not a regular file and so must not be synced.
+fn host_file_metadata(host_dir: &Dir, path: &Utf8Path) -> io::Result<Option<Metadata>> {
+    match host_dir.symlink_metadata(path) {
+        Ok(metadata) if metadata.is_file() => Ok(Some(metadata)),
+        Ok(_) => {
+            warn_not_regular_file(path);
+            Ok(None)
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            log_absent_on_host(path);
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+fn warn_not_regular_file(path: &Utf8Path) {
+    tracing::warn!(%path, "host credential is not a regular file; not syncing it");
+}
+
+fn log_absent_on_host(path: &Utf8Path) {
+    tracing::debug!(%path, "credential is absent on the host; not creating it");
+}
+
+/// Replace `path` with `contents` by writing a sibling temporary file and
+/// renaming it into place, so readers never observe a partial credential.
+/// The file takes the host `mode` on Unix hosts.
+fn write_atomically(host_dir: &Dir, path: &Utf8Path, contents: &[u8], mode: u32) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("'{path}' does not name a file")))?;
+    let temp_path = path.with_file_name(format!(".{file_name}{TEMP_FILE_SUFFIX}"));
+
+    let written = write_temp_file(host_dir, &temp_path, contents, mode)
+        .and_then(|()| host_dir.rename(&temp_path, host_dir, path));
+    if written.is_err() && host_dir.remove_file(&temp_path).is_err() {
+        tracing::debug!(%temp_path, "failed to remove temporary credential file");
+    }
+    written
+}
+
+fn write_temp_file(
+    host_dir: &Dir,
+    temp_path: &Utf8Path,
+    contents: &[u8],
+    mode: u32,
+) -> io::Result<()> {
+    let mut file = host_dir.open_with(temp_path, &temp_file_options())?;
+    set_mode(&file, mode)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+fn modified_secs(metadata: &Metadata) -> io::Result<u64> {
+    let modified = metadata.modified()?.into_std();
+    Ok(modified
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs()))
+}
+
+#[cfg(unix)]
+fn host_mode(metadata: &Metadata) -> u32 {
+    use cap_std::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+const fn host_mode(_metadata: &Metadata) -> u32 {
+    TEMP_FILE_MODE
+}
+
+fn temp_file_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use cap_std::fs::OpenOptionsExt;
+
+        options.mode(TEMP_FILE_MODE);
+    }
+    options
+}
+
+#[cfg(unix)]
+fn set_mode(file: &File, mode: u32) -> io::Result<()> {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    file.set_permissions(Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_file: &File, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
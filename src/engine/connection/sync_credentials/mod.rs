@@ -0,0 +1,262 @@
+//! Credential sync-back from container filesystems to the host.
+//!
+//! Agents rotate OAuth refresh tokens inside their credential directories, so
+//! the host copy uploaded at startup goes stale once a session refreshes it.
+//! At teardown this module downloads each credential source that is present
+//! on the host back out of the container, compares each credential file the
+//! family lists in its sync files by content hash and modification time, and
+//! writes newer container copies back through the host directory capability.
+//! Write-backs keep the host file's mode, and files that do not already exist
+//! on the host are never created.
+
+use std::io;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use cap_std::ambient_authority;
+use cap_std::fs_utf8::Dir;
+
+mod host_sync;
+
+use super::EngineConnector;
+use super::download::{ArchiveDownload, ContainerDownloader, collect_archive_async};
+use super::upload_credentials::{
+    LocalUploadError, map_local_upload_error, select_credential_sources, select_error_path,
+};
+use crate::config::{AppConfig, CredentialFamily, DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES};
+use crate::error::PodbotError;
+use host_sync::sync_archive;
+
+/// Parameters required to sync refreshed credentials back to the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialSyncRequest {
+    container_id: String,
+    host_home_dir: Utf8PathBuf,
+    families: Vec<CredentialFamily>,
+    max_download_bytes: u64,
+}
+
+impl CredentialSyncRequest {
+    /// Create a sync request for the given families within the default
+    /// per-source download cap.
+    #[must_use]
+    pub fn new(
+        container_id: impl Into<String>,
+        host_home_dir: impl Into<Utf8PathBuf>,
+        families: Vec<CredentialFamily>,
+    ) -> Self {
+        Self {
+            container_id: container_id.into(),
+            host_home_dir: host_home_dir.into(),
+            families,
+            max_download_bytes: DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES,
+        }
+    }
+
+    /// Return this request with each source's download capped at
+    /// `max_download_bytes`.
+    #[must_use]
+    pub const fn with_max_download_bytes(mut self, max_download_bytes: u64) -> Self {
+        self.max_download_bytes = max_download_bytes;
+        self
+    }
+
+    /// Build a request from resolved application configuration.
+    ///
+    /// Returns `None` unless `creds.sync_back` is enabled. The request covers
+    /// the same families, globs and byte cap as the upload.
+    #[must_use]
+    pub fn from_app_config(
+        container_id: impl Into<String>,
+        host_home_dir: impl Into<Utf8PathBuf>,
+        config: &AppConfig,
+    ) -> Option<Self> {
+        config.creds.sync_back.then(|| {
+            let families = config.creds.families_for_agent(config.agent.kind);
+            Self::new(container_id, host_home_dir, families)
+                .with_max_download_bytes(config.creds.max_upload_bytes)
+        })
+    }
+
+    /// Return the source container identifier.
+    #[must_use]
+    pub fn container_id(&self) -> &str {
+        &self.container_id
+    }
+
+    /// Return the credential families to sync, in order.
+    #[must_use]
+    pub fn families(&self) -> &[CredentialFamily] {
+        &self.families
+    }
+
+    /// Return the largest archive, in bytes, downloaded for one source.
+    #[must_use]
+    pub const fn max_download_bytes(&self) -> u64 {
+        self.max_download_bytes
+    }
+
+    /// Open the configured host home directory as a capability-oriented handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` when the host home directory cannot be opened.
+    pub fn open_host_home_dir(&self) -> io::Result<Dir> {
+        Dir::open_ambient_dir(&self.host_home_dir, ambient_authority()).map_err(|error| {
+            io::Error::other(format!(
+                "failed to open host home directory '{}': {error}",
+                self.host_home_dir
+            ))
+        })
+    }
+}
+
+/// What sync-back did with one credential file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialSyncAction {
+    /// The container copy was newer, or missing on the host, and replaced the
+    /// host file.
+    WrittenBack,
+    /// The container and host copies have the same content.
+    Unchanged,
+    /// The copies differ but the host file is at least as new, so it was kept.
+    HostNewer,
+}
+
+/// A credential file compared during sync-back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncedCredentialFile {
+    host_path: Utf8PathBuf,
+    action: CredentialSyncAction,
+}
+
+impl SyncedCredentialFile {
+    /// Return the host path of the file, beneath its configured source.
+    ///
+    /// Paths under home-relative sources are relative to the home directory.
+    #[must_use]
+    pub fn host_path(&self) -> &Utf8Path {
+        &self.host_path
+    }
+
+    /// Return what sync-back did with the file.
+    #[must_use]
+    pub const fn action(&self) -> CredentialSyncAction {
+        self.action
+    }
+}
+
+/// Result of a credential sync-back operation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialSyncResult {
+    files: Vec<SyncedCredentialFile>,
+}
+
+impl CredentialSyncResult {
+    /// Return every compared file in family and archive order.
+    #[must_use]
+    pub fn files(&self) -> &[SyncedCredentialFile] {
+        &self.files
+    }
+
+    /// Return the files whose container copy replaced the host copy.
+    pub fn written_back(&self) -> impl Iterator<Item = &SyncedCredentialFile> {
+        self.files
+            .iter()
+            .filter(|file| file.action == CredentialSyncAction::WrittenBack)
+    }
+}
+
+impl EngineConnector {
+    /// Sync refreshed credentials from a container back to the host (async
+    /// version).
+    ///
+    /// Only sources present on the host are synced, since only those were
+    /// uploaded. Each is downloaded from its container path; regular files
+    /// the family's globs select are compared with their host copies. A file
+    /// is written back when it is missing on the host, or when its content
+    /// differs and its container modification time is newer. Writes go to a
+    /// temporary file beside the target that is then renamed over it, and
+    /// keep the host file's permissions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::DownloadFailed` when a download fails or
+    /// exceeds the request's cap, and `FilesystemError::IoError` when the
+    /// host side cannot be read or written.
+    pub async fn sync_credentials_back_async<D: ContainerDownloader>(
+        downloader: &D,
+        request: &CredentialSyncRequest,
+    ) -> Result<CredentialSyncResult, PodbotError> {
+        let host_home_dir = request.open_host_home_dir().map_err(|error| {
+            map_local_upload_error(LocalUploadError {
+                path: request.host_home_dir.clone(),
+                error,
+            })
+        })?;
+
+        Self::sync_credentials_back_with_host_home_dir_async(downloader, request, &host_home_dir)
+            .await
+    }
+
+    /// Sync refreshed credentials from a container back to the host (async
+    /// version), using a pre-opened host home directory capability.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::DownloadFailed` when a download fails or
+    /// exceeds the request's cap, and `FilesystemError::IoError` when the
+    /// host side cannot be read or written.
+    pub async fn sync_credentials_back_with_host_home_dir_async<D: ContainerDownloader>(
+        downloader: &D,
+        request: &CredentialSyncRequest,
+        host_home_dir: &Dir,
+    ) -> Result<CredentialSyncResult, PodbotError> {
+        let local_error = |error: io::Error| {
+            map_local_upload_error(LocalUploadError {
+                path: select_error_path(&error, &request.host_home_dir, &request.families),
+                error,
+            })
+        };
+        let sources =
+            select_credential_sources(host_home_dir, request.families()).map_err(local_error)?;
+        let mut files = Vec::new();
+
+        for source in &sources {
+            let archive_bytes = collect_archive_async(
+                downloader,
+                ArchiveDownload {
+                    container_id: request.container_id(),
+                    container_path: &source.container_path,
+                    max_bytes: request.max_download_bytes(),
+                },
+            )
+            .await?;
+            let synced =
+                sync_archive(host_home_dir, source, &archive_bytes).map_err(local_error)?;
+            files.extend(synced);
+        }
+
+        Ok(CredentialSyncResult { files })
+    }
+
+    /// Sync refreshed credentials from a container back to the host.
+    ///
+    /// This synchronous helper blocks on [`Self::sync_credentials_back_async`]
+    /// via a caller-provided `Tokio` runtime handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::DownloadFailed` when a download fails or
+    /// exceeds the request's cap, and `FilesystemError::IoError` when the
+    /// host side cannot be read or written.
+    pub fn sync_credentials_back<D: ContainerDownloader>(
+        runtime: &tokio::runtime::Handle,
+        downloader: &D,
+        request: &CredentialSyncRequest,
+    ) -> Result<CredentialSyncResult, PodbotError> {
+        runtime.block_on(Self::sync_credentials_back_async(downloader, request))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,319 @@
+//! Unit tests for credential sync-back.
+
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bollard::query_parameters::DownloadFromContainerOptions;
+use mockall::mock;
+use rstest::{fixture, rstest};
+use tar::{Builder, EntryType, Header};
+use tempfile::TempDir;
+
+use super::*;
+use crate::config::CredsConfig;
+use crate::engine::connection::download::DownloadFromContainerStream;
+
+mock! {
+    Downloader {}
+
+    impl ContainerDownloader for Downloader {
+        fn download_from_container(
+            &self,
+            container_id: &str,
+            options: Option<DownloadFromContainerOptions>,
+        ) -> DownloadFromContainerStream<'_>;
+    }
+}
+
+struct HostHome {
+    _temp_dir: TempDir,
+    dir: Dir,
+    path: Utf8PathBuf,
+}
+
+#[fixture]
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Runtime::new().expect("test requires a Tokio runtime")
+}
+
+#[fixture]
+fn host_home() -> HostHome {
+    let temp_dir = tempfile::tempdir().expect("tempdir creation should succeed");
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("tempdir path should be valid UTF-8");
+    let dir = Dir::open_ambient_dir(&path, ambient_authority()).expect("tempdir should open");
+    HostHome {
+        _temp_dir: temp_dir,
+        dir,
+        path,
+    }
+}
+
+/// Container modification time an hour after now, newer than any host file.
+fn later() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+        .saturating_add(3600)
+}
+
+fn container_archive(files: &[(&str, &str, u64)]) -> Vec<u8> {
+    let mut builder = Builder::new(Vec::new());
+    let mut directory = Header::new_gnu();
+    directory.set_entry_type(EntryType::Directory);
+    directory.set_size(0);
+    directory.set_mode(0o700);
+    builder
+        .append_data(&mut directory, ".claude/", io::empty())
+        .expect("directory entry should append");
+    for (path, contents, mtime) in files {
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(u64::try_from(contents.len()).unwrap_or(u64::MAX));
+        header.set_mode(0o644);
+        header.set_mtime(*mtime);
+        builder
+            .append_data(&mut header, format!(".claude/{path}"), contents.as_bytes())
+            .expect("file entry should append");
+    }
+    builder.into_inner().expect("archive should finish")
+}
+
+fn downloader_yielding(archive: Vec<u8>) -> MockDownloader {
+    let mut downloader = MockDownloader::new();
+    downloader
+        .expect_download_from_container()
+        .withf(|container_id, options| {
+            container_id == "sandbox"
+                && options
+                    .as_ref()
+                    .is_some_and(|requested| requested.path == "/root/.claude")
+        })
+        .times(1)
+        .return_once(move |_, _| Box::pin(futures_util::stream::iter(vec![Ok(archive)])));
+    downloader
+}
+
+fn claude() -> CredentialFamily {
+    CredentialFamily::builtin("claude").expect("claude should be built in")
+}
+
+fn sync(
+    runtime: &tokio::runtime::Runtime,
+    downloader: &MockDownloader,
+    home: &HostHome,
+    families: Vec<CredentialFamily>,
+) -> CredentialSyncResult {
+    let request = CredentialSyncRequest::new("sandbox", home.path.clone(), families);
+    EngineConnector::sync_credentials_back(runtime.handle(), downloader, &request)
+        .expect("sync should succeed")
+}
+
+fn write_host_file(home: &HostHome, path: &str, contents: &str) {
+    home.dir
+        .create_dir_all(".claude")
+        .expect("host credential directory should be created");
+    home.dir
+        .write(path, contents)
+        .expect("host credential file should be written");
+}
+
+fn read_host_file(home: &HostHome, path: &str) -> String {
+    home.dir
+        .read_to_string(path)
+        .expect("host credential file should be readable")
+}
+
+fn actions(result: &CredentialSyncResult) -> Vec<(&str, CredentialSyncAction)> {
+    result
+        .files()
+        .iter()
+        .map(|file| (file.host_path().as_str(), file.action()))
+        .collect()
+}
+
+#[rstest]
+fn newer_container_copies_replace_host_files(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+) {
+    write_host_file(&host_home, ".claude/.credentials.json", "stale");
+    write_host_file(&host_home, ".claude/settings.json", "host settings");
+    let downloader = downloader_yielding(container_archive(&[
+        (".credentials.json", "rotated", later()),
+        ("settings.json", "container settings", later()),
+    ]));
+
+    let result = sync(&runtime, &downloader, &host_home, vec![claude()]);
+
+    assert_eq!(
+        actions(&result),
+        [(
+            ".claude/.credentials.json",
+            CredentialSyncAction::WrittenBack
+        )]
+    );
+    assert_eq!(
+        read_host_file(&host_home, ".claude/.credentials.json"),
+        "rotated"
+    );
+    assert_eq!(
+        read_host_file(&host_home, ".claude/settings.json"),
+        "host settings"
+    );
+    assert!(
+        !host_home
+            .dir
+            .exists(".claude/..credentials.json.podbot-sync")
+    );
+}
+
+#[rstest]
+#[case::same_contents(("stale", later()), CredentialSyncAction::Unchanged)]
+#[case::host_newer(("older", 0), CredentialSyncAction::HostNewer)]
+fn container_copies_that_are_not_newer_are_left_alone(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+    #[case] container_copy: (&str, u64),
+    #[case] expected: CredentialSyncAction,
+) {
+    write_host_file(&host_home, ".claude/.credentials.json", "stale");
+    let (contents, mtime) = container_copy;
+    let downloader =
+        downloader_yielding(container_archive(&[(".credentials.json", contents, mtime)]));
+
+    let result = sync(&runtime, &downloader, &host_home, vec![claude()]);
+
+    assert_eq!(actions(&result), [(".claude/.credentials.json", expected)]);
+    assert_eq!(
+        read_host_file(&host_home, ".claude/.credentials.json"),
+        "stale"
+    );
+}
+
+#[rstest]
+fn credential_files_absent_on_the_host_are_not_created(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+) {
+    write_host_file(&host_home, ".claude/settings.json", "{}");
+    let downloader = downloader_yielding(container_archive(&[(
+        ".credentials.json",
+        "planted",
+        later(),
+    )]));
+
+    let result = sync(&runtime, &downloader, &host_home, vec![claude()]);
+
+    assert!(result.files().is_empty());
+    assert!(!host_home.dir.exists(".claude/.credentials.json"));
+}
+
+#[cfg(unix)]
+#[rstest]
+fn written_back_files_keep_host_permissions(runtime: tokio::runtime::Runtime, host_home: HostHome) {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    write_host_file(&host_home, ".claude/.credentials.json", "stale");
+    host_home
+        .dir
+        .set_permissions(".claude/.credentials.json", Permissions::from_mode(0o640))
+        .expect("host permissions should be set");
+    let downloader = downloader_yielding(container_archive(&[(
+        ".credentials.json",
+        "rotated",
+        later(),
+    )]));
+
+    sync(&runtime, &downloader, &host_home, vec![claude()]);
+
+    let mode = host_home
+        .dir
+        .metadata(".claude/.credentials.json")
+        .expect("synced file should exist")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o7777, 0o640);
+}
+
+#[rstest]
+fn files_outside_the_family_globs_are_not_synced(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+) {
+    write_host_file(&host_home, ".claude/.credentials.json", "stale");
+    let downloader = downloader_yielding(container_archive(&[(
+        ".credentials.json",
+        "rotated",
+        later(),
+    )]));
+    let filtered = claude().with_filters(Vec::new(), vec![String::from("*.json")]);
+
+    let result = sync(&runtime, &downloader, &host_home, vec![filtered]);
+
+    assert!(result.files().is_empty());
+    assert_eq!(
+        read_host_file(&host_home, ".claude/.credentials.json"),
+        "stale"
+    );
+}
+
+#[rstest]
+fn custom_sync_files_replace_the_family_defaults(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+) {
+    write_host_file(&host_home, ".claude/.credentials.json", "stale");
+    write_host_file(&host_home, ".claude/settings.json", "host settings");
+    let downloader = downloader_yielding(container_archive(&[
+        (".credentials.json", "rotated", later()),
+        ("settings.json", "container settings", later()),
+    ]));
+    let family = claude().with_sync_files(vec![Utf8PathBuf::from("settings.json")]);
+
+    let result = sync(&runtime, &downloader, &host_home, vec![family]);
+
+    assert_eq!(
+        actions(&result),
+        [(".claude/settings.json", CredentialSyncAction::WrittenBack)]
+    );
+    assert_eq!(
+        read_host_file(&host_home, ".claude/.credentials.json"),
+        "stale"
+    );
+}
+
+#[rstest]
+fn sources_missing_on_the_host_are_not_downloaded(
+    runtime: tokio::runtime::Runtime,
+    host_home: HostHome,
+) {
+    let mut downloader = MockDownloader::new();
+    downloader.expect_download_from_container().never();
+
+    let result = sync(&runtime, &downloader, &host_home, vec![claude()]);
+
+    assert!(result.files().is_empty());
+}
+
+#[rstest]
+#[case::disabled(false)]
+#[case::enabled(true)]
+fn app_config_requests_follow_sync_back(#[case] sync_back: bool) {
+    let config = AppConfig {
+        creds: CredsConfig {
+            sync_back,
+            max_upload_bytes: 1024,
+            ..CredsConfig::default()
+        },
+        ..AppConfig::default()
+    };
+
+    let request = CredentialSyncRequest::from_app_config("sandbox", "/home/user", &config);
+
+    assert_eq!(request.is_some(), sync_back);
+    assert!(request.is_none_or(|built| {
+        built.max_download_bytes() == 1024
+            && built.families() == config.creds.families_for_agent(config.agent.kind)
+    }));
+}
@@ -0,0 +1,49 @@
+//! Archive upload into container filesystems.
+//!
+//! This module wraps Bollard's `upload_to_container` behind a small trait seam
+//! shared by credential injection and file copy.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use bollard::query_parameters::UploadToContainerOptions;
+use bollard::{Docker, body_full};
+
+/// Boxed future type returned by [`ContainerUploader`] implementors.
+pub type UploadToContainerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(), bollard::errors::Error>> + Send + 'a>>;
+
+/// Behaviour required to upload an archive payload into a container.
+///
+/// This abstraction keeps credential-upload and copy logic testable without a
+/// live daemon.
+pub trait ContainerUploader {
+    /// Upload a tar archive payload into `container_id`.
+    fn upload_to_container(
+        &self,
+        container_id: &str,
+        options: Option<UploadToContainerOptions>,
+        archive_bytes: Vec<u8>,
+    ) -> UploadToContainerFuture<'_>;
+}
+
+impl ContainerUploader for Docker {
+    fn upload_to_container(
+        &self,
+        container_id: &str,
+        options: Option<UploadToContainerOptions>,
+        archive_bytes: Vec<u8>,
+    ) -> UploadToContainerFuture<'_> {
+        let container_id_owned = String::from(container_id);
+
+        Box::pin(async move {
+            Self::upload_to_container(
+                self,
+                &container_id_owned,
+                options,
+                body_full(archive_bytes.into()),
+            )
+            .await
+        })
+    }
+}
@@ -4,7 +4,7 @@ use std::io;
 
 use camino::{Utf8Path, Utf8PathBuf};
 
-use super::{CLAUDE_CREDENTIAL_DIR, CODEX_CREDENTIAL_DIR};
+use crate::config::CredentialFamily;
 use crate::error::{FilesystemError, PodbotError};
 
 #[derive(Debug)]
@@ -21,14 +21,20 @@ pub(crate) fn map_local_upload_error(local_error: LocalUploadError) -> PodbotErr
     })
 }
 
-pub(crate) fn select_error_path(error: &impl ToString, host_home_dir: &Utf8Path) -> Utf8PathBuf {
+/// Attribute a plan error to the family source its message names, falling back
+/// to the host home directory.
+pub(crate) fn select_error_path(
+    error: &impl ToString,
+    host_home_dir: &Utf8Path,
+    families: &[CredentialFamily],
+) -> Utf8PathBuf {
     let error_message = error.to_string();
-    if error_message.contains(CLAUDE_CREDENTIAL_DIR) {
-        return host_home_dir.join(CLAUDE_CREDENTIAL_DIR);
-    }
-    if error_message.contains(CODEX_CREDENTIAL_DIR) {
-        return host_home_dir.join(CODEX_CREDENTIAL_DIR);
-    }
-
-    host_home_dir.to_path_buf()
+    families
+        .iter()
+        .flat_map(CredentialFamily::sources)
+        .find(|source| error_message.contains(source.as_str()))
+        .map_or_else(
+            || host_home_dir.to_path_buf(),
+            |source| host_home_dir.join(source),
+        )
 }
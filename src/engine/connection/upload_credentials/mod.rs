@@ -1,101 +1,90 @@
 //! Credential upload to container filesystems using tar archives.
 //!
-//! This module builds tar payloads from host credential directories and uploads
-//! them to a running container via `Bollard`.
+//! This module selects host credential directories from the enabled credential
+//! families, packs the entries their globs select with the shared archive
+//! helpers within a byte budget, and uploads them to a running container via
+//! `Bollard`.
 
-use std::future::Future;
 use std::io;
-use std::pin::Pin;
 
 use bollard::query_parameters::{UploadToContainerOptions, UploadToContainerOptionsBuilder};
-use bollard::{Docker, body_full};
 use camino::Utf8PathBuf;
 use cap_std::ambient_authority;
 use cap_std::fs_utf8::Dir;
 
-mod archive;
 mod error_mapping;
 mod plan_builder;
 
 use super::EngineConnector;
-use crate::config::AppConfig;
-use crate::error::{ContainerError, PodbotError};
 #[cfg(test)]
-use archive::normalize_archive_path;
+use super::archive::normalize_archive_path;
+use super::upload::ContainerUploader;
+#[cfg(test)]
+use super::upload::UploadToContainerFuture;
+use crate::config::{
+    AppConfig, CREDENTIAL_TARGET_ROOT, CredentialFamily, CredsConfig,
+    DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES,
+};
+use crate::error::{ContainerError, PodbotError};
 pub(crate) use error_mapping::{LocalUploadError, map_local_upload_error, select_error_path};
-pub(crate) use plan_builder::build_upload_plan;
-
-const CONTAINER_HOME_DIR: &str = "/root";
-const CLAUDE_CREDENTIAL_DIR: &str = ".claude";
-const CODEX_CREDENTIAL_DIR: &str = ".codex";
-
-/// Boxed future type returned by [`ContainerUploader`] implementors.
-pub type UploadToContainerFuture<'a> =
-    Pin<Box<dyn Future<Output = Result<(), bollard::errors::Error>> + Send + 'a>>;
-
-/// Behaviour required to upload an archive payload into a container.
-///
-/// This abstraction keeps credential-upload logic testable without a live
-/// daemon.
-pub trait ContainerUploader {
-    /// Upload a tar archive payload into `container_id`.
-    fn upload_to_container(
-        &self,
-        container_id: &str,
-        options: Option<UploadToContainerOptions>,
-        archive_bytes: Vec<u8>,
-    ) -> UploadToContainerFuture<'_>;
-}
+pub(crate) use plan_builder::{SelectedSource, build_upload_plan, select_credential_sources};
 
-impl ContainerUploader for Docker {
-    fn upload_to_container(
-        &self,
-        container_id: &str,
-        options: Option<UploadToContainerOptions>,
-        archive_bytes: Vec<u8>,
-    ) -> UploadToContainerFuture<'_> {
-        let container_id_owned = String::from(container_id);
-
-        Box::pin(async move {
-            Self::upload_to_container(
-                self,
-                &container_id_owned,
-                options,
-                body_full(archive_bytes.into()),
-            )
-            .await
-        })
-    }
-}
+const CONTAINER_HOME_DIR: &str = CREDENTIAL_TARGET_ROOT;
 
 /// Parameters required to upload host credentials into a container.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CredentialUploadRequest {
     container_id: String,
     host_home_dir: Utf8PathBuf,
-    copy_claude: bool,
-    copy_codex: bool,
+    families: Vec<CredentialFamily>,
+    max_upload_bytes: u64,
 }
 
 impl CredentialUploadRequest {
-    /// Create a new credential-upload request.
+    /// Create a new credential-upload request for the Claude and Codex
+    /// families.
     #[must_use]
     pub fn new(
         container_id: impl Into<String>,
         host_home_dir: impl Into<Utf8PathBuf>,
         copy_claude: bool,
         copy_codex: bool,
+    ) -> Self {
+        let creds = CredsConfig {
+            copy_claude,
+            copy_codex,
+            ..CredsConfig::default()
+        };
+        Self::with_families(container_id, host_home_dir, creds.enabled_families())
+    }
+
+    /// Create a credential-upload request for the given families, uploaded in
+    /// order within the default byte budget.
+    #[must_use]
+    pub fn with_families(
+        container_id: impl Into<String>,
+        host_home_dir: impl Into<Utf8PathBuf>,
+        families: Vec<CredentialFamily>,
     ) -> Self {
         Self {
             container_id: container_id.into(),
             host_home_dir: host_home_dir.into(),
-            copy_claude,
-            copy_codex,
+            families,
+            max_upload_bytes: DEFAULT_CREDENTIAL_UPLOAD_MAX_BYTES,
         }
     }
 
+    /// Return this request with the file payload capped at `max_upload_bytes`.
+    #[must_use]
+    pub const fn with_max_upload_bytes(mut self, max_upload_bytes: u64) -> Self {
+        self.max_upload_bytes = max_upload_bytes;
+        self
+    }
+
     /// Build a request from resolved application configuration.
     ///
+    /// Only the enabled families that serve `agent.kind` are uploaded.
+    ///
     /// # Examples
     ///
     /// ```ignore
@@ -118,12 +107,9 @@ impl CredentialUploadRequest {
         host_home_dir: impl Into<Utf8PathBuf>,
         config: &AppConfig,
     ) -> Self {
-        Self::new(
-            container_id,
-            host_home_dir,
-            config.creds.copy_claude,
-            config.creds.copy_codex,
-        )
+        let families = config.creds.families_for_agent(config.agent.kind);
+        Self::with_families(container_id, host_home_dir, families)
+            .with_max_upload_bytes(config.creds.max_upload_bytes)
     }
 
     /// Return the target container identifier.
@@ -132,6 +118,18 @@ impl CredentialUploadRequest {
         &self.container_id
     }
 
+    /// Return the credential families to upload, in order.
+    #[must_use]
+    pub fn families(&self) -> &[CredentialFamily] {
+        &self.families
+    }
+
+    /// Return the most file bytes the upload may carry.
+    #[must_use]
+    pub const fn max_upload_bytes(&self) -> u64 {
+        self.max_upload_bytes
+    }
+
     /// Open the configured host home directory as a capability-oriented handle.
     ///
     /// This handle can be reused across multiple upload calls to avoid repeated
@@ -150,36 +148,78 @@ impl CredentialUploadRequest {
     }
 }
 
+/// Why a host credential entry was left out of an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialSkipReason {
+    /// The family's include or exclude globs did not select the entry.
+    Filtered,
+    /// The file did not fit within the upload's byte budget.
+    SizeLimit,
+}
+
+/// A host credential entry left out of an upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedCredentialEntry {
+    container_path: String,
+    reason: CredentialSkipReason,
+}
+
+impl SkippedCredentialEntry {
+    /// Return the absolute container path the entry would have had.
+    ///
+    /// A skipped directory is reported once, without its contents.
+    #[must_use]
+    pub fn container_path(&self) -> &str {
+        &self.container_path
+    }
+
+    /// Return why the entry was skipped.
+    #[must_use]
+    pub const fn reason(&self) -> CredentialSkipReason {
+        self.reason
+    }
+}
+
 /// Result of a credential upload operation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CredentialUploadResult {
     expected_container_paths: Vec<String>,
+    skipped_entries: Vec<SkippedCredentialEntry>,
 }
 
 impl CredentialUploadResult {
     /// Return expected absolute credential paths inside the container.
     ///
-    /// These paths are reported in deterministic order (`.claude`, then
-    /// `.codex`) and include only credential families that were both enabled and
-    /// present on the host.
+    /// These paths are reported in family order (built-in families such as
+    /// `.claude` and `.codex` first, then custom families) and include only
+    /// sources that were both enabled and present on the host.
     #[must_use]
     pub fn expected_container_paths(&self) -> &[String] {
         &self.expected_container_paths
     }
+
+    /// Return the host entries left out of the upload, in archive walk order.
+    #[must_use]
+    pub fn skipped_entries(&self) -> &[SkippedCredentialEntry] {
+        &self.skipped_entries
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CredentialUploadPlan {
     archive_bytes: Vec<u8>,
     expected_container_paths: Vec<String>,
+    skipped_entries: Vec<SkippedCredentialEntry>,
 }
 
 impl EngineConnector {
     /// Upload selected host credentials into a container (async version).
     ///
     /// Missing source directories are skipped. This keeps behaviour predictable
-    /// for hosts that use only one agent while leaving both credential toggles
-    /// enabled by default.
+    /// for hosts that use only some agents while leaving several credential
+    /// families enabled. Entries the families' globs reject, and files that
+    /// would exceed the request's byte budget, are left out and reported in
+    /// [`CredentialUploadResult::skipped_entries`].
     ///
     /// # Errors
     ///
@@ -216,7 +256,7 @@ impl EngineConnector {
         let container_id = request.container_id().to_owned();
         let plan = build_upload_plan(host_home_dir, request).map_err(|error| {
             map_local_upload_error(LocalUploadError {
-                path: select_error_path(&error, &request.host_home_dir),
+                path: select_error_path(&error, &request.host_home_dir, &request.families),
                 error,
             })
         })?;
@@ -224,11 +264,13 @@ impl EngineConnector {
         let CredentialUploadPlan {
             archive_bytes,
             expected_container_paths,
+            skipped_entries,
         } = plan;
 
         if expected_container_paths.is_empty() {
             return Ok(CredentialUploadResult {
                 expected_container_paths,
+                skipped_entries,
             });
         }
 
@@ -244,6 +286,7 @@ impl EngineConnector {
 
         Ok(CredentialUploadResult {
             expected_container_paths,
+            skipped_entries,
         })
     }
 
@@ -2,89 +2,192 @@
 
 use std::io;
 
+use camino::{Utf8Path, Utf8PathBuf};
+use cap_std::ambient_authority;
 use cap_std::fs_utf8::Dir;
 
-use super::archive::build_tar_archive;
 use super::{
-    CLAUDE_CREDENTIAL_DIR, CODEX_CREDENTIAL_DIR, CONTAINER_HOME_DIR, CredentialUploadPlan,
-    CredentialUploadRequest,
+    CONTAINER_HOME_DIR, CredentialSkipReason, CredentialUploadPlan, CredentialUploadRequest,
+    SkippedCredentialEntry,
 };
+use crate::config::CredentialFamily;
+use crate::engine::connection::archive::{
+    ArchiveSource, EntryFilter, FilteredArchiveSource, SkipReason, build_budgeted_tar_archive,
+};
+
+/// A present credential source and where it lands in the container.
+#[derive(Debug)]
+pub(crate) struct SelectedSource {
+    /// Source as configured, relative to the home directory or absolute.
+    pub(crate) source: Utf8PathBuf,
+    /// Directory handle the source is read from.
+    pub(crate) host_dir: HostDir,
+    /// Source path within `host_dir`.
+    pub(crate) host_name: String,
+    /// Path recorded in the archive, relative to the container home.
+    pub(crate) archive_name: String,
+    /// Absolute path of the source inside the container.
+    pub(crate) container_path: String,
+    /// The family's include and exclude globs.
+    pub(crate) filter: EntryFilter,
+    /// Files, relative to the source, that sync-back may update.
+    pub(crate) sync_files: Vec<Utf8PathBuf>,
+}
+
+/// Home-relative sources share the home handle; absolute sources open their
+/// parent directory.
+#[derive(Debug)]
+pub(crate) enum HostDir {
+    Home,
+    Opened(Dir),
+}
 
-#[derive(Debug, Default)]
-struct SelectedSources {
-    source_directory_names: Vec<&'static str>,
-    expected_container_paths: Vec<String>,
+impl HostDir {
+    pub(crate) const fn resolve<'a>(&'a self, host_home_dir: &'a Dir) -> &'a Dir {
+        match self {
+            Self::Home => host_home_dir,
+            Self::Opened(dir) => dir,
+        }
+    }
 }
 
 /// Try to include a credential source in the upload plan.
 ///
-/// Returns `Ok(Some((dir_name, container_path)))` when the source is enabled,
-/// present, and valid. Returns `Ok(None)` when disabled or missing. Returns
-/// `Err` when the source exists but is invalid.
+/// Returns `Ok(Some(source))` when the source is present and valid. Returns
+/// `Ok(None)` when it is missing. Returns `Err` when the source exists but is
+/// invalid.
 fn include_credential_source(
     host_home_dir: &Dir,
-    is_enabled: bool,
-    directory_name: &'static str,
-) -> io::Result<Option<(&'static str, String)>> {
-    if !is_enabled {
-        return Ok(None);
-    }
-
-    match host_home_dir.metadata(directory_name) {
+    family: &CredentialFamily,
+    source: &Utf8Path,
+) -> io::Result<Option<SelectedSource>> {
+    let Some(file_name) = source.file_name() else {
+        return Err(io::Error::other(format!(
+            "credential source '{source}' does not name a directory"
+        )));
+    };
+    let (host_dir, host_name) = if source.is_absolute() {
+        let parent = source.parent().unwrap_or(source);
+        match Dir::open_ambient_dir(parent, ambient_authority()) {
+            Ok(dir) => (HostDir::Opened(dir), file_name),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(io::Error::other(format!(
+                    "failed to inspect credential source '{source}': {error}"
+                )));
+            }
+        }
+    } else {
+        (HostDir::Home, source.as_str())
+    };
+    match host_dir.resolve(host_home_dir).metadata(host_name) {
         Ok(metadata) if metadata.is_dir() => {
-            let container_path = format!("{CONTAINER_HOME_DIR}/{directory_name}");
-            Ok(Some((directory_name, container_path)))
+            let container_path = family.target().join(file_name);
+            let archive_name = container_path
+                .strip_prefix(CONTAINER_HOME_DIR)
+                .map_err(|_| {
+                    io::Error::other(format!(
+                        "credential target '{}' is outside '{CONTAINER_HOME_DIR}'",
+                        family.target()
+                    ))
+                })?
+                .to_string();
+            Ok(Some(SelectedSource {
+                source: source.to_path_buf(),
+                host_dir,
+                host_name: String::from(host_name),
+                archive_name,
+                container_path: container_path.into_string(),
+                filter: EntryFilter::new(family.include(), family.exclude()),
+                sync_files: family.sync_files().to_vec(),
+            }))
         }
         Ok(_) => Err(io::Error::other(format!(
-            "credential source '{directory_name}' exists but is not a directory"
+            "credential source '{source}' exists but is not a directory"
         ))),
         Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
         Err(error) => Err(io::Error::other(format!(
-            "failed to inspect credential source '{directory_name}': {error}"
+            "failed to inspect credential source '{source}': {error}"
         ))),
     }
 }
 
+/// Resolve the sources of `families` that are present on the host, in
+/// family order.
+///
+/// # Errors
+///
+/// Returns an error when a source exists but is not a directory or cannot be
+/// inspected.
+pub(crate) fn select_credential_sources(
+    host_home_dir: &Dir,
+    families: &[CredentialFamily],
+) -> io::Result<Vec<SelectedSource>> {
+    Ok(families
+        .iter()
+        .flat_map(|family| {
+            family
+                .sources()
+                .iter()
+                .map(move |source| include_credential_source(host_home_dir, family, source))
+        })
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
 pub(crate) fn build_upload_plan(
     host_home_dir: &Dir,
     request: &CredentialUploadRequest,
 ) -> io::Result<CredentialUploadPlan> {
-    let selected_source_pairs = [
-        (request.copy_claude, CLAUDE_CREDENTIAL_DIR),
-        (request.copy_codex, CODEX_CREDENTIAL_DIR),
-    ]
-    .into_iter()
-    .map(|(is_enabled, directory_name)| {
-        include_credential_source(host_home_dir, is_enabled, directory_name)
-    })
-    .collect::<io::Result<Vec<_>>>()?
-    .into_iter()
-    .flatten();
-
-    let (source_directory_names, expected_container_paths) = selected_source_pairs.unzip();
-    let selected_sources = SelectedSources {
-        source_directory_names,
-        expected_container_paths,
-    };
+    let selected_sources = select_credential_sources(host_home_dir, request.families())?;
 
-    build_plan_from_selected_sources(host_home_dir, selected_sources)
+    build_plan_from_selected_sources(host_home_dir, selected_sources, request.max_upload_bytes())
 }
 
 fn build_plan_from_selected_sources(
     host_home_dir: &Dir,
-    selected_sources: SelectedSources,
+    selected_sources: Vec<SelectedSource>,
+    max_upload_bytes: u64,
 ) -> io::Result<CredentialUploadPlan> {
-    if selected_sources.source_directory_names.is_empty() {
+    if selected_sources.is_empty() {
         return Ok(CredentialUploadPlan {
             archive_bytes: vec![],
             expected_container_paths: vec![],
+            skipped_entries: vec![],
         });
     }
 
-    let archive_bytes = build_tar_archive(host_home_dir, &selected_sources.source_directory_names)?;
+    let archive_sources: Vec<FilteredArchiveSource<'_>> = selected_sources
+        .iter()
+        .map(|source| FilteredArchiveSource {
+            host_dir: source.host_dir.resolve(host_home_dir),
+            source: ArchiveSource {
+                host_name: &source.host_name,
+                archive_name: &source.archive_name,
+            },
+            filter: &source.filter,
+        })
+        .collect();
+    let archive = build_budgeted_tar_archive(&archive_sources, max_upload_bytes)?;
 
     Ok(CredentialUploadPlan {
-        archive_bytes,
-        expected_container_paths: selected_sources.expected_container_paths,
+        archive_bytes: archive.archive_bytes,
+        expected_container_paths: selected_sources
+            .into_iter()
+            .map(|source| source.container_path)
+            .collect(),
+        skipped_entries: archive
+            .skipped_entries
+            .into_iter()
+            .map(|skipped| SkippedCredentialEntry {
+                container_path: format!("{CONTAINER_HOME_DIR}/{}", skipped.archive_path),
+                reason: match skipped.reason {
+                    SkipReason::Filtered => CredentialSkipReason::Filtered,
+                    SkipReason::OverBudget => CredentialSkipReason::SizeLimit,
+                },
+            })
+            .collect(),
     })
 }
@@ -1,6 +1,7 @@
 //! Unit tests for credential upload planning and archive generation.
 
 mod tar_archive;
+mod upload_filters;
 mod upload_flow;
 mod upload_flow_filesystem_errors;
 
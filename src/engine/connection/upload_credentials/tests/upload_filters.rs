@@ -0,0 +1,116 @@
+//! Unit tests for credential include/exclude globs and the upload size cap.
+
+use rstest::rstest;
+
+use super::tar_archive::parse_archive_entries;
+use super::*;
+use crate::config::CredentialFamily;
+
+fn skipped(result: &CredentialUploadResult) -> Vec<(&str, CredentialSkipReason)> {
+    result
+        .skipped_entries()
+        .iter()
+        .map(|entry| (entry.container_path(), entry.reason()))
+        .collect()
+}
+
+#[rstest]
+fn upload_credentials_applies_family_globs(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+    host_home_dir: std::io::Result<(tempfile::TempDir, camino::Utf8PathBuf)>,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let (_tmp, host_home) = host_home_dir?;
+
+    let claude_dir = host_home.join(".claude");
+    create_dir(&claude_dir.join("projects"))?;
+    create_dir(&claude_dir.join("statsig"))?;
+    write_file(&claude_dir.join(".credentials.json"), "{}\n")?;
+    write_file(&claude_dir.join("settings.json"), "{}\n")?;
+    write_file(&claude_dir.join("history.txt"), "ls\n")?;
+    write_file(&claude_dir.join("projects/session.jsonl"), "{}\n")?;
+    write_file(&claude_dir.join("statsig/cache"), "x\n")?;
+
+    let claude = CredentialFamily::builtin("claude")
+        .ok_or_else(|| io_error("claude should be built in"))?
+        .with_filters(
+            vec![String::from("*.json"), String::from("projects")],
+            vec![String::from("projects")],
+        );
+    let request =
+        CredentialUploadRequest::with_families("container-globs", host_home, vec![claude]);
+    let (uploader, captured) = successful_uploader();
+
+    let result = runtime_handle
+        .block_on(EngineConnector::upload_credentials_async(
+            &uploader, &request,
+        ))
+        .map_err(|error| io_error(format!("upload should succeed: {error}")))?;
+
+    let entries = parse_archive_entries(&captured_call(&captured)?.archive_bytes)?;
+    let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    let expected_entries = [
+        ".claude/",
+        ".claude/.credentials.json",
+        ".claude/settings.json",
+    ];
+    ensure(
+        paths == expected_entries,
+        format!("expected archive entries {expected_entries:?}, got {paths:?}"),
+    )?;
+
+    let expected_skips = [
+        ("/root/.claude/history.txt", CredentialSkipReason::Filtered),
+        ("/root/.claude/projects", CredentialSkipReason::Filtered),
+        ("/root/.claude/statsig", CredentialSkipReason::Filtered),
+    ];
+    ensure(
+        skipped(&result) == expected_skips,
+        format!(
+            "expected skipped entries {expected_skips:?}, got {:?}",
+            skipped(&result)
+        ),
+    )
+}
+
+#[rstest]
+fn upload_credentials_skips_files_beyond_the_size_cap(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+    host_home_dir: std::io::Result<(tempfile::TempDir, camino::Utf8PathBuf)>,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let (_tmp, host_home) = host_home_dir?;
+
+    let codex_dir = host_home.join(".codex");
+    create_dir(&codex_dir)?;
+    write_file(&codex_dir.join("auth.json"), "0123456789")?;
+    write_file(&codex_dir.join("big.log"), &"x".repeat(64))?;
+    write_file(&codex_dir.join("config.toml"), "abcde")?;
+
+    let request = CredentialUploadRequest::new("container-cap", host_home, false, true)
+        .with_max_upload_bytes(16);
+    let (uploader, captured) = successful_uploader();
+
+    let result = runtime_handle
+        .block_on(EngineConnector::upload_credentials_async(
+            &uploader, &request,
+        ))
+        .map_err(|error| io_error(format!("upload should succeed: {error}")))?;
+
+    let entries = parse_archive_entries(&captured_call(&captured)?.archive_bytes)?;
+    let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    let expected_entries = [".codex/", ".codex/auth.json", ".codex/config.toml"];
+    ensure(
+        paths == expected_entries,
+        format!("expected archive entries {expected_entries:?}, got {paths:?}"),
+    )?;
+
+    let expected_skips = [("/root/.codex/big.log", CredentialSkipReason::SizeLimit)];
+    ensure(
+        skipped(&result) == expected_skips,
+        format!(
+            "expected skipped entries {expected_skips:?}, got {:?}",
+            skipped(&result)
+        ),
+    )
+}
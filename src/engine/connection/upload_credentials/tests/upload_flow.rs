@@ -5,7 +5,7 @@ use rstest::rstest;
 
 use super::tar_archive::parse_archive_entries;
 use super::*;
-use crate::config::{AppConfig, CredsConfig};
+use crate::config::{AgentConfig, AgentKind, AppConfig, CredentialFamily, CredsConfig};
 use crate::error::{ContainerError, PodbotError};
 
 struct ToggleCase {
@@ -38,6 +38,11 @@ fn upload_credentials_uploads_selected_sources_and_reports_paths(
         creds: CredsConfig {
             copy_claude: true,
             copy_codex: true,
+            ..CredsConfig::default()
+        },
+        agent: AgentConfig {
+            kind: AgentKind::Custom,
+            ..AgentConfig::default()
         },
         ..AppConfig::default()
     };
@@ -340,3 +345,64 @@ fn upload_credentials_with_host_home_dir_uses_provided_capability(
 
     Ok(())
 }
+
+#[rstest]
+fn upload_credentials_places_family_sources_under_their_targets(
+    runtime: std::io::Result<tokio::runtime::Runtime>,
+    host_home_dir: std::io::Result<(tempfile::TempDir, camino::Utf8PathBuf)>,
+) -> std::io::Result<()> {
+    let runtime_handle = runtime?;
+    let (_tmp, host_home) = host_home_dir?;
+
+    let gh_dir = host_home.join(".config/gh");
+    create_dir(&gh_dir)?;
+    write_file(&gh_dir.join("hosts.yml"), "github.com: {}\n")?;
+
+    let tool_dir = host_home.join("elsewhere/tool-creds");
+    create_dir(&tool_dir)?;
+    write_file(&tool_dir.join("token"), "secret\n")?;
+
+    let gh = CredentialFamily::builtin("gh").ok_or_else(|| io_error("gh should be built in"))?;
+    let custom = CredentialFamily::new(
+        "tool",
+        vec![tool_dir.clone(), Utf8PathBuf::from(".missing")],
+        "/root/.tool",
+        None,
+    );
+    let request =
+        CredentialUploadRequest::with_families("container-families", host_home, vec![gh, custom]);
+    let (uploader, captured) = successful_uploader();
+
+    let result = runtime_handle
+        .block_on(EngineConnector::upload_credentials_async(
+            &uploader, &request,
+        ))
+        .map_err(|error| io_error(format!("upload should succeed: {error}")))?;
+
+    let expected_paths = [
+        String::from("/root/.config/gh"),
+        String::from("/root/.tool/tool-creds"),
+    ];
+    ensure(
+        result.expected_container_paths() == expected_paths.as_slice(),
+        format!(
+            "expected uploaded paths {expected_paths:?}, got {:?}",
+            result.expected_container_paths()
+        ),
+    )?;
+
+    let entries = parse_archive_entries(&captured_call(&captured)?.archive_bytes)?;
+    let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    let expected_entries = [
+        ".config/",
+        ".config/gh/",
+        ".config/gh/hosts.yml",
+        ".tool/",
+        ".tool/tool-creds/",
+        ".tool/tool-creds/token",
+    ];
+    ensure(
+        paths == expected_entries,
+        format!("expected archive entries {expected_entries:?}, got {paths:?}"),
+    )
+}
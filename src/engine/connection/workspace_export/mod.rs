@@ -0,0 +1,341 @@
+//! Workspace export from a running sandbox container to the host.
+//!
+//! Patch and bundle exports run `git format-patch` or `git bundle` inside the
+//! container, writing to a scratch directory unique to the export, then
+//! stream the generated file out of its download archive through the
+//! [`ContainerDownloader`] seam and remove the scratch directory. Tar exports
+//! stream the workspace directory archive directly. All host writes go
+//! through a capability-scoped [`Dir`] handle and land atomically via a
+//! temporary file and rename.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use camino::Utf8Path;
+use cap_std::fs_utf8::{Dir, File};
+
+use super::download::{ArchiveDownload, ContainerDownloader, stream_archive_async};
+use crate::api::WorkspacePath;
+use crate::engine::{ContainerExecClient, EngineConnector, ExecMode, ExecRequest};
+use crate::error::{ConfigError, ContainerError, FilesystemError, PodbotError};
+
+mod single_file;
+
+use single_file::SingleFileExtractor;
+
+/// Upper bound on the archive size accepted from the container.
+///
+/// Exports larger than this are rejected so a runaway workspace cannot
+/// exhaust host disk space.
+pub const MAX_EXPORT_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Prefix of the per-export scratch directory created inside the container.
+const CONTAINER_EXPORT_DIR_PREFIX: &str = "/tmp/podbot-export";
+
+/// Sequence number distinguishing exports made by this process.
+static NEXT_EXPORT: AtomicU64 = AtomicU64::new(1);
+
+/// Output format produced by a workspace export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceExportFormat {
+    /// Mailbox-style patch series from `git format-patch --stdout`.
+    Patch,
+    /// Git bundle containing the commits since the base revision.
+    Bundle,
+    /// Tar archive of the whole workspace directory.
+    Tar,
+}
+
+/// Request for exporting a container workspace to the host.
+pub struct WorkspaceExportRequest<'a> {
+    /// Target container identifier.
+    pub container_id: &'a str,
+    /// Validated absolute in-container workspace path.
+    pub workspace_path: &'a WorkspacePath,
+    /// Requested export format.
+    pub format: WorkspaceExportFormat,
+    /// Revision the patch series or bundle starts from. Ignored for tar.
+    pub base_revision: &'a str,
+    /// Host directory capability that receives the export.
+    pub output_dir: &'a Dir,
+    /// Host output path; its file name is created inside `output_dir`.
+    pub output_path: &'a Utf8Path,
+}
+
+/// Successful workspace export result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceExportResult {
+    /// Number of bytes written to the host output file.
+    pub bytes_written: u64,
+}
+
+/// Export the container workspace to the requested host file.
+///
+/// # Errors
+///
+/// Returns `ContainerError::ExecFailed` when the in-container Git command
+/// fails, `ContainerError::DownloadFailed` when the archive cannot be
+/// streamed or exceeds [`MAX_EXPORT_BYTES`], and `FilesystemError::IoError`
+/// when the host output cannot be written.
+pub fn export_workspace<C>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    request: &WorkspaceExportRequest<'_>,
+) -> Result<WorkspaceExportResult, PodbotError>
+where
+    C: ContainerExecClient + ContainerDownloader + Sync,
+{
+    let file_name = output_file_name(request.output_path)?;
+    match request.format {
+        WorkspaceExportFormat::Tar => export_tar(runtime, client, request, file_name),
+        WorkspaceExportFormat::Patch | WorkspaceExportFormat::Bundle => {
+            export_git_artefact(runtime, client, request, file_name)
+        }
+    }
+}
+
+fn export_tar<C>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    request: &WorkspaceExportRequest<'_>,
+    file_name: &str,
+) -> Result<WorkspaceExportResult, PodbotError>
+where
+    C: ContainerDownloader + Sync,
+{
+    let bytes_written = write_host_file(request, file_name, |file| {
+        runtime.block_on(stream_archive_async(
+            client,
+            ArchiveDownload {
+                container_id: request.container_id,
+                container_path: request.workspace_path.as_str(),
+                max_bytes: MAX_EXPORT_BYTES,
+            },
+            |chunk| write_chunk(file, request.output_path, chunk),
+        ))
+    })?;
+    Ok(WorkspaceExportResult { bytes_written })
+}
+
+fn export_git_artefact<C>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    request: &WorkspaceExportRequest<'_>,
+    file_name: &str,
+) -> Result<WorkspaceExportResult, PodbotError>
+where
+    C: ContainerExecClient + ContainerDownloader + Sync,
+{
+    let container_dir = unique_container_dir();
+    let container_output = container_output_path(&container_dir, request.format);
+    let download = ArchiveDownload {
+        container_id: request.container_id,
+        container_path: &container_output,
+        max_bytes: MAX_EXPORT_BYTES,
+    };
+    let exported = run_export_command(runtime, client, request, &container_output).and_then(|()| {
+        // Unwrap the generated file from its archive as the archive arrives.
+        write_host_file(request, file_name, |file| {
+            let mut extractor = SingleFileExtractor::new(request.container_id);
+            runtime.block_on(stream_archive_async(client, download, |chunk| {
+                extractor.push(chunk, |data| write_chunk(file, request.output_path, data))
+            }))?;
+            extractor.finish()
+        })
+    });
+    remove_container_dir(runtime, client, request.container_id, &container_dir);
+    Ok(WorkspaceExportResult {
+        bytes_written: exported?,
+    })
+}
+
+/// Write the host output through a temporary file that `write` fills, then
+/// rename it into place. The temporary file is removed on any failure.
+fn write_host_file<F>(
+    request: &WorkspaceExportRequest<'_>,
+    file_name: &str,
+    write: F,
+) -> Result<u64, PodbotError>
+where
+    F: FnOnce(&mut File) -> Result<u64, PodbotError>,
+{
+    let host_error = |error: std::io::Error| host_write_failed(request.output_path, &error);
+    let temp_name = temp_file_name(file_name);
+    let mut file = request.output_dir.create(&temp_name).map_err(host_error)?;
+
+    let written = write(&mut file).and_then(|written| {
+        file.sync_all().map_err(host_error)?;
+        Ok(written)
+    });
+    drop(file);
+    if written.is_err() {
+        discard_temp_file(request.output_dir, &temp_name);
+        return written;
+    }
+
+    finish_temp_file(request, &temp_name, file_name)?;
+    written
+}
+
+fn run_export_command<C: ContainerExecClient + Sync>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    request: &WorkspaceExportRequest<'_>,
+    container_output: &str,
+) -> Result<(), PodbotError> {
+    let command = export_command(request, container_output);
+    let exec_request = ExecRequest::new(request.container_id, command, ExecMode::Detached)?;
+    let result = EngineConnector::exec(runtime, client, &exec_request)?;
+
+    if result.exit_code() != 0 {
+        return Err(ContainerError::ExecFailed {
+            container_id: String::from(request.container_id),
+            message: format!(
+                "{} failed with exit code {}",
+                export_label(request.format),
+                result.exit_code()
+            ),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Remove the per-export scratch directory inside the container.
+///
+/// Failures are logged rather than returned: the export itself has already
+/// succeeded or failed, and a leftover directory under `/tmp` is harmless.
+fn remove_container_dir<C: ContainerExecClient + Sync>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    container_id: &str,
+    container_dir: &str,
+) {
+    if let Some(failure) = run_removal(runtime, client, container_id, container_dir) {
+        tracing::debug!(
+            container_dir,
+            failure,
+            "failed to remove workspace export scratch directory"
+        );
+    }
+}
+
+fn run_removal<C: ContainerExecClient + Sync>(
+    runtime: &tokio::runtime::Handle,
+    client: &C,
+    container_id: &str,
+    container_dir: &str,
+) -> Option<String> {
+    let command = vec![
+        String::from("rm"),
+        String::from("-rf"),
+        String::from("--"),
+        String::from(container_dir),
+    ];
+    match ExecRequest::new(container_id, command, ExecMode::Detached)
+        .and_then(|exec_request| EngineConnector::exec(runtime, client, &exec_request))
+    {
+        Ok(result) if result.exit_code() == 0 => None,
+        Ok(result) => Some(format!("exit code {}", result.exit_code())),
+        Err(error) => Some(error.to_string()),
+    }
+}
+
+fn export_command(request: &WorkspaceExportRequest<'_>, container_output: &str) -> Vec<String> {
+    let script = match request.format {
+        WorkspaceExportFormat::Bundle => {
+            r#"mkdir -p "$(dirname "$3")" && git -C "$1" bundle create "$3" "$2..HEAD""#
+        }
+        WorkspaceExportFormat::Patch | WorkspaceExportFormat::Tar => {
+            r#"mkdir -p "$(dirname "$3")" && git -C "$1" format-patch --stdout "$2" > "$3""#
+        }
+    };
+    vec![
+        String::from("sh"),
+        String::from("-c"),
+        String::from(script),
+        String::from("podbot-export"),
+        String::from(request.workspace_path.as_str()),
+        String::from(request.base_revision),
+        String::from(container_output),
+    ]
+}
+
+const fn export_label(format: WorkspaceExportFormat) -> &'static str {
+    match format {
+        WorkspaceExportFormat::Bundle => "git bundle",
+        WorkspaceExportFormat::Patch | WorkspaceExportFormat::Tar => "git format-patch",
+    }
+}
+
+/// Return a scratch directory path no other export from this host process
+/// shares, so concurrent exports into one container cannot collide.
+fn unique_container_dir() -> String {
+    let sequence = NEXT_EXPORT.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{CONTAINER_EXPORT_DIR_PREFIX}-{}-{sequence}",
+        std::process::id()
+    )
+}
+
+fn container_output_path(container_dir: &str, format: WorkspaceExportFormat) -> String {
+    let extension = match format {
+        WorkspaceExportFormat::Bundle => "bundle",
+        WorkspaceExportFormat::Patch | WorkspaceExportFormat::Tar => "patch",
+    };
+    format!("{container_dir}/workspace.{extension}")
+}
+
+fn output_file_name(output_path: &Utf8Path) -> Result<&str, PodbotError> {
+    output_path.file_name().ok_or_else(|| {
+        PodbotError::from(ConfigError::InvalidValue {
+            field: String::from("export.out"),
+            reason: format!("'{output_path}' does not name a file"),
+        })
+    })
+}
+
+fn temp_file_name(file_name: &str) -> String {
+    format!(".{file_name}.podbot-partial")
+}
+
+fn finish_temp_file(
+    request: &WorkspaceExportRequest<'_>,
+    temp_name: &str,
+    file_name: &str,
+) -> Result<(), PodbotError> {
+    request
+        .output_dir
+        .rename(temp_name, request.output_dir, file_name)
+        .map_err(|error| {
+            discard_temp_file(request.output_dir, temp_name);
+            host_write_failed(request.output_path, &error)
+        })
+}
+
+fn discard_temp_file(output_dir: &Dir, temp_name: &str) {
+    if let Err(error) = output_dir.remove_file(temp_name) {
+        tracing::debug!(
+            temp_name,
+            error = %error,
+            "failed to remove partial workspace export"
+        );
+    }
+}
+
+fn write_chunk(file: &mut File, output_path: &Utf8Path, chunk: &[u8]) -> Result<(), PodbotError> {
+    file.write_all(chunk)
+        .map_err(|error| host_write_failed(output_path, &error))
+}
+
+fn host_write_failed(output_path: &Utf8Path, error: &std::io::Error) -> PodbotError {
+    PodbotError::from(FilesystemError::IoError {
+        path: PathBuf::from(output_path.as_std_path()),
+        message: error.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
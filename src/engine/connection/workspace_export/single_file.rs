@@ -0,0 +1,160 @@
+//! Incremental extraction of the single file in a streamed tar archive.
+//!
+//! Patch and bundle exports download a tar archive that wraps one generated
+//! file. [`SingleFileExtractor`] walks that archive chunk by chunk as it
+//! arrives and hands the file's contents to a sink, so the export never has
+//! to hold the whole archive in memory.
+
+use crate::error::{ContainerError, PodbotError};
+
+/// Size of a tar header and of the blocks entry data is padded to.
+const BLOCK_SIZE: u64 = 512;
+
+/// Where the extractor is within the archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Collecting the next 512-byte header.
+    Header,
+    /// Discarding the data and padding of an entry that is not the file.
+    Skip { remaining: u64 },
+    /// Delivering the data of the first regular file.
+    File { remaining: u64 },
+    /// The file has been delivered; the rest of the archive is ignored.
+    Done,
+}
+
+/// Streaming reader for the first regular file in a tar archive.
+#[derive(Debug)]
+pub(super) struct SingleFileExtractor<'a> {
+    container_id: &'a str,
+    header: Vec<u8>,
+    state: State,
+    delivered: u64,
+}
+
+impl<'a> SingleFileExtractor<'a> {
+    /// Create an extractor for an archive downloaded from `container_id`.
+    pub(super) const fn new(container_id: &'a str) -> Self {
+        Self {
+            container_id,
+            header: Vec::new(),
+            state: State::Header,
+            delivered: 0,
+        }
+    }
+
+    /// Consume the next archive `chunk`, passing file contents to `sink`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::DownloadFailed` when a header is malformed,
+    /// and any error produced by `sink`.
+    pub(super) fn push<F>(&mut self, mut chunk: &[u8], mut sink: F) -> Result<(), PodbotError>
+    where
+        F: FnMut(&[u8]) -> Result<(), PodbotError>,
+    {
+        while !chunk.is_empty() {
+            chunk = match self.state {
+                State::Header => self.read_header(chunk)?,
+                State::Skip { remaining } => {
+                    let (_, rest) = split_at_most(chunk, remaining);
+                    self.advance(chunk.len() - rest.len());
+                    rest
+                }
+                State::File { remaining } => {
+                    let (data, rest) = split_at_most(chunk, remaining);
+                    sink(data)?;
+                    self.advance(data.len());
+                    rest
+                }
+                State::Done => return Ok(()),
+            };
+        }
+        Ok(())
+    }
+
+    /// Finish the archive, returning the number of file bytes delivered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::DownloadFailed` when the archive ended before
+    /// a complete regular file was read.
+    pub(super) fn finish(self) -> Result<u64, PodbotError> {
+        if self.state == State::Done {
+            return Ok(self.delivered);
+        }
+        Err(self.malformed(String::from(
+            "export archive did not contain a regular file",
+        )))
+    }
+
+    fn read_header<'c>(&mut self, chunk: &'c [u8]) -> Result<&'c [u8], PodbotError> {
+        let block_size = usize::try_from(BLOCK_SIZE).unwrap_or(usize::MAX);
+        let wanted = block_size.saturating_sub(self.header.len());
+        let (bytes, rest) = chunk.split_at(wanted.min(chunk.len()));
+        self.header.extend_from_slice(bytes);
+        if self.header.len() < block_size {
+            return Ok(rest);
+        }
+        let block = std::mem::take(&mut self.header);
+        self.state = self.next_state(&block)?;
+        Ok(rest)
+    }
+
+    fn next_state(&self, block: &[u8]) -> Result<State, PodbotError> {
+        if block.iter().all(|byte| *byte == 0) {
+            return Ok(State::Header);
+        }
+        let header = tar::Header::from_byte_slice(block);
+        let size = header
+            .entry_size()
+            .map_err(|error| self.malformed(format!("invalid export archive: {error}")))?;
+        if header.entry_type().is_file() {
+            return Ok(if size == 0 {
+                State::Done
+            } else {
+                State::File { remaining: size }
+            });
+        }
+        Ok(State::Skip {
+            remaining: size.div_ceil(BLOCK_SIZE).saturating_mul(BLOCK_SIZE),
+        })
+    }
+
+    fn advance(&mut self, consumed_bytes: usize) {
+        let consumed = u64::try_from(consumed_bytes).unwrap_or(u64::MAX);
+        self.state = match self.state {
+            State::Skip { remaining } if remaining > consumed => State::Skip {
+                remaining: remaining - consumed,
+            },
+            State::File { remaining } => {
+                self.delivered = self.delivered.saturating_add(consumed);
+                if remaining > consumed {
+                    State::File {
+                        remaining: remaining - consumed,
+                    }
+                } else {
+                    State::Done
+                }
+            }
+            State::Header | State::Skip { .. } | State::Done => State::Header,
+        };
+    }
+
+    fn malformed(&self, message: String) -> PodbotError {
+        PodbotError::from(ContainerError::DownloadFailed {
+            container_id: String::from(self.container_id),
+            message,
+        })
+    }
+}
+
+/// Split off at most `limit` bytes from the front of `chunk`.
+fn split_at_most(chunk: &[u8], limit: u64) -> (&[u8], &[u8]) {
+    let at = usize::try_from(limit).map_or(chunk.len(), |limit_bytes| limit_bytes.min(chunk.len()));
+    chunk.split_at(at)
+}
+
+#[cfg(test)]
+#[path = "single_file_tests.rs"]
+mod tests;
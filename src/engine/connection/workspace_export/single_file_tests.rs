@@ -0,0 +1,100 @@
+//! Unit tests for streamed single-file archive extraction.
+
+use std::io;
+
+use rstest::rstest;
+
+use super::SingleFileExtractor;
+use crate::error::{ContainerError, PodbotError};
+
+fn file_archive(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut file = tar::Header::new_gnu();
+    file.set_size(u64::try_from(contents.len()).map_err(io::Error::other)?);
+    file.set_mode(0o644);
+    file.set_cksum();
+    builder.append_data(&mut file, "workspace.patch", contents)?;
+    builder.into_inner()
+}
+
+fn directory_then_file_archive(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut directory = tar::Header::new_gnu();
+    directory.set_entry_type(tar::EntryType::Directory);
+    directory.set_size(0);
+    directory.set_mode(0o755);
+    directory.set_cksum();
+    builder.append_data(&mut directory, "export/", io::empty())?;
+    let mut file = tar::Header::new_gnu();
+    file.set_size(u64::try_from(contents.len()).map_err(io::Error::other)?);
+    file.set_mode(0o644);
+    file.set_cksum();
+    builder.append_data(&mut file, "export/workspace.patch", contents)?;
+    builder.into_inner()
+}
+
+fn extract(archive: &[u8], chunk_size: usize) -> Result<(Vec<u8>, u64), PodbotError> {
+    let mut extractor = SingleFileExtractor::new("sandbox-export");
+    let mut contents = Vec::new();
+    for chunk in archive.chunks(chunk_size) {
+        extractor.push(chunk, |data| {
+            contents.extend_from_slice(data);
+            Ok(())
+        })?;
+    }
+    let delivered = extractor.finish()?;
+    Ok((contents, delivered))
+}
+
+#[rstest]
+#[case::whole(4096)]
+#[case::header_split(7)]
+#[case::single_bytes(1)]
+fn extractor_skips_leading_entries_and_reads_the_file(#[case] chunk_size: usize) {
+    let archive = directory_then_file_archive(b"patch body").expect("archive should build");
+
+    let extracted = extract(&archive, chunk_size).expect("file should be extracted");
+
+    assert_eq!(extracted, (b"patch body".to_vec(), 10));
+}
+
+#[rstest]
+fn truncated_archive_is_rejected() {
+    let archive = file_archive(&[b'x'; 600]).expect("archive should build");
+
+    let result = extract(archive.get(..700).unwrap_or_default(), 4096);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Container(ContainerError::DownloadFailed { message, .. }))
+                if message == "export archive did not contain a regular file"
+        ),
+        "expected DownloadFailed, got {result:?}"
+    );
+}
+
+#[rstest]
+fn archive_without_regular_file_is_rejected() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Directory);
+    header.set_size(0);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "export/", io::empty())
+        .expect("directory entry should append");
+    let archive = builder.into_inner().expect("archive should build");
+
+    let result = extract(&archive, 4096);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Container(ContainerError::DownloadFailed { message, .. }))
+                if message == "export archive did not contain a regular file"
+        ),
+        "expected DownloadFailed, got {result:?}"
+    );
+}
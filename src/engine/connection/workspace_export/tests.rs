@@ -0,0 +1,381 @@
+//! Unit tests for workspace export command construction and host writes.
+
+use std::io;
+
+use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions};
+use bollard::query_parameters::DownloadFromContainerOptions;
+use camino::Utf8PathBuf;
+use cap_std::ambient_authority;
+use mockall::mock;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::*;
+use crate::engine::connection::download::DownloadFromContainerStream;
+use crate::engine::{CreateExecFuture, InspectExecFuture, ResizeExecFuture, StartExecFuture};
+
+mock! {
+    Engine {}
+
+    impl ContainerExecClient for Engine {
+        fn create_exec(
+            &self,
+            container_id: &str,
+            options: CreateExecOptions<String>,
+        ) -> CreateExecFuture<'_>;
+        fn start_exec(
+            &self,
+            exec_id: &str,
+            options: Option<StartExecOptions>,
+        ) -> StartExecFuture<'_>;
+        fn inspect_exec(&self, exec_id: &str) -> InspectExecFuture<'_>;
+        fn resize_exec(
+            &self,
+            exec_id: &str,
+            options: ResizeExecOptions,
+        ) -> ResizeExecFuture<'_>;
+    }
+
+    impl ContainerDownloader for Engine {
+        fn download_from_container(
+            &self,
+            container_id: &str,
+            options: Option<DownloadFromContainerOptions>,
+        ) -> DownloadFromContainerStream<'_>;
+    }
+}
+
+struct HostOutput {
+    _temp_dir: TempDir,
+    dir: Dir,
+    path: Utf8PathBuf,
+}
+
+#[fixture]
+fn runtime() -> io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Runtime::new()
+}
+
+#[fixture]
+fn host_output() -> io::Result<HostOutput> {
+    let temp_dir = tempfile::tempdir()?;
+    let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .map_err(|_| io::Error::other("tempdir path should be valid UTF-8"))?;
+    let dir = Dir::open_ambient_dir(&root, ambient_authority())?;
+    Ok(HostOutput {
+        _temp_dir: temp_dir,
+        dir,
+        path: root.join("review.out"),
+    })
+}
+
+fn workspace() -> Result<WorkspacePath, PodbotError> {
+    WorkspacePath::parse("/work")
+}
+
+fn request<'a>(
+    workspace_path: &'a WorkspacePath,
+    format: WorkspaceExportFormat,
+    output: &'a HostOutput,
+) -> WorkspaceExportRequest<'a> {
+    WorkspaceExportRequest {
+        container_id: "sandbox-export",
+        workspace_path,
+        format,
+        base_revision: "origin/main",
+        output_dir: &output.dir,
+        output_path: &output.path,
+    }
+}
+
+fn single_file_archive(name: &str, contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(u64::try_from(contents.len()).map_err(io::Error::other)?);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, contents)?;
+    builder.into_inner()
+}
+
+/// Returns whether `path` is a file inside a per-export scratch directory.
+fn is_scratch_output(path: &str, file_name: &str) -> bool {
+    path.strip_prefix("/tmp/podbot-export-")
+        .and_then(|rest| rest.strip_suffix(file_name))
+        .and_then(|dir| dir.strip_suffix('/'))
+        .is_some_and(|dir| !dir.contains('/'))
+}
+
+fn expect_exec(
+    engine: &mut MockEngine,
+    script_fragment: &'static str,
+    output_name: &'static str,
+    exit_code: i64,
+) {
+    engine
+        .expect_create_exec()
+        .withf(move |container_id, options| {
+            container_id == "sandbox-export"
+                && options.cmd.as_ref().is_some_and(|cmd| {
+                    cmd.get(2)
+                        .is_some_and(|script| script.contains(script_fragment))
+                        && cmd.get(4..6).is_some_and(|args| {
+                            args == [String::from("/work"), String::from("origin/main")]
+                        })
+                        && cmd
+                            .get(6)
+                            .is_some_and(|output| is_scratch_output(output, output_name))
+                })
+        })
+        .times(1)
+        .returning(|_, _| exec_created("exec-id"));
+    engine
+        .expect_start_exec()
+        .times(1..=2)
+        .returning(|_, _| Box::pin(async { Ok(bollard::exec::StartExecResults::Detached) }));
+    expect_exit(engine, "exec-id", exit_code);
+}
+
+/// Expect one removal of a per-export scratch directory.
+fn expect_cleanup(engine: &mut MockEngine) {
+    engine
+        .expect_create_exec()
+        .withf(|container_id, options| {
+            container_id == "sandbox-export"
+                && options.cmd.as_ref().is_some_and(|cmd| {
+                    cmd.get(..3).is_some_and(|rm| rm == ["rm", "-rf", "--"])
+                        && cmd
+                            .get(3)
+                            .is_some_and(|dir| is_scratch_output(&format!("{dir}/x"), "x"))
+                })
+        })
+        .times(1)
+        .returning(|_, _| exec_created("cleanup-id"));
+    expect_exit(engine, "cleanup-id", 0);
+}
+
+fn exec_created(id: &'static str) -> CreateExecFuture<'static> {
+    Box::pin(async move {
+        Ok(bollard::exec::CreateExecResults {
+            id: String::from(id),
+        })
+    })
+}
+
+fn expect_exit(engine: &mut MockEngine, exec_id: &'static str, exit_code: i64) {
+    engine
+        .expect_inspect_exec()
+        .withf(move |id| id == exec_id)
+        .times(1)
+        .returning(move |_| {
+            Box::pin(async move {
+                Ok(bollard::models::ExecInspectResponse {
+                    exit_code: Some(exit_code),
+                    running: Some(false),
+                    ..Default::default()
+                })
+            })
+        });
+}
+
+fn expect_download(
+    engine: &mut MockEngine,
+    matches_path: impl Fn(&str) -> bool + Send + 'static,
+    chunks: Vec<Result<Vec<u8>, bollard::errors::Error>>,
+) {
+    engine
+        .expect_download_from_container()
+        .withf(move |container_id, options| {
+            container_id == "sandbox-export"
+                && options
+                    .as_ref()
+                    .is_some_and(|requested| matches_path(&requested.path))
+        })
+        .times(1)
+        .return_once(move |_, _| Box::pin(futures_util::stream::iter(chunks)));
+}
+
+fn workspace_root(path: &str) -> bool {
+    path == "/work"
+}
+
+fn ensure(condition: bool, failure_message: impl Into<String>) -> io::Result<()> {
+    if condition {
+        return Ok(());
+    }
+
+    Err(io::Error::other(failure_message.into()))
+}
+
+fn host_entries(output: &HostOutput) -> io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in output.dir.entries()? {
+        names.push(entry?.file_name()?);
+    }
+    names.sort();
+    Ok(names)
+}
+
+#[rstest]
+#[case::patch(
+    WorkspaceExportFormat::Patch,
+    "format-patch --stdout",
+    "workspace.patch"
+)]
+#[case::bundle(WorkspaceExportFormat::Bundle, "bundle create", "workspace.bundle")]
+fn git_exports_write_generated_file_to_host(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    #[case] format: WorkspaceExportFormat,
+    #[case] script_fragment: &'static str,
+    #[case] output_name: &'static str,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let output = host_output()?;
+    let workspace_path = workspace().map_err(io::Error::other)?;
+    let mut engine = MockEngine::new();
+    expect_exec(&mut engine, script_fragment, output_name, 0);
+    expect_cleanup(&mut engine);
+    let archive = single_file_archive("workspace.export", b"generated export")?;
+    expect_download(
+        &mut engine,
+        move |path| is_scratch_output(path, output_name),
+        vec![Ok(archive)],
+    );
+
+    let result = export_workspace(
+        rt.handle(),
+        &engine,
+        &request(&workspace_path, format, &output),
+    )
+    .map_err(io::Error::other)?;
+
+    ensure(
+        result.bytes_written == 16,
+        "export should report bytes written",
+    )?;
+    ensure(
+        output.dir.read("review.out")? == b"generated export",
+        "host file should hold the generated export",
+    )?;
+    ensure(
+        host_entries(&output)? == [String::from("review.out")],
+        "no temporary files should remain",
+    )
+}
+
+#[rstest]
+fn concurrent_git_exports_use_distinct_scratch_directories() {
+    let first = unique_container_dir();
+    let second = unique_container_dir();
+
+    assert_ne!(first, second);
+    assert!(first.starts_with("/tmp/podbot-export-"), "{first}");
+}
+
+#[rstest]
+fn failing_git_command_reports_exec_failure_without_writing(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_output: io::Result<HostOutput>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let output = host_output?;
+    let workspace_path = workspace().map_err(io::Error::other)?;
+    let mut engine = MockEngine::new();
+    expect_exec(&mut engine, "format-patch --stdout", "workspace.patch", 128);
+    expect_cleanup(&mut engine);
+    engine.expect_download_from_container().never();
+
+    let result = export_workspace(
+        rt.handle(),
+        &engine,
+        &request(&workspace_path, WorkspaceExportFormat::Patch, &output),
+    );
+
+    ensure(
+        matches!(
+            &result,
+            Err(PodbotError::Container(ContainerError::ExecFailed { message, .. }))
+                if message == "git format-patch failed with exit code 128"
+        ),
+        format!("expected ExecFailed, got {result:?}"),
+    )?;
+    ensure(
+        host_entries(&output)?.is_empty(),
+        "failed export should not write to the host",
+    )
+}
+
+#[rstest]
+fn tar_export_streams_workspace_archive_to_host(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_output: io::Result<HostOutput>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let output = host_output?;
+    let workspace_path = workspace().map_err(io::Error::other)?;
+    let mut engine = MockEngine::new();
+    engine.expect_create_exec().never();
+    expect_download(
+        &mut engine,
+        workspace_root,
+        vec![Ok(b"tar-".to_vec()), Ok(b"bytes".to_vec())],
+    );
+
+    let result = export_workspace(
+        rt.handle(),
+        &engine,
+        &request(&workspace_path, WorkspaceExportFormat::Tar, &output),
+    )
+    .map_err(io::Error::other)?;
+
+    ensure(
+        result.bytes_written == 9,
+        "export should report bytes written",
+    )?;
+    ensure(
+        output.dir.read("review.out")? == b"tar-bytes",
+        "host file should hold the streamed archive",
+    )
+}
+
+#[rstest]
+fn interrupted_tar_export_leaves_no_partial_file(
+    runtime: io::Result<tokio::runtime::Runtime>,
+    host_output: io::Result<HostOutput>,
+) -> io::Result<()> {
+    let rt = runtime?;
+    let output = host_output?;
+    let workspace_path = workspace().map_err(io::Error::other)?;
+    let mut engine = MockEngine::new();
+    expect_download(
+        &mut engine,
+        workspace_root,
+        vec![
+            Ok(b"partial".to_vec()),
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 500,
+                message: String::from("stream reset"),
+            }),
+        ],
+    );
+
+    let result = export_workspace(
+        rt.handle(),
+        &engine,
+        &request(&workspace_path, WorkspaceExportFormat::Tar, &output),
+    );
+
+    ensure(
+        matches!(
+            &result,
+            Err(PodbotError::Container(
+                ContainerError::DownloadFailed { .. }
+            ))
+        ),
+        format!("expected DownloadFailed, got {result:?}"),
+    )?;
+    ensure(
+        host_entries(&output)?.is_empty(),
+        "interrupted export should remove its partial file",
+    )
+}
@@ -14,19 +14,31 @@
 
 mod connection;
 
+#[cfg(feature = "experimental")]
+pub(crate) use connection::{AcpSessionEvent, SessionEventSink, TokenRevocation};
 #[cfg(any(feature = "internal", test))]
 pub use connection::{
-    ContainerCreator, ContainerExecClient, ContainerSecurityOptions, ContainerUploader,
-    CreateContainerFuture, CreateContainerRequest, CreateExecFuture, CredentialUploadRequest,
-    CredentialUploadResult, EngineConnector, ExecMode, ExecRequest, ExecResult, GitIdentityResult,
-    HostCommandRunner, HostGitIdentity, InspectExecFuture, RepositoryCloneRequest,
-    RepositoryCloneResult, ResizeExecFuture, SelinuxLabelMode, SocketResolver, StartExecFuture,
-    SystemCommandRunner, UploadToContainerFuture, clone_repository_into_workspace,
-    configure_git_identity, read_host_git_identity,
+    AgentEnv, ContainerCreator, ContainerDownloader, ContainerExecClient, ContainerSecurityOptions,
+    ContainerUploader, CopyRequest, CopyResult, CreateContainerFuture, CreateContainerRequest,
+    CreateExecFuture, CredentialSkipReason, CredentialSyncAction, CredentialSyncRequest,
+    CredentialSyncResult, CredentialUploadRequest, CredentialUploadResult,
+    DownloadFromContainerStream, EngineConnector, ExecMode, ExecRequest, ExecResult,
+    GitIdentityResult, HostCommandRunner, HostCopyPath, HostGitIdentity, InspectExecFuture,
+    MAX_EXPORT_BYTES, RepositoryCloneRequest, RepositoryCloneResult, ResizeExecFuture,
+    SelinuxLabelMode, SkippedCredentialEntry, SocketResolver, StartExecFuture,
+    SyncedCredentialFile, SystemCommandRunner, UploadToContainerFuture, WorkspaceExportFormat,
+    WorkspaceExportRequest, WorkspaceExportResult, clone_repository_into_workspace,
+    configure_git_identity, copy_from_container, copy_to_container, export_workspace,
+    read_host_git_identity,
 };
 #[cfg(not(any(feature = "internal", test)))]
 pub(crate) use connection::{
-    ContainerExecClient, EngineConnector, ExecMode, ExecRequest, SocketResolver,
+    AgentEnv, ContainerExecClient, EngineConnector, ExecMode, ExecRequest, SocketResolver,
+};
+#[cfg(all(feature = "experimental", not(any(feature = "internal", test))))]
+pub(crate) use connection::{
+    ContainerDownloader, CopyRequest, HostCopyPath, WorkspaceExportFormat, WorkspaceExportRequest,
+    copy_from_container, copy_to_container, export_workspace,
 };
 
 #[cfg(test)]
@@ -113,6 +113,15 @@ For Podman: Use socket at /run/user/$UID/podman/podman.sock"
         message: String,
     },
 
+    /// Failed to download files from a container.
+    #[error("failed to download files from container '{container_id}': {message}")]
+    DownloadFailed {
+        /// The ID of the source container.
+        container_id: String,
+        /// A description of the download failure.
+        message: String,
+    },
+
     /// Failed to execute a command in a container.
     #[error("failed to execute command in container '{container_id}': {message}")]
     ExecFailed {
@@ -152,6 +161,8 @@ pub enum GitHubError {
     AuthenticationFailed {
         /// A description of the authentication failure.
         message: String,
+        /// Whether retrying the operation could succeed.
+        kind: GitHubFailureKind,
     },
 
     /// Failed to load the GitHub App private key.
@@ -163,11 +174,24 @@ pub enum GitHubError {
         message: String,
     },
 
+    /// The App installation covering a repository could not be resolved.
+    #[error("failed to resolve GitHub App installation for '{repository}': {message}")]
+    InstallationLookupFailed {
+        /// The repository in `owner/name` form.
+        repository: String,
+        /// A description of the lookup failure.
+        message: String,
+        /// Whether retrying the operation could succeed.
+        kind: GitHubFailureKind,
+    },
+
     /// Failed to acquire an installation token.
     #[error("failed to acquire installation token: {message}")]
     TokenAcquisitionFailed {
         /// A description of the token acquisition failure.
         message: String,
+        /// Whether retrying the operation could succeed.
+        kind: GitHubFailureKind,
     },
 
     /// The installation token has expired.
@@ -179,9 +203,63 @@ pub enum GitHubError {
     TokenRefreshFailed {
         /// A description of the refresh failure.
         message: String,
+        /// Whether retrying the operation could succeed.
+        kind: GitHubFailureKind,
+    },
+
+    /// Failed to revoke an installation token when a session ended.
+    #[error("failed to revoke installation token: {message}")]
+    TokenRevocationFailed {
+        /// A description of the revocation failure.
+        message: String,
+        /// Whether retrying the operation could succeed.
+        kind: GitHubFailureKind,
     },
 }
 
+impl GitHubError {
+    /// Return the retry classification of this failure.
+    ///
+    /// Key-loading failures and expired tokens are always permanent.
+    #[must_use]
+    pub const fn failure_kind(&self) -> GitHubFailureKind {
+        match self {
+            Self::AuthenticationFailed { kind, .. }
+            | Self::InstallationLookupFailed { kind, .. }
+            | Self::TokenAcquisitionFailed { kind, .. }
+            | Self::TokenRefreshFailed { kind, .. }
+            | Self::TokenRevocationFailed { kind, .. } => *kind,
+            Self::PrivateKeyLoadFailed { .. } | Self::TokenExpired => GitHubFailureKind::Permanent,
+        }
+    }
+}
+
+/// Retry classification of a failed GitHub operation.
+///
+/// The kind is set where the failure is observed, from the HTTP status or
+/// transport error, so retry decisions never depend on message wording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHubFailureKind {
+    /// GitHub rejected the request because a rate limit was exhausted.
+    RateLimited,
+    /// GitHub answered with a 5xx status.
+    Unavailable,
+    /// The request did not complete, for example because of a network or DNS
+    /// failure.
+    Transport,
+    /// Any other failure, such as rejected credentials or missing
+    /// permissions, which retrying cannot fix.
+    Permanent,
+}
+
+impl GitHubFailureKind {
+    /// Report whether a failure of this kind is worth retrying.
+    #[must_use]
+    pub const fn is_transient(self) -> bool {
+        !matches!(self, Self::Permanent)
+    }
+}
+
 /// Errors that can occur during filesystem operations.
 #[derive(Debug, Error)]
 pub enum FilesystemError {
@@ -104,6 +104,18 @@ fn container_error_start_failed_includes_container_id(container_id: String) {
     );
 }
 
+#[rstest]
+fn container_error_download_failed_includes_container_id(container_id: String) {
+    let error = ContainerError::DownloadFailed {
+        container_id,
+        message: String::from("no such file"),
+    };
+    assert_eq!(
+        error.to_string(),
+        "failed to download files from container 'abc123': no such file"
+    );
+}
+
 #[rstest]
 #[case::health_check_failed(
     ContainerError::HealthCheckFailed { message: String::from("ping failed") },
@@ -133,6 +145,7 @@ fn github_error_token_expired_displays_correctly() {
 #[rstest]
 fn github_error_auth_failed_displays_message() {
     let error = GitHubError::AuthenticationFailed {
+        kind: GitHubFailureKind::Permanent,
         message: String::from("invalid signature"),
     };
     assert_eq!(
@@ -141,6 +154,18 @@ fn github_error_auth_failed_displays_message() {
     );
 }
 
+#[rstest]
+fn github_error_token_revocation_failed_displays_message() {
+    let error = GitHubError::TokenRevocationFailed {
+        kind: GitHubFailureKind::Permanent,
+        message: String::from("GitHub responded with HTTP 500"),
+    };
+    assert_eq!(
+        error.to_string(),
+        "failed to revoke installation token: GitHub responded with HTTP 500"
+    );
+}
+
 #[rstest]
 fn filesystem_error_io_error_displays_message(config_path: PathBuf) {
     let error = FilesystemError::IoError {
@@ -181,6 +206,7 @@ fn podbot_error_wraps_container_error(container_id: String) {
 #[rstest]
 fn podbot_error_wraps_github_error() {
     let github_error = GitHubError::TokenRefreshFailed {
+        kind: GitHubFailureKind::Permanent,
         message: String::from("rate limited"),
     };
     let podbot_error: PodbotError = github_error.into();
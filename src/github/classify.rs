@@ -1,9 +1,10 @@
 //! Error classification for GitHub API responses.
 //!
 //! Maps HTTP status codes and error messages into actionable user-facing
-//! messages with remediation hints.
+//! messages with remediation hints, and records a [`GitHubFailureKind`] on
+//! each classified error so retry decisions do not depend on message text.
 
-use crate::error::GitHubError;
+use crate::error::{GitHubError, GitHubFailureKind};
 
 /// Classify a GitHub API error into an actionable authentication failure.
 ///
@@ -19,10 +20,13 @@ pub(super) fn classify_github_api_error(error: octocrab::Error) -> GitHubError {
         octocrab::Error::GitHub { ref source, .. } => {
             let code = source.status_code.as_u16();
             let raw = format!("{error}");
-            let msg = classify_by_status(code, &raw);
-            GitHubError::AuthenticationFailed { message: msg }
+            GitHubError::AuthenticationFailed {
+                message: classify_by_status(code, &raw),
+                kind: failure_kind_for_status(code, &raw),
+            }
         }
         _ => GitHubError::AuthenticationFailed {
+            kind: GitHubFailureKind::Transport,
             message: format!(
                 concat!(
                     "failed to validate GitHub App credentials: {error}. ",
@@ -35,6 +39,51 @@ pub(super) fn classify_github_api_error(error: octocrab::Error) -> GitHubError {
     }
 }
 
+/// Classify a failed repository installation lookup.
+///
+/// A 404 from `GET /repos/{owner}/{repo}/installation` means the App is not
+/// installed on the repository, or cannot see it, rather than an unknown App,
+/// so it gets its own hint. Every other failure is classified by
+/// [`classify_github_api_error`].
+pub(super) fn classify_installation_lookup_error(
+    error: octocrab::Error,
+    repository: &str,
+) -> GitHubError {
+    let status = match &error {
+        octocrab::Error::GitHub { source, .. } => Some(source.status_code.as_u16()),
+        _ => None,
+    };
+    let (message, kind) = match status {
+        Some(404) => (
+            installation_not_found_message(repository, &error.to_string()),
+            GitHubFailureKind::Permanent,
+        ),
+        _ => match classify_github_api_error(error) {
+            GitHubError::AuthenticationFailed { message, kind } => (message, kind),
+            other => (other.to_string(), other.failure_kind()),
+        },
+    };
+    GitHubError::InstallationLookupFailed {
+        repository: String::from(repository),
+        message,
+        kind,
+    }
+}
+
+/// Format the message for an App that is not installed on `repository`.
+fn installation_not_found_message(repository: &str, full_error: &str) -> String {
+    format!(
+        concat!(
+            "App is not installed on {repository} (HTTP 404). ",
+            "Hint: Install the GitHub App on the repository's owner and grant it ",
+            "access to the repository, or set github.installation_id explicitly. ",
+            "Raw error: {raw}",
+        ),
+        repository = repository,
+        raw = full_error,
+    )
+}
+
 /// Format a classified message for a known HTTP status code.
 ///
 /// `full_error` is the complete `Display` output from the Octocrab error,
@@ -99,6 +148,29 @@ pub(crate) fn classify_by_status(code: u16, full_error: &str) -> String {
     }
 }
 
+/// Return the retry classification for a GitHub response with status `code`.
+///
+/// `full_error` distinguishes a rate-limited 403 from missing permissions, as
+/// in [`classify_by_status`].
+#[must_use]
+pub(crate) fn failure_kind_for_status(code: u16, full_error: &str) -> GitHubFailureKind {
+    match code {
+        403 if is_rate_limited(full_error) => GitHubFailureKind::RateLimited,
+        500..=599 => GitHubFailureKind::Unavailable,
+        _ => GitHubFailureKind::Permanent,
+    }
+}
+
+/// Report whether a classified GitHub failure is transient.
+///
+/// Rate limits, GitHub 5xx responses, and transport failures are transient.
+/// Rejected credentials, missing permissions, unknown Apps, and key-loading
+/// failures are permanent, so retrying them cannot succeed.
+#[must_use]
+pub(crate) const fn is_transient_failure(error: &GitHubError) -> bool {
+    error.failure_kind().is_transient()
+}
+
 /// Check whether a GitHub API error message indicates a rate-limit
 /// response rather than a genuine permissions failure.
 ///
@@ -139,6 +211,70 @@ mod tests {
         assert!(is_rate_limited("api Rate Limit exceeded"));
     }
 
+    #[rstest::rstest]
+    #[case::rate_limited(403, "API rate limit exceeded", GitHubFailureKind::RateLimited)]
+    #[case::server_error(503, "Service Unavailable", GitHubFailureKind::Unavailable)]
+    #[case::bad_credentials(401, "Bad credentials", GitHubFailureKind::Permanent)]
+    #[case::forbidden(
+        403,
+        "Resource not accessible by integration",
+        GitHubFailureKind::Permanent
+    )]
+    #[case::not_found(404, "Not Found", GitHubFailureKind::Permanent)]
+    fn classified_status_codes_map_to_retry_policy(
+        #[case] code: u16,
+        #[case] raw: &str,
+        #[case] expected: GitHubFailureKind,
+    ) {
+        let error = GitHubError::TokenAcquisitionFailed {
+            message: classify_by_status(code, raw),
+            kind: failure_kind_for_status(code, raw),
+        };
+
+        assert_eq!(error.failure_kind(), expected);
+        assert_eq!(is_transient_failure(&error), expected.is_transient());
+    }
+
+    #[test]
+    fn missing_installations_are_permanent() {
+        let error = GitHubError::InstallationLookupFailed {
+            repository: String::from("leynos/podbot"),
+            message: installation_not_found_message("leynos/podbot", "Not Found"),
+            kind: GitHubFailureKind::Permanent,
+        };
+
+        assert!(
+            error
+                .to_string()
+                .contains("App is not installed on leynos/podbot")
+        );
+        assert!(!is_transient_failure(&error));
+    }
+
+    #[test]
+    fn retry_decisions_ignore_message_wording() {
+        let reworded = GitHubError::TokenAcquisitionFailed {
+            message: String::from("throttled by GitHub"),
+            kind: GitHubFailureKind::RateLimited,
+        };
+        let misleading = GitHubError::TokenAcquisitionFailed {
+            message: classify_by_status(503, "Service Unavailable"),
+            kind: GitHubFailureKind::Permanent,
+        };
+
+        assert!(is_transient_failure(&reworded));
+        assert!(!is_transient_failure(&misleading));
+    }
+
+    #[test]
+    fn key_and_expiry_failures_are_permanent() {
+        assert!(!is_transient_failure(&GitHubError::TokenExpired));
+        assert!(!is_transient_failure(&GitHubError::PrivateKeyLoadFailed {
+            path: std::path::PathBuf::from("/keys/app.pem"),
+            message: String::from("Check network connectivity"),
+        }));
+    }
+
     #[test]
     fn is_rate_limited_rejects_non_rate_limit_messages() {
         assert!(!is_rate_limited("Resource not accessible by integration"));
@@ -51,6 +51,35 @@ fn build_app_client_with_zero_app_id_succeeds(
     Ok(())
 }
 
+#[rstest]
+#[case::enterprise("https://github.example.com/api/v3", true)]
+#[case::invalid("https://github example.com", false)]
+fn build_app_client_with_base_uri_checks_url(
+    valid_rsa_pem: String,
+    temp_key_dir: io::Result<(TempDir, Utf8Dir)>,
+    #[case] api_base_url: &str,
+    #[case] expected_ok: bool,
+) -> eyre::Result<()> {
+    let (_tmp, dir) = temp_key_dir?;
+    dir.write("key.pem", &valid_rsa_pem)?;
+    let path = Utf8Path::new("/display/key.pem");
+    let key = load_private_key_from_dir(&dir, "key.pem", path).expect("should load valid key");
+    let rt = tokio::runtime::Runtime::new()?;
+    let _guard = rt.enter();
+    let result = build_app_client_with_base_uri(12345, key, Some(api_base_url));
+    ensure!(
+        result.is_ok() == expected_ok,
+        "unexpected result for {api_base_url}: {result:?}"
+    );
+    if let Err(error) = result {
+        ensure!(
+            error.to_string().contains("invalid GitHub API base URL"),
+            "error should name the base URL: {error}"
+        );
+    }
+    Ok(())
+}
+
 #[rstest]
 fn build_app_client_without_runtime_returns_error(
     valid_rsa_pem: String,
@@ -96,6 +125,7 @@ fn authentication_failed_error_includes_context(
     #[case] expected_context: &str,
 ) {
     let error = GitHubError::AuthenticationFailed {
+        kind: GitHubFailureKind::Permanent,
         message: String::from(message),
     };
     let display = error.to_string();
@@ -174,6 +204,7 @@ async fn validate_with_client_propagates_mock_error() {
     mock.expect_validate_credentials().times(1).returning(|| {
         Box::pin(async {
             Err(GitHubError::AuthenticationFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: String::from("mock authentication failure"),
             })
         })
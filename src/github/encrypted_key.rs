@@ -0,0 +1,144 @@
+//! Decryption of PKCS#8 encrypted private keys.
+//!
+//! An App key stored as `ENCRYPTED PRIVATE KEY` is decrypted with a
+//! passphrase read from an environment variable, a file, or the standard
+//! output of a command. The decrypted key is re-encoded as an unencrypted
+//! PKCS#8 PEM held in a [`SecretString`] and then validated like any other
+//! key. Neither the passphrase nor the key material appears in errors; errors
+//! name the passphrase source instead, for example
+//! `env:PODBOT_KEY_PASSPHRASE`. The passphrase is registered with the
+//! [`SecretRegistry`] as soon as it is read, so diagnostics never carry it
+//! either.
+
+use camino::Utf8Path;
+use cap_std::ambient_authority;
+use cap_std::fs_utf8::Dir;
+use pkcs8::der::Decode;
+use pkcs8::{Document, EncryptedPrivateKeyInfo, LineEnding};
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::api::SecretRegistry;
+use crate::config::PassphraseSource;
+use crate::error::GitHubError;
+
+use super::pem_validation::is_pkcs8_encrypted_pem;
+use super::private_key_source::{load_failed, run_secret_command};
+
+/// PEM label for the decrypted, unencrypted PKCS#8 key.
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// Decrypt `pem` with the configured passphrase when it is an encrypted
+/// PKCS#8 key.
+///
+/// Unencrypted keys, and encrypted keys without a passphrase source, are
+/// returned unchanged so `parse_rsa_pem` can accept or reject them.
+pub(super) fn decrypt_if_encrypted<E: mockable::Env>(
+    pem: SecretString,
+    passphrase: Option<&PassphraseSource>,
+    env: &E,
+    label: &Utf8Path,
+) -> Result<SecretString, GitHubError> {
+    let Some(source) = passphrase.filter(|_| is_pkcs8_encrypted_pem(pem.expose_secret())) else {
+        return Ok(pem);
+    };
+    let secret = read_passphrase(source, env)
+        .map_err(|message| load_failed(label, &format!("failed to read passphrase {message}")))?;
+    decrypt_pkcs8_pem(pem.expose_secret(), &secret, label)
+}
+
+fn decrypt_pkcs8_pem(
+    pem: &str,
+    passphrase: &SecretString,
+    label: &Utf8Path,
+) -> Result<SecretString, GitHubError> {
+    let (_, document) = Document::from_pem(pem.trim())
+        .map_err(|error| load_failed(label, &format!("invalid encrypted private key: {error}")))?;
+    let info = EncryptedPrivateKeyInfo::from_der(document.as_bytes())
+        .map_err(|error| load_failed(label, &format!("invalid encrypted private key: {error}")))?;
+    let decrypted = info
+        .decrypt(passphrase.expose_secret())
+        .map_err(|error| decryption_failed(label, error))?;
+    decrypted
+        .to_pem(PRIVATE_KEY_LABEL, LineEnding::LF)
+        .map(|key_pem| SecretString::from(key_pem.as_str()))
+        .map_err(|error| load_failed(label, &format!("failed to decode decrypted key: {error}")))
+}
+
+/// Report a wrong passphrase separately from other decryption failures.
+///
+/// A wrong passphrase normally fails the cipher padding check, and
+/// occasionally yields plaintext that is not valid DER. `pkcs5` 0.7 reports
+/// the padding failure as `EncryptFailed` even when decrypting.
+fn decryption_failed(label: &Utf8Path, error: pkcs8::Error) -> GitHubError {
+    match error {
+        pkcs8::Error::EncryptedPrivateKey(
+            pkcs8::pkcs5::Error::DecryptFailed | pkcs8::pkcs5::Error::EncryptFailed,
+        )
+        | pkcs8::Error::Asn1(_) => load_failed(
+            label,
+            "failed to decrypt private key: the passphrase is incorrect",
+        ),
+        other => load_failed(label, &format!("failed to decrypt private key: {other}")),
+    }
+}
+
+/// Read the passphrase and register it for redaction, returning an error
+/// message that names the source.
+fn read_passphrase<E: mockable::Env>(
+    source: &PassphraseSource,
+    env: &E,
+) -> Result<SecretString, String> {
+    let label = passphrase_label(source);
+    let passphrase = match source {
+        PassphraseSource::EnvVar(name) => env
+            .string(name)
+            .map(SecretString::from)
+            .ok_or_else(|| String::from("environment variable is not set")),
+        PassphraseSource::File(path) => read_passphrase_file(path),
+        PassphraseSource::Command(command) => run_secret_command(command),
+    };
+    let secret = passphrase
+        .map(|secret| trim_line_ending(&secret))
+        .map_err(|message| format!("from {label}: {message}"))?;
+    SecretRegistry::global().register_value(secret.expose_secret());
+    Ok(secret)
+}
+
+fn read_passphrase_file(path: &Utf8Path) -> Result<SecretString, String> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_str().is_empty())
+        .unwrap_or_else(|| Utf8Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| String::from("path does not contain a filename"))?;
+    Dir::open_ambient_dir(parent, ambient_authority())
+        .and_then(|dir| dir.read_to_string(file_name))
+        .map(SecretString::from)
+        .map_err(|error| format!("failed to read file: {error}"))
+}
+
+/// Drop a single trailing newline, which files and command output usually
+/// end with.
+fn trim_line_ending(secret: &SecretString) -> SecretString {
+    let value = secret.expose_secret();
+    let trimmed = value
+        .strip_suffix('\n')
+        .map_or(value, |line| line.strip_suffix('\r').unwrap_or(line));
+    SecretString::from(trimmed)
+}
+
+/// Name a passphrase source for error messages without exposing its contents.
+fn passphrase_label(source: &PassphraseSource) -> String {
+    match source {
+        PassphraseSource::EnvVar(name) => format!("env:{name}"),
+        PassphraseSource::File(path) => format!("file:{path}"),
+        PassphraseSource::Command(command) => {
+            format!("command:{}", command.first().map_or("", String::as_str))
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "encrypted_key_tests.rs"]
+mod tests;
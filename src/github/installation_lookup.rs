@@ -0,0 +1,149 @@
+//! Installation lookup for repositories.
+//!
+//! When `github.installation_id` is unset, podbot asks GitHub which
+//! installation of the App covers a repository through
+//! `GET /repos/{owner}/{repo}/installation`, authenticated with the App JWT.
+//! An installation belongs to an account, so every repository with the same
+//! owner resolves to the same installation and [`InstallationResolver`] only
+//! asks once per owner.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::error::GitHubError;
+
+use super::GitHubInstallationLookupClient;
+
+/// Resolves App installation IDs for repositories, caching them per owner.
+///
+/// Owners are compared case-insensitively, matching GitHub's handling of
+/// account names.
+pub struct InstallationResolver<'a> {
+    client: &'a dyn GitHubInstallationLookupClient,
+    cache: Mutex<HashMap<String, u64>>,
+}
+
+impl<'a> InstallationResolver<'a> {
+    /// Create a resolver with an empty cache that looks installations up
+    /// through `client`.
+    #[must_use]
+    pub fn new(client: &'a dyn GitHubInstallationLookupClient) -> Self {
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the installation ID covering `owner/name`.
+    ///
+    /// A cached ID for `owner` is returned without contacting GitHub. Failed
+    /// lookups are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitHubError::InstallationLookupFailed`] when the App is not
+    /// installed on the repository or the lookup fails.
+    pub async fn resolve(&self, owner: &str, name: &str) -> Result<u64, GitHubError> {
+        let key = owner.to_ascii_lowercase();
+        if let Some(installation_id) = self.cached(&key) {
+            return Ok(installation_id);
+        }
+
+        let installation_id = self
+            .client
+            .repository_installation_id(String::from(owner), String::from(name))
+            .await?;
+        self.remember(key, installation_id);
+        Ok(installation_id)
+    }
+
+    fn cached(&self, owner: &str) -> Option<u64> {
+        let installation_id = self.cache().get(owner).copied()?;
+        tracing::debug!(
+            owner,
+            installation_id,
+            "using cached GitHub App installation"
+        );
+        Some(installation_id)
+    }
+
+    fn remember(&self, owner: String, installation_id: u64) {
+        tracing::debug!(
+            owner = %owner,
+            installation_id,
+            "resolved GitHub App installation"
+        );
+        self.cache().insert(owner, installation_id);
+    }
+
+    fn cache(&self) -> MutexGuard<'_, HashMap<String, u64>> {
+        // The map is always left consistent, so a poisoned lock is safe to
+        // reuse.
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for repository installation lookup.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::error::GitHubFailureKind;
+    use crate::github::MockGitHubInstallationLookupClient;
+
+    fn lookup_returning(
+        expected_calls: usize,
+        result: impl Fn(&str) -> Result<u64, GitHubError> + Send + 'static,
+    ) -> MockGitHubInstallationLookupClient {
+        let mut client = MockGitHubInstallationLookupClient::new();
+        client
+            .expect_repository_installation_id()
+            .times(expected_calls)
+            .returning(move |owner, _| Box::pin(std::future::ready(result(&owner))));
+        client
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn resolve_caches_installations_per_owner() {
+        let client = lookup_returning(2, |owner| Ok(if owner == "leynos" { 7 } else { 9 }));
+        let resolver = InstallationResolver::new(&client);
+
+        let first = resolver.resolve("leynos", "podbot").await;
+        let same_owner = resolver.resolve("Leynos", "ortho-config").await;
+        let other_owner = resolver.resolve("example", "widgets").await;
+
+        assert_eq!(first.ok(), Some(7));
+        assert_eq!(same_owner.ok(), Some(7));
+        assert_eq!(other_owner.ok(), Some(9));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn resolve_does_not_cache_failures() {
+        let client = lookup_returning(2, |_| {
+            Err(GitHubError::InstallationLookupFailed {
+                kind: GitHubFailureKind::Permanent,
+                repository: String::from("leynos/podbot"),
+                message: String::from("App is not installed on leynos/podbot (HTTP 404)"),
+            })
+        });
+        let resolver = InstallationResolver::new(&client);
+
+        let first = resolver.resolve("leynos", "podbot").await;
+        let second = resolver.resolve("leynos", "podbot").await;
+
+        for result in [first, second] {
+            assert!(
+                matches!(
+                    &result,
+                    Err(GitHubError::InstallationLookupFailed { repository, .. })
+                        if repository == "leynos/podbot"
+                ),
+                "expected lookup failure, got {result:?}"
+            );
+        }
+    }
+}
@@ -3,18 +3,19 @@
 //! This module owns the adapter boundary for short-lived GitHub App
 //! installation access tokens. It converts Octocrab's secret token type into a
 //! Podbot-owned value that exposes the token string only through an explicit
-//! accessor and keeps non-secret expiry metadata available for logging and
-//! refresh scheduling.
+//! accessor and keeps non-secret expiry and scope metadata available for
+//! logging and refresh scheduling.
 
 use std::fmt;
 use std::time::{Duration, Instant, SystemTime};
 
-use octocrab::Octocrab;
-use secrecy::ExposeSecret;
 use tracing::{info, warn};
 
-use crate::error::GitHubError;
+use crate::api::SecretRegistry;
+use crate::error::{GitHubError, GitHubFailureKind};
+use crate::github::OctocrabAppClient;
 use crate::github::classify::classify_github_api_error;
+use crate::github::token_scope::{AccessTokenResponse, InstallationTokenScope};
 
 const GITHUB_INSTALLATION_TOKEN_LIFETIME: Duration = Duration::from_secs(60 * 60);
 
@@ -25,6 +26,7 @@ pub struct InstallationAccessToken {
     acquired_at: SystemTime,
     expires_at: SystemTime,
     refresh_after: SystemTime,
+    scope: InstallationTokenScope,
 }
 
 impl InstallationAccessToken {
@@ -42,6 +44,7 @@ impl InstallationAccessToken {
         let expires_at = acquired_at
             .checked_add(GITHUB_INSTALLATION_TOKEN_LIFETIME)
             .ok_or_else(|| GitHubError::TokenAcquisitionFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: String::from(
                     "failed to compute installation token metadata: expiry time overflowed",
                 ),
@@ -66,6 +69,7 @@ impl InstallationAccessToken {
     ) -> Result<Self, GitHubError> {
         if expires_at.duration_since(acquired_at).is_err() {
             return Err(GitHubError::TokenAcquisitionFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: String::from(
                     "failed to compute installation token metadata: expiry time precedes acquisition time",
                 ),
@@ -74,6 +78,7 @@ impl InstallationAccessToken {
 
         let refresh_after = expires_at.checked_sub(expiry_buffer).ok_or_else(|| {
             GitHubError::TokenAcquisitionFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: String::from(
                     "failed to compute installation token metadata: refresh time underflowed",
                 ),
@@ -82,20 +87,30 @@ impl InstallationAccessToken {
 
         if refresh_after.duration_since(acquired_at).is_err() {
             return Err(GitHubError::TokenAcquisitionFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: String::from(
                     "failed to compute installation token metadata: refresh time precedes acquisition time",
                 ),
             });
         }
 
+        SecretRegistry::global().register_value(&token);
         Ok(Self {
             token,
             acquired_at,
             expires_at,
             refresh_after,
+            scope: InstallationTokenScope::default(),
         })
     }
 
+    /// Returns the token with the scope GitHub granted recorded.
+    #[must_use]
+    pub fn with_scope(mut self, scope: InstallationTokenScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
     /// Returns the token string for Git credential delivery.
     #[must_use]
     pub fn token(&self) -> &str {
@@ -120,7 +135,15 @@ impl InstallationAccessToken {
         self.refresh_after
     }
 
-    /// Logs token timing metadata without exposing the token value.
+    /// Returns the repositories and permissions the token is limited to.
+    ///
+    /// The scope is empty for tokens acquired without restrictions.
+    #[must_use]
+    pub const fn scope(&self) -> &InstallationTokenScope {
+        &self.scope
+    }
+
+    /// Logs token timing and scope metadata without exposing the token value.
     pub fn log_timing(&self, installation_id: u64, expiry_buffer: Duration) {
         info!(
             installation_id,
@@ -128,6 +151,8 @@ impl InstallationAccessToken {
             expires_at = ?self.expires_at,
             refresh_after = ?self.refresh_after,
             expiry_buffer_seconds = expiry_buffer.as_secs(),
+            repositories = ?self.scope.repositories(),
+            permissions = ?self.scope.permissions(),
             "acquired GitHub App installation token"
         );
     }
@@ -141,42 +166,61 @@ impl fmt::Debug for InstallationAccessToken {
             .field("acquired_at", &self.acquired_at)
             .field("expires_at", &self.expires_at)
             .field("refresh_after", &self.refresh_after)
+            .field("scope", &self.scope)
             .finish()
     }
 }
 
-pub(super) async fn acquire_with_octocrab_installation(
-    installation: &Octocrab,
+/// Acquire an installation token limited to `scope` with the App client.
+///
+/// Octocrab's cached installation-token path cannot send a request body and
+/// hides the response headers, so this posts `scope` to
+/// `/app/installations/{id}/access_tokens` directly and records the
+/// repositories and permissions GitHub reports as granted. An empty `scope`
+/// requests an unscoped token. Every response's rate-limit budget, including
+/// a failure's, is recorded on `app`.
+pub(super) async fn acquire_scoped_with_octocrab_app(
+    app: &OctocrabAppClient,
     installation_id: u64,
+    scope: &InstallationTokenScope,
     expiry_buffer: Duration,
 ) -> Result<InstallationAccessToken, GitHubError> {
     let acquired_at = SystemTime::now();
-    let chrono_buffer = chrono::Duration::from_std(expiry_buffer).map_err(|error| {
-        GitHubError::TokenAcquisitionFailed {
-            message: format!("invalid token expiry buffer: {error}"),
-        }
-    })?;
+    let route = format!("/app/installations/{installation_id}/access_tokens");
 
     let started_at = Instant::now();
-    let secret_result = installation
-        .installation_token_with_buffer(chrono_buffer)
+    let response_result = app
+        .rate_limits
+        .parse::<AccessTokenResponse>(app.client._post(route.as_str(), Some(scope)).await)
         .await;
+    let response =
+        observe_acquisition(installation_id, expiry_buffer, started_at, response_result)?;
+    let (token, granted) = response.into_token_and_scope();
+    let access_token =
+        InstallationAccessToken::new(token, acquired_at, expiry_buffer)?.with_scope(granted);
+    access_token.log_timing(installation_id, expiry_buffer);
+    Ok(access_token)
+}
+
+/// Record metrics for an acquisition attempt and classify any failure.
+fn observe_acquisition<T>(
+    installation_id: u64,
+    expiry_buffer: Duration,
+    started_at: Instant,
+    result: Result<T, octocrab::Error>,
+) -> Result<T, GitHubError> {
     let elapsed = started_at.elapsed();
-    let secret = match secret_result {
-        Ok(secret) => {
+    match result {
+        Ok(value) => {
             record_token_acquisition_metrics("success", elapsed);
-            secret
+            Ok(value)
         }
         Err(error) => {
             record_token_acquisition_metrics("failure", elapsed);
             warn_token_acquisition_failure(installation_id, expiry_buffer, elapsed, &error);
-            return Err(classify_token_error(error));
+            Err(classify_token_error(error))
         }
-    };
-    let token = secret.expose_secret().to_owned();
-    let access_token = InstallationAccessToken::new(token, acquired_at, expiry_buffer)?;
-    access_token.log_timing(installation_id, expiry_buffer);
-    Ok(access_token)
+    }
 }
 
 fn record_token_acquisition_metrics(status: &'static str, elapsed: Duration) {
@@ -229,14 +273,17 @@ fn is_timeout_error(error: &octocrab::Error) -> bool {
 
 fn classify_token_error(error: octocrab::Error) -> GitHubError {
     let is_github_response = matches!(&error, octocrab::Error::GitHub { .. });
-    let message = classify_github_api_error(error).to_string();
+    let classified = classify_github_api_error(error);
+    let kind = classified.failure_kind();
+    let message = classified.to_string();
 
     if is_github_response {
         GitHubError::TokenAcquisitionFailed {
             message: format!("GitHub rejected installation token acquisition: {message}"),
+            kind,
         }
     } else {
-        GitHubError::TokenAcquisitionFailed { message }
+        GitHubError::TokenAcquisitionFailed { message, kind }
     }
 }
 
@@ -6,8 +6,12 @@ use rstest::{fixture, rstest};
 use snafu::GenerateImplicitData;
 
 use super::*;
+use crate::config::TokenPermissionLevel;
 use crate::github::test_support::{CounterEvent, RecordingMetrics};
-use crate::github::{MockGitHubInstallationTokenClient, acquire_installation_token_with_client};
+use crate::github::{
+    MockGitHubInstallationTokenClient, acquire_installation_token_with_client,
+    acquire_scoped_installation_token_with_client,
+};
 
 const FIXTURE_TOKEN: &str = "ghs_secret_fixture_token";
 const INSTALLATION_ID: u64 = 42;
@@ -60,7 +64,7 @@ fn assert_metadata_rejects(
         expiry_buffer,
     );
     match result {
-        Err(GitHubError::TokenAcquisitionFailed { message }) => {
+        Err(GitHubError::TokenAcquisitionFailed { message, .. }) => {
             assert!(
                 message.contains(expected_fragment),
                 "expected message to contain {expected_fragment:?}, got: {message}"
@@ -128,6 +132,47 @@ async fn acquire_with_client_returns_token(acquired_at: SystemTime, expiry_buffe
     assert_eq!(result.expect("mocked acquisition should succeed"), expected);
 }
 
+#[rstest]
+#[tokio::test]
+async fn acquire_scoped_with_client_records_granted_scope(
+    acquired_at: SystemTime,
+    expiry_buffer: Duration,
+) {
+    let requested = InstallationTokenScope::default()
+        .with_repository("podbot")
+        .with_permission("contents", TokenPermissionLevel::Write)
+        .with_permission("pull_requests", TokenPermissionLevel::Write);
+    let expected_scope = requested.clone();
+    let mut mock = MockGitHubInstallationTokenClient::new();
+    mock.expect_acquire_scoped_installation_token()
+        .withf(move |installation_id, scope, _| {
+            *installation_id == INSTALLATION_ID && *scope == expected_scope
+        })
+        .times(1)
+        .return_once(move |_, scope, buffer| {
+            let token =
+                InstallationAccessToken::new(String::from(FIXTURE_TOKEN), acquired_at, buffer)
+                    .map(|token| token.with_scope(scope));
+            Box::pin(async move { token })
+        });
+
+    let token = acquire_scoped_installation_token_with_client(
+        &mock,
+        INSTALLATION_ID,
+        &requested,
+        expiry_buffer,
+    )
+    .await
+    .expect("mocked acquisition should succeed");
+
+    assert_eq!(token.scope(), &requested);
+    let debug_output = format!("{token:?}");
+    assert!(
+        debug_output.contains("pull_requests") && !debug_output.contains(FIXTURE_TOKEN),
+        "debug output should show scope but not the token: {debug_output}"
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn acquire_with_client_maps_semantic_failure(expiry_buffer: Duration) {
@@ -137,6 +182,7 @@ async fn acquire_with_client_maps_semantic_failure(expiry_buffer: Duration) {
         .returning(|_, _| {
             Box::pin(async {
                 Err(GitHubError::TokenAcquisitionFailed {
+                    kind: GitHubFailureKind::Permanent,
                     message: String::from("installation suspended"),
                 })
             })
@@ -146,7 +192,7 @@ async fn acquire_with_client_maps_semantic_failure(expiry_buffer: Duration) {
         acquire_installation_token_with_client(&mock, INSTALLATION_ID, expiry_buffer).await;
 
     match result {
-        Err(GitHubError::TokenAcquisitionFailed { message }) => {
+        Err(GitHubError::TokenAcquisitionFailed { message, .. }) => {
             assert!(message.contains("installation suspended"));
             assert!(
                 !message.contains(FIXTURE_TOKEN),
@@ -169,7 +215,8 @@ fn token_error_mapping_preserves_transport_failure_context() {
     let classified = classify_token_error(error);
 
     match classified {
-        GitHubError::TokenAcquisitionFailed { message } => {
+        GitHubError::TokenAcquisitionFailed { message, kind } => {
+            assert_eq!(kind, GitHubFailureKind::Transport);
             assert!(
                 message.contains("connectivity") || message.contains("network"),
                 "expected transport remediation context in: {message}"
@@ -5,15 +5,28 @@
 //! and validating credentials against the GitHub API. It validates that
 //! private key files contain PEM-encoded RSA keys, rejecting Ed25519 and
 //! ECDSA keys at load time because GitHub App authentication requires
-//! RS256.
+//! RS256. Acquired installation tokens are written atomically into a
+//! per-container runtime directory that is bind-mounted into the sandbox, and
+//! a refresh loop replaces them before they expire. When no installation ID is
+//! configured, the installation is looked up from the target repository. The
+//! last token is revoked when the session ends. The rate-limit budget reported
+//! by each response is tracked so callers can pace their requests.
 //!
 //! **Stability:** This module is internal to the library and subject to
 //! change as the GitHub integration stabilizes.
 
 mod classify;
+mod encrypted_key;
+mod installation_lookup;
 mod installation_token;
 mod pem_validation;
+mod private_key_source;
+mod rate_limit;
 mod retry_metrics;
+mod token_file;
+mod token_refresh;
+mod token_revocation;
+mod token_scope;
 
 use std::future::Future;
 use std::path::PathBuf;
@@ -24,16 +37,39 @@ use camino::Utf8Path;
 use cap_std::ambient_authority;
 use cap_std::fs_utf8::Dir;
 use jsonwebtoken::EncodingKey;
+use secrecy::{ExposeSecret, SecretString};
 
 use octocrab::Octocrab;
-use octocrab::models::{AppId, InstallationId};
+use octocrab::models::AppId;
 use octocrab::service::middleware::retry::RetryConfig;
 
-use crate::error::GitHubError;
-use classify::classify_github_api_error;
+use crate::config::{PassphraseSource, PrivateKeySource};
+use crate::error::{GitHubError, GitHubFailureKind};
+use classify::{classify_github_api_error, classify_installation_lookup_error};
+pub use installation_lookup::InstallationResolver;
 pub use installation_token::InstallationAccessToken;
+#[cfg(any(feature = "internal", test))]
 use pem_validation::parse_rsa_pem;
+#[cfg(any(feature = "internal", test))]
+pub use private_key_source::load_private_key_from_source;
+pub use private_key_source::load_private_key_off_runtime;
+#[cfg(any(feature = "internal", test))]
+pub use rate_limit::RateLimitSnapshot;
+pub use rate_limit::{RateLimitBudget, RateLimitTracker};
 use retry_metrics::PodbotOctocrabRetryMetrics;
+pub use token_file::TokenRuntimeDir;
+#[cfg(any(feature = "internal", test))]
+pub use token_file::{
+    CONTAINER_SECRETS_DIR, CONTAINER_TOKEN_PATH, READY_FILE_NAME, TOKEN_FILE_NAME,
+};
+#[cfg(any(feature = "internal", test))]
+pub use token_refresh::RefreshSleeper;
+pub use token_refresh::{
+    ContainerLiveness, RefreshLoopExit, RefreshRetryPolicy, TokenRefreshLoop, TokioRefreshSleeper,
+    random_jitter,
+};
+pub use token_revocation::revoke_installation_token;
+pub use token_scope::InstallationTokenScope;
 
 /// A boxed future for async trait methods.
 ///
@@ -64,6 +100,7 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 /// - The file contains an encrypted private key.
 /// - The file contains an ECDSA or Ed25519 key.
 /// - The content is not a valid PEM-encoded RSA private key.
+#[cfg(any(feature = "internal", test))]
 pub fn load_private_key(key_path: &Utf8Path) -> Result<EncodingKey, GitHubError> {
     let (dir, file_name) = open_key_directory(key_path)?;
     load_private_key_from_dir(&dir, file_name, key_path)
@@ -92,12 +129,33 @@ pub fn load_private_key(key_path: &Utf8Path) -> Result<EncodingKey, GitHubError>
 /// - No Tokio runtime context is active.
 /// - The Octocrab builder fails to construct the HTTP client (for
 ///   example, due to TLS initialization failure).
+#[cfg(any(feature = "internal", test))]
 pub fn build_app_client(app_id: u64, private_key: EncodingKey) -> Result<Octocrab, GitHubError> {
+    build_app_client_with_base_uri(app_id, private_key, None)
+}
+
+/// Build an authenticated Octocrab client against a specific REST API.
+///
+/// `api_base_url` selects the API root, such as
+/// `https://github.example.com/api/v3` for GitHub Enterprise Server; `None`
+/// targets `https://api.github.com`. Any path in the base URL is kept as a
+/// prefix of every request path.
+///
+/// # Errors
+///
+/// Returns [`GitHubError::AuthenticationFailed`] in the same cases as
+/// [`build_app_client`], and when `api_base_url` is not a valid URI.
+pub fn build_app_client_with_base_uri(
+    app_id: u64,
+    private_key: EncodingKey,
+    api_base_url: Option<&str>,
+) -> Result<Octocrab, GitHubError> {
     // Guard: Octocrab's build() internally spawns a Tower Buffer task
     // via tokio::spawn. Without an active runtime the call panics.
     // Check up front and return a descriptive error instead.
     let _handle =
         tokio::runtime::Handle::try_current().map_err(|_| GitHubError::AuthenticationFailed {
+            kind: GitHubFailureKind::Permanent,
             message: String::from(
                 "failed to build GitHub App client: \
                  no Tokio runtime context is active \
@@ -105,15 +163,28 @@ pub fn build_app_client(app_id: u64, private_key: EncodingKey) -> Result<Octocra
             ),
         })?;
 
-    Octocrab::builder()
+    let builder = Octocrab::builder()
         .app(AppId(app_id), private_key)
         .add_retry_config(RetryConfig::HandleRateLimits {
             metrics: Arc::new(PodbotOctocrabRetryMetrics),
             max_retries: 3,
             min_wait_seconds: 1,
-        })
+        });
+    let configured = match api_base_url {
+        Some(base_url) => {
+            builder
+                .base_uri(base_url)
+                .map_err(|error| GitHubError::AuthenticationFailed {
+                    kind: GitHubFailureKind::Permanent,
+                    message: format!("invalid GitHub API base URL '{base_url}': {error}"),
+                })?
+        }
+        None => builder,
+    };
+    configured
         .build()
         .map_err(|error| GitHubError::AuthenticationFailed {
+            kind: GitHubFailureKind::Permanent,
             message: format!("failed to build GitHub App client: {error}"),
         })
 }
@@ -155,27 +226,87 @@ pub trait GitHubInstallationTokenClient: Send + Sync {
         installation_id: u64,
         expiry_buffer: std::time::Duration,
     ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>>;
+
+    /// Acquires an installation access token limited to `scope`.
+    ///
+    /// The returned token records the scope GitHub granted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitHubError::TokenAcquisitionFailed`] if GitHub rejects the
+    /// request, for example because a repository is outside the installation
+    /// or a permission exceeds what the App was granted.
+    fn acquire_scoped_installation_token(
+        &self,
+        installation_id: u64,
+        scope: InstallationTokenScope,
+        expiry_buffer: std::time::Duration,
+    ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>>;
+}
+
+/// Trait for resolving the App installation that covers a repository.
+///
+/// Production code uses [`OctocrabAppClient`], which authenticates the lookup
+/// with the App JWT, while tests inject mock implementations via `mockall`.
+#[cfg_attr(test, mockall::automock)]
+pub trait GitHubInstallationLookupClient: Send + Sync {
+    /// Looks up the installation ID of the App on `owner/name`.
+    ///
+    /// Calls `GET /repos/{owner}/{repo}/installation`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitHubError::InstallationLookupFailed`] if the App is not
+    /// installed on the repository or GitHub rejects the request.
+    fn repository_installation_id(
+        &self,
+        owner: String,
+        name: String,
+    ) -> BoxFuture<'_, Result<u64, GitHubError>>;
 }
 
 /// Production implementation of [`GitHubAppClient`] using Octocrab.
+///
+/// Every response the client receives updates its App rate-limit budget.
 pub struct OctocrabAppClient {
     client: Octocrab,
+    rate_limits: RateLimitTracker,
 }
 
 impl OctocrabAppClient {
     /// Creates a new `OctocrabAppClient` from an authenticated Octocrab
     /// instance.
     #[must_use]
-    pub const fn new(client: Octocrab) -> Self {
-        Self { client }
+    pub fn new(client: Octocrab) -> Self {
+        Self {
+            client,
+            rate_limits: RateLimitTracker::new(RateLimitBudget::App),
+        }
+    }
+
+    /// Returns the tracker holding the App's latest rate-limit budget.
+    ///
+    /// The tracker shares its snapshot with the client, so a clone keeps
+    /// observing later responses.
+    #[must_use]
+    pub const fn rate_limits(&self) -> &RateLimitTracker {
+        &self.rate_limits
+    }
+
+    /// Returns the App's rate-limit budget from the latest response that
+    /// reported one.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub fn rate_limit(&self) -> Option<RateLimitSnapshot> {
+        self.rate_limits.snapshot()
     }
 }
 
 impl GitHubAppClient for OctocrabAppClient {
     fn validate_credentials(&self) -> BoxFuture<'_, Result<(), GitHubError>> {
         Box::pin(async move {
-            self.client
-                .get::<(), _, ()>("/app", None)
+            self.rate_limits
+                .parse::<()>(self.client._get("/app").await)
                 .await
                 .map_err(classify_github_api_error)?;
             Ok(())
@@ -190,20 +321,52 @@ impl GitHubInstallationTokenClient for OctocrabAppClient {
         expiry_buffer: std::time::Duration,
     ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>> {
         Box::pin(async move {
-            let installation = self
-                .client
-                .installation(InstallationId(installation_id))
-                .map_err(|error| GitHubError::TokenAcquisitionFailed {
-                    message: format!("failed to prepare GitHub installation client: {error}"),
-                })?;
-            installation_token::acquire_with_octocrab_installation(
-                &installation,
+            installation_token::acquire_scoped_with_octocrab_app(
+                self,
                 installation_id,
+                &InstallationTokenScope::default(),
                 expiry_buffer,
             )
             .await
         })
     }
+
+    fn acquire_scoped_installation_token(
+        &self,
+        installation_id: u64,
+        scope: InstallationTokenScope,
+        expiry_buffer: std::time::Duration,
+    ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>> {
+        Box::pin(async move {
+            installation_token::acquire_scoped_with_octocrab_app(
+                self,
+                installation_id,
+                &scope,
+                expiry_buffer,
+            )
+            .await
+        })
+    }
+}
+
+impl GitHubInstallationLookupClient for OctocrabAppClient {
+    fn repository_installation_id(
+        &self,
+        owner: String,
+        name: String,
+    ) -> BoxFuture<'_, Result<u64, GitHubError>> {
+        Box::pin(async move {
+            let route = format!("/repos/{owner}/{name}/installation");
+            let installation = self
+                .rate_limits
+                .parse::<octocrab::models::Installation>(self.client._get(route.as_str()).await)
+                .await
+                .map_err(|error| {
+                    classify_installation_lookup_error(error, &format!("{owner}/{name}"))
+                })?;
+            Ok(installation.id.0)
+        })
+    }
 }
 
 /// Validates GitHub App credentials by loading the private key, building
@@ -241,20 +404,72 @@ impl GitHubInstallationTokenClient for OctocrabAppClient {
 /// # }
 /// # }
 /// ```
+#[cfg(any(feature = "internal", test))]
 pub async fn validate_app_credentials(
     app_id: u64,
     private_key_path: &Utf8Path,
 ) -> Result<(), GitHubError> {
-    let redacted_private_key_path = private_key_path.file_name().unwrap_or("<redacted>");
+    validate_app_credentials_with_base_uri(app_id, private_key_path, None).await
+}
+
+/// Validates GitHub App credentials against a specific REST API.
+///
+/// Behaves like [`validate_app_credentials`], calling `/app` under
+/// `api_base_url` when one is given, as GitHub Enterprise Server requires.
+///
+/// # Errors
+///
+/// Returns the same errors as [`validate_app_credentials`], and
+/// [`GitHubError::AuthenticationFailed`] when `api_base_url` is not a valid
+/// URI.
+#[cfg(any(feature = "internal", test))]
+pub async fn validate_app_credentials_with_base_uri(
+    app_id: u64,
+    private_key_path: &Utf8Path,
+    api_base_url: Option<&str>,
+) -> Result<(), GitHubError> {
+    let source = PrivateKeySource::Path(private_key_path.to_owned());
+    validate_app_credentials_from_source(app_id, &source, None, api_base_url).await
+}
+
+/// Validates GitHub App credentials whose private key comes from `source`.
+///
+/// The key is loaded with [`load_private_key_off_runtime`], decrypting it
+/// with `passphrase` when it is an encrypted PKCS#8 key and reading
+/// environment-variable sources from the process environment.
+///
+/// # Errors
+///
+/// Returns the same errors as [`validate_app_credentials_with_base_uri`].
+pub async fn validate_app_credentials_from_source(
+    app_id: u64,
+    source: &PrivateKeySource,
+    passphrase: Option<&PassphraseSource>,
+    api_base_url: Option<&str>,
+) -> Result<(), GitHubError> {
+    log_validation(app_id, source);
+    let private_key = load_private_key_off_runtime(source, passphrase).await?;
+    let octocrab = build_app_client_with_base_uri(app_id, private_key, api_base_url)?;
+    let client = OctocrabAppClient::new(octocrab);
+    validate_with_client(&client).await
+}
+
+fn log_validation(app_id: u64, source: &PrivateKeySource) {
     tracing::debug!(
         app_id,
-        private_key_path = %redacted_private_key_path,
+        private_key_source = %redacted_key_source(source),
         "validating GitHub App credentials"
     );
-    let private_key = load_private_key(private_key_path)?;
-    let octocrab = build_app_client(app_id, private_key)?;
-    let client = OctocrabAppClient::new(octocrab);
-    validate_with_client(&client).await
+}
+
+/// Describe a key source for logs without naming secrets or full paths.
+fn redacted_key_source(source: &PrivateKeySource) -> &str {
+    match source {
+        PrivateKeySource::Path(path) => path.file_name().unwrap_or("<redacted>"),
+        PrivateKeySource::EnvVar(_) => "<environment variable>",
+        PrivateKeySource::Fd(_) => "<file descriptor>",
+        PrivateKeySource::Command(_) => "<command>",
+    }
 }
 
 /// Validates credentials using the provided client.
@@ -288,6 +503,24 @@ pub async fn acquire_installation_token_with_client(
         .acquire_installation_token(installation_id, expiry_buffer)
         .await
 }
+
+/// Acquires an installation token limited to `scope` using the provided
+/// client.
+///
+/// # Errors
+///
+/// Returns [`GitHubError::TokenAcquisitionFailed`] if token acquisition fails.
+pub async fn acquire_scoped_installation_token_with_client(
+    client: &dyn GitHubInstallationTokenClient,
+    installation_id: u64,
+    scope: &InstallationTokenScope,
+    expiry_buffer: std::time::Duration,
+) -> Result<InstallationAccessToken, GitHubError> {
+    client
+        .acquire_scoped_installation_token(installation_id, scope.clone(), expiry_buffer)
+        .await
+}
+#[cfg(any(feature = "internal", test))]
 pub async fn validate_with_factory<F, C>(
     app_id: u64,
     private_key_path: &Utf8Path,
@@ -306,13 +539,14 @@ where
 ///
 /// Separated from [`load_private_key`] for testability: tests provide a
 /// `cap_std::fs_utf8::Dir` backed by a temporary directory.
+#[cfg(any(feature = "internal", test))]
 fn load_private_key_from_dir(
     dir: &Dir,
     file_name: &str,
     display_path: &Utf8Path,
 ) -> Result<EncodingKey, GitHubError> {
     let pem_contents = read_key_file(dir, file_name, display_path)?;
-    parse_rsa_pem(&pem_contents, display_path)
+    parse_rsa_pem(pem_contents.expose_secret(), display_path)
 }
 
 /// Open the parent directory of the key path as a capability handle.
@@ -343,15 +577,16 @@ fn read_key_file(
     dir: &Dir,
     file_name: &str,
     display_path: &Utf8Path,
-) -> Result<String, GitHubError> {
-    let contents =
-        dir.read_to_string(file_name)
-            .map_err(|error| GitHubError::PrivateKeyLoadFailed {
-                path: PathBuf::from(display_path.as_std_path()),
-                message: format!("failed to read file: {error}"),
-            })?;
-
-    if contents.trim().is_empty() {
+) -> Result<SecretString, GitHubError> {
+    let contents = dir
+        .read_to_string(file_name)
+        .map(SecretString::from)
+        .map_err(|error| GitHubError::PrivateKeyLoadFailed {
+            path: PathBuf::from(display_path.as_std_path()),
+            message: format!("failed to read file: {error}"),
+        })?;
+
+    if contents.expose_secret().trim().is_empty() {
         return Err(GitHubError::PrivateKeyLoadFailed {
             path: PathBuf::from(display_path.as_std_path()),
             message: "file is empty".to_owned(),
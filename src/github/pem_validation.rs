@@ -3,15 +3,18 @@
 //! This module validates PEM-encoded private key files, rejecting unsupported
 //! key types with error messages. ECDSA (SEC 1 format), public keys,
 //! certificates, and encrypted keys are detected by their PEM headers and
-//! produce specific error messages. Ed25519 and other PKCS#8-wrapped keys
-//! that are not RSA fall through to the `jsonwebtoken` parser, which returns
-//! a generic "invalid RSA private key" error.
+//! produce specific error messages. Encrypted PKCS#8 keys reach this module
+//! only when no passphrase is configured; see the `encrypted_key` module.
+//! Ed25519 and other PKCS#8-wrapped keys that are not RSA fall through to the
+//! `jsonwebtoken` parser, which returns a generic "invalid RSA private key"
+//! error.
 
 use std::path::PathBuf;
 
 use camino::Utf8Path;
 use jsonwebtoken::EncodingKey;
 
+use crate::api::SecretRegistry;
 use crate::error::GitHubError;
 
 /// PEM tag for ECDSA private keys (SEC 1 / RFC 5915 format).
@@ -93,47 +96,61 @@ pub(super) fn validate_rsa_pem(
     Ok(())
 }
 
-/// Check whether PEM content represents an encrypted private key.
-///
-/// Detects both PKCS#8 encrypted keys (`ENCRYPTED PRIVATE KEY` header)
-/// and legacy OpenSSL-encrypted keys with a `Proc-Type: 4,ENCRYPTED`
-/// header. Leading blank lines and whitespace are skipped so the check
-/// works with padded input.
-fn is_encrypted_pem(pem_contents: &str) -> bool {
-    let mut lines = pem_contents
+/// Non-blank, trimmed PEM lines, skipping leading blank lines.
+fn pem_lines(pem_contents: &str) -> impl Iterator<Item = &str> {
+    pem_contents
         .lines()
         .map(str::trim)
-        .skip_while(|line| line.is_empty());
+        .skip_while(|line| line.is_empty())
+}
 
-    // PKCS#8 encrypted key: explicit header on the first non-blank line.
-    let first_line_encrypted = lines
+/// Check whether PEM content is a PKCS#8 encrypted private key
+/// (`ENCRYPTED PRIVATE KEY` header on the first non-blank line).
+pub(super) fn is_pkcs8_encrypted_pem(pem_contents: &str) -> bool {
+    pem_lines(pem_contents)
         .next()
-        .is_some_and(|first| first.contains(ENCRYPTED_PRIVATE_KEY_TAG));
+        .is_some_and(|first| first.contains(ENCRYPTED_PRIVATE_KEY_TAG))
+}
 
-    // Legacy OpenSSL: Proc-Type header before the blank-line separator.
-    let legacy_encrypted = lines
+/// Check whether PEM content is a legacy OpenSSL-encrypted key, marked by a
+/// `Proc-Type: 4,ENCRYPTED` header before the blank-line separator.
+fn is_legacy_encrypted_pem(pem_contents: &str) -> bool {
+    pem_lines(pem_contents)
+        .skip(1)
         .take_while(|line| !line.is_empty())
-        .any(|line| line.starts_with("Proc-Type:") && line.contains("4,ENCRYPTED"));
-
-    first_line_encrypted || legacy_encrypted
+        .any(|line| line.starts_with("Proc-Type:") && line.contains("4,ENCRYPTED"))
 }
 
 /// Parse PEM content into an RSA `EncodingKey`.
 ///
-/// Encrypted private keys (for example, those with a
-/// `BEGIN ENCRYPTED PRIVATE KEY` header or a `Proc-Type: 4,ENCRYPTED`
-/// header) are rejected with a specific error message, since
-/// `EncodingKey::from_rsa_pem` does not support them.
+/// Encrypted private keys are rejected with a specific error message, since
+/// `EncodingKey::from_rsa_pem` does not support them. For PKCS#8 keys the
+/// message points at the passphrase settings; legacy OpenSSL encryption
+/// (`Proc-Type: 4,ENCRYPTED`) must be converted to PKCS#8 first.
 pub(super) fn parse_rsa_pem(
     pem_contents: &str,
     display_path: &Utf8Path,
 ) -> Result<EncodingKey, GitHubError> {
-    if is_encrypted_pem(pem_contents) {
+    SecretRegistry::global().register_value(pem_contents);
+    if is_pkcs8_encrypted_pem(pem_contents) {
+        return Err(GitHubError::PrivateKeyLoadFailed {
+            path: PathBuf::from(display_path.as_std_path()),
+            message: concat!(
+                "encrypted private key requires a passphrase; set ",
+                "github.private_key_passphrase_env, github.private_key_passphrase_file, ",
+                "or github.private_key_passphrase_command"
+            )
+            .to_owned(),
+        });
+    }
+
+    if is_legacy_encrypted_pem(pem_contents) {
         return Err(GitHubError::PrivateKeyLoadFailed {
             path: PathBuf::from(display_path.as_std_path()),
             message: concat!(
-                "encrypted private keys are not supported; ",
-                "provide an unencrypted RSA private key"
+                "legacy OpenSSL-encrypted private keys are not supported; convert ",
+                "the key to encrypted PKCS#8 with: ",
+                "openssl pkcs8 -topk8 -v2 aes-256-cbc -in <keyfile>"
             )
             .to_owned(),
         });
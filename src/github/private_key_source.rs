@@ -0,0 +1,245 @@
+//! Private key loading from configured key sources.
+//!
+//! Besides a PEM file, the GitHub App key can come from an environment
+//! variable, a file descriptor inherited from the parent process, or the
+//! standard output of a command such as a secrets-manager CLI. Each source
+//! yields the PEM as a [`SecretString`] and goes through the same
+//! `parse_rsa_pem` validation as file-based keys, so unsupported key types are
+//! reported the same way whatever the source. Encrypted PKCS#8 keys from any
+//! source are decrypted first when a passphrase source is configured. Error
+//! messages name the source but never include key material, passphrases, or
+//! command arguments.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use jsonwebtoken::EncodingKey;
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::config::{PassphraseSource, PrivateKeySource};
+use crate::error::GitHubError;
+
+use super::encrypted_key::decrypt_if_encrypted;
+use super::pem_validation::parse_rsa_pem;
+use super::{open_key_directory, read_key_file};
+
+/// Load a GitHub App RSA private key from `source`.
+///
+/// [`PrivateKeySource::Path`] reads the file like
+/// [`load_private_key`](super::load_private_key). Environment variables are
+/// read through `env`. Commands run with a null standard input and the
+/// caller's standard error, and must exit successfully. When `passphrase` is
+/// set, an `ENCRYPTED PRIVATE KEY` is decrypted with it before parsing.
+///
+/// # Errors
+///
+/// Returns [`GitHubError::PrivateKeyLoadFailed`] when the source cannot be
+/// read, yields an empty or non-UTF-8 key, cannot be decrypted, or does not
+/// contain a supported RSA private key. The error path names the source, for
+/// example `env:PODBOT_APP_KEY`, `fd:3`, or `command:vault`.
+pub fn load_private_key_from_source<E: mockable::Env>(
+    source: &PrivateKeySource,
+    passphrase: Option<&PassphraseSource>,
+    env: &E,
+) -> Result<EncodingKey, GitHubError> {
+    let label = source_label(source);
+    let pem = match source {
+        PrivateKeySource::Path(path) => read_path(path)?,
+        PrivateKeySource::EnvVar(name) => read_env(env, name, &label)?,
+        PrivateKeySource::Fd(fd) => read_fd(*fd, &label)?,
+        PrivateKeySource::Command(argv) => {
+            run_secret_command(argv).map_err(|message| load_failed(&label, &message))?
+        }
+    };
+
+    if pem.expose_secret().trim().is_empty() {
+        return Err(load_failed(&label, "private key is empty"));
+    }
+    let key_pem = decrypt_if_encrypted(pem, passphrase, env, &label)?;
+    parse_rsa_pem(key_pem.expose_secret(), &label)
+}
+
+/// Load a GitHub App RSA private key from `source` on Tokio's blocking pool.
+///
+/// Reading files and running key or passphrase commands block, so async
+/// callers use this instead of [`load_private_key_from_source`] to keep them
+/// off the runtime's worker threads. Environment-variable sources are read
+/// from the process environment.
+///
+/// # Errors
+///
+/// Returns the same errors as [`load_private_key_from_source`], and
+/// [`GitHubError::PrivateKeyLoadFailed`] if the blocking task fails.
+pub async fn load_private_key_off_runtime(
+    source: &PrivateKeySource,
+    passphrase: Option<&PassphraseSource>,
+) -> Result<EncodingKey, GitHubError> {
+    let label = source_label(source);
+    let owned_source = source.clone();
+    let owned_passphrase = passphrase.cloned();
+    tokio::task::spawn_blocking(move || {
+        load_private_key_from_source(
+            &owned_source,
+            owned_passphrase.as_ref(),
+            &mockable::DefaultEnv::new(),
+        )
+    })
+    .await
+    .unwrap_or_else(|error| Err(load_failed(&label, &format!("key loading failed: {error}"))))
+}
+
+/// Name a key source for error messages without exposing its contents.
+fn source_label(source: &PrivateKeySource) -> Utf8PathBuf {
+    match source {
+        PrivateKeySource::Path(path) => path.clone(),
+        PrivateKeySource::EnvVar(name) => Utf8PathBuf::from(format!("env:{name}")),
+        PrivateKeySource::Fd(fd) => Utf8PathBuf::from(format!("fd:{fd}")),
+        PrivateKeySource::Command(argv) => Utf8PathBuf::from(format!(
+            "command:{}",
+            argv.first().map_or("", String::as_str)
+        )),
+    }
+}
+
+fn read_path(path: &Utf8Path) -> Result<SecretString, GitHubError> {
+    let (dir, file_name) = open_key_directory(path)?;
+    read_key_file(&dir, file_name, path)
+}
+
+fn read_env<E: mockable::Env>(
+    env: &E,
+    name: &str,
+    label: &Utf8Path,
+) -> Result<SecretString, GitHubError> {
+    env.string(name)
+        .map(SecretString::from)
+        .ok_or_else(|| load_failed(label, "environment variable is not set"))
+}
+
+#[cfg(unix)]
+fn read_fd(fd: i32, label: &Utf8Path) -> Result<SecretString, GitHubError> {
+    // Reopening through /dev/fd avoids taking ownership of the descriptor, so
+    // the caller's handle is neither closed nor moved.
+    std::fs::read_to_string(format!("/dev/fd/{fd}"))
+        .map(SecretString::from)
+        .map_err(|error| load_failed(label, &format!("failed to read file descriptor: {error}")))
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: i32, label: &Utf8Path) -> Result<SecretString, GitHubError> {
+    Err(load_failed(
+        label,
+        "file descriptor key sources are only supported on Unix",
+    ))
+}
+
+/// Largest standard output accepted from a secret command; PEM keys and
+/// passphrases are a few kilobytes at most.
+const MAX_SECRET_COMMAND_OUTPUT: usize = 64 * 1024;
+
+/// Longest a secret command may run before it is killed.
+const SECRET_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run `command` and capture its standard output as a secret.
+///
+/// The command is killed if it prints more than
+/// [`MAX_SECRET_COMMAND_OUTPUT`] bytes or runs longer than
+/// [`SECRET_COMMAND_TIMEOUT`]. The error message never includes the
+/// command's arguments or output.
+pub(super) fn run_secret_command(command: &[String]) -> Result<SecretString, String> {
+    run_secret_command_within(command, SECRET_COMMAND_TIMEOUT)
+}
+
+fn run_secret_command_within(
+    command: &[String],
+    timeout: Duration,
+) -> Result<SecretString, String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(String::from("command is empty"));
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|error| format!("failed to run command: {error}"))?;
+
+    let deadline = Instant::now() + timeout;
+    let finished = read_bounded_stdout(&mut child, deadline)
+        .and_then(|stdout| wait_for_exit(&mut child, deadline).map(|status| (stdout, status)));
+    let (stdout, status) = finished.inspect_err(|_| kill_quietly(&mut child))?;
+
+    if !status.success() {
+        return Err(format!("command exited with {status}"));
+    }
+    String::from_utf8(stdout)
+        .map(SecretString::from)
+        .map_err(|_| String::from("command output is not valid UTF-8"))
+}
+
+/// Read `child`'s standard output on a helper thread, giving up at
+/// `deadline` or once the output exceeds [`MAX_SECRET_COMMAND_OUTPUT`].
+fn read_bounded_stdout(child: &mut Child, deadline: Instant) -> Result<Vec<u8>, String> {
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| String::from("command output is unavailable"))?;
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let limit = u64::try_from(MAX_SECRET_COMMAND_OUTPUT).unwrap_or(u64::MAX);
+        let read = stdout.take(limit + 1).read_to_end(&mut output);
+        drop(sender.send(read.map(|_| output)));
+    });
+    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(Ok(output)) if output.len() > MAX_SECRET_COMMAND_OUTPUT => Err(format!(
+            "command output exceeds {MAX_SECRET_COMMAND_OUTPUT} bytes"
+        )),
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(error)) => Err(format!("failed to read command output: {error}")),
+        Err(_) => Err(timed_out()),
+    }
+}
+
+/// Wait for `child` to exit, giving up at `deadline`.
+fn wait_for_exit(child: &mut Child, deadline: Instant) -> Result<ExitStatus, String> {
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|error| format!("failed to wait for command: {error}"))?
+        {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            return Err(timed_out());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn timed_out() -> String {
+    String::from("command did not finish in time")
+}
+
+/// Kill and reap `child`, ignoring failures because it may already have
+/// exited.
+fn kill_quietly(child: &mut Child) {
+    drop(child.kill());
+    drop(child.wait());
+}
+
+pub(super) fn load_failed(label: &Utf8Path, message: &str) -> GitHubError {
+    GitHubError::PrivateKeyLoadFailed {
+        path: PathBuf::from(label.as_std_path()),
+        message: String::from(message),
+    }
+}
+
+#[cfg(test)]
+#[path = "private_key_source_tests.rs"]
+mod tests;
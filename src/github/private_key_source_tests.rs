@@ -0,0 +1,207 @@
+//! Unit tests for loading private keys from environment variables, file
+//! descriptors, and commands.
+
+use mockable::MockEnv;
+use rstest::{fixture, rstest};
+
+use super::*;
+
+const KEY_ENV: &str = "PODBOT_TEST_APP_KEY";
+
+#[fixture]
+fn valid_rsa_pem() -> String {
+    include_str!("../../tests/fixtures/test_rsa_private_key.pem").to_owned()
+}
+
+#[fixture]
+fn ec_pem() -> String {
+    include_str!("../../tests/fixtures/test_ec_private_key.pem").to_owned()
+}
+
+fn env_with_key(value: Option<String>) -> MockEnv {
+    let mut env = MockEnv::new();
+    env.expect_string()
+        .returning(move |name| value.clone().filter(|_| name == KEY_ENV));
+    env
+}
+
+fn load_error(source: &PrivateKeySource, env: &MockEnv) -> (PathBuf, String) {
+    match load_private_key_from_source(source, None, env) {
+        Err(GitHubError::PrivateKeyLoadFailed { path, message }) => (path, message),
+        Err(other) => panic!("expected PrivateKeyLoadFailed, got {other:?}"),
+        Ok(_) => panic!("expected key loading to fail"),
+    }
+}
+
+#[rstest]
+fn env_var_source_loads_valid_key(valid_rsa_pem: String) {
+    let env = env_with_key(Some(valid_rsa_pem));
+    let source = PrivateKeySource::EnvVar(String::from(KEY_ENV));
+
+    let result = load_private_key_from_source(&source, None, &env);
+
+    assert!(result.is_ok(), "expected key to load from environment");
+}
+
+#[rstest]
+#[case::unset(None, "environment variable is not set")]
+#[case::blank(Some(String::from("  \n")), "private key is empty")]
+fn env_var_source_reports_missing_key(#[case] value: Option<String>, #[case] expected: &str) {
+    let env = env_with_key(value);
+    let source = PrivateKeySource::EnvVar(String::from(KEY_ENV));
+
+    let (path, message) = load_error(&source, &env);
+
+    assert_eq!(path, PathBuf::from(format!("env:{KEY_ENV}")));
+    assert_eq!(message, expected);
+}
+
+#[rstest]
+fn env_var_source_rejects_non_rsa_keys_like_files(ec_pem: String) {
+    let env = env_with_key(Some(ec_pem));
+    let source = PrivateKeySource::EnvVar(String::from(KEY_ENV));
+
+    let (_, message) = load_error(&source, &env);
+
+    assert!(
+        message.contains("ECDSA"),
+        "expected the shared PEM classification, got: {message}"
+    );
+}
+
+#[cfg(unix)]
+#[rstest]
+fn fd_source_reads_inherited_descriptor(valid_rsa_pem: String) {
+    use std::io::{Seek, Write};
+    use std::os::fd::AsRawFd;
+
+    let mut file = tempfile::tempfile().expect("temporary file should be created");
+    file.write_all(valid_rsa_pem.as_bytes())
+        .expect("key should be written");
+    file.rewind().expect("file should rewind");
+    let source = PrivateKeySource::Fd(file.as_raw_fd());
+
+    let result = load_private_key_from_source(&source, None, &MockEnv::new());
+
+    assert!(result.is_ok(), "expected key to load from file descriptor");
+}
+
+#[cfg(unix)]
+#[rstest]
+fn fd_source_reports_closed_descriptor() {
+    let source = PrivateKeySource::Fd(987_654);
+
+    let (path, message) = load_error(&source, &MockEnv::new());
+
+    assert_eq!(path, PathBuf::from("fd:987654"));
+    assert!(message.starts_with("failed to read file descriptor"));
+}
+
+#[cfg(unix)]
+#[rstest]
+fn command_source_reads_standard_output(valid_rsa_pem: String) {
+    let key_dir = tempfile::tempdir().expect("tempdir should be created");
+    let key_path = key_dir.path().join("key.pem");
+    std::fs::write(&key_path, valid_rsa_pem).expect("key should be written");
+    let source =
+        PrivateKeySource::Command(vec![String::from("cat"), key_path.display().to_string()]);
+
+    let result = load_private_key_from_source(&source, None, &MockEnv::new());
+
+    assert!(result.is_ok(), "expected key to load from command output");
+}
+
+#[cfg(unix)]
+#[rstest]
+#[case::failing(vec!["sh", "-c", "exit 3"], "command:sh", "command exited with exit status: 3")]
+#[case::silent(vec!["true"], "command:true", "private key is empty")]
+fn command_source_reports_unusable_output(
+    #[case] argv: Vec<&str>,
+    #[case] expected_path: &str,
+    #[case] expected_message: &str,
+) {
+    let source = PrivateKeySource::Command(argv.into_iter().map(String::from).collect());
+
+    let (path, message) = load_error(&source, &MockEnv::new());
+
+    assert_eq!(path, PathBuf::from(expected_path));
+    assert_eq!(message, expected_message);
+}
+
+#[rstest]
+fn command_source_reports_missing_program() {
+    let source = PrivateKeySource::Command(vec![
+        String::from("podbot-missing-secrets-cli"),
+        String::from("--token=not-in-errors"),
+    ]);
+
+    let (path, message) = load_error(&source, &MockEnv::new());
+
+    assert_eq!(path, PathBuf::from("command:podbot-missing-secrets-cli"));
+    assert!(message.starts_with("failed to run command"));
+    assert!(!message.contains("not-in-errors"));
+}
+
+#[cfg(unix)]
+#[rstest]
+fn command_source_rejects_oversized_output() {
+    let source = PrivateKeySource::Command(
+        ["sh", "-c", "yes podbot-not-a-key"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    );
+
+    let (path, message) = load_error(&source, &MockEnv::new());
+
+    assert_eq!(path, PathBuf::from("command:sh"));
+    assert_eq!(
+        message,
+        format!("command output exceeds {MAX_SECRET_COMMAND_OUTPUT} bytes")
+    );
+}
+
+#[cfg(unix)]
+#[rstest]
+fn command_that_outlives_the_timeout_is_killed() {
+    let command = ["sleep", "30"].map(String::from);
+    let started = Instant::now();
+
+    let result = run_secret_command_within(&command, Duration::from_millis(100));
+
+    assert_eq!(
+        result.err().as_deref(),
+        Some("command did not finish in time")
+    );
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[cfg(unix)]
+#[rstest]
+fn command_that_closes_its_output_early_is_still_timed_out() {
+    let command = ["sh", "-c", "exec >&-; exec sleep 30"].map(String::from);
+
+    let result = run_secret_command_within(&command, Duration::from_millis(100));
+
+    assert_eq!(
+        result.err().as_deref(),
+        Some("command did not finish in time")
+    );
+}
+
+#[tokio::test]
+async fn off_runtime_loading_reports_source_errors() {
+    let source = PrivateKeySource::Command(vec![String::from("podbot-missing-secrets-cli")]);
+
+    let result = load_private_key_off_runtime(&source, None).await;
+
+    assert!(
+        matches!(
+            &result,
+            Err(GitHubError::PrivateKeyLoadFailed { message, .. })
+                if message.starts_with("failed to run command")
+        ),
+        "expected the command failure, got {:?}",
+        result.err()
+    );
+}
@@ -0,0 +1,173 @@
+//! GitHub API rate-limit budget tracking.
+//!
+//! GitHub reports the remaining request budget of the authenticating
+//! credential in `x-ratelimit-*` headers on every REST response. Podbot
+//! records them from each App and installation-token response it makes, keeps
+//! the latest values in a [`RateLimitTracker`] so callers can read a
+//! [`RateLimitSnapshot`], and publishes them as gauges so concurrent sessions
+//! sharing one App budget can be watched before it runs out.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body_util::combinators::BoxBody;
+use octocrab::FromResponse;
+
+/// Which credential's budget a response reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitBudget {
+    /// Requests authenticated with the App JWT, including token acquisition.
+    App,
+    /// Requests authenticated with an installation token.
+    Installation,
+}
+
+impl RateLimitBudget {
+    const fn as_label(self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::Installation => "installation",
+        }
+    }
+}
+
+/// Rate-limit budget reported by one GitHub response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitSnapshot {
+    /// Requests allowed in the current window (`x-ratelimit-limit`).
+    pub limit: u32,
+    /// Requests left in the current window (`x-ratelimit-remaining`).
+    pub remaining: u32,
+    /// Requests made in the current window (`x-ratelimit-used`).
+    pub used: u32,
+    /// When the window resets (`x-ratelimit-reset`).
+    pub reset_at: SystemTime,
+    /// Rate-limit resource the response counted against, such as `core`.
+    pub resource: String,
+}
+
+impl RateLimitSnapshot {
+    /// Parse the `x-ratelimit-*` headers of a response.
+    ///
+    /// Returns `None` when the remaining count or reset time is missing or
+    /// malformed. A missing limit or used count is taken as unknown and
+    /// derived from the other values where possible.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let remaining = header_number::<u32>(headers, "x-ratelimit-remaining")?;
+        let reset_seconds = header_number::<u64>(headers, "x-ratelimit-reset")?;
+        let limit = header_number(headers, "x-ratelimit-limit").unwrap_or(remaining);
+        Some(Self {
+            limit,
+            remaining,
+            used: header_number(headers, "x-ratelimit-used")
+                .unwrap_or_else(|| limit.saturating_sub(remaining)),
+            reset_at: SystemTime::UNIX_EPOCH + Duration::from_secs(reset_seconds),
+            resource: headers
+                .get("x-ratelimit-resource")
+                .and_then(|value| value.to_str().ok())
+                .map_or_else(|| String::from("core"), String::from),
+        })
+    }
+
+    /// Returns whether at most `reserve` requests are left and the window has
+    /// not yet reset at `now`.
+    #[must_use]
+    pub fn is_exhausted(&self, reserve: u32, now: SystemTime) -> bool {
+        self.remaining <= reserve && self.reset_at > now
+    }
+}
+
+fn header_number<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Latest rate-limit budget observed for one credential.
+///
+/// Clones share the same snapshot, so a tracker can be handed to the code
+/// that makes requests and to the code that paces them.
+#[derive(Debug, Clone)]
+pub struct RateLimitTracker {
+    budget: RateLimitBudget,
+    latest: Arc<Mutex<Option<RateLimitSnapshot>>>,
+}
+
+impl RateLimitTracker {
+    /// Create a tracker for `budget` that has not observed a response yet.
+    #[must_use]
+    pub fn new(budget: RateLimitBudget) -> Self {
+        Self {
+            budget,
+            latest: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Return the budget this tracker records.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub const fn budget(&self) -> RateLimitBudget {
+        self.budget
+    }
+
+    /// Return the most recently observed budget, if any response carried
+    /// rate-limit headers.
+    #[must_use]
+    pub fn snapshot(&self) -> Option<RateLimitSnapshot> {
+        self.latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Record the budget reported by `headers` and publish it as gauges.
+    ///
+    /// Responses without rate-limit headers leave the snapshot unchanged.
+    pub fn observe(&self, headers: &HeaderMap) {
+        if let Some(snapshot) = publish_rate_limit(self.budget, headers) {
+            *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(snapshot);
+        }
+    }
+
+    /// Observe the headers of a raw Octocrab response, then map it the way
+    /// Octocrab's typed request helpers do.
+    pub(super) async fn parse<R: FromResponse>(
+        &self,
+        sent: octocrab::Result<http::Response<BoxBody<Bytes, octocrab::Error>>>,
+    ) -> octocrab::Result<R> {
+        let response = sent?;
+        self.observe(response.headers());
+        R::from_response(octocrab::map_github_error(response).await?).await
+    }
+}
+
+/// Publish the budget reported by `headers` as gauges without keeping it.
+pub(super) fn publish_rate_limit(
+    budget: RateLimitBudget,
+    headers: &HeaderMap,
+) -> Option<RateLimitSnapshot> {
+    let snapshot = RateLimitSnapshot::from_headers(headers)?;
+    record_rate_limit_gauges(budget, &snapshot);
+    Some(snapshot)
+}
+
+fn record_rate_limit_gauges(budget: RateLimitBudget, snapshot: &RateLimitSnapshot) {
+    let labels = [
+        ("budget", String::from(budget.as_label())),
+        ("resource", snapshot.resource.clone()),
+    ];
+    metrics::gauge!("podbot.github.rate_limit.limit", &labels).set(f64::from(snapshot.limit));
+    metrics::gauge!("podbot.github.rate_limit.remaining", &labels)
+        .set(f64::from(snapshot.remaining));
+    let until_reset = snapshot
+        .reset_at
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+    metrics::gauge!("podbot.github.rate_limit.reset_seconds", &labels)
+        .set(until_reset.as_secs_f64());
+}
+
+#[cfg(test)]
+#[path = "rate_limit_tests.rs"]
+mod tests;
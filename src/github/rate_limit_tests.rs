@@ -0,0 +1,254 @@
+//! Unit tests for rate-limit header parsing, tracking, and gauges.
+
+use http::HeaderValue;
+use octocrab::Octocrab;
+use octocrab::service::middleware::retry::RetryConfig;
+use rstest::rstest;
+
+use super::*;
+use crate::github::test_support::{GaugeEvent, LocalApiStandIn, RecordingMetrics};
+use crate::github::{GitHubInstallationTokenClient, OctocrabAppClient};
+
+const RESET: u64 = 1_900_000_000;
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    pairs
+        .iter()
+        .map(|(name, value)| {
+            (
+                http::HeaderName::from_static(name),
+                HeaderValue::from_str(value).expect("header value should be valid"),
+            )
+        })
+        .collect()
+}
+
+fn budget_headers(remaining: &str) -> HeaderMap {
+    headers(&[
+        ("x-ratelimit-limit", "5000"),
+        ("x-ratelimit-remaining", remaining),
+        ("x-ratelimit-used", "4990"),
+        ("x-ratelimit-reset", "1900000000"),
+        ("x-ratelimit-resource", "core"),
+    ])
+}
+
+fn reset_at() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(RESET)
+}
+
+#[rstest]
+fn snapshot_reads_every_rate_limit_header() {
+    assert_eq!(
+        RateLimitSnapshot::from_headers(&budget_headers("10")),
+        Some(RateLimitSnapshot {
+            limit: 5000,
+            remaining: 10,
+            used: 4990,
+            reset_at: reset_at(),
+            resource: String::from("core"),
+        })
+    );
+}
+
+#[rstest]
+fn snapshot_defaults_optional_headers() {
+    let snapshot = RateLimitSnapshot::from_headers(&headers(&[
+        ("x-ratelimit-limit", "5000"),
+        ("x-ratelimit-remaining", "4000"),
+        ("x-ratelimit-reset", "1900000000"),
+    ]))
+    .expect("remaining and reset are enough");
+
+    assert_eq!(snapshot.used, 1000);
+    assert_eq!(snapshot.resource, "core");
+}
+
+#[rstest]
+#[case::no_headers(&[])]
+#[case::no_reset(&[("x-ratelimit-remaining", "10")])]
+#[case::no_remaining(&[("x-ratelimit-reset", "1900000000")])]
+#[case::malformed(&[("x-ratelimit-remaining", "many"), ("x-ratelimit-reset", "1900000000")])]
+fn snapshot_needs_remaining_and_reset(#[case] pairs: &[(&'static str, &str)]) {
+    assert_eq!(RateLimitSnapshot::from_headers(&headers(pairs)), None);
+}
+
+#[rstest]
+#[case::below_reserve(10, reset_at() - Duration::from_secs(1), true)]
+#[case::at_reserve(50, reset_at() - Duration::from_secs(1), true)]
+#[case::above_reserve(51, reset_at() - Duration::from_secs(1), false)]
+#[case::window_reset(0, reset_at(), false)]
+fn exhaustion_respects_reserve_and_reset(
+    #[case] remaining: u32,
+    #[case] now: SystemTime,
+    #[case] expected: bool,
+) {
+    let snapshot = RateLimitSnapshot {
+        limit: 5000,
+        remaining,
+        used: 5000 - remaining,
+        reset_at: reset_at(),
+        resource: String::from("core"),
+    };
+
+    assert_eq!(snapshot.is_exhausted(50, now), expected);
+}
+
+#[rstest]
+fn tracker_keeps_latest_budget_and_ignores_headerless_responses() {
+    let tracker = RateLimitTracker::new(RateLimitBudget::App);
+    let observer = tracker.clone();
+
+    observer.observe(&budget_headers("10"));
+    observer.observe(&budget_headers("9"));
+    observer.observe(&HeaderMap::new());
+
+    assert_eq!(
+        tracker.snapshot().map(|snapshot| snapshot.remaining),
+        Some(9)
+    );
+}
+
+#[rstest]
+fn tracker_publishes_budget_gauges() {
+    let recorder = RecordingMetrics::default();
+    let tracker = RateLimitTracker::new(RateLimitBudget::Installation);
+
+    metrics::with_local_recorder(&recorder, || tracker.observe(&budget_headers("10")));
+
+    let labels = vec![
+        (String::from("budget"), String::from("installation")),
+        (String::from("resource"), String::from("core")),
+    ];
+    let events = recorder.gauge_events();
+    assert_eq!(
+        events.get(..2),
+        Some(
+            &[
+                GaugeEvent {
+                    name: String::from("podbot.github.rate_limit.limit"),
+                    labels: labels.clone(),
+                    value: 5000.0,
+                },
+                GaugeEvent {
+                    name: String::from("podbot.github.rate_limit.remaining"),
+                    labels: labels.clone(),
+                    value: 10.0,
+                },
+            ][..]
+        )
+    );
+    assert!(
+        events.iter().any(
+            |event| event.name == "podbot.github.rate_limit.reset_seconds"
+                && event.labels == labels
+        ),
+        "expected a reset gauge: {events:?}"
+    );
+}
+
+#[tokio::test]
+async fn parse_records_budget_from_raw_responses() {
+    let stand_in = LocalApiStandIn::start_with(
+        "200 OK",
+        &[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "4321"),
+            ("x-ratelimit-reset", "1900000000"),
+        ],
+        "null",
+    )
+    .await
+    .expect("stand-in should bind a local port");
+    let client = Octocrab::builder()
+        .add_retry_config(RetryConfig::None)
+        .base_uri(stand_in.base_url())
+        .expect("stand-in URL should be valid")
+        .build()
+        .expect("client should build");
+    let tracker = RateLimitTracker::new(RateLimitBudget::App);
+
+    let result = tracker.parse::<()>(client._get("/app").await).await;
+
+    assert!(result.is_ok(), "expected the response to parse: {result:?}");
+    assert_eq!(
+        tracker.snapshot().map(|snapshot| snapshot.remaining),
+        Some(4321)
+    );
+}
+
+#[tokio::test]
+async fn parse_records_budget_from_error_responses() {
+    let stand_in = LocalApiStandIn::start_with(
+        "403 Forbidden",
+        &[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1900000000"),
+        ],
+        r#"{"message":"API rate limit exceeded"}"#,
+    )
+    .await
+    .expect("stand-in should bind a local port");
+    let client = Octocrab::builder()
+        .add_retry_config(RetryConfig::None)
+        .base_uri(stand_in.base_url())
+        .expect("stand-in URL should be valid")
+        .build()
+        .expect("client should build");
+    let tracker = RateLimitTracker::new(RateLimitBudget::App);
+
+    let result = tracker.parse::<()>(client._get("/app").await).await;
+
+    assert!(
+        matches!(result, Err(octocrab::Error::GitHub { .. })),
+        "expected a GitHub error: {result:?}"
+    );
+    assert_eq!(
+        tracker.snapshot().map(|snapshot| snapshot.remaining),
+        Some(0)
+    );
+}
+
+#[tokio::test]
+async fn unscoped_token_acquisition_records_the_app_budget() {
+    let stand_in = LocalApiStandIn::start_with(
+        "201 Created",
+        &[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "4999"),
+            ("x-ratelimit-reset", "1900000000"),
+        ],
+        r#"{"token":"ghs_fixture","permissions":{"contents":"read"}}"#,
+    )
+    .await
+    .expect("stand-in should bind a local port");
+    let client = OctocrabAppClient::new(
+        Octocrab::builder()
+            .add_retry_config(RetryConfig::None)
+            .base_uri(stand_in.base_url())
+            .expect("stand-in URL should be valid")
+            .build()
+            .expect("client should build"),
+    );
+
+    let result = client
+        .acquire_installation_token(42, Duration::from_secs(300))
+        .await;
+
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the request");
+    assert!(
+        head.starts_with("POST /app/installations/42/access_tokens "),
+        "{head}"
+    );
+    assert_eq!(
+        result.map(|token| String::from(token.token())).ok(),
+        Some(String::from("ghs_fixture"))
+    );
+    assert_eq!(
+        client.rate_limit().map(|snapshot| snapshot.remaining),
+        Some(4999)
+    );
+}
@@ -1,16 +1,19 @@
-//! Shared test-support types for GitHub metrics.
+//! Shared test-support types for GitHub metrics and API calls.
 //!
-//! Provides `CounterEvent`, `HistogramEvent`, `RecordingMetrics`,
-//! `RecordedCounter`, and `RecordedHistogram` so unit tests and integration
+//! Provides `CounterEvent`, `HistogramEvent`, `GaugeEvent`,
+//! `RecordingMetrics`, `RecordedCounter`, `RecordedHistogram`, and
+//! `RecordedGauge` so unit tests and integration
 //! tests can use the same metrics recorder implementation without
-//! duplicating the types.
+//! duplicating the types, and `LocalApiStandIn`, a one-request HTTP server
+//! that stands in for the GitHub REST API.
 
 #![cfg(any(test, feature = "internal"))]
 
+use std::io;
 use std::sync::{Arc, Mutex};
 
 use metrics::{
-    Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
     SharedString, Unit,
 };
 
@@ -28,10 +31,18 @@ pub struct HistogramEvent {
     pub value: f64,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct GaugeEvent {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
 #[derive(Clone, Default)]
 pub struct RecordingMetrics {
     events: Arc<Mutex<Vec<CounterEvent>>>,
     histogram_events: Arc<Mutex<Vec<HistogramEvent>>>,
+    gauge_events: Arc<Mutex<Vec<GaugeEvent>>>,
 }
 
 impl RecordingMetrics {
@@ -50,6 +61,14 @@ impl RecordingMetrics {
             Err(error) => panic!("histogram events lock should not be poisoned: {error}"),
         }
     }
+
+    #[must_use]
+    pub fn gauge_events(&self) -> Vec<GaugeEvent> {
+        match self.gauge_events.lock() {
+            Ok(events) => events.clone(),
+            Err(error) => panic!("gauge events lock should not be poisoned: {error}"),
+        }
+    }
 }
 
 fn extract_key_labels(key: &Key) -> Vec<(String, String)> {
@@ -76,8 +95,15 @@ impl Recorder for RecordingMetrics {
         }))
     }
 
-    fn register_gauge(&self, _key: &Key, _metadata: &Metadata<'_>) -> Gauge {
-        Gauge::noop()
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(Arc::new(RecordedGauge {
+            event: GaugeEvent {
+                name: key.name().to_owned(),
+                labels: extract_key_labels(key),
+                value: 0.0,
+            },
+            events: Arc::clone(&self.gauge_events),
+        }))
     }
 
     fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
@@ -127,3 +153,108 @@ impl HistogramFn for RecordedHistogram {
         }
     }
 }
+
+/// Records each `set` as a [`GaugeEvent`]; relative updates are ignored.
+pub struct RecordedGauge {
+    event: GaugeEvent,
+    events: Arc<Mutex<Vec<GaugeEvent>>>,
+}
+
+impl GaugeFn for RecordedGauge {
+    fn increment(&self, _value: f64) {}
+
+    fn decrement(&self, _value: f64) {}
+
+    fn set(&self, value: f64) {
+        let mut event = self.event.clone();
+        event.value = value;
+        match self.events.lock() {
+            Ok(mut events) => events.push(event),
+            Err(error) => panic!("gauge events lock should not be poisoned: {error}"),
+        }
+    }
+}
+
+/// One-request local HTTP server standing in for the GitHub REST API.
+///
+/// The server answers the first request with a fixed status, headers, and
+/// body, then closes the connection.
+pub struct LocalApiStandIn {
+    base_url: String,
+    request: tokio::task::JoinHandle<io::Result<String>>,
+}
+
+impl LocalApiStandIn {
+    /// Start a stand-in that answers with `status`, such as
+    /// `"204 No Content"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error when no local port can be bound.
+    pub async fn start(status: &'static str) -> io::Result<Self> {
+        Self::start_with(status, &[], "").await
+    }
+
+    /// Start a stand-in that answers with `status`, the extra `headers`, and
+    /// `body`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error when no local port can be bound.
+    pub async fn start_with(
+        status: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> io::Result<Self> {
+        let extra_headers = headers
+            .iter()
+            .fold(String::new(), |mut lines, (name, value)| {
+                lines.push_str(name);
+                lines.push_str(": ");
+                lines.push_str(value);
+                lines.push_str("\r\n");
+                lines
+            });
+        let response = format!(
+            "HTTP/1.1 {status}\r\n{extra_headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let request = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let head = read_request_head(&mut stream).await?;
+            tokio::io::AsyncWriteExt::write_all(&mut stream, response.as_bytes()).await?;
+            Ok(head)
+        });
+        Ok(Self { base_url, request })
+    }
+
+    /// Return the base URL to configure as the GitHub API root.
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Wait for the request and return its request line and headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error when the request could not be read or answered.
+    pub async fn request_head(self) -> io::Result<String> {
+        self.request.await.map_err(io::Error::other)?
+    }
+}
+
+async fn read_request_head(stream: &mut tokio::net::TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0_u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = tokio::io::AsyncReadExt::read(stream, &mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend(buffer.iter().take(read));
+    }
+    String::from_utf8(head).map_err(io::Error::other)
+}
@@ -0,0 +1,333 @@
+//! Host-side runtime directory and atomic writer for installation tokens.
+//!
+//! The token daemon keeps the current GitHub App installation token in
+//! `$XDG_RUNTIME_DIR/podbot/<container_name>/secrets/ghapp_token`. The
+//! directory is keyed by container name because the name is known before the
+//! container is created, so the bind mount can be part of the create request.
+//! The directories are restricted to mode `0700` and the token file to mode
+//! `0600`. Each write goes to a temporary file in the per-container directory
+//! and is then renamed over the token, so readers observe either the previous
+//! token or the new one and never a partial write.
+//!
+//! The container receives only the `secrets` subdirectory as a read-only bind
+//! mount at `/run/secrets`, which exposes the token at
+//! `/run/secrets/ghapp_token`. The temporary file and the `ready` marker stay
+//! in the per-container directory, outside the mount. The directory is
+//! mounted rather than the file because a single-file bind mount pins the
+//! original inode, so a renamed replacement on the host would never become
+//! visible inside the container.
+//!
+//! When the daemon stops, [`TokenRuntimeDir::remove`] deletes the directory so
+//! no token outlives the daemon that maintains it.
+
+use std::io::{self, Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use cap_std::ambient_authority;
+use cap_std::fs::{DirBuilder, OpenOptions};
+use cap_std::fs_utf8::Dir;
+use secrecy::SecretString;
+
+use super::InstallationAccessToken;
+use crate::error::{ConfigError, FilesystemError, PodbotError};
+
+/// File name of the installation token inside the runtime directory.
+pub const TOKEN_FILE_NAME: &str = "ghapp_token";
+
+/// Container directory that receives the runtime directory bind mount.
+#[cfg(any(feature = "internal", test))]
+pub const CONTAINER_SECRETS_DIR: &str = "/run/secrets";
+
+/// Path at which the installation token is readable inside the container.
+#[cfg(any(feature = "internal", test))]
+pub const CONTAINER_TOKEN_PATH: &str = "/run/secrets/ghapp_token";
+
+/// File name of the readiness marker inside the runtime directory.
+pub const READY_FILE_NAME: &str = "ready";
+
+/// Subdirectory of the runtime directory mounted at [`CONTAINER_SECRETS_DIR`].
+const SECRETS_DIR_NAME: &str = "secrets";
+
+const RUNTIME_DIR_ENV: &str = "XDG_RUNTIME_DIR";
+const PODBOT_RUNTIME_DIR: &str = "podbot";
+const TEMPORARY_TOKEN_FILE_NAME: &str = ".ghapp_token.podbot-partial";
+const DIRECTORY_MODE: u32 = 0o700;
+const TOKEN_FILE_MODE: u32 = 0o600;
+
+/// Per-container runtime directory holding the installation token.
+#[derive(Debug)]
+pub struct TokenRuntimeDir {
+    parent: Dir,
+    name: String,
+    dir: Dir,
+    secrets: Dir,
+    path: Utf8PathBuf,
+}
+
+impl TokenRuntimeDir {
+    /// Create the runtime directory under `$XDG_RUNTIME_DIR/podbot/`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingRequired` when `XDG_RUNTIME_DIR` is unset
+    /// or empty, and the errors documented on [`Self::create_in`].
+    pub fn create<E: mockable::Env>(env: &E, container_name: &str) -> Result<Self, PodbotError> {
+        let runtime_root = runtime_root(env).ok_or_else(|| ConfigError::MissingRequired {
+            field: String::from(RUNTIME_DIR_ENV),
+        })?;
+        Self::create_in(Utf8Path::new(&runtime_root), container_name)
+    }
+
+    /// Open the runtime directory created for `container_name` under
+    /// `$XDG_RUNTIME_DIR/podbot/`.
+    ///
+    /// Returns `None` when `XDG_RUNTIME_DIR` is unset or empty, when
+    /// `container_name` is not a single path segment, or when no directory
+    /// exists for the container.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors documented on [`Self::create_in`] when the existing
+    /// directory cannot be opened or restricted.
+    pub fn open_existing<E: mockable::Env>(
+        env: &E,
+        container_name: &str,
+    ) -> Result<Option<Self>, PodbotError> {
+        let Some(runtime_root) = runtime_root(env) else {
+            return Ok(None);
+        };
+        let root = Utf8Path::new(&runtime_root);
+        let exists = validate_container_name(container_name).is_ok()
+            && root.join(PODBOT_RUNTIME_DIR).join(container_name).is_dir();
+        if !exists {
+            return Ok(None);
+        }
+        Self::create_in(root, container_name).map(Some)
+    }
+
+    /// Create the runtime directory under `<runtime_root>/podbot/`.
+    ///
+    /// Existing directories are reused, but their modes are reset to `0700`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidValue` when `container_name` is not a
+    /// single path segment, and `FilesystemError` variants when a directory
+    /// cannot be opened, created, or restricted.
+    pub fn create_in(runtime_root: &Utf8Path, container_name: &str) -> Result<Self, PodbotError> {
+        validate_container_name(container_name)?;
+        let root = Dir::open_ambient_dir(runtime_root, ambient_authority())
+            .map_err(|error| filesystem_error(runtime_root, &error))?;
+
+        let podbot_path = runtime_root.join(PODBOT_RUNTIME_DIR);
+        let podbot_dir = create_private_dir(&root, PODBOT_RUNTIME_DIR)
+            .map_err(|error| filesystem_error(&podbot_path, &error))?;
+
+        let path = podbot_path.join(container_name);
+        let dir = create_private_dir(&podbot_dir, container_name)
+            .map_err(|error| filesystem_error(&path, &error))?;
+        let secrets = create_private_dir(&dir, SECRETS_DIR_NAME)
+            .map_err(|error| filesystem_error(&path.join(SECRETS_DIR_NAME), &error))?;
+        Ok(Self {
+            parent: podbot_dir,
+            name: String::from(container_name),
+            dir,
+            secrets,
+            path,
+        })
+    }
+
+    /// Return the name of the container the directory belongs to.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub fn container_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the host path of the runtime directory.
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Return the host path of the directory mounted into the container.
+    #[must_use]
+    pub fn secrets_path(&self) -> Utf8PathBuf {
+        self.path.join(SECRETS_DIR_NAME)
+    }
+
+    /// Return the host path of the token file.
+    #[must_use]
+    pub fn token_path(&self) -> Utf8PathBuf {
+        self.secrets_path().join(TOKEN_FILE_NAME)
+    }
+
+    /// Return the host path of the readiness marker.
+    #[must_use]
+    pub fn ready_path(&self) -> Utf8PathBuf {
+        self.path.join(READY_FILE_NAME)
+    }
+
+    /// Return the read-only bind specification for container creation.
+    ///
+    /// Only the `secrets` subdirectory is mounted. The value uses the
+    /// engine's `host:container:ro` bind syntax.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub fn bind_mount(&self) -> String {
+        format!("{}:{CONTAINER_SECRETS_DIR}:ro", self.secrets_path())
+    }
+
+    /// Atomically replace the token file with `token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesystemError` variants when the temporary file cannot be
+    /// written or renamed over the token file.
+    pub fn write_token(&self, token: &InstallationAccessToken) -> Result<(), PodbotError> {
+        self.replace_token_file(token.token().as_bytes())
+            .map_err(|error| filesystem_error(&self.token_path(), &error))
+    }
+
+    /// Read the token most recently written to the token file.
+    ///
+    /// Returns `None` when no token has been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesystemError` variants when the token file exists but
+    /// cannot be read.
+    pub fn read_token(&self) -> Result<Option<SecretString>, PodbotError> {
+        match self.secrets.read_to_string(TOKEN_FILE_NAME) {
+            Ok(token) => Ok(Some(SecretString::from(token))),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(filesystem_error(&self.token_path(), &error)),
+        }
+    }
+
+    /// Create the empty readiness marker, replacing any previous marker.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesystemError` variants when the marker cannot be written.
+    pub fn write_ready_marker(&self) -> Result<(), PodbotError> {
+        self.dir
+            .write(READY_FILE_NAME, [])
+            .map_err(|error| filesystem_error(&self.ready_path(), &error))
+    }
+
+    /// Remove the runtime directory together with the token and marker.
+    ///
+    /// A directory that is already gone is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesystemError` variants when the directory cannot be removed.
+    pub fn remove(&self) -> Result<(), PodbotError> {
+        match self.parent.remove_dir_all(&self.name) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(filesystem_error(&self.path, &error))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn replace_token_file(&self, contents: &[u8]) -> io::Result<()> {
+        // A temporary file left by an interrupted write may carry a wider mode,
+        // so remove it and create a fresh file with the token mode.
+        match self.dir.remove_file(TEMPORARY_TOKEN_FILE_NAME) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        restrict_new_file_mode(&mut options);
+        let mut file = self.dir.open_with(TEMPORARY_TOKEN_FILE_NAME, &options)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+
+        self.dir
+            .rename(TEMPORARY_TOKEN_FILE_NAME, &self.secrets, TOKEN_FILE_NAME)
+    }
+}
+
+fn runtime_root<E: mockable::Env>(env: &E) -> Option<String> {
+    env.string(RUNTIME_DIR_ENV)
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn validate_container_name(container_name: &str) -> Result<(), PodbotError> {
+    let is_single_segment = !container_name.trim().is_empty()
+        && container_name != "."
+        && container_name != ".."
+        && !container_name.contains(['/', '\\', '\0']);
+    if is_single_segment {
+        return Ok(());
+    }
+
+    Err(PodbotError::from(ConfigError::InvalidValue {
+        field: String::from("container_name"),
+        reason: format!("'{container_name}' must be a single path segment"),
+    }))
+}
+
+fn create_private_dir(parent: &Dir, name: &str) -> io::Result<Dir> {
+    let mut builder = DirBuilder::new();
+    restrict_new_dir_mode(&mut builder);
+    match parent.create_dir_with(name, &builder) {
+        Err(error) if error.kind() != io::ErrorKind::AlreadyExists => return Err(error),
+        _ => {}
+    }
+    restrict_existing_dir_mode(parent, name)?;
+    parent.open_dir(name)
+}
+
+#[cfg(unix)]
+fn restrict_new_dir_mode(builder: &mut DirBuilder) {
+    use cap_std::fs::DirBuilderExt;
+
+    builder.mode(DIRECTORY_MODE);
+}
+
+#[cfg(not(unix))]
+const fn restrict_new_dir_mode(_builder: &mut DirBuilder) {}
+
+#[cfg(unix)]
+fn restrict_existing_dir_mode(parent: &Dir, name: &str) -> io::Result<()> {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    parent.set_permissions(name, Permissions::from_mode(DIRECTORY_MODE))
+}
+
+#[cfg(not(unix))]
+fn restrict_existing_dir_mode(_parent: &Dir, _name: &str) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn restrict_new_file_mode(options: &mut OpenOptions) {
+    use cap_std::fs::OpenOptionsExt;
+
+    options.mode(TOKEN_FILE_MODE);
+}
+
+#[cfg(not(unix))]
+const fn restrict_new_file_mode(_options: &mut OpenOptions) {}
+
+fn filesystem_error(path: &Utf8Path, error: &io::Error) -> PodbotError {
+    let std_path = path.as_std_path().to_path_buf();
+    PodbotError::from(match error.kind() {
+        io::ErrorKind::NotFound => FilesystemError::NotFound { path: std_path },
+        io::ErrorKind::PermissionDenied => FilesystemError::PermissionDenied { path: std_path },
+        _ => FilesystemError::IoError {
+            path: std_path,
+            message: error.to_string(),
+        },
+    })
+}
+
+#[cfg(test)]
+#[path = "token_file_tests.rs"]
+mod tests;
@@ -0,0 +1,270 @@
+//! Unit tests for the token runtime directory and atomic token writer.
+
+use std::time::{Duration, SystemTime};
+
+use camino::Utf8PathBuf;
+use mockable::MockEnv;
+use rstest::{fixture, rstest};
+use secrecy::ExposeSecret;
+use tempfile::TempDir;
+
+use super::*;
+
+const CONTAINER_NAME: &str = "sandbox-token";
+
+struct RuntimeRoot {
+    _temp_dir: TempDir,
+    path: Utf8PathBuf,
+}
+
+#[fixture]
+fn runtime_root() -> RuntimeRoot {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("tempdir path should be valid UTF-8");
+    RuntimeRoot {
+        _temp_dir: temp_dir,
+        path,
+    }
+}
+
+fn token(value: &str) -> InstallationAccessToken {
+    InstallationAccessToken::new(
+        String::from(value),
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+        Duration::from_secs(300),
+    )
+    .expect("token metadata should be representable")
+}
+
+#[rstest]
+fn runtime_dir_is_created_per_container(runtime_root: RuntimeRoot) {
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+
+    let expected = runtime_root.path.join("podbot").join(CONTAINER_NAME);
+    let secrets = expected.join("secrets");
+    assert_eq!(runtime_dir.path(), expected);
+    assert_eq!(runtime_dir.container_name(), CONTAINER_NAME);
+    assert_eq!(runtime_dir.token_path(), secrets.join(TOKEN_FILE_NAME));
+    assert_eq!(runtime_dir.ready_path(), expected.join(READY_FILE_NAME));
+    assert_eq!(
+        runtime_dir.bind_mount(),
+        format!("{secrets}:/run/secrets:ro")
+    );
+    assert!(secrets.is_dir());
+}
+
+#[rstest]
+fn write_token_replaces_previous_token(runtime_root: RuntimeRoot) {
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+
+    runtime_dir
+        .write_token(&token("ghs_first"))
+        .expect("first write should succeed");
+    runtime_dir
+        .write_token(&token("ghs_second"))
+        .expect("second write should succeed");
+
+    let contents =
+        std::fs::read_to_string(runtime_dir.token_path()).expect("token should be readable");
+    assert_eq!(contents, "ghs_second");
+    assert_eq!(
+        mounted_entries(&runtime_dir),
+        [TOKEN_FILE_NAME],
+        "no temporary file should remain"
+    );
+}
+
+fn mounted_entries(runtime_dir: &TokenRuntimeDir) -> Vec<std::ffi::OsString> {
+    std::fs::read_dir(runtime_dir.secrets_path())
+        .expect("secrets directory should be listable")
+        .map(|entry| entry.expect("entry should be readable").file_name())
+        .collect()
+}
+
+#[rstest]
+fn only_the_token_is_visible_through_the_mount(runtime_root: RuntimeRoot) {
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+    std::fs::write(
+        runtime_dir.path().join(".ghapp_token.podbot-partial"),
+        "stale",
+    )
+    .expect("stale temporary file should be written");
+
+    runtime_dir
+        .write_token(&token("ghs_fixture"))
+        .expect("token write should succeed");
+    runtime_dir
+        .write_ready_marker()
+        .expect("ready marker should be written");
+
+    assert_eq!(mounted_entries(&runtime_dir), [TOKEN_FILE_NAME]);
+    assert!(runtime_dir.ready_path().is_file());
+}
+
+#[cfg(unix)]
+#[rstest]
+fn runtime_dir_and_token_use_private_modes(runtime_root: RuntimeRoot) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let podbot_path = runtime_root.path.join("podbot");
+    std::fs::create_dir(&podbot_path).expect("pre-existing directory should be created");
+    std::fs::set_permissions(&podbot_path, std::fs::Permissions::from_mode(0o755))
+        .expect("pre-existing directory mode should be set");
+
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+    std::fs::write(
+        runtime_dir.path().join(".ghapp_token.podbot-partial"),
+        "stale",
+    )
+    .expect("stale temporary file should be written");
+    runtime_dir
+        .write_token(&token("ghs_fixture"))
+        .expect("token write should succeed");
+
+    let mode_of = |path: &Utf8Path| {
+        std::fs::metadata(path)
+            .expect("metadata should be readable")
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode_of(&podbot_path), 0o700);
+    assert_eq!(mode_of(runtime_dir.path()), 0o700);
+    assert_eq!(mode_of(&runtime_dir.secrets_path()), 0o700);
+    assert_eq!(mode_of(&runtime_dir.token_path()), 0o600);
+}
+
+#[rstest]
+#[case::empty("")]
+#[case::current(".")]
+#[case::parent("..")]
+#[case::nested("a/b")]
+fn create_rejects_container_names_that_are_not_single_segments(
+    runtime_root: RuntimeRoot,
+    #[case] container_name: &str,
+) {
+    let result = TokenRuntimeDir::create_in(&runtime_root.path, container_name);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::InvalidValue { field, .. }))
+                if field == "container_name"
+        ),
+        "expected container_name rejection, got {result:?}"
+    );
+}
+
+#[rstest]
+fn create_requires_xdg_runtime_dir() {
+    let mut env = MockEnv::new();
+    env.expect_string().returning(|_| None);
+
+    let result = TokenRuntimeDir::create(&env, CONTAINER_NAME);
+
+    assert!(
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field == "XDG_RUNTIME_DIR"
+        ),
+        "expected missing XDG_RUNTIME_DIR, got {result:?}"
+    );
+}
+
+#[rstest]
+fn create_reads_runtime_root_from_environment(runtime_root: RuntimeRoot) {
+    let root = runtime_root.path.to_string();
+    let mut env = MockEnv::new();
+    env.expect_string()
+        .returning(move |key| (key == "XDG_RUNTIME_DIR").then(|| root.clone()));
+
+    let runtime_dir =
+        TokenRuntimeDir::create(&env, CONTAINER_NAME).expect("runtime directory should be created");
+
+    assert_eq!(
+        runtime_dir.path(),
+        runtime_root.path.join("podbot").join(CONTAINER_NAME)
+    );
+}
+
+#[rstest]
+fn open_existing_only_opens_directories_a_daemon_created(runtime_root: RuntimeRoot) {
+    let root = runtime_root.path.to_string();
+    let mut env = MockEnv::new();
+    env.expect_string()
+        .returning(move |key| (key == "XDG_RUNTIME_DIR").then(|| root.clone()));
+
+    let before = TokenRuntimeDir::open_existing(&env, CONTAINER_NAME)
+        .expect("a missing directory should not be an error");
+    TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+    let after = TokenRuntimeDir::open_existing(&env, CONTAINER_NAME)
+        .expect("the existing directory should open");
+    let invalid = TokenRuntimeDir::open_existing(&env, "../escape")
+        .expect("an invalid container name should not be an error");
+
+    assert!(before.is_none());
+    assert_eq!(
+        after.as_ref().map(TokenRuntimeDir::path),
+        Some(
+            runtime_root
+                .path
+                .join("podbot")
+                .join(CONTAINER_NAME)
+                .as_path()
+        )
+    );
+    assert!(invalid.is_none());
+}
+
+#[rstest]
+fn remove_deletes_runtime_directory_and_contents(runtime_root: RuntimeRoot) {
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+    runtime_dir
+        .write_token(&token("ghs_fixture"))
+        .expect("token write should succeed");
+    runtime_dir
+        .write_ready_marker()
+        .expect("ready marker should be written");
+    assert!(runtime_dir.ready_path().is_file());
+
+    runtime_dir
+        .remove()
+        .expect("runtime directory should be removed");
+    runtime_dir
+        .remove()
+        .expect("removing a missing directory should succeed");
+
+    assert!(!runtime_dir.path().exists());
+    assert!(runtime_root.path.join("podbot").is_dir());
+}
+
+#[rstest]
+fn read_token_returns_last_written_token(runtime_root: RuntimeRoot) {
+    let runtime_dir = TokenRuntimeDir::create_in(&runtime_root.path, CONTAINER_NAME)
+        .expect("runtime directory should be created");
+    let before = runtime_dir
+        .read_token()
+        .expect("missing token should not be an error");
+
+    runtime_dir
+        .write_token(&token("ghs_first"))
+        .expect("first write should succeed");
+    runtime_dir
+        .write_token(&token("ghs_second"))
+        .expect("second write should succeed");
+    let after = runtime_dir.read_token().expect("token should be readable");
+
+    assert!(before.is_none());
+    assert_eq!(
+        after.as_ref().map(ExposeSecret::expose_secret),
+        Some("ghs_second")
+    );
+}
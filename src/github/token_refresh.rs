@@ -0,0 +1,292 @@
+//! Scheduled refresh of installation tokens for the token daemon.
+//!
+//! The refresh loop sleeps until a token's `refresh_after` time, acquires a
+//! replacement, and writes it atomically through [`TokenRuntimeDir`]. Transient
+//! failures (rate limits, GitHub 5xx responses, and transport errors) are
+//! retried with jittered exponential backoff; permanent failures such as
+//! rejected credentials end the loop with an error. Before each attempt the
+//! loop checks the App's rate-limit budget and, when it is nearly spent,
+//! waits for the window to reset instead of spending the rest. While waiting,
+//! the loop polls the target container and stops cleanly once it disappears.
+//!
+//! Time is read through `mockable::Clock` and waiting goes through
+//! [`RefreshSleeper`], so tests can drive the loop without real delays.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use mockable::Clock;
+use tracing::{info, warn};
+
+use super::classify::is_transient_failure;
+use super::{
+    BoxFuture, GitHubInstallationTokenClient, InstallationAccessToken, InstallationTokenScope,
+    RateLimitTracker, TokenRuntimeDir, acquire_scoped_installation_token_with_client,
+};
+use crate::error::{GitHubError, PodbotError};
+
+/// Waits for a duration on behalf of the refresh loop.
+pub trait RefreshSleeper: Send + Sync {
+    /// Resolve once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()>;
+}
+
+/// Production [`RefreshSleeper`] backed by the Tokio timer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRefreshSleeper;
+
+impl RefreshSleeper for TokioRefreshSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Reports whether the container that consumes the token still exists.
+pub trait ContainerLiveness: Send + Sync {
+    /// Return `false` once the target container has been removed.
+    fn container_exists(&self) -> BoxFuture<'_, bool>;
+}
+
+/// Timing policy for the refresh loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshRetryPolicy {
+    /// Backoff before the first retry, before jitter.
+    pub initial_backoff: Duration,
+    /// Upper bound on the backoff between retries, before jitter.
+    pub max_backoff: Duration,
+    /// Total acquisition attempts per refresh, including the first.
+    pub max_attempts: u32,
+    /// Longest wait between container liveness checks.
+    pub liveness_interval: Duration,
+    /// Remaining App requests at or below which the loop waits for the
+    /// rate-limit window to reset before acquiring a token.
+    pub rate_limit_reserve: u32,
+}
+
+impl Default for RefreshRetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_attempts: 8,
+            liveness_interval: Duration::from_secs(30),
+            rate_limit_reserve: 50,
+        }
+    }
+}
+
+impl RefreshRetryPolicy {
+    /// Return the delay before retry number `attempt`, counting from zero.
+    ///
+    /// The exponential delay is capped at `max_backoff`; half of it is fixed
+    /// and the other half is scaled by `jitter`, which is clamped to `[0, 1]`.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let fixed = exponential / 2;
+        fixed + fixed.mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// Reason a refresh loop stopped without an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshLoopExit {
+    /// The target container no longer exists.
+    ContainerGone,
+}
+
+/// Refresh loop for one container's installation token.
+pub struct TokenRefreshLoop<'a> {
+    /// Client used to acquire replacement tokens.
+    pub client: &'a dyn GitHubInstallationTokenClient,
+    /// GitHub App installation to acquire tokens for.
+    pub installation_id: u64,
+    /// Repositories and permissions each replacement token is limited to.
+    pub scope: &'a InstallationTokenScope,
+    /// Buffer subtracted from expiry when scheduling the next refresh.
+    pub expiry_buffer: Duration,
+    /// Runtime directory that receives each replacement token.
+    pub runtime_dir: &'a TokenRuntimeDir,
+    /// Source of the current time.
+    pub clock: &'a dyn Clock,
+    /// Waits between liveness checks and retries.
+    pub sleeper: &'a dyn RefreshSleeper,
+    /// Reports whether the target container still exists.
+    pub liveness: &'a dyn ContainerLiveness,
+    /// Latest App rate-limit budget, updated by `client`'s responses.
+    pub rate_limits: &'a RateLimitTracker,
+    /// Returns a jitter factor in `[0, 1]` for each retry.
+    pub jitter: fn() -> f64,
+    /// Retry and polling policy.
+    pub policy: RefreshRetryPolicy,
+}
+
+impl TokenRefreshLoop<'_> {
+    /// Keep `token` fresh until the target container disappears.
+    ///
+    /// # Errors
+    ///
+    /// Returns the classified `GitHubError` when a refresh fails permanently,
+    /// `GitHubError::TokenRefreshFailed` when transient failures exhaust the
+    /// retry budget, and `FilesystemError` variants when a replacement token
+    /// cannot be written.
+    pub async fn run(
+        &self,
+        token: InstallationAccessToken,
+    ) -> Result<RefreshLoopExit, PodbotError> {
+        let mut refresh_after = token.refresh_after();
+        loop {
+            if !self.wait_until(refresh_after).await {
+                return Ok(RefreshLoopExit::ContainerGone);
+            }
+            match self.refresh().await? {
+                Some(next) => refresh_after = next.refresh_after(),
+                None => return Ok(RefreshLoopExit::ContainerGone),
+            }
+        }
+    }
+
+    /// Sleep until `deadline`, returning `false` if the container disappears.
+    async fn wait_until(&self, refresh_after: SystemTime) -> bool {
+        let deadline = DateTime::<Utc>::from(refresh_after);
+        loop {
+            if !self.liveness.container_exists().await {
+                return false;
+            }
+            let remaining = (deadline - self.clock.utc()).to_std().unwrap_or_default();
+            if remaining.is_zero() {
+                return true;
+            }
+            self.sleeper
+                .sleep(remaining.min(self.policy.liveness_interval))
+                .await;
+        }
+    }
+
+    /// Acquire and write a replacement token, retrying transient failures.
+    ///
+    /// Returns `None` if the container disappears between retries.
+    async fn refresh(&self) -> Result<Option<InstallationAccessToken>, PodbotError> {
+        let mut attempt = 0;
+        loop {
+            if !self.wait_for_rate_limit().await {
+                return Ok(None);
+            }
+            let error = match acquire_scoped_installation_token_with_client(
+                self.client,
+                self.installation_id,
+                self.scope,
+                self.expiry_buffer,
+            )
+            .await
+            {
+                Ok(token) => return self.store(token).map(Some),
+                Err(error) => error,
+            };
+
+            attempt += 1;
+            if !is_transient_failure(&error) || attempt >= self.policy.max_attempts {
+                record_token_refresh_metrics("failure");
+                return Err(give_up(error, attempt));
+            }
+            self.back_off(attempt - 1, &error).await;
+            if !self.liveness.container_exists().await {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Wait for the rate-limit window to reset when the App budget is at or
+    /// below the policy's reserve, returning `false` if the container
+    /// disappears meanwhile.
+    async fn wait_for_rate_limit(&self) -> bool {
+        let Some(snapshot) = self.rate_limits.snapshot() else {
+            return true;
+        };
+        let now = SystemTime::from(self.clock.utc());
+        if !snapshot.is_exhausted(self.policy.rate_limit_reserve, now) {
+            return true;
+        }
+        record_token_refresh_metrics("rate_limited");
+        warn!(
+            installation_id = self.installation_id,
+            remaining = snapshot.remaining,
+            reserve = self.policy.rate_limit_reserve,
+            reset_at = ?snapshot.reset_at,
+            "GitHub App rate-limit budget is nearly spent; waiting for it to reset"
+        );
+        self.wait_until(snapshot.reset_at).await
+    }
+
+    fn store(
+        &self,
+        token: InstallationAccessToken,
+    ) -> Result<InstallationAccessToken, PodbotError> {
+        if let Err(error) = self.runtime_dir.write_token(&token) {
+            record_token_refresh_metrics("failure");
+            return Err(error);
+        }
+        record_token_refresh_metrics("success");
+        info!(
+            installation_id = self.installation_id,
+            expires_at = ?token.expires_at(),
+            refresh_after = ?token.refresh_after(),
+            "refreshed GitHub App installation token"
+        );
+        Ok(token)
+    }
+
+    async fn back_off(&self, retry: u32, error: &GitHubError) {
+        let backoff = self.policy.backoff(retry, (self.jitter)());
+        record_token_refresh_metrics("retry");
+        metrics::histogram!(
+            "podbot.github.token_refresh.backoff_seconds",
+            "operation" => "token_refresh",
+        )
+        .record(backoff.as_secs_f64());
+        warn!(
+            installation_id = self.installation_id,
+            retry,
+            backoff_seconds = backoff.as_secs_f64(),
+            error = %error,
+            "transient installation token refresh failure; retrying"
+        );
+        self.sleeper.sleep(backoff).await;
+    }
+}
+
+/// Return a jitter factor in `[0, 1]` from the standard library's random
+/// hasher keys.
+#[must_use]
+pub fn random_jitter() -> f64 {
+    let bits = RandomState::new().build_hasher().finish() >> 32;
+    Duration::from_secs(bits).div_duration_f64(Duration::from_secs(u64::from(u32::MAX)))
+}
+
+fn give_up(error: GitHubError, attempts: u32) -> PodbotError {
+    if is_transient_failure(&error) {
+        return PodbotError::from(GitHubError::TokenRefreshFailed {
+            message: format!("giving up after {attempts} attempts: {error}"),
+            kind: error.failure_kind(),
+        });
+    }
+    PodbotError::from(error)
+}
+
+fn record_token_refresh_metrics(status: &'static str) {
+    metrics::counter!(
+        "podbot.github.token_refresh.total",
+        "operation" => "token_refresh",
+        "status" => status,
+    )
+    .increment(1);
+}
+
+#[cfg(test)]
+#[path = "token_refresh_tests.rs"]
+mod tests;
@@ -0,0 +1,491 @@
+//! Unit tests for the installation token refresh loop.
+//!
+//! The loop runs on a current-thread runtime so thread-local `tracing`
+//! subscribers and `metrics` recorders observe every event it emits. A shared
+//! fake clock advances whenever the loop sleeps, so scheduling is exercised
+//! without real delays.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use camino::Utf8PathBuf;
+use chrono::Local;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::*;
+use crate::github::MockGitHubInstallationTokenClient;
+use crate::github::classify::{classify_by_status, failure_kind_for_status};
+use crate::github::test_support::{CounterEvent, RecordingMetrics};
+
+const FIXTURE_TOKEN: &str = "ghs_refreshed_fixture_token";
+const INSTALLATION_ID: u64 = 42;
+const EXPIRY_BUFFER: Duration = Duration::from_secs(300);
+
+/// Clock and sleeper sharing one fake timeline.
+#[derive(Clone)]
+struct FakeTime {
+    now: Arc<Mutex<SystemTime>>,
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl FakeTime {
+    fn starting_at(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+            sleeps: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn now(&self) -> SystemTime {
+        *self.now.lock().expect("clock lock should succeed")
+    }
+
+    fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps
+            .lock()
+            .expect("sleep lock should succeed")
+            .clone()
+    }
+}
+
+impl Clock for FakeTime {
+    fn local(&self) -> DateTime<Local> {
+        DateTime::<Local>::from(self.now())
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from(self.now())
+    }
+}
+
+impl RefreshSleeper for FakeTime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        *self.now.lock().expect("clock lock should succeed") += duration;
+        self.sleeps
+            .lock()
+            .expect("sleep lock should succeed")
+            .push(duration);
+        Box::pin(std::future::ready(()))
+    }
+}
+
+/// Liveness probe that reports the container present for a fixed number of
+/// checks.
+struct PresentFor {
+    remaining_checks: AtomicUsize,
+}
+
+impl PresentFor {
+    const fn checks(count: usize) -> Self {
+        Self {
+            remaining_checks: AtomicUsize::new(count),
+        }
+    }
+}
+
+impl ContainerLiveness for PresentFor {
+    fn container_exists(&self) -> BoxFuture<'_, bool> {
+        let present = self
+            .remaining_checks
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        Box::pin(std::future::ready(present))
+    }
+}
+
+struct Harness {
+    _temp_dir: TempDir,
+    runtime_dir: TokenRuntimeDir,
+    time: FakeTime,
+    rate_limits: RateLimitTracker,
+}
+
+#[fixture]
+fn harness() -> Harness {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("tempdir path should be valid UTF-8");
+    let runtime_dir = TokenRuntimeDir::create_in(&root, "sandbox-refresh")
+        .expect("runtime directory should be created");
+    Harness {
+        _temp_dir: temp_dir,
+        runtime_dir,
+        time: FakeTime::starting_at(SystemTime::UNIX_EPOCH + Duration::from_secs(10_000)),
+        rate_limits: RateLimitTracker::new(crate::github::RateLimitBudget::App),
+    }
+}
+
+/// Token whose refresh time falls `refresh_in` after `now`.
+fn token_refreshing_in(now: SystemTime, refresh_in: Duration) -> InstallationAccessToken {
+    InstallationAccessToken::from_metadata(
+        String::from("ghs_initial_fixture_token"),
+        now,
+        now + refresh_in + EXPIRY_BUFFER,
+        EXPIRY_BUFFER,
+    )
+    .expect("token metadata should be consistent")
+}
+
+fn fresh_token(acquired_at: SystemTime) -> Result<InstallationAccessToken, GitHubError> {
+    InstallationAccessToken::new(String::from(FIXTURE_TOKEN), acquired_at, EXPIRY_BUFFER)
+}
+
+fn fixture_scope() -> InstallationTokenScope {
+    InstallationTokenScope::default()
+        .with_repository("podbot")
+        .with_permission("contents", crate::config::TokenPermissionLevel::Write)
+}
+
+fn classified_failure(code: u16, raw: &str) -> GitHubError {
+    GitHubError::TokenAcquisitionFailed {
+        message: classify_by_status(code, raw),
+        kind: failure_kind_for_status(code, raw),
+    }
+}
+
+fn client_with_results(
+    results: Vec<Result<InstallationAccessToken, GitHubError>>,
+) -> MockGitHubInstallationTokenClient {
+    let queue = Arc::new(Mutex::new(results.into_iter()));
+    let mut client = MockGitHubInstallationTokenClient::new();
+    client
+        .expect_acquire_scoped_installation_token()
+        .withf(|installation_id, scope, buffer| {
+            *installation_id == INSTALLATION_ID
+                && *scope == fixture_scope()
+                && *buffer == EXPIRY_BUFFER
+        })
+        .returning(move |_, _, _| {
+            let next = queue
+                .lock()
+                .expect("result queue lock should succeed")
+                .next()
+                .expect("test should configure enough acquisition results");
+            Box::pin(std::future::ready(next))
+        });
+    client
+}
+
+struct LoopRun {
+    result: Result<RefreshLoopExit, PodbotError>,
+    logs: String,
+    counters: Vec<CounterEvent>,
+}
+
+fn run_loop(
+    harness: &Harness,
+    client: &MockGitHubInstallationTokenClient,
+    liveness: &PresentFor,
+    token: InstallationAccessToken,
+) -> LoopRun {
+    let scope = fixture_scope();
+    let refresh_loop = TokenRefreshLoop {
+        client,
+        installation_id: INSTALLATION_ID,
+        scope: &scope,
+        expiry_buffer: EXPIRY_BUFFER,
+        runtime_dir: &harness.runtime_dir,
+        clock: &harness.time,
+        sleeper: &harness.time,
+        liveness,
+        rate_limits: &harness.rate_limits,
+        jitter: || 0.0,
+        policy: RefreshRetryPolicy {
+            max_attempts: 3,
+            ..RefreshRetryPolicy::default()
+        },
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime should build");
+    let recorder = RecordingMetrics::default();
+    let mut result = None;
+    let logs = capture_logs(|| {
+        metrics::with_local_recorder(&recorder, || {
+            result = Some(runtime.block_on(refresh_loop.run(token)));
+        });
+    });
+    LoopRun {
+        result: result.expect("refresh loop should run"),
+        logs,
+        counters: recorder.events(),
+    }
+}
+
+fn statuses(counters: &[CounterEvent]) -> Vec<String> {
+    counters
+        .iter()
+        .filter(|event| event.name == "podbot.github.token_refresh.total")
+        .filter_map(|event| {
+            event
+                .labels
+                .iter()
+                .find(|(key, _)| key == "status")
+                .map(|(_, value)| value.clone())
+        })
+        .collect()
+}
+
+#[rstest]
+fn loop_sleeps_until_refresh_after_and_writes_replacement(harness: Harness) {
+    let start = harness.time.now();
+    let client = client_with_results(vec![fresh_token(start + Duration::from_secs(75))]);
+    // Four checks cover the wait; the fifth, after the refresh, ends the loop.
+    let liveness = PresentFor::checks(4);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::from_secs(75)),
+    );
+
+    assert_eq!(run.result.ok(), Some(RefreshLoopExit::ContainerGone));
+    assert_eq!(
+        harness.time.sleeps(),
+        [
+            Duration::from_secs(30),
+            Duration::from_secs(30),
+            Duration::from_secs(15)
+        ]
+    );
+    let contents = std::fs::read_to_string(harness.runtime_dir.token_path())
+        .expect("refreshed token should be readable");
+    assert_eq!(contents, FIXTURE_TOKEN);
+    assert_eq!(statuses(&run.counters), ["success"]);
+    assert!(
+        !run.logs.contains(FIXTURE_TOKEN),
+        "logs must not contain token values: {}",
+        run.logs
+    );
+}
+
+#[rstest]
+fn loop_waits_for_rate_limit_reset_when_budget_is_nearly_spent(harness: Harness) {
+    let start = harness.time.now();
+    let reset = start + Duration::from_secs(75);
+    let reset_seconds = reset
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("reset should follow the epoch")
+        .as_secs()
+        .to_string();
+    let headers: http::HeaderMap = [
+        ("x-ratelimit-remaining", String::from("10")),
+        ("x-ratelimit-reset", reset_seconds),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        (
+            http::HeaderName::from_static(name),
+            http::HeaderValue::from_str(&value).expect("header value should be valid"),
+        )
+    })
+    .collect();
+    harness.rate_limits.observe(&headers);
+    let client = client_with_results(vec![fresh_token(reset)]);
+    // Two checks cover the refresh wait and three the rate-limit wait; the
+    // sixth, after the refresh, ends the loop.
+    let liveness = PresentFor::checks(5);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::from_secs(30)),
+    );
+
+    assert_eq!(run.result.ok(), Some(RefreshLoopExit::ContainerGone));
+    assert_eq!(
+        harness.time.sleeps(),
+        [
+            Duration::from_secs(30),
+            Duration::from_secs(30),
+            Duration::from_secs(15)
+        ]
+    );
+    assert_eq!(statuses(&run.counters), ["rate_limited", "success"]);
+    assert!(
+        run.logs.contains("rate-limit budget is nearly spent"),
+        "expected a rate-limit warning: {}",
+        run.logs
+    );
+}
+
+#[rstest]
+fn transient_failures_are_retried_with_backoff(harness: Harness) {
+    let start = harness.time.now();
+    let client = client_with_results(vec![
+        Err(classified_failure(503, "Service Unavailable")),
+        Err(classified_failure(403, "API rate limit exceeded")),
+        fresh_token(start),
+    ]);
+    let liveness = PresentFor::checks(3);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::ZERO),
+    );
+
+    assert_eq!(run.result.ok(), Some(RefreshLoopExit::ContainerGone));
+    assert_eq!(
+        harness.time.sleeps(),
+        [Duration::from_millis(500), Duration::from_secs(1)]
+    );
+    assert_eq!(statuses(&run.counters), ["retry", "retry", "success"]);
+    assert!(run.logs.contains("retrying"), "logs: {}", run.logs);
+    assert!(!run.logs.contains(FIXTURE_TOKEN));
+}
+
+#[rstest]
+fn permanent_failures_end_the_loop_without_retrying(harness: Harness) {
+    let start = harness.time.now();
+    let client = client_with_results(vec![Err(classified_failure(401, "Bad credentials"))]);
+    let liveness = PresentFor::checks(usize::MAX);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::ZERO),
+    );
+
+    assert!(
+        matches!(
+            &run.result,
+            Err(PodbotError::GitHub(GitHubError::TokenAcquisitionFailed { message, .. }))
+                if message.contains("HTTP 401")
+        ),
+        "expected permanent failure, got {:?}",
+        run.result
+    );
+    assert!(harness.time.sleeps().is_empty());
+    assert_eq!(statuses(&run.counters), ["failure"]);
+}
+
+#[rstest]
+fn exhausted_retries_report_refresh_failure(harness: Harness) {
+    let start = harness.time.now();
+    let client = client_with_results(vec![
+        Err(classified_failure(502, "Bad Gateway")),
+        Err(classified_failure(502, "Bad Gateway")),
+        Err(classified_failure(502, "Bad Gateway")),
+    ]);
+    let liveness = PresentFor::checks(usize::MAX);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::ZERO),
+    );
+
+    assert!(
+        matches!(
+            &run.result,
+            Err(PodbotError::GitHub(GitHubError::TokenRefreshFailed { message, .. }))
+                if message.contains("giving up after 3 attempts")
+        ),
+        "expected exhausted retries, got {:?}",
+        run.result
+    );
+    assert_eq!(statuses(&run.counters), ["retry", "retry", "failure"]);
+    assert!(!harness.runtime_dir.token_path().exists());
+}
+
+#[rstest]
+fn loop_stops_when_container_disappears_before_refresh(harness: Harness) {
+    let start = harness.time.now();
+    let mut client = MockGitHubInstallationTokenClient::new();
+    client.expect_acquire_scoped_installation_token().never();
+    let liveness = PresentFor::checks(1);
+
+    let run = run_loop(
+        &harness,
+        &client,
+        &liveness,
+        token_refreshing_in(start, Duration::from_secs(3_300)),
+    );
+
+    assert_eq!(run.result.ok(), Some(RefreshLoopExit::ContainerGone));
+    assert_eq!(harness.time.sleeps(), [Duration::from_secs(30)]);
+}
+
+#[rstest]
+#[case::first_retry(0, 0.0, Duration::from_millis(500))]
+#[case::first_retry_full_jitter(0, 1.0, Duration::from_secs(1))]
+#[case::third_retry(2, 0.5, Duration::from_secs(3))]
+#[case::capped(10, 1.0, Duration::from_secs(60))]
+#[case::jitter_is_clamped(1, 4.0, Duration::from_secs(2))]
+fn backoff_doubles_and_applies_half_jitter(
+    #[case] retry: u32,
+    #[case] jitter: f64,
+    #[case] expected: Duration,
+) {
+    assert_eq!(
+        RefreshRetryPolicy::default().backoff(retry, jitter),
+        expected
+    );
+}
+
+#[rstest]
+fn random_jitter_stays_in_unit_interval() {
+    for _ in 0..64 {
+        let jitter = random_jitter();
+        assert!(
+            (0.0..=1.0).contains(&jitter),
+            "jitter out of range: {jitter}"
+        );
+    }
+}
+
+fn capture_logs(run_test: impl FnOnce()) -> String {
+    let buffer = Arc::new(Mutex::new(Vec::<u8>::new()));
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .without_time()
+        .with_ansi(false)
+        .with_writer(SharedLogBuffer {
+            buffer: Arc::clone(&buffer),
+        })
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, run_test);
+
+    let bytes = buffer
+        .lock()
+        .expect("log buffer lock should succeed")
+        .clone();
+    String::from_utf8(bytes).expect("logs should be UTF-8")
+}
+
+#[derive(Clone)]
+struct SharedLogBuffer {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl<'writer> tracing_subscriber::fmt::MakeWriter<'writer> for SharedLogBuffer {
+    type Writer = Self;
+
+    fn make_writer(&'writer self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl std::io::Write for SharedLogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer
+            .lock()
+            .map_err(|error| std::io::Error::other(format!("log buffer poisoned: {error}")))?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
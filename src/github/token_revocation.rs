@@ -0,0 +1,110 @@
+//! Revocation of installation tokens when a session ends.
+//!
+//! Installation tokens stay valid for up to an hour after they are issued, so
+//! the token daemon revokes the last token it wrote once the session stops.
+//! GitHub revokes a token through `DELETE /installation/token`, authenticated
+//! with the token itself. Revocation is best effort: callers treat a failure
+//! as a warning, because the token still expires on its own.
+
+use std::time::Duration;
+
+use octocrab::Octocrab;
+use octocrab::service::middleware::retry::RetryConfig;
+use secrecy::{ExposeSecret, SecretString};
+
+use super::RateLimitBudget;
+use super::rate_limit::publish_rate_limit;
+use crate::error::{GitHubError, GitHubFailureKind};
+
+/// Longest time to wait for GitHub to confirm a revocation, so a slow API
+/// cannot hold up shutdown.
+const REVOCATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Revoke the installation token `token`.
+///
+/// `api_base_url` selects the REST API root as for
+/// [`build_app_client_with_base_uri`](super::build_app_client_with_base_uri);
+/// `None` targets `https://api.github.com`. Each attempt is counted in
+/// `podbot.github.token_revocation.total` by status, and the installation's
+/// rate-limit budget from the response is published as gauges.
+///
+/// # Errors
+///
+/// Returns [`GitHubError::TokenRevocationFailed`] when the client cannot be
+/// built, the request fails or times out, or GitHub answers with anything
+/// other than a success status. A 401 response usually means the token had
+/// already expired or been revoked.
+pub async fn revoke_installation_token(
+    token: &SecretString,
+    api_base_url: Option<&str>,
+) -> Result<(), GitHubError> {
+    let result = send_revocation(token, api_base_url).await;
+    record_token_revocation_metrics(if result.is_ok() { "success" } else { "failure" });
+    result
+}
+
+async fn send_revocation(
+    token: &SecretString,
+    api_base_url: Option<&str>,
+) -> Result<(), GitHubError> {
+    let client = build_token_client(token, api_base_url)?;
+    let request = client._delete("/installation/token", None::<&()>);
+    let response = tokio::time::timeout(REVOCATION_TIMEOUT, request)
+        .await
+        .map_err(|_| revocation_failed(format!("no response within {REVOCATION_TIMEOUT:?}")))?
+        .map_err(|error| revocation_failed(format!("request failed: {error}")))?;
+
+    publish_rate_limit(RateLimitBudget::Installation, response.headers());
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    Err(revocation_failed(
+        if status == http::StatusCode::UNAUTHORIZED {
+            String::from("GitHub rejected the token (HTTP 401); it may already have expired")
+        } else {
+            format!("GitHub responded with HTTP {}", status.as_u16())
+        },
+    ))
+}
+
+/// Build a client that authenticates with the installation token itself.
+///
+/// Retries are disabled: a single best-effort attempt keeps shutdown prompt.
+fn build_token_client(
+    token: &SecretString,
+    api_base_url: Option<&str>,
+) -> Result<Octocrab, GitHubError> {
+    let builder = Octocrab::builder()
+        .personal_token(token.expose_secret().to_owned())
+        .add_retry_config(RetryConfig::None);
+    let configured = match api_base_url {
+        Some(base_url) => builder.base_uri(base_url).map_err(|error| {
+            revocation_failed(format!("invalid GitHub API base URL '{base_url}': {error}"))
+        })?,
+        None => builder,
+    };
+    configured
+        .build()
+        .map_err(|error| revocation_failed(format!("failed to build GitHub client: {error}")))
+}
+
+const fn revocation_failed(message: String) -> GitHubError {
+    GitHubError::TokenRevocationFailed {
+        message,
+        kind: GitHubFailureKind::Permanent,
+    }
+}
+
+fn record_token_revocation_metrics(status: &'static str) {
+    metrics::counter!(
+        "podbot.github.token_revocation.total",
+        "operation" => "token_revocation",
+        "status" => status,
+    )
+    .increment(1);
+}
+
+#[cfg(test)]
+#[path = "token_revocation_tests.rs"]
+mod tests;
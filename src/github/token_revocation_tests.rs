@@ -0,0 +1,94 @@
+//! Unit tests for installation-token revocation against a local API stand-in.
+
+use rstest::rstest;
+
+use super::*;
+use crate::github::test_support::{CounterEvent, LocalApiStandIn, RecordingMetrics};
+
+const TOKEN: &str = "ghs_revocation_fixture";
+
+async fn revoke_against(status: &'static str) -> (Result<(), GitHubError>, String) {
+    let stand_in = LocalApiStandIn::start(status)
+        .await
+        .expect("stand-in should bind a local port");
+    let result =
+        revoke_installation_token(&SecretString::from(TOKEN), Some(stand_in.base_url())).await;
+    let head = stand_in
+        .request_head()
+        .await
+        .expect("stand-in should receive the request");
+    (result, head)
+}
+
+#[tokio::test]
+async fn revocation_deletes_the_token_with_its_own_credentials() {
+    let (result, head) = revoke_against("204 No Content").await;
+
+    assert!(result.is_ok(), "expected revocation to succeed: {result:?}");
+    assert!(
+        head.starts_with("DELETE /installation/token HTTP/1.1\r\n"),
+        "unexpected request: {head}"
+    );
+    assert!(
+        head.to_ascii_lowercase()
+            .contains(&format!("authorization: bearer {TOKEN}")),
+        "request should authenticate with the token: {head}"
+    );
+}
+
+#[rstest]
+#[case::expired("401 Unauthorized", "may already have expired")]
+#[case::server_error("500 Internal Server Error", "HTTP 500")]
+#[tokio::test]
+async fn revocation_failures_are_reported(#[case] status: &'static str, #[case] expected: &str) {
+    let (result, _) = revoke_against(status).await;
+
+    assert!(
+        matches!(
+            &result,
+            Err(GitHubError::TokenRevocationFailed { message, .. }) if message.contains(expected)
+        ),
+        "expected revocation failure mentioning {expected:?}, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn unreachable_api_is_reported_as_revocation_failure() {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .expect("listener should bind");
+    let base_url = format!(
+        "http://{}",
+        listener
+            .local_addr()
+            .expect("listener should have an address")
+    );
+    drop(listener);
+
+    let result = revoke_installation_token(&SecretString::from(TOKEN), Some(&base_url)).await;
+
+    assert!(
+        matches!(result, Err(GitHubError::TokenRevocationFailed { .. })),
+        "expected revocation failure, got {result:?}"
+    );
+}
+
+#[rstest]
+#[case::success("success")]
+#[case::failure("failure")]
+fn record_token_revocation_metrics_counts_attempts(#[case] status: &'static str) {
+    let recorder = RecordingMetrics::default();
+    metrics::with_local_recorder(&recorder, || record_token_revocation_metrics(status));
+
+    assert_eq!(
+        recorder.events(),
+        vec![CounterEvent {
+            name: "podbot.github.token_revocation.total".to_owned(),
+            labels: vec![
+                ("operation".to_owned(), "token_revocation".to_owned()),
+                ("status".to_owned(), status.to_owned()),
+            ],
+            value: 1,
+        }]
+    );
+}
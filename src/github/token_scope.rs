@@ -0,0 +1,193 @@
+//! Repository and permission scoping for installation tokens.
+//!
+//! GitHub lets an App narrow an installation token to named repositories and
+//! to a subset of the installation's permissions through the access-token
+//! request body. [`InstallationTokenScope`] is that request body. Once GitHub
+//! has answered, the same type records the scope it actually granted as
+//! non-secret token metadata.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TokenPermissionLevel;
+
+/// Repositories and permissions an installation token is limited to.
+///
+/// An empty repository list or permission map leaves that dimension
+/// unrestricted, so the default scope requests every repository and permission
+/// available to the installation.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "internal")]
+/// # {
+/// use podbot::config::TokenPermissionLevel;
+/// use podbot::github::InstallationTokenScope;
+///
+/// let scope = InstallationTokenScope::default()
+///     .with_repository("podbot")
+///     .with_permission("contents", TokenPermissionLevel::Write);
+/// assert_eq!(scope.repositories(), ["podbot"]);
+/// assert!(!scope.is_unrestricted());
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallationTokenScope {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    repositories: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    permissions: BTreeMap<String, TokenPermissionLevel>,
+}
+
+impl InstallationTokenScope {
+    /// Return the scope with `name` added to the repository list.
+    ///
+    /// Names are repository names without the owner; GitHub resolves them
+    /// against the installation's account.
+    #[must_use]
+    pub fn with_repository(mut self, name: impl Into<String>) -> Self {
+        self.repositories.push(name.into());
+        self
+    }
+
+    /// Return the scope with `permission` requested at `level`.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub fn with_permission(
+        mut self,
+        permission: impl Into<String>,
+        level: TokenPermissionLevel,
+    ) -> Self {
+        self.permissions.insert(permission.into(), level);
+        self
+    }
+
+    /// Return the scope with every entry of `permissions` requested.
+    #[must_use]
+    pub fn with_permissions(
+        mut self,
+        permissions: &BTreeMap<String, TokenPermissionLevel>,
+    ) -> Self {
+        self.permissions.extend(
+            permissions
+                .iter()
+                .map(|(permission, level)| (permission.clone(), *level)),
+        );
+        self
+    }
+
+    /// Return the repository names the token is limited to.
+    #[must_use]
+    pub fn repositories(&self) -> &[String] {
+        &self.repositories
+    }
+
+    /// Return the permissions the token is limited to.
+    #[must_use]
+    pub const fn permissions(&self) -> &BTreeMap<String, TokenPermissionLevel> {
+        &self.permissions
+    }
+
+    /// Return whether neither repositories nor permissions are restricted.
+    #[cfg(any(feature = "internal", test))]
+    #[must_use]
+    pub fn is_unrestricted(&self) -> bool {
+        self.repositories.is_empty() && self.permissions.is_empty()
+    }
+}
+
+/// Access-token response body, limited to the fields podbot reads.
+#[derive(Deserialize)]
+pub(super) struct AccessTokenResponse {
+    token: String,
+    #[serde(default)]
+    permissions: BTreeMap<String, TokenPermissionLevel>,
+    #[serde(default)]
+    repositories: Vec<GrantedRepository>,
+}
+
+#[derive(Deserialize)]
+struct GrantedRepository {
+    name: String,
+}
+
+impl AccessTokenResponse {
+    /// Split the response into the token and the scope GitHub granted.
+    pub(super) fn into_token_and_scope(self) -> (String, InstallationTokenScope) {
+        let granted = InstallationTokenScope {
+            repositories: self
+                .repositories
+                .into_iter()
+                .map(|repository| repository.name)
+                .collect(),
+            permissions: self.permissions,
+        };
+        (self.token, granted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for installation token scopes.
+
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+
+    #[rstest]
+    fn default_scope_serializes_to_an_empty_request_body() {
+        let body = serde_json::to_value(InstallationTokenScope::default())
+            .expect("scope should serialize");
+
+        assert_eq!(body, json!({}));
+    }
+
+    #[rstest]
+    fn scope_serializes_repositories_and_permissions() {
+        let scope = InstallationTokenScope::default()
+            .with_repository("podbot")
+            .with_permission("contents", TokenPermissionLevel::Write)
+            .with_permission("pull_requests", TokenPermissionLevel::Write)
+            .with_permission("metadata", TokenPermissionLevel::Read);
+
+        let body = serde_json::to_value(&scope).expect("scope should serialize");
+
+        assert_eq!(
+            body,
+            json!({
+                "repositories": ["podbot"],
+                "permissions": {
+                    "contents": "write",
+                    "metadata": "read",
+                    "pull_requests": "write",
+                },
+            })
+        );
+    }
+
+    #[rstest]
+    fn response_reports_granted_scope() {
+        let response: AccessTokenResponse = serde_json::from_value(json!({
+            "token": "ghs_fixture",
+            "expires_at": "2026-01-01T00:00:00Z",
+            "permissions": { "contents": "write", "metadata": "read" },
+            "repository_selection": "selected",
+            "repositories": [{ "id": 1, "name": "podbot", "full_name": "leynos/podbot" }],
+        }))
+        .expect("response should deserialize");
+
+        let (token, granted) = response.into_token_and_scope();
+
+        assert_eq!(token, "ghs_fixture");
+        assert_eq!(
+            granted,
+            InstallationTokenScope::default()
+                .with_repository("podbot")
+                .with_permission("contents", TokenPermissionLevel::Write)
+                .with_permission("metadata", TokenPermissionLevel::Read)
+        );
+    }
+}
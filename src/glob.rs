@@ -0,0 +1,100 @@
+//! Wildcard matching shared by podbot's glob dialects.
+//!
+//! `[[github.apps]]` owner globs, ACP permission-rule titles, and archive
+//! include and exclude patterns all match with `*` (any run) and `?` (one
+//! element). [`sequence_matches`] implements that matching once over any
+//! element type, so archive paths can treat a `**` segment as a run of
+//! segments, and [`wildcards_overlap`] decides whether two character globs
+//! can match the same string.
+
+/// Match `text` against `pattern`, where `*` matches any run of characters
+/// and `?` matches exactly one.
+pub(crate) fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let wanted: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = text.chars().collect();
+    sequence_matches(
+        &wanted,
+        &chars,
+        |element| *element == '*',
+        |element, ch| *element == '?' || element == ch,
+    )
+}
+
+/// Match `text` against `pattern`, where elements for which `is_run` holds
+/// match any run of text elements and every other element must `accept`
+/// exactly one.
+///
+/// The matcher backtracks only to the most recent run, so it takes
+/// `O(pattern × text)` steps even for adversarial input.
+pub(crate) fn sequence_matches<P, T>(
+    pattern: &[P],
+    text: &[T],
+    is_run: impl Fn(&P) -> bool,
+    accepts: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut run: Option<(usize, usize)> = None;
+    while let Some(actual) = text.get(t) {
+        match pattern.get(p) {
+            Some(element) if is_run(element) => {
+                run = Some((p, t));
+                p += 1;
+            }
+            Some(element) if accepts(element, actual) => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((run_p, run_t)) = run else {
+                    return false;
+                };
+                p = run_p + 1;
+                t = run_t + 1;
+                run = Some((run_p, run_t + 1));
+            }
+        }
+    }
+    pattern.get(p..).is_some_and(|rest| rest.iter().all(is_run))
+}
+
+/// Returns whether some string matches both `left` and `right`, where `*`
+/// matches any run of characters and `?` matches exactly one.
+///
+/// A string without wildcards is a glob that matches only itself, so this
+/// also answers whether a glob matches a string. The table holds one entry
+/// per pair of suffixes, so the check takes `O(left × right)` steps.
+pub(crate) fn wildcards_overlap(left: &str, right: &str) -> bool {
+    let left_chars: Vec<char> = left.chars().collect();
+    let right_chars: Vec<char> = right.chars().collect();
+    let width = right_chars.len() + 1;
+    // `overlaps[i * width + j]` says whether `left[i..]` and `right[j..]`
+    // overlap; it is filled from the ends of both globs backwards.
+    let mut overlaps = vec![false; (left_chars.len() + 1) * width];
+    for i in (0..=left_chars.len()).rev() {
+        for j in (0..=right_chars.len()).rev() {
+            let at = |di: usize, dj: usize| {
+                overlaps
+                    .get((i + di) * width + j + dj)
+                    .copied()
+                    .unwrap_or(false)
+            };
+            let overlap = match (left_chars.get(i), right_chars.get(j)) {
+                (None, None) => true,
+                (Some('*'), next) => at(1, 0) || (next.is_some() && at(0, 1)),
+                (next, Some('*')) => at(0, 1) || (next.is_some() && at(1, 0)),
+                (Some(left_char), Some(right_char)) => {
+                    (*left_char == '?' || *right_char == '?' || left_char == right_char) && at(1, 1)
+                }
+                _ => false,
+            };
+            if let Some(slot) = overlaps.get_mut(i * width + j) {
+                *slot = overlap;
+            }
+        }
+    }
+    overlaps.first().copied().unwrap_or(false)
+}
+
+#[cfg(test)]
+#[path = "glob_tests.rs"]
+mod tests;
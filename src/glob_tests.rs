@@ -0,0 +1,65 @@
+//! Unit tests for the shared wildcard matcher.
+
+use rstest::rstest;
+
+use super::*;
+
+#[rstest]
+#[case::literal("Read file", "Read file", true)]
+#[case::star_prefix("*.rs", "src/lib.rs", true)]
+#[case::star_middle("Run `*`", "Run `cargo test`", true)]
+#[case::question("file?", "file1", true)]
+#[case::question_needs_a_char("file?", "file", false)]
+#[case::mismatch("Read *", "Edit src", false)]
+#[case::trailing_text("Read", "Read file", false)]
+#[case::backtracking("*a*b", "xaxxaxb", true)]
+#[case::empty_text("*", "", true)]
+#[case::empty_pattern("", "x", false)]
+fn wildcards_match_text(#[case] pattern: &str, #[case] text: &str, #[case] expected: bool) {
+    assert_eq!(wildcard_matches(pattern, text), expected);
+}
+
+#[rstest]
+fn adversarial_patterns_match_in_polynomial_time() {
+    let pattern = format!("{}b", "*a".repeat(30));
+    let text = "a".repeat(200);
+
+    assert!(!wildcard_matches(&pattern, &text));
+}
+
+#[rstest]
+#[case::run_of_segments(&["**", "auth.json"], &["a", "b", "auth.json"], true)]
+#[case::empty_run(&["**", "auth.json"], &["auth.json"], true)]
+#[case::wrong_tail(&["**", "auth.json"], &["a", "auth.yaml"], false)]
+fn runs_can_span_any_element_type(
+    #[case] pattern: &[&str],
+    #[case] text: &[&str],
+    #[case] expected: bool,
+) {
+    let matched = sequence_matches(
+        pattern,
+        text,
+        |segment| *segment == "**",
+        |segment, actual| segment == actual,
+    );
+
+    assert_eq!(matched, expected);
+}
+
+#[rstest]
+#[case::identical("leynos", "leynos", true)]
+#[case::glob_and_literal("ley*", "leynos", true)]
+#[case::disjoint_literals("leynos", "octo", false)]
+#[case::prefix_and_suffix("ley*", "*nos", true)]
+#[case::different_prefixes("ley*", "oct*", false)]
+#[case::question_and_star("?", "*", true)]
+#[case::lengths_differ("??", "?", false)]
+#[case::different_suffixes("a*c", "ab?d", false)]
+fn overlapping_globs_share_a_match(
+    #[case] left: &str,
+    #[case] right: &str,
+    #[case] expected: bool,
+) {
+    assert_eq!(wildcards_overlap(left, right), expected);
+    assert_eq!(wildcards_overlap(right, left), expected);
+}
@@ -36,6 +36,7 @@ pub mod error;
 pub mod github;
 #[cfg(all(feature = "experimental", not(any(feature = "internal", test))))]
 mod github;
+mod glob;
 
 #[cfg(test)]
 mod tests {
@@ -10,13 +10,18 @@
 //! 3. Environment variables (`PODBOT_*`)
 //! 4. Command-line arguments
 
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
+use std::process::ExitCode;
 
 use clap::Parser;
-use eyre::{Report, Result as EyreResult};
+use eyre::Report;
 use mockable::DefaultClock;
-use podbot::api::{CommandOutcome, ExecMode, ExecRequest};
-use podbot::cli::{Cli, Commands, ExecArgs, HostArgs, StopArgs, TokenDaemonArgs};
+use podbot::api::{
+    CommandOutcome, ExecMode, ExecRequest, RedactingMakeWriter, RedactingWriter, SecretRegistry,
+};
+use podbot::cli::{
+    Cli, Commands, CpArgs, ExecArgs, ExportArgs, HostArgs, StopArgs, TokenDaemonArgs,
+};
 use podbot::config::{AppConfig, load_config};
 use podbot::error::ConfigError;
 use podbot::error::Result as PodbotResult;
@@ -26,25 +31,54 @@ use podbot::error::Result as PodbotResult;
 /// Loads configuration with layered precedence via `OrthoConfig`, then dispatches
 /// to the appropriate subcommand handler.
 ///
-/// Uses `eyre::Result` as the return type to provide human-readable error reports
-/// with backtraces when available.
-fn main() -> EyreResult<()> {
+/// Errors are rendered as `eyre` reports, with their full cause chain and
+/// backtraces when available, and written to stderr with registered secrets
+/// scrubbed.
+fn main() -> ExitCode {
+    install_diagnostics();
+
     // Parse CLI first (for subcommand dispatch and global options).
     let cli = Cli::parse();
 
     // Load configuration with layered precedence: defaults < file < env < CLI.
     // The CLI is passed to extract --config, --engine-socket, and --image.
     let options = cli.config_load_options();
-    let config = load_config(&options).map_err(Report::from)?;
-    match run(&cli, &config) {
-        Ok(CommandOutcome::Success) => Ok(()),
+    match load_config(&options).and_then(|config| run(&cli, &config)) {
+        Ok(CommandOutcome::Success) => ExitCode::SUCCESS,
         Ok(CommandOutcome::CommandExit { code }) => {
             std::process::exit(normalize_process_exit_code(code))
         }
-        Err(error) => Err(Report::from(error)),
+        Err(error) => report_error(std::io::stderr(), error),
+    }
+}
+
+/// Send warnings and errors to stderr with registered secrets scrubbed.
+///
+/// Colour is disabled because escape codes between a field name and its
+/// value would hide sensitive keys from redaction.
+fn install_diagnostics() {
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(RedactingMakeWriter::new(std::io::stderr))
+        .finish();
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        tracing::debug!("a global tracing subscriber is already installed");
     }
 }
 
+/// Write a command error to `out` as an `eyre` report, scrubbing registered
+/// secrets from the rendered chain through a [`RedactingWriter`], and return
+/// the failure exit code.
+fn report_error(out: impl Write, error: podbot::error::PodbotError) -> ExitCode {
+    let mut writer = RedactingWriter::new(out, SecretRegistry::global());
+    let written =
+        writeln!(writer, "Error: {:?}", Report::from(error)).and_then(|()| writer.flush());
+    // A failed write to stderr has nowhere to be reported.
+    drop(written);
+    ExitCode::FAILURE
+}
+
 /// Execute the CLI command, returning domain-specific errors.
 ///
 /// Keeps semantic errors inside the run loop so the CLI boundary owns
@@ -74,10 +108,12 @@ fn run(cli: &Cli, config: &AppConfig) -> PodbotResult<CommandOutcome> {
             }
             .into())
         }
-        Commands::TokenDaemon(args) => run_token_daemon_cli(args),
+        Commands::TokenDaemon(args) => run_token_daemon_cli(config, args),
         Commands::Ps => list_containers_cli(),
-        Commands::Stop(args) => stop_container_cli(args),
+        Commands::Stop(args) => stop_container_cli(config, args),
         Commands::Exec(args) => exec_in_container_cli(config, args),
+        Commands::Export(args) => export_workspace_cli(config, args),
+        Commands::Cp(args) => copy_cli(config, args),
     }
 }
 
@@ -196,10 +232,13 @@ fn host_agent_cli(config: &AppConfig, _args: &HostArgs) -> CommandOutcome {
 
 /// CLI adapter for the token refresh daemon.
 #[expect(clippy::print_stdout, reason = "CLI output is the intended behaviour")]
-fn run_token_daemon_cli(args: &TokenDaemonArgs) -> PodbotResult<CommandOutcome> {
+fn run_token_daemon_cli(
+    config: &AppConfig,
+    args: &TokenDaemonArgs,
+) -> PodbotResult<CommandOutcome> {
     println!("Starting token daemon for container {}", args.container_id);
-    let result = run_token_daemon_api(&args.container_id)?;
-    println!("Token daemon not yet implemented.");
+    let result = run_token_daemon_api(config, args)?;
+    println!("Token daemon stopped for container {}", args.container_id);
     Ok(result)
 }
 
@@ -214,9 +253,9 @@ fn list_containers_cli() -> PodbotResult<CommandOutcome> {
 
 /// CLI adapter for stopping a running container.
 #[expect(clippy::print_stdout, reason = "CLI output is the intended behaviour")]
-fn stop_container_cli(args: &StopArgs) -> PodbotResult<CommandOutcome> {
+fn stop_container_cli(config: &AppConfig, args: &StopArgs) -> PodbotResult<CommandOutcome> {
     println!("Stopping container {}", args.container);
-    let result = stop_container_api(&args.container)?;
+    let result = stop_container_api(config, &args.container)?;
     println!("Container stop not yet implemented.");
     Ok(result)
 }
@@ -239,6 +278,22 @@ fn exec_in_container_cli(config: &AppConfig, args: &ExecArgs) -> PodbotResult<Co
     podbot::api::exec(config, &request)
 }
 
+/// CLI adapter for exporting a session workspace to a host file.
+#[expect(clippy::print_stdout, reason = "CLI output is the intended behaviour")]
+fn export_workspace_cli(config: &AppConfig, args: &ExportArgs) -> PodbotResult<CommandOutcome> {
+    let result = export_workspace_api(config, args)?;
+    println!("Exported workspace from {} to {}", args.session, args.out);
+    Ok(result)
+}
+
+/// CLI adapter for copying files between the host and a container.
+#[expect(clippy::print_stdout, reason = "CLI output is the intended behaviour")]
+fn copy_cli(config: &AppConfig, args: &CpArgs) -> PodbotResult<CommandOutcome> {
+    let result = copy_api(config, args)?;
+    println!("Copied {} to {}", args.source, args.destination);
+    Ok(result)
+}
+
 /// Normalize container exit codes to process exit codes.
 ///
 /// Container engines can report values outside the platform shell convention.
@@ -284,12 +339,22 @@ fn run_agent_api(
 }
 
 #[cfg(feature = "experimental")]
-fn run_token_daemon_api(container_id: &str) -> PodbotResult<CommandOutcome> {
-    podbot::api::run_token_daemon(container_id)
+fn run_token_daemon_api(
+    config: &AppConfig,
+    args: &TokenDaemonArgs,
+) -> PodbotResult<CommandOutcome> {
+    let mut request = podbot::api::TokenDaemonRequest::new(&args.container_id);
+    if let Some(repo) = &args.repo {
+        request = request.with_repository(podbot::api::RepositoryRef::parse(repo)?);
+    }
+    podbot::api::run_token_daemon(config, &request)
 }
 
 #[cfg(not(feature = "experimental"))]
-fn run_token_daemon_api(_container_id: &str) -> PodbotResult<CommandOutcome> {
+fn run_token_daemon_api(
+    _config: &AppConfig,
+    _args: &TokenDaemonArgs,
+) -> PodbotResult<CommandOutcome> {
     experimental_only("token-daemon")
 }
 
@@ -304,15 +369,54 @@ fn list_containers_api() -> PodbotResult<CommandOutcome> {
 }
 
 #[cfg(feature = "experimental")]
-fn stop_container_api(container: &str) -> PodbotResult<CommandOutcome> {
-    podbot::api::stop_container(container)
+fn stop_container_api(config: &AppConfig, container: &str) -> PodbotResult<CommandOutcome> {
+    podbot::api::stop_container(config, container)
 }
 
 #[cfg(not(feature = "experimental"))]
-fn stop_container_api(_container: &str) -> PodbotResult<CommandOutcome> {
+fn stop_container_api(_config: &AppConfig, _container: &str) -> PodbotResult<CommandOutcome> {
     experimental_only("stop")
 }
 
+#[cfg(feature = "experimental")]
+fn export_workspace_api(config: &AppConfig, args: &ExportArgs) -> PodbotResult<CommandOutcome> {
+    let mut request =
+        podbot::api::ExportRequest::new(&args.session, args.format.into(), args.out.clone())?;
+    if let Some(base) = &args.base {
+        request = request.with_base(base)?;
+    }
+    podbot::api::export_workspace(config, &request)
+}
+
+#[cfg(not(feature = "experimental"))]
+fn export_workspace_api(_config: &AppConfig, _args: &ExportArgs) -> PodbotResult<CommandOutcome> {
+    experimental_only("export")
+}
+
+#[cfg(feature = "experimental")]
+fn copy_api(config: &AppConfig, args: &CpArgs) -> PodbotResult<CommandOutcome> {
+    let operands = args.operands()?;
+    let mut request = podbot::api::CopyRequest::new(
+        operands.container,
+        operands.container_path,
+        operands.host_path,
+    )?;
+    if let Some(max_bytes) = args.max_bytes {
+        request = request.with_max_bytes(max_bytes);
+    }
+    match operands.direction {
+        podbot::cli::CopyDirection::ToContainer => podbot::api::copy_to_container(config, &request),
+        podbot::cli::CopyDirection::FromContainer => {
+            podbot::api::copy_from_container(config, &request)
+        }
+    }
+}
+
+#[cfg(not(feature = "experimental"))]
+fn copy_api(_config: &AppConfig, _args: &CpArgs) -> PodbotResult<CommandOutcome> {
+    experimental_only("cp")
+}
+
 #[cfg(test)]
 #[path = "bin_tests/main_tests.rs"]
 mod tests;
@@ -34,6 +34,7 @@ fn set_github_config(
             app_id,
             installation_id,
             private_key_path: private_key_path.map(Utf8PathBuf::from),
+            ..Default::default()
         },
         ..Default::default()
     };
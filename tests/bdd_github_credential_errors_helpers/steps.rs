@@ -9,7 +9,7 @@ use cap_std::fs_utf8::Dir;
 use rstest_bdd_macros::{given, when};
 
 use jsonwebtoken::EncodingKey;
-use podbot::error::GitHubError;
+use podbot::error::{GitHubError, GitHubFailureKind};
 use podbot::github::{
     BoxFuture, GitHubAppClient, test_classify_error_message, validate_with_factory,
 };
@@ -73,7 +73,12 @@ fn configure_mock_client(mock_response: MockHttpResponse) -> MockGitHubAppClient
         .times(1)
         .returning(move || {
             let msg = message.clone();
-            Box::pin(async move { Err(GitHubError::AuthenticationFailed { message: msg }) })
+            Box::pin(async move {
+                Err(GitHubError::AuthenticationFailed {
+                    message: msg,
+                    kind: GitHubFailureKind::Permanent,
+                })
+            })
         });
     mock_client
 }
@@ -8,7 +8,7 @@ use cap_std::fs_utf8::Dir;
 use rstest_bdd_macros::{given, when};
 
 use jsonwebtoken::EncodingKey;
-use podbot::error::GitHubError;
+use podbot::error::{GitHubError, GitHubFailureKind};
 use podbot::github::{BoxFuture, GitHubAppClient, validate_with_factory};
 
 use super::state::{
@@ -70,6 +70,7 @@ fn configure_mock_client(mock_response: MockApiResponse) -> MockGitHubAppClient
                 .returning(|| {
                     Box::pin(async {
                         Err(GitHubError::AuthenticationFailed {
+                            kind: GitHubFailureKind::Permanent,
                             message: String::from(concat!(
                                 "failed to validate GitHub App credentials: ",
                                 "401 Unauthorized - invalid credentials",
@@ -85,6 +86,7 @@ fn configure_mock_client(mock_response: MockApiResponse) -> MockGitHubAppClient
                 .returning(|| {
                     Box::pin(async {
                         Err(GitHubError::AuthenticationFailed {
+                            kind: GitHubFailureKind::Permanent,
                             message: String::from(concat!(
                                 "failed to validate GitHub App credentials: ",
                                 "500 Internal Server Error",
@@ -106,6 +108,7 @@ fn build_factory(
         // Verify the app_id is correctly passed through the orchestration
         if received_app_id != expected_app_id {
             return Err(GitHubError::AuthenticationFailed {
+                kind: GitHubFailureKind::Permanent,
                 message: format!(
                     "app_id mismatch: expected {expected_app_id}, received {received_app_id}"
                 ),
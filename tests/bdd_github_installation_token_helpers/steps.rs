@@ -3,9 +3,9 @@
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use podbot::error::GitHubError;
+use podbot::error::{GitHubError, GitHubFailureKind};
 use podbot::github::{
-    BoxFuture, GitHubInstallationTokenClient, InstallationAccessToken,
+    BoxFuture, GitHubInstallationTokenClient, InstallationAccessToken, InstallationTokenScope,
     acquire_installation_token_with_client,
 };
 use rstest_bdd_macros::{given, when};
@@ -23,6 +23,13 @@ mockall::mock! {
             installation_id: u64,
             expiry_buffer: Duration,
         ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>>;
+
+        fn acquire_scoped_installation_token(
+            &self,
+            installation_id: u64,
+            scope: InstallationTokenScope,
+            expiry_buffer: Duration,
+        ) -> BoxFuture<'_, Result<InstallationAccessToken, GitHubError>>;
     }
 }
 
@@ -100,6 +107,7 @@ fn configure_mock_client(inputs: &TokenInputs) -> StepResult<MockGitHubInstallat
                 .returning(|_, _| {
                     Box::pin(async {
                         Err(GitHubError::TokenAcquisitionFailed {
+                            kind: GitHubFailureKind::Permanent,
                             message: String::from("GitHub rejected installation token acquisition"),
                         })
                     })
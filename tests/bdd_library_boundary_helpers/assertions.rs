@@ -100,7 +100,7 @@ fn all_stubs_succeed(library_boundary_state: &LibraryBoundaryState) -> StepResul
         .get()
         .ok_or_else(|| String::from("stub_outcomes should be set"))?;
 
-    const EXPECTED_STUB_COUNT: usize = 4;
+    const EXPECTED_STUB_COUNT: usize = 3;
     if outcomes.results.len() != EXPECTED_STUB_COUNT {
         return Err(format!(
             "expected {EXPECTED_STUB_COUNT} stub outcomes but found {}",
@@ -36,8 +36,7 @@ pub(crate) enum ConfigResult {
 /// Collected outcomes from stub orchestration functions.
 #[derive(Debug, Clone)]
 pub(crate) struct StubOutcomes {
-    /// Results from `run_agent`, `list_containers`, `stop_container`,
-    /// and `run_token_daemon`.
+    /// Results from `run_agent`, `list_containers`, and `stop_container`.
     pub(crate) results: Vec<LibraryResult>,
 }
 
@@ -12,7 +12,7 @@ use mockable::MockEnv;
 use mockall::mock;
 use podbot::api::{ExecMode, ExecRequest, RunRequest};
 #[cfg(feature = "experimental")]
-use podbot::api::{list_containers, run_agent, run_token_daemon, stop_container};
+use podbot::api::{list_containers, run_agent, stop_container};
 #[cfg(feature = "experimental")]
 use podbot::config::AppConfig;
 use podbot::config::{ConfigLoadOptions, ConfigOverrides, load_config_with_env};
@@ -209,11 +209,7 @@ fn when_stubs_called(library_boundary_state: &LibraryBoundaryState) -> StepResul
         Ok(outcome) => results.push(LibraryResult::Ok(outcome)),
         Err(e) => results.push(LibraryResult::Err(Arc::new(e))),
     }
-    match stop_container("test-ctr") {
-        Ok(outcome) => results.push(LibraryResult::Ok(outcome)),
-        Err(e) => results.push(LibraryResult::Err(Arc::new(e))),
-    }
-    match run_token_daemon("test-ctr") {
+    match stop_container(&config, "test-ctr") {
         Ok(outcome) => results.push(LibraryResult::Ok(outcome)),
         Err(e) => results.push(LibraryResult::Err(Arc::new(e))),
     }
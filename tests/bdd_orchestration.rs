@@ -59,10 +59,10 @@ fn list_containers_stub_returns_success(orchestration_state: OrchestrationState)
 
 #[scenario(
     path = "tests/features/orchestration.feature",
-    name = "Token daemon stub returns success"
+    name = "Token daemon requires GitHub configuration"
 )]
 #[cfg(feature = "experimental")]
 #[serial]
-fn token_daemon_stub_returns_success(orchestration_state: OrchestrationState) {
+fn token_daemon_requires_github_configuration(orchestration_state: OrchestrationState) {
     let _ = orchestration_state;
 }
@@ -47,3 +47,24 @@ fn outcome_is_command_exit(
         )),
     }
 }
+
+#[then("the outcome is an error mentioning {fragment}")]
+fn outcome_is_error_mentioning(
+    orchestration_state: &OrchestrationState,
+    fragment: String,
+) -> StepResult<()> {
+    let result = orchestration_state
+        .result
+        .get()
+        .ok_or_else(|| String::from("result should be set"))?;
+
+    match result {
+        OrchestrationResult::Err(msg) if msg.contains(&fragment) => Ok(()),
+        OrchestrationResult::Err(msg) => Err(format!(
+            "expected error mentioning {fragment}, got error: {msg}"
+        )),
+        OrchestrationResult::Ok(outcome) => Err(format!(
+            "expected error mentioning {fragment}, got {outcome:?}"
+        )),
+    }
+}
@@ -16,7 +16,9 @@ use futures_util::stream;
 use mockall::mock;
 use podbot::api::{CommandOutcome, ExecMode, ExecRequest};
 #[cfg(feature = "experimental")]
-use podbot::api::{RunRequest, list_containers, run_agent, run_token_daemon, stop_container};
+use podbot::api::{
+    RunRequest, TokenDaemonRequest, list_containers, run_agent, run_token_daemon, stop_container,
+};
 #[cfg(feature = "experimental")]
 use podbot::config::AppConfig;
 use podbot::engine::{
@@ -139,7 +141,8 @@ fn when_stop_invoked(
     orchestration_state: &OrchestrationState,
     container: String,
 ) -> StepResult<()> {
-    invoke_orchestration(orchestration_state, || stop_container(&container));
+    let config = AppConfig::default();
+    invoke_orchestration(orchestration_state, || stop_container(&config, &container));
     Ok(())
 }
 
@@ -164,7 +167,9 @@ fn when_token_daemon_invoked(
     orchestration_state: &OrchestrationState,
     container: String,
 ) -> StepResult<()> {
-    invoke_orchestration(orchestration_state, || run_token_daemon(&container));
+    invoke_orchestration(orchestration_state, || {
+        run_token_daemon(&AppConfig::default(), &TokenDaemonRequest::new(&container))
+    });
     Ok(())
 }
 
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use podbot::api::{AskpassPath, BranchName, RepositoryRef, WorkspacePath};
+use podbot::config::GitHubConfig;
 use podbot::engine::{RepositoryCloneRequest, clone_repository_into_workspace};
 use rstest_bdd_macros::{given, when};
 
@@ -147,6 +148,7 @@ fn execute_clone(
             branch,
             workspace_base_dir: &inputs.workspace_base_dir,
             askpass_path: &inputs.askpass_path,
+            web_base_url: GitHubConfig::default().web_base_url_or_default(),
         },
     )
 }
@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions};
 use mockall::mock;
 use podbot::api::{AskpassPath, BranchName, RepositoryRef, WorkspacePath};
+use podbot::config::GitHubConfig;
 use podbot::engine::{
     ContainerExecClient, CreateExecFuture, InspectExecFuture, RepositoryCloneRequest,
     ResizeExecFuture, StartExecFuture, clone_repository_into_workspace,
@@ -167,6 +168,7 @@ fn invoke_clone(
             branch: &branch,
             workspace_base_dir: &workspace,
             askpass_path: &askpass,
+            web_base_url: GitHubConfig::default().web_base_url_or_default(),
         },
     )
 }
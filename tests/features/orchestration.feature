@@ -33,6 +33,6 @@ Feature: Command orchestration
     When list containers orchestration is invoked
     Then the outcome is success
 
-  Scenario: Token daemon stub returns success
+  Scenario: Token daemon requires GitHub configuration
     When token daemon orchestration is invoked with container test-ctr
-    Then the outcome is success
+    Then the outcome is an error mentioning github.app_id
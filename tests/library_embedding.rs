@@ -17,7 +17,7 @@ use futures_util::stream;
 use mockall::mock;
 use rstest::{fixture, rstest};
 
-use podbot::api::{CommandOutcome, ExecMode, ExecRequest, RunRequest};
+use podbot::api::{CommandOutcome, ExecMode, ExecRequest, RunRequest, TokenDaemonRequest};
 #[cfg(feature = "experimental")]
 use podbot::api::{list_containers, run_agent, run_token_daemon, stop_container};
 #[cfg(feature = "experimental")]
@@ -227,12 +227,26 @@ fn stub_orchestration_functions_return_success() {
         "list_containers should return Success"
     );
     assert!(
-        matches!(stop_container("test-ctr"), Ok(CommandOutcome::Success)),
+        matches!(
+            stop_container(&config, "test-ctr"),
+            Ok(CommandOutcome::Success)
+        ),
         "stop_container should return Success"
     );
+}
+
+#[rstest]
+#[cfg(feature = "experimental")]
+fn run_token_daemon_requires_github_configuration() {
+    let result = run_token_daemon(&AppConfig::default(), &TokenDaemonRequest::new("test-ctr"));
+
     assert!(
-        matches!(run_token_daemon("test-ctr"), Ok(CommandOutcome::Success)),
-        "run_token_daemon should return Success"
+        matches!(
+            &result,
+            Err(PodbotError::Config(ConfigError::MissingRequired { field }))
+                if field.contains("github.app_id")
+        ),
+        "run_token_daemon should require GitHub configuration, got {result:?}"
     );
 }
 